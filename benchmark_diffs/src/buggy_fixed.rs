@@ -22,11 +22,7 @@ fn test_simple_1() {
         node_store: NodeStore::new(),
     };
     let mut md_cache = Default::default();
-    let mut java_tree_gen = JavaTreeGen {
-        line_break: "\n".as_bytes().to_vec(),
-        stores: &mut stores,
-        md_cache: &mut md_cache,
-    };
+    let mut java_tree_gen = JavaTreeGen::new(&mut stores, &mut md_cache);
     let (src_tr, dst_tr) = parse_string_pair(&mut java_tree_gen, &buggy, &fixed);
 
     println!(
@@ -60,11 +56,7 @@ fn test_crash1() {
         node_store: NodeStore::new(),
     };
     let mut md_cache = Default::default();
-    let mut java_tree_gen = JavaTreeGen {
-        line_break: "\n".as_bytes().to_vec(),
-        stores: &mut stores,
-        md_cache: &mut md_cache,
-    };
+    let mut java_tree_gen = JavaTreeGen::new(&mut stores, &mut md_cache);
     print!("{:?} len={}: ", buggy_path, buggy.len());
     let (src_tr, dst_tr) = parse_string_pair(&mut java_tree_gen, &buggy, &fixed);
     let len = algorithms::gumtree::diff(
@@ -99,11 +91,7 @@ mod examples {
             node_store: NodeStore::new(),
         };
         let mut md_cache = Default::default();
-        let mut java_tree_gen = JavaTreeGen {
-            line_break: "\n".as_bytes().to_vec(),
-            stores: &mut stores,
-            md_cache: &mut md_cache,
-        };
+        let mut java_tree_gen = JavaTreeGen::new(&mut stores, &mut md_cache);
         print!("len={}: ", buggy.len());
         let (src_tr, dst_tr) = parse_string_pair(&mut java_tree_gen, &buggy, &fixed);
         let len = algorithms::gumtree::diff(
@@ -150,11 +138,7 @@ mod examples {
             node_store: NodeStore::new(),
         };
         let mut md_cache = Default::default();
-        let mut java_tree_gen = JavaTreeGen {
-            line_break: "\n".as_bytes().to_vec(),
-            stores: &mut stores,
-            md_cache: &mut md_cache,
-        };
+        let mut java_tree_gen = JavaTreeGen::new(&mut stores, &mut md_cache);
         print!("len={}: ", buggy.len());
         let (src_tr, dst_tr) = parse_string_pair(&mut java_tree_gen, &buggy, &fixed);
         let len = algorithms::gumtree::diff(
@@ -238,11 +222,7 @@ mod examples {
             node_store: NodeStore::new(),
         };
         let mut md_cache = Default::default();
-        let mut java_tree_gen = JavaTreeGen {
-            line_break: "\n".as_bytes().to_vec(),
-            stores: &mut stores,
-            md_cache: &mut md_cache,
-        };
+        let mut java_tree_gen = JavaTreeGen::new(&mut stores, &mut md_cache);
         let now = Instant::now();
 
         println!("{} len={}", "buggy", buggy.len());
@@ -1183,11 +1163,7 @@ fn compare_perfs() {
         node_store: NodeStore::new(),
     };
     let mut md_cache = Default::default();
-    let mut java_tree_gen = JavaTreeGen {
-        line_break: "\n".as_bytes().to_vec(),
-        stores: &mut stores,
-        md_cache: &mut md_cache,
-    };
+    let mut java_tree_gen = JavaTreeGen::new(&mut stores, &mut md_cache);
     let now = Instant::now();
 
     println!("{:?} len={}", buggy_path, buggy.len());
@@ -1266,11 +1242,7 @@ pub fn bad_perfs() {
         node_store: NodeStore::new(),
     };
     let mut md_cache = Default::default();
-    let mut java_tree_gen = JavaTreeGen {
-        line_break: "\n".as_bytes().to_vec(),
-        stores: &mut stores,
-        md_cache: &mut md_cache,
-    };
+    let mut java_tree_gen = JavaTreeGen::new(&mut stores, &mut md_cache);
     let now = Instant::now();
 
     println!("{:?} len={}", buggy_path, buggy.len());
@@ -1328,11 +1300,7 @@ pub fn bad_perfs2() {
         node_store: NodeStore::new(),
     };
     let mut md_cache = Default::default();
-    let mut java_tree_gen = JavaTreeGen {
-        line_break: "\n".as_bytes().to_vec(),
-        stores: &mut stores,
-        md_cache: &mut md_cache,
-    };
+    let mut java_tree_gen = JavaTreeGen::new(&mut stores, &mut md_cache);
     let now = Instant::now();
 
     println!("{:?} len={}", buggy_path, buggy.len());
@@ -1480,11 +1448,7 @@ fn bad_perfs_helper(buggy_path: std::path::PathBuf, fixed_path: std::path::PathB
         node_store: NodeStore::new(),
     };
     let mut md_cache = Default::default();
-    let mut java_tree_gen = JavaTreeGen {
-        line_break: "\n".as_bytes().to_vec(),
-        stores: &mut stores,
-        md_cache: &mut md_cache,
-    };
+    let mut java_tree_gen = JavaTreeGen::new(&mut stores, &mut md_cache);
     let (src_tr, dst_tr) = parse_string_pair(&mut java_tree_gen, &buggy, &fixed);
     let buggy_s = src_tr.local.metrics.size;
     let fixed_s = dst_tr.local.metrics.size;
@@ -1596,11 +1560,7 @@ fn test_all() {
                 node_store: NodeStore::new(),
             };
             let mut md_cache = Default::default();
-            let mut java_tree_gen = JavaTreeGen {
-                line_break: "\n".as_bytes().to_vec(),
-                stores: &mut stores,
-                md_cache: &mut md_cache,
-            };
+            let mut java_tree_gen = JavaTreeGen::new(&mut stores, &mut md_cache);
             let now = Instant::now();
 
             println!("{:?} len={}", buggy_path, buggy.len());
@@ -1728,11 +1688,7 @@ pub fn run(buggy_path: &Path, fixed_path: &Path, name: &Path) -> Option<String>
         node_store: NodeStore::new(),
     };
     let mut md_cache = Default::default();
-    let mut java_tree_gen = JavaTreeGen {
-        line_break: "\n".as_bytes().to_vec(),
-        stores: &mut stores,
-        md_cache: &mut md_cache,
-    };
+    let mut java_tree_gen = JavaTreeGen::new(&mut stores, &mut md_cache);
     println!("{:?} len={}", name, buggy.len());
     let (src_tr, dst_tr) = parse_string_pair(&mut java_tree_gen, &buggy, &fixed);
     let buggy_s = src_tr.local.metrics.size;
@@ -80,11 +80,7 @@ impl JavaPreprocessFileSys {
         } else {
             "\n".as_bytes().to_vec()
         };
-        JavaTreeGen {
-            line_break,
-            stores: &mut self.main_stores,
-            md_cache: &mut self.java_md_cache,
-        }
+        JavaTreeGen::new(&mut self.main_stores, &mut self.java_md_cache).with_line_break(line_break)
     }
 
     pub(crate) fn help_handle_java_file(
@@ -10,7 +10,10 @@ use hyper_ast::{
     store::defaults::NodeIdentifier,
     types::{HyperAST, HyperType, IterableChildren, TypeStore, WithChildren, WithStats},
 };
-use hyper_diff::{decompressed_tree_store::ShallowDecompressedTreeStore, matchers::Mapper};
+use hyper_diff::{
+    decompressed_tree_store::ShallowDecompressedTreeStore,
+    matchers::{Mapper, MatcherConfig},
+};
 
 use crate::{matching, no_space, utils::get_pair_simp};
 
@@ -106,7 +109,7 @@ pub(crate) fn added_deleted(
                     mapper.mapping.dst_arena.len(),
                 );
 
-                let vec_store = matching::full2(hyperast, mapper);
+                let vec_store = matching::full2(hyperast, mapper, &MatcherConfig::default());
 
                 dbg!();
                 entry
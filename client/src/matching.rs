@@ -13,11 +13,13 @@ use hyper_diff::matchers::mapping_store::DefaultMultiMappingStore;
 use hyper_diff::matchers::mapping_store::MappingStore;
 use hyper_diff::matchers::mapping_store::VecStore;
 use hyper_diff::matchers::Mapping;
+use hyper_diff::matchers::MatcherConfig;
 
 pub fn top_down<'store, HAST: HyperAST<'store>>(
     hyperast: &'store HAST,
     src_arena: &mut LazyPostOrder<HAST::T, u32>,
     dst_arena: &mut LazyPostOrder<HAST::T, u32>,
+    config: &MatcherConfig,
 ) -> DefaultMultiMappingStore<u32>
 where
     HAST::IdN: Clone + Debug + Eq,
@@ -28,9 +30,19 @@ where
 {
     let mut mm: DefaultMultiMappingStore<_> = Default::default();
     mm.topit(src_arena.len(), dst_arena.len());
-    Mapper::<_, _, _, VecStore<u32>>::compute_multimapping::<_, 1>(
-        hyperast, src_arena, dst_arena, &mut mm,
-    );
+    if config.is_default() {
+        Mapper::<_, _, _, VecStore<u32>>::compute_multimapping::<_, 1>(
+            hyperast, src_arena, dst_arena, &mut mm,
+        );
+    } else {
+        Mapper::<_, _, _, VecStore<u32>>::compute_multimapping_with(
+            hyperast,
+            src_arena,
+            dst_arena,
+            &mut mm,
+            config.min_height,
+        );
+    }
     mm
 }
 
@@ -43,6 +55,7 @@ pub fn full<'store, HAST: HyperAST<'store>>(
         &mut LazyPostOrder<HAST::T, u32>,
         VecStore<u32>,
     >,
+    config: &MatcherConfig,
 ) where
     HAST::IdN: Clone + Debug + Eq,
     HAST::Label: Clone + Copy + Eq + Debug,
@@ -50,11 +63,26 @@ pub fn full<'store, HAST: HyperAST<'store>>(
     <HAST::T as types::WithChildren>::ChildIdx: Debug,
     HAST::T: 'store + types::WithHashs + types::WithStats,
 {
-    let mm = LazyGreedySubtreeMatcher::<_, _, _, VecStore<_>>::compute_multi_mapping::<
-        DefaultMultiMappingStore<_>,
-    >(mapper);
+    let mm = if config.is_default() {
+        LazyGreedySubtreeMatcher::<_, _, _, VecStore<_>>::compute_multi_mapping::<
+            DefaultMultiMappingStore<_>,
+        >(mapper)
+    } else {
+        LazyGreedySubtreeMatcher::<_, _, _, VecStore<_>>::compute_multi_mapping_with::<
+            DefaultMultiMappingStore<_>,
+        >(mapper, config.min_height)
+    };
     LazyGreedySubtreeMatcher::<_, _, _, VecStore<_>>::filter_mappings(mapper, &mm);
-    GreedyBottomUpMatcher::<_, _, _, _, VecStore<_>>::execute(mapper, hyperast.label_store());
+    if config.is_default() {
+        GreedyBottomUpMatcher::<_, _, _, _, VecStore<_>>::execute(mapper, hyperast.label_store());
+    } else {
+        GreedyBottomUpMatcher::<_, _, _, _, VecStore<_>>::execute_with(
+            mapper,
+            hyperast.label_store(),
+            config.size_threshold,
+            config.sim_threshold,
+        );
+    }
 }
 
 pub fn full2<'store, HAST: HyperAST<'store>>(
@@ -66,6 +94,7 @@ pub fn full2<'store, HAST: HyperAST<'store>>(
         &mut LazyPostOrder<HAST::T, u32>,
         VecStore<u32>,
     >,
+    config: &MatcherConfig,
 ) -> VecStore<u32>
 where
     HAST::IdN: Clone + Debug + Eq,
@@ -74,9 +103,15 @@ where
     <HAST::T as types::WithChildren>::ChildIdx: Debug,
     HAST::T: 'store + types::WithHashs + types::WithStats,
 {
-    let mm = LazyGreedySubtreeMatcher::<_, _, _, VecStore<_>>::compute_multi_mapping::<
-        DefaultMultiMappingStore<_>,
-    >(&mut mapper);
+    let mm = if config.is_default() {
+        LazyGreedySubtreeMatcher::<_, _, _, VecStore<_>>::compute_multi_mapping::<
+            DefaultMultiMappingStore<_>,
+        >(&mut mapper)
+    } else {
+        LazyGreedySubtreeMatcher::<_, _, _, VecStore<_>>::compute_multi_mapping_with::<
+            DefaultMultiMappingStore<_>,
+        >(&mut mapper, config.min_height)
+    };
     LazyGreedySubtreeMatcher::<_, _, _, VecStore<_>>::filter_mappings(&mut mapper, &mm);
     use hidding_wrapper::*;
 
@@ -111,10 +146,19 @@ where
                 mappings,
             },
         };
-        GreedyBottomUpMatcher::<_, _, _, _, VecStore<_>>::execute(
-            &mut mapper,
-            hyperast.label_store(),
-        );
+        if config.is_default() {
+            GreedyBottomUpMatcher::<_, _, _, _, VecStore<_>>::execute(
+                &mut mapper,
+                hyperast.label_store(),
+            );
+        } else {
+            GreedyBottomUpMatcher::<_, _, _, _, VecStore<_>>::execute_with(
+                &mut mapper,
+                hyperast.label_store(),
+                config.size_threshold,
+                config.sim_threshold,
+            );
+        }
     }
     mapper.mapping.mappings
 }
@@ -23,11 +23,11 @@ use hyper_diff::{
     },
     matchers::{
         mapping_store::{self, MonoMappingStore, MultiMappingStore},
-        Mapper,
+        Mapper, MatcherConfig,
     },
 };
 use serde::{Deserialize, Serialize};
-use serde_aux::prelude::deserialize_bool_from_anything;
+use serde_aux::prelude::{deserialize_bool_from_anything, deserialize_option_number_from_string};
 use tokio::time::Instant;
 
 use crate::{
@@ -60,6 +60,31 @@ pub struct TrackingQuery {
     before: Option<String>,
     #[serde(flatten)]
     flags: Flags,
+    #[serde(flatten)]
+    thresholds: Thresholds,
+}
+
+/// Thresholds of the matchers, the ones of [`MatcherConfig::default`] are used when missing
+#[derive(serde::Deserialize, serde::Serialize, Default, Clone, Debug)]
+#[serde(default)]
+pub(crate) struct Thresholds {
+    #[serde(deserialize_with = "deserialize_option_number_from_string")]
+    pub(crate) min_height: Option<usize>,
+    #[serde(deserialize_with = "deserialize_option_number_from_string")]
+    pub(crate) size_threshold: Option<usize>,
+    #[serde(deserialize_with = "deserialize_option_number_from_string")]
+    pub(crate) sim_threshold: Option<f64>,
+}
+
+impl From<&Thresholds> for MatcherConfig {
+    fn from(value: &Thresholds) -> Self {
+        let default = MatcherConfig::default();
+        MatcherConfig {
+            min_height: value.min_height.unwrap_or(default.min_height),
+            size_threshold: value.size_threshold.unwrap_or(default.size_threshold),
+            sim_threshold: value.sim_threshold.unwrap_or(default.sim_threshold),
+        }
+    }
 }
 
 #[derive(serde::Deserialize, serde::Serialize, Default, Clone, Debug)]
@@ -330,7 +355,9 @@ pub fn track_code(
         end,
         before,
         flags,
+        thresholds,
     } = query;
+    let config: MatcherConfig = (&thresholds).into();
     let repo_specifier = hyper_ast_cvs_git::git::Forge::Github.repo(user, name);
     let repo_handle = state
         .repositories
@@ -379,6 +406,7 @@ pub fn track_code(
             start,
             end,
             &flags,
+            &config,
         ) {
             MappingResult::Direct { src: aaa, matches } => {
                 let aaa = aaa.globalize(repository.spec, commit);
@@ -461,7 +489,9 @@ pub(crate) fn track_code_at_path(
         end,
         before,
         flags,
+        thresholds,
     } = query;
+    let config: MatcherConfig = (&thresholds).into();
     let TrackingAtPathParam {
         user,
         name,
@@ -520,7 +550,15 @@ pub(crate) fn track_code_at_path(
         } else {
             commits[1]
         };
-        match aux2(state.clone(), &repository, src_oid, dst_oid, &path, &flags) {
+        match aux2(
+            state.clone(),
+            &repository,
+            src_oid,
+            dst_oid,
+            &path,
+            &flags,
+            &config,
+        ) {
             MappingResult::Direct { src: aaa, matches } => {
                 let aaa = aaa.globalize(repository.spec, commit);
                 let (src, intermediary) = if let Some(src) = source {
@@ -618,7 +656,9 @@ pub(crate) fn track_code_at_path_with_changes(
         end: _,
         before,
         flags,
+        thresholds,
     } = query;
+    let config: MatcherConfig = (&thresholds).into();
     let TrackingAtPathParam {
         user,
         name,
@@ -675,7 +715,15 @@ pub(crate) fn track_code_at_path_with_changes(
                 message: "this commit has no parent".into(),
             });
         };
-        match aux2(state.clone(), &repository, src_oid, dst_oid, &path, &flags) {
+        match aux2(
+            state.clone(),
+            &repository,
+            src_oid,
+            dst_oid,
+            &path,
+            &flags,
+            &config,
+        ) {
             MappingResult::Direct { src: aaa, matches } => {
                 let changes = changes::added_deleted(state, &repository, dst_oid, ori_oid.unwrap())
                     .map_err(|err| TrackingError {
@@ -850,6 +898,7 @@ fn aux(
     start: Option<usize>,
     end: Option<usize>,
     flags: &Flags,
+    config: &MatcherConfig,
 ) -> MappingResult {
     let repositories = state.repositories.read().unwrap();
     let commit_src = repositories
@@ -909,6 +958,7 @@ fn aux(
         path_to_target,
         no_spaces_path_to_target,
         flags,
+        config,
         start,
         end,
         &state.partial_decomps,
@@ -928,6 +978,7 @@ fn aux2(
     dst_oid: hyper_ast_cvs_git::git::Oid,
     path: &[usize],
     flags: &Flags,
+    config: &MatcherConfig,
 ) -> MappingResult {
     let repositories = state.repositories.read().unwrap();
     let commit_src = repositories
@@ -978,6 +1029,7 @@ fn aux2(
         path_to_target,
         no_spaces_path_to_target,
         flags,
+        config,
         range.start,
         range.end,
         &state.partial_decomps,
@@ -995,6 +1047,7 @@ fn aux_aux(
     path_to_target: Vec<u16>,
     no_spaces_path_to_target: Vec<u16>,
     flags: &Flags,
+    config: &MatcherConfig,
     start: usize,
     end: usize,
     partial_decomps: &PartialDecompCache,
@@ -1062,7 +1115,7 @@ fn aux_aux(
             let src = &src_tr;
             let dst = &dst_tr;
             let (src_arena, dst_arena) = (pair.0.get_mut(), pair.1.get_mut());
-            matching::top_down(hyperast, src_arena, dst_arena)
+            matching::top_down(hyperast, src_arena, dst_arena, config)
         };
         let (mapper_src_arena, mapper_dst_arena) = (pair.0.get_mut(), pair.1.get_mut());
         let mapper_mappings = &mapped;
@@ -1267,53 +1320,75 @@ fn aux_aux(
         }
     }
 
-    let mapped = {
-        let mappings_cache = mappings_alone;
-        use hyper_diff::matchers::mapping_store::MappingStore;
-        use hyper_diff::matchers::mapping_store::VecStore;
-        let hyperast = stores;
-        use hyper_diff::matchers::Mapping;
+    // only the mappings computed with the default thresholds are cached
+    let cached;
+    let uncached;
+    let mapper_mappings = if config.is_default() {
+        cached = {
+            let mappings_cache = mappings_alone;
+            use hyper_diff::matchers::mapping_store::MappingStore;
+            use hyper_diff::matchers::mapping_store::VecStore;
+            let hyperast = stores;
+            use hyper_diff::matchers::Mapping;
 
-        dbg!();
-        match mappings_cache.entry((src_tr, dst_tr)) {
-            dashmap::mapref::entry::Entry::Occupied(entry) => entry.into_ref().downgrade(),
-            dashmap::mapref::entry::Entry::Vacant(entry) => {
-                let mappings = VecStore::default();
-                let (src_arena, dst_arena) = (pair.0.get_mut(), pair.1.get_mut());
-                dbg!(src_arena.len());
-                dbg!(dst_arena.len());
-                let src_size = stores.node_store.resolve(src_tr).size();
-                let dst_size = stores.node_store.resolve(dst_tr).size();
-                dbg!(src_size);
-                dbg!(dst_size);
-                let mut mapper = Mapper {
-                    hyperast,
-                    mapping: Mapping {
-                        src_arena,
-                        dst_arena,
-                        mappings,
-                    },
-                };
-                dbg!();
-                dbg!(mapper.mapping.src_arena.len());
-                dbg!(mapper.mapping.dst_arena.len());
-                mapper.mapping.mappings.topit(
-                    mapper.mapping.src_arena.len(),
-                    mapper.mapping.dst_arena.len(),
-                );
-                dbg!();
+            dbg!();
+            match mappings_cache.entry((src_tr, dst_tr)) {
+                dashmap::mapref::entry::Entry::Occupied(entry) => entry.into_ref().downgrade(),
+                dashmap::mapref::entry::Entry::Vacant(entry) => {
+                    let mappings = VecStore::default();
+                    let (src_arena, dst_arena) = (pair.0.get_mut(), pair.1.get_mut());
+                    dbg!(src_arena.len());
+                    dbg!(dst_arena.len());
+                    let src_size = stores.node_store.resolve(src_tr).size();
+                    let dst_size = stores.node_store.resolve(dst_tr).size();
+                    dbg!(src_size);
+                    dbg!(dst_size);
+                    let mut mapper = Mapper {
+                        hyperast,
+                        mapping: Mapping {
+                            src_arena,
+                            dst_arena,
+                            mappings,
+                        },
+                    };
+                    dbg!();
+                    dbg!(mapper.mapping.src_arena.len());
+                    dbg!(mapper.mapping.dst_arena.len());
+                    mapper.mapping.mappings.topit(
+                        mapper.mapping.src_arena.len(),
+                        mapper.mapping.dst_arena.len(),
+                    );
+                    dbg!();
 
-                let vec_store = matching::full2(hyperast, mapper);
+                    let vec_store = matching::full2(hyperast, mapper, config);
 
-                dbg!();
-                entry
-                    .insert((crate::MappingStage::Bottomup, vec_store))
-                    .downgrade()
+                    dbg!();
+                    entry
+                        .insert((crate::MappingStage::Bottomup, vec_store))
+                        .downgrade()
+                }
             }
-        }
+        };
+        &cached.1
+    } else {
+        use hyper_diff::matchers::mapping_store::MappingStore;
+        let (src_arena, dst_arena) = (pair.0.get_mut(), pair.1.get_mut());
+        let mut mapper = Mapper {
+            hyperast: stores,
+            mapping: hyper_diff::matchers::Mapping {
+                src_arena,
+                dst_arena,
+                mappings: mapping_store::VecStore::default(),
+            },
+        };
+        mapper.mapping.mappings.topit(
+            mapper.mapping.src_arena.len(),
+            mapper.mapping.dst_arena.len(),
+        );
+        uncached = matching::full2(stores, mapper, config);
+        &uncached
     };
     let (mapper_src_arena, mapper_dst_arena) = (pair.0.get_mut(), pair.1.get_mut());
-    let mapper_mappings = &mapped.1;
     let root = mapper_src_arena.root();
     let mapping_target =
         mapper_src_arena.child_decompressed(node_store, &root, &no_spaces_path_to_target);
@@ -0,0 +1,471 @@
+//! Processing of the languages that only need the syntax of their files,
+//! eg. typescript, python or rust.
+//!
+//! Their files are generated by [`hyper_ast::tree_gen::basic::BasicTreeGen`]
+//! and their source directories are processed the same way,
+//! the build system processors (eg. npm, pip or cargo) handle the rest.
+use std::{iter::Peekable, marker::PhantomData, path::Components};
+
+use git2::{Oid, Repository};
+use hyper_ast::{
+    filter::BloomSize,
+    hashed::{self, IndexingHashBuilder, MetaDataHashsBuilder, SyntaxNodeHashs},
+    store::{
+        defaults::{LabelIdentifier, NodeIdentifier},
+        nodes::legion::PendingInsert,
+    },
+    tree_gen::{
+        basic::{eq_node, BasicLang, BasicTreeGen, FNode, Local},
+        SubTreeMetrics,
+    },
+    types::LabelStore,
+};
+use tuples::CombinConcat;
+
+use crate::{
+    git::BasicGitObject,
+    preprocessed::{IsSkippedAna, RepositoryProcessor},
+    processing::{
+        erased::{CommitProcExt, ParametrizedCommitProcessor2Handle},
+        CacheHolding, CachesHolding, InFiles, ObjectName,
+    },
+    Accumulator, Processor, SimpleStores, TStore,
+};
+
+/// The files of a language processed by [`BasicProcessor`], eg. [`crate::processing::file_sys::Ts`].
+pub trait BasicFiles:
+    'static + InFiles + CachesHolding<Caches = crate::processing::caches::Basic> + Send + Sync
+{
+    type Lang: BasicLang;
+    /// directories that are not part of the sources, eg. node_modules
+    type Skipped: InFiles;
+    const DIRECTORY: <Self::Lang as BasicLang>::Type;
+
+    fn handle_file<'stores, 'cache, 'b: 'stores>(
+        tree_gen: &mut BasicTreeGen<'stores, 'cache, Self::Lang, TStore>,
+        name: &ObjectName,
+        text: &'b [u8],
+    ) -> Result<FNode, ()>;
+}
+
+pub(crate) fn prepare_dir_exploration(tree: git2::Tree) -> Vec<BasicGitObject> {
+    tree.iter()
+        .rev()
+        .map(TryInto::try_into)
+        .filter_map(|x| x.ok())
+        .collect()
+}
+
+pub struct BasicAcc {
+    pub(crate) name: String,
+    pub(crate) children: Vec<NodeIdentifier>,
+    pub(crate) children_names: Vec<LabelIdentifier>,
+    pub(crate) metrics: SubTreeMetrics<SyntaxNodeHashs<u32>>,
+}
+
+impl BasicAcc {
+    pub(crate) fn new(name: String) -> Self {
+        Self {
+            name,
+            children_names: Default::default(),
+            children: Default::default(),
+            metrics: Default::default(),
+        }
+    }
+}
+
+impl From<String> for BasicAcc {
+    fn from(name: String) -> Self {
+        Self::new(name)
+    }
+}
+
+impl hyper_ast::tree_gen::Accumulator for BasicAcc {
+    type Node = (LabelIdentifier, (Local, IsSkippedAna));
+    fn push(&mut self, (name, (full_node, _skiped_ana)): Self::Node) {
+        self.children.push(full_node.compressed_node);
+        self.children_names.push(name);
+        self.metrics.acc(full_node.metrics);
+    }
+}
+
+impl Accumulator for BasicAcc {
+    type Unlabeled = (Local, IsSkippedAna);
+}
+
+pub struct BasicProcessor<'repo, 'prepro, 'd, 'c, F: BasicFiles> {
+    repository: &'repo Repository,
+    prepro: &'prepro mut RepositoryProcessor,
+    stack: Vec<(Oid, Vec<BasicGitObject>, BasicAcc)>,
+    pub dir_path: &'d mut Peekable<Components<'c>>,
+    parameters: &'d ParametrizedCommitProcessor2Handle<BasicProc<F>>,
+}
+
+impl<'repo, 'b, 'd, 'c, F: BasicFiles> BasicProcessor<'repo, 'b, 'd, 'c, F> {
+    pub(crate) fn new(
+        repository: &'repo Repository,
+        prepro: &'b mut RepositoryProcessor,
+        dir_path: &'d mut Peekable<Components<'c>>,
+        name: &ObjectName,
+        oid: git2::Oid,
+        parameters: &'d ParametrizedCommitProcessor2Handle<BasicProc<F>>,
+    ) -> Self {
+        let tree = repository.find_tree(oid).unwrap();
+        let prepared = prepare_dir_exploration(tree);
+        let name = name.try_into().unwrap();
+        let stack = vec![(oid, prepared, BasicAcc::new(name))];
+        Self {
+            stack,
+            repository,
+            prepro,
+            dir_path,
+            parameters,
+        }
+    }
+}
+
+impl<'repo, 'b, 'd, 'c, F: BasicFiles> Processor<BasicAcc>
+    for BasicProcessor<'repo, 'b, 'd, 'c, F>
+{
+    fn pre(&mut self, current_object: BasicGitObject) {
+        match current_object {
+            BasicGitObject::Tree(oid, name) => {
+                if F::Skipped::matches(&name) {
+                    log::debug!("skip {:?}", name.try_str());
+                    return;
+                }
+                self.handle_tree_cached(oid, name);
+            }
+            BasicGitObject::Blob(oid, name) => {
+                if F::matches(&name) {
+                    let (name, full_node) = self
+                        .prepro
+                        .help_handle_basic_file(oid, &name, self.repository, *self.parameters)
+                        .unwrap();
+                    let w = &mut self.stack.last_mut().unwrap().2;
+                    assert!(!w.children_names.contains(&name));
+                    hyper_ast::tree_gen::Accumulator::push(w, (name, full_node));
+                } else {
+                    log::debug!("not a source file {:?}", name.try_str());
+                }
+            }
+        }
+    }
+    fn post(&mut self, oid: Oid, acc: BasicAcc) -> Option<(Local, IsSkippedAna)> {
+        let skiped_ana = true;
+        let name = acc.name.clone();
+        let key = (oid, name.as_bytes().into());
+        let full_node = make::<F>(acc, self.prepro.main_stores_mut());
+        self.prepro
+            .processing_systems
+            .mut_or_default::<BasicProcessorHolder<F>>()
+            .get_caches_mut()
+            .object_map
+            .insert(key, (full_node.clone(), skiped_ana));
+        let name = self.prepro.main_stores.label_store.get_or_insert(name);
+        if self.stack.is_empty() {
+            Some((full_node, skiped_ana))
+        } else {
+            let w = &mut self.stack.last_mut().unwrap().2;
+            assert!(
+                !w.children_names.contains(&name),
+                "{:?} {:?}",
+                w.children_names,
+                name
+            );
+            hyper_ast::tree_gen::Accumulator::push(w, (name, (full_node, skiped_ana)));
+            None
+        }
+    }
+
+    fn stack(&mut self) -> &mut Vec<(Oid, Vec<BasicGitObject>, BasicAcc)> {
+        &mut self.stack
+    }
+}
+
+impl<'repo, 'prepro, 'd, 'c, F: BasicFiles> BasicProcessor<'repo, 'prepro, 'd, 'c, F> {
+    fn handle_tree_cached(&mut self, oid: Oid, name: ObjectName) {
+        if let Some(already) = self
+            .prepro
+            .processing_systems
+            .mut_or_default::<BasicProcessorHolder<F>>()
+            .get_caches_mut()
+            .object_map
+            .get(&(oid, name.clone()))
+        {
+            // reinit already computed node for post order
+            let full_node = already.clone();
+            let w = &mut self.stack.last_mut().unwrap().2;
+            let name = self.prepro.intern_object_name(&name);
+            assert!(!w.children_names.contains(&name));
+            hyper_ast::tree_gen::Accumulator::push(w, (name, full_node));
+        } else {
+            log::info!("tree {:?}", name.try_str());
+            let tree = self.repository.find_tree(oid).unwrap();
+            let prepared: Vec<BasicGitObject> = prepare_dir_exploration(tree);
+            self.stack
+                .push((oid, prepared, BasicAcc::new(name.try_into().unwrap())));
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct Parameter;
+pub(crate) struct BasicProcessorHolder<F>(Option<BasicProc<F>>);
+impl<F> Default for BasicProcessorHolder<F> {
+    fn default() -> Self {
+        Self(None)
+    }
+}
+pub(crate) struct BasicProc<F> {
+    parameter: Parameter,
+    cache: crate::processing::caches::Basic,
+    commits: std::collections::HashMap<git2::Oid, crate::Commit>,
+    phantom: PhantomData<F>,
+}
+impl<F: BasicFiles> crate::processing::erased::Parametrized for BasicProcessorHolder<F> {
+    type T = Parameter;
+    fn register_param(
+        &mut self,
+        t: Self::T,
+    ) -> crate::processing::erased::ParametrizedCommitProcessorHandle {
+        let l = self
+            .0
+            .iter()
+            .position(|x| &x.parameter == &t)
+            .unwrap_or_else(|| {
+                let l = 0;
+                self.0 = Some(BasicProc {
+                    parameter: t,
+                    cache: Default::default(),
+                    commits: Default::default(),
+                    phantom: PhantomData,
+                });
+                l
+            });
+        use crate::processing::erased::ConfigParametersHandle;
+        use crate::processing::erased::ParametrizedCommitProc;
+        use crate::processing::erased::ParametrizedCommitProcessorHandle;
+        ParametrizedCommitProcessorHandle(self.erased_handle(), ConfigParametersHandle(l))
+    }
+}
+impl<F: BasicFiles> crate::processing::erased::CommitProc for BasicProc<F> {
+    fn process_root_tree(
+        &mut self,
+        repository: &git2::Repository,
+        tree_oid: &git2::Oid,
+    ) -> hyper_ast::store::defaults::NodeIdentifier {
+        unimplemented!("source files are processed by the processor of their build system")
+    }
+
+    fn prepare_processing(
+        &self,
+        repository: &git2::Repository,
+        tree_oid: crate::preprocessed::CommitBuilder,
+    ) -> Box<dyn crate::processing::erased::PreparedCommitProc> {
+        unimplemented!("source files are processed by the processor of their build system")
+    }
+
+    fn get_commit(&self, commit_oid: git2::Oid) -> Option<&crate::Commit> {
+        self.commits.get(&commit_oid)
+    }
+}
+
+impl<F: BasicFiles> crate::processing::erased::CommitProcExt for BasicProc<F> {
+    type Holder = BasicProcessorHolder<F>;
+}
+impl<F: BasicFiles> crate::processing::erased::ParametrizedCommitProc2 for BasicProcessorHolder<F> {
+    type Proc = BasicProc<F>;
+
+    fn with_parameters_mut(
+        &mut self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &mut Self::Proc {
+        assert_eq!(0, parameters.0);
+        self.0.as_mut().unwrap()
+    }
+
+    fn with_parameters(
+        &self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &Self::Proc {
+        assert_eq!(0, parameters.0);
+        self.0.as_ref().unwrap()
+    }
+}
+impl<F> CacheHolding<crate::processing::caches::Basic> for BasicProc<F> {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::Basic {
+        &mut self.cache
+    }
+    fn get_caches(&self) -> &crate::processing::caches::Basic {
+        &self.cache
+    }
+}
+impl<F> CacheHolding<crate::processing::caches::Basic> for BasicProcessorHolder<F> {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::Basic {
+        &mut self.0.as_mut().unwrap().cache
+    }
+    fn get_caches(&self) -> &crate::processing::caches::Basic {
+        &self.0.as_ref().unwrap().cache
+    }
+}
+
+impl RepositoryProcessor {
+    fn handle_basic_blob<F: BasicFiles>(
+        &mut self,
+        oid: Oid,
+        name: &ObjectName,
+        repository: &Repository,
+        parameters: ParametrizedCommitProcessor2Handle<BasicProc<F>>,
+    ) -> Result<(Local, IsSkippedAna), crate::ParseErr> {
+        self.processing_systems.caching_blob_handler::<F>().handle2(
+            oid,
+            repository,
+            &name,
+            parameters,
+            |c, n, t| {
+                let mut tree_gen = BasicTreeGen::new(
+                    &mut self.main_stores,
+                    &mut c
+                        .mut_or_default::<BasicProcessorHolder<F>>()
+                        .get_caches_mut()
+                        .md_cache,
+                );
+                if t.contains(&b'\r') {
+                    tree_gen.line_break = "\r\n".as_bytes().to_vec();
+                }
+                F::handle_file(&mut tree_gen, n, t)
+                    .map_err(|_| crate::ParseErr::IllFormed)
+                    .map(|x| (x.local.clone(), false))
+            },
+        )
+    }
+
+    /// Handles a source file, returning it with its interned name.
+    pub(crate) fn help_handle_basic_file<F: BasicFiles>(
+        &mut self,
+        oid: Oid,
+        name: &ObjectName,
+        repository: &Repository,
+        parameters: ParametrizedCommitProcessor2Handle<BasicProc<F>>,
+    ) -> Result<<BasicAcc as hyper_ast::tree_gen::Accumulator>::Node, crate::ParseErr> {
+        let full_node = self.handle_basic_blob(oid, name, repository, parameters)?;
+        let name = self.intern_object_name(name);
+        Ok((name, full_node))
+    }
+
+    pub(crate) fn handle_basic_directory<'b, 'd: 'b, F: BasicFiles>(
+        &mut self,
+        repository: &Repository,
+        dir_path: &'b mut Peekable<Components<'d>>,
+        name: &ObjectName,
+        oid: git2::Oid,
+    ) -> (Local, IsSkippedAna) {
+        let h = self
+            .processing_systems
+            .mut_or_default::<BasicProcessorHolder<F>>();
+
+        let handle = BasicProc::<F>::register_param(h, Parameter);
+        BasicProcessor::<F>::new(repository, self, dir_path, name, oid, &handle).process()
+    }
+
+    /// Handles a source directory, returning it with its interned name.
+    pub(crate) fn help_handle_basic_folder<'a, 'b, 'c, 'd: 'c, F: BasicFiles>(
+        &'a mut self,
+        repository: &'b Repository,
+        dir_path: &'c mut Peekable<Components<'d>>,
+        oid: Oid,
+        name: &ObjectName,
+    ) -> <BasicAcc as hyper_ast::tree_gen::Accumulator>::Node {
+        let full_node = self.handle_basic_directory::<F>(repository, dir_path, name, oid);
+        let name = self.intern_object_name(name);
+        (name, full_node)
+    }
+}
+
+fn make<F: BasicFiles>(acc: BasicAcc, stores: &mut SimpleStores) -> Local {
+    let node_store = &mut stores.node_store;
+    let label_store = &mut stores.label_store;
+
+    let hashs = acc.metrics.hashs;
+    let size = acc.metrics.size + 1;
+    let height = acc.metrics.height + 1;
+    let size_no_spaces = acc.metrics.size_no_spaces + 1;
+    let hbuilder = hashed::Builder::new(hashs, &F::DIRECTORY, &acc.name, size_no_spaces);
+    let hashable = &hbuilder.most_discriminating();
+    let label_id = label_store.get_or_insert(acc.name.clone());
+
+    let eq = eq_node(&F::DIRECTORY, Some(&label_id), &acc.children);
+
+    let insertion = node_store.prepare_insertion(&hashable, eq);
+
+    let hashs = hbuilder.build();
+    let metrics = SubTreeMetrics {
+        size,
+        height,
+        size_no_spaces,
+        hashs,
+    };
+
+    let compressed_node = if let Some(id) = insertion.occupied_id() {
+        id
+    } else {
+        compress(
+            insertion,
+            F::DIRECTORY,
+            label_id,
+            acc.children,
+            acc.children_names,
+            size,
+            height,
+            size_no_spaces,
+            hashs,
+        )
+    };
+    Local {
+        compressed_node,
+        metrics,
+        ana: None,
+    }
+}
+
+fn compress<T: 'static + Send + Sync>(
+    insertion: PendingInsert,
+    kind: T,
+    label_id: LabelIdentifier,
+    children: Vec<NodeIdentifier>,
+    children_names: Vec<LabelIdentifier>,
+    size: u32,
+    height: u32,
+    size_no_spaces: u32,
+    hashs: SyntaxNodeHashs<u32>,
+) -> NodeIdentifier {
+    use hyper_ast::store::nodes::legion::{compo, compo::CS, NodeStore};
+    let vacant = insertion.vacant();
+    macro_rules! insert {
+        ( $c0:expr, $($c:expr),* $(,)? ) => {{
+            let c = $c0;
+            $(
+                let c = c.concat($c);
+            )*
+            NodeStore::insert_after_prepare(vacant, c)
+        }};
+    }
+    match children.len() {
+        0 => insert!((kind, label_id, hashs, BloomSize::None),),
+        _ => {
+            assert_eq!(children_names.len(), children.len());
+            let c = (
+                kind,
+                label_id,
+                compo::Size(size),
+                compo::Height(height),
+                compo::SizeNoSpaces(size_no_spaces),
+                hashs,
+                CS(children_names.into_boxed_slice()),
+                CS(children.into_boxed_slice()),
+            );
+            insert!(c, (BloomSize::Much,))
+        }
+    }
+}
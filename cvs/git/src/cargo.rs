@@ -0,0 +1,200 @@
+use std::path::PathBuf;
+
+use hyper_ast::{
+    store::defaults::{LabelIdentifier, NodeIdentifier},
+    tree_gen::SubTreeMetrics,
+};
+use hyper_ast_gen_ts_rust::legion as rust_tree_gen;
+use hyper_ast_gen_ts_xml::legion::XmlTreeGen;
+
+use crate::{
+    processing::ObjectName, Accumulator, DefaultMetrics, TStore, PROPAGATE_ERROR_ON_BAD_CST_NODE,
+};
+
+pub(crate) fn handle_cargo_toml_file<'a>(
+    tree_gen: &mut XmlTreeGen<'a, TStore>,
+    name: &ObjectName,
+    text: &'a [u8],
+) -> Result<CargoToml, ()> {
+    let content: CargoTomlContent = match toml::from_slice(text) {
+        Ok(content) => content,
+        Err(err) => {
+            log::warn!("bad Cargo.toml: {}", err);
+            if PROPAGATE_ERROR_ON_BAD_CST_NODE {
+                return Err(());
+            } else {
+                Default::default()
+            }
+        }
+    };
+    // TODO use a toml generator, for now Cargo.toml files are placeholders like Makefiles
+    let tree = match XmlTreeGen::<TStore>::tree_sitter_parse(b"<cargo></cargo>") {
+        Ok(tree) => tree,
+        Err(tree) => {
+            log::warn!("bad CST");
+            log::debug!("{:?}", name.try_str());
+            log::debug!("{}", tree.root_node().to_sexp());
+            if PROPAGATE_ERROR_ON_BAD_CST_NODE {
+                return Err(());
+            } else {
+                tree
+            }
+        }
+    };
+    let x = tree_gen
+        .generate_file(name.as_bytes(), b"<cargo></cargo>", tree.walk())
+        .local;
+    let workspace = content.workspace.unwrap_or_default();
+    let x = CargoToml {
+        compressed_node: x.compressed_node,
+        metrics: x.metrics,
+        members: workspace.members,
+        exclude: workspace.exclude,
+    };
+    Ok(x)
+}
+
+#[derive(serde::Deserialize, Default)]
+struct CargoTomlContent {
+    #[serde(default)]
+    workspace: Option<Workspace>,
+}
+
+/// only the members are needed to find the crates of a workspace,
+/// other fields eg. `[workspace.dependencies]` are ignored
+#[derive(serde::Deserialize, Default)]
+struct Workspace {
+    #[serde(default)]
+    members: Vec<String>,
+    #[serde(default)]
+    exclude: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CargoToml {
+    pub compressed_node: NodeIdentifier,
+    pub metrics: DefaultMetrics,
+    members: Vec<String>,
+    exclude: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MD {
+    pub(crate) metrics: DefaultMetrics,
+}
+
+/// A directory of a rust project,
+/// either a crate or a workspace ie. it contains a Cargo.toml,
+/// or a plain directory containing crates.
+pub struct CargoModuleAcc {
+    pub(crate) name: String,
+    pub(crate) children_names: Vec<LabelIdentifier>,
+    pub(crate) children: Vec<NodeIdentifier>,
+    pub(crate) metrics: DefaultMetrics,
+    /// globs of the workspace members still to be matched, relative to this directory
+    pub(crate) sub_modules: Option<Vec<PathBuf>>,
+    /// paths excluded from the workspace, relative to this directory
+    pub(crate) excluded: Vec<PathBuf>,
+}
+
+impl From<String> for CargoModuleAcc {
+    fn from(name: String) -> Self {
+        Self::new(name)
+    }
+}
+
+impl CargoModuleAcc {
+    pub(crate) fn new(name: String) -> Self {
+        Self {
+            name,
+            children_names: Default::default(),
+            children: Default::default(),
+            metrics: Default::default(),
+            sub_modules: None,
+            excluded: Default::default(),
+        }
+    }
+    pub(crate) fn with_content(
+        name: String,
+        sub_modules: Vec<PathBuf>,
+        excluded: Vec<PathBuf>,
+    ) -> Self {
+        Self {
+            name,
+            children_names: Default::default(),
+            children: Default::default(),
+            metrics: Default::default(),
+            sub_modules: if sub_modules.is_empty() {
+                None
+            } else {
+                Some(sub_modules)
+            },
+            excluded,
+        }
+    }
+}
+
+impl CargoModuleAcc {
+    pub(crate) fn push_cargo_toml(&mut self, name: LabelIdentifier, full_node: CargoToml) {
+        self.children.push(full_node.compressed_node);
+        self.children_names.push(name);
+        let members = full_node
+            .members
+            .iter()
+            .map(|x| x.trim_start_matches("./").into());
+        self.sub_modules
+            .get_or_insert_with(Default::default)
+            .extend(members);
+        let exclude = full_node
+            .exclude
+            .iter()
+            .map(|x| PathBuf::from(x.trim_start_matches("./")));
+        self.excluded.extend(exclude);
+        self.metrics.acc(full_node.metrics);
+    }
+    pub fn push_submodule(&mut self, name: LabelIdentifier, full_node: (NodeIdentifier, MD)) {
+        self.children.push(full_node.0);
+        self.children_names.push(name);
+        self.metrics.acc(full_node.1.metrics);
+    }
+    pub(crate) fn push_source_file(
+        &mut self,
+        name: LabelIdentifier,
+        full_node: rust_tree_gen::Local,
+        skiped_ana: bool,
+    ) {
+        self.children.push(full_node.compressed_node);
+        self.children_names.push(name);
+        self.metrics.acc(SubTreeMetrics {
+            hashs: full_node.metrics.hashs,
+            size: full_node.metrics.size,
+            height: full_node.metrics.height,
+            size_no_spaces: full_node.metrics.size_no_spaces,
+        });
+    }
+    pub(crate) fn push_source_directory(
+        &mut self,
+        name: LabelIdentifier,
+        full_node: rust_tree_gen::Local,
+    ) {
+        self.children.push(full_node.compressed_node);
+        self.children_names.push(name);
+        self.metrics.acc(SubTreeMetrics {
+            hashs: full_node.metrics.hashs,
+            size: full_node.metrics.size,
+            height: full_node.metrics.height,
+            size_no_spaces: full_node.metrics.size_no_spaces,
+        });
+    }
+}
+
+impl hyper_ast::tree_gen::Accumulator for CargoModuleAcc {
+    type Node = (LabelIdentifier, (NodeIdentifier, MD));
+    fn push(&mut self, (name, full_node): Self::Node) {
+        self.push_submodule(name, full_node)
+    }
+}
+
+impl Accumulator for CargoModuleAcc {
+    type Unlabeled = (NodeIdentifier, MD);
+}
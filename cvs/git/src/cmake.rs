@@ -0,0 +1,527 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use hyper_ast::{
+    store::defaults::{LabelIdentifier, NodeIdentifier},
+    tree_gen::SubTreeMetrics,
+};
+use hyper_ast_gen_ts_cpp::legion as cpp_tree_gen;
+use hyper_ast_gen_ts_xml::legion::XmlTreeGen;
+
+use crate::{
+    processing::ObjectName, Accumulator, DefaultMetrics, TStore, PROPAGATE_ERROR_ON_BAD_CST_NODE,
+};
+
+pub(crate) fn handle_cmakelists_file<'a>(
+    tree_gen: &mut XmlTreeGen<'a, TStore>,
+    name: &ObjectName,
+    text: &'a [u8],
+) -> Result<CMakeLists, ()> {
+    let tree = match XmlTreeGen::<TStore>::tree_sitter_parse(b"<cmake></cmake>") {
+        Ok(tree) => tree,
+        Err(tree) => {
+            log::warn!("bad CST");
+            log::debug!("{:?}", name.try_str());
+            log::debug!("{}", tree.root_node().to_sexp());
+            if PROPAGATE_ERROR_ON_BAD_CST_NODE {
+                return Err(());
+            } else {
+                tree
+            }
+        }
+    };
+    let x = tree_gen
+        .generate_file(name.as_bytes(), b"<cmake></cmake>", tree.walk())
+        .local;
+    let text = std::str::from_utf8(text).map_err(|_| ())?;
+    let (submodules, targets) = interpret(parse_commands(text));
+    let x = CMakeLists {
+        compressed_node: x.compressed_node,
+        metrics: x.metrics,
+        submodules,
+        targets,
+    };
+    Ok(x)
+}
+
+#[derive(Debug, Clone)]
+pub struct CMakeLists {
+    pub compressed_node: NodeIdentifier,
+    pub metrics: DefaultMetrics,
+    submodules: Vec<String>,
+    targets: Vec<Target>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetKind {
+    Executable,
+    Library,
+}
+
+/// A target declared with `add_executable` or `add_library`,
+/// sources are relative to the directory of the CMakeLists.txt declaring the target.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Target {
+    pub name: String,
+    pub kind: TargetKind,
+    pub sources: Vec<PathBuf>,
+}
+
+/// Component of the directory nodes of CMake modules,
+/// ie. the targets declared in their CMakeLists.txt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Targets(pub Box<[Target]>);
+
+impl hyper_ast::store::nodes::legion::persist::Persist for Targets {
+    fn save(
+        &self,
+        w: &mut dyn std::io::Write,
+        _: &hyper_ast::store::nodes::legion::persist::SavedIds,
+    ) -> std::io::Result<()> {
+        use hyper_ast::store::persist::{write_str, write_u32, write_u8};
+        write_u32(w, self.0.len() as u32)?;
+        for t in self.0.iter() {
+            write_str(w, &t.name)?;
+            write_u8(w, t.kind as u8)?;
+            write_u32(w, t.sources.len() as u32)?;
+            for s in &t.sources {
+                write_str(w, &s.to_string_lossy())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn load(
+        r: &mut dyn std::io::Read,
+        _: &hyper_ast::store::nodes::legion::persist::LoadedIds,
+    ) -> std::io::Result<Self> {
+        use hyper_ast::store::persist::{invalid_data, read_string, read_u32, read_u8};
+        let len = read_u32(r)?;
+        let mut targets = Vec::with_capacity(len as usize);
+        for _ in 0..len {
+            let name = read_string(r)?;
+            let kind = match read_u8(r)? {
+                0 => TargetKind::Executable,
+                1 => TargetKind::Library,
+                k => return Err(invalid_data(format!("invalid target kind {}", k))),
+            };
+            let len = read_u32(r)?;
+            let sources = (0..len)
+                .map(|_| read_string(r).map(PathBuf::from))
+                .collect::<std::io::Result<_>>()?;
+            targets.push(Target {
+                name,
+                kind,
+                sources,
+            });
+        }
+        Ok(Self(targets.into_boxed_slice()))
+    }
+}
+
+/// Splits a CMakeLists.txt in command invocations ie. a name and its arguments.
+/// Arguments are unquoted but variables are not expanded.
+fn parse_commands(text: &str) -> Vec<(String, Vec<String>)> {
+    let mut res = vec![];
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '#' {
+            skip_comment(&mut chars);
+            continue;
+        }
+        if !(c.is_ascii_alphabetic() || c == '_') {
+            continue;
+        }
+        let mut name = c.to_string();
+        while let Some(&c) = chars.peek() {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                name.push(c);
+                chars.next();
+            } else {
+                break;
+            }
+        }
+        while chars.peek().map_or(false, |c| *c == ' ' || *c == '\t') {
+            chars.next();
+        }
+        if chars.peek() != Some(&'(') {
+            continue;
+        }
+        chars.next();
+        res.push((name.to_lowercase(), parse_arguments(&mut chars)));
+    }
+    res
+}
+
+/// Consumes arguments up to the closing parenthesis of the command,
+/// parentheses nested in arguments eg. in conditions are dropped.
+fn parse_arguments(chars: &mut std::iter::Peekable<std::str::Chars>) -> Vec<String> {
+    let mut args = vec![];
+    let mut current = String::new();
+    let mut depth = 0;
+    let mut quoted = false;
+    while let Some(c) = chars.next() {
+        if quoted {
+            match c {
+                '"' => {
+                    quoted = false;
+                    args.push(std::mem::take(&mut current));
+                }
+                '\\' => {
+                    if let Some(c) = chars.next() {
+                        current.push(c);
+                    }
+                }
+                c => current.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' => quoted = true,
+            '#' => skip_comment(chars),
+            '(' => depth += 1,
+            ')' if depth == 0 => break,
+            ')' => depth -= 1,
+            c if c.is_whitespace() => {
+                if !current.is_empty() {
+                    args.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        args.push(current);
+    }
+    args
+}
+
+/// Skips a line comment or a bracket comment eg. `#[[ ... ]]`, the `#` being already consumed.
+fn skip_comment(chars: &mut std::iter::Peekable<std::str::Chars>) {
+    if chars.peek() == Some(&'[') {
+        let mut prev = ' ';
+        while let Some(c) = chars.next() {
+            if prev == ']' && c == ']' {
+                return;
+            }
+            prev = c;
+        }
+    } else {
+        while let Some(c) = chars.next() {
+            if c == '\n' {
+                return;
+            }
+        }
+    }
+}
+
+const TARGET_KEYWORDS: &[&str] = &[
+    "STATIC",
+    "SHARED",
+    "MODULE",
+    "OBJECT",
+    "INTERFACE",
+    "EXCLUDE_FROM_ALL",
+    "WIN32",
+    "MACOSX_BUNDLE",
+    "PRIVATE",
+    "PUBLIC",
+];
+
+/// Extracts the sub directories and the targets declared by the commands.
+/// Only `set` and `list(APPEND ...)` are considered to expand variables,
+/// thus sources computed with other commands eg. `file(GLOB ...)` are missed.
+fn interpret(commands: Vec<(String, Vec<String>)>) -> (Vec<String>, Vec<Target>) {
+    let mut vars: HashMap<String, Vec<String>> = HashMap::default();
+    vars.insert("CMAKE_CURRENT_SOURCE_DIR".to_owned(), vec![".".to_owned()]);
+    vars.insert("CMAKE_CURRENT_LIST_DIR".to_owned(), vec![".".to_owned()]);
+    let mut submodules = vec![];
+    let mut targets: Vec<Target> = vec![];
+    for (name, args) in commands {
+        match name.as_str() {
+            "set" => {
+                if let Some((var, values)) = args.split_first() {
+                    let values = values
+                        .iter()
+                        .take_while(|x| *x != "CACHE" && *x != "PARENT_SCOPE");
+                    let values = expand(&vars, values);
+                    vars.insert(var.to_owned(), values);
+                }
+            }
+            "list" if args.len() > 1 && args[0] == "APPEND" => {
+                let values = expand(&vars, args[2..].iter());
+                vars.entry(args[1].to_owned()).or_default().extend(values);
+            }
+            "add_subdirectory" => {
+                if let Some(dir) = expand(&vars, args.first().into_iter()).pop() {
+                    if let Some(dir) = normalize(&dir) {
+                        submodules.push(dir.to_string_lossy().into_owned());
+                    }
+                }
+            }
+            "add_executable" | "add_library" => {
+                let Some((target, rest)) = args.split_first() else {
+                    continue;
+                };
+                if rest.iter().any(|x| x == "IMPORTED" || x == "ALIAS") {
+                    continue;
+                }
+                let kind = if name == "add_executable" {
+                    TargetKind::Executable
+                } else {
+                    TargetKind::Library
+                };
+                let rest = rest
+                    .iter()
+                    .filter(|x| !TARGET_KEYWORDS.contains(&x.as_str()));
+                let sources = expand(&vars, rest)
+                    .iter()
+                    .filter_map(|x| normalize(x))
+                    .collect();
+                targets.push(Target {
+                    name: target.to_owned(),
+                    kind,
+                    sources,
+                });
+            }
+            "target_sources" => {
+                let Some((target, rest)) = args.split_first() else {
+                    continue;
+                };
+                let Some(target) = targets.iter_mut().find(|x| &x.name == target) else {
+                    continue;
+                };
+                let rest = rest
+                    .iter()
+                    .filter(|x| !TARGET_KEYWORDS.contains(&x.as_str()));
+                let sources = expand(&vars, rest);
+                target
+                    .sources
+                    .extend(sources.iter().filter_map(|x| normalize(x)));
+            }
+            _ => (),
+        }
+    }
+    (submodules, targets)
+}
+
+/// Expands `${VAR}` references, list variables being splitted in multiple values.
+/// Values with unknown variables or generator expressions are dropped.
+fn expand<'a>(
+    vars: &HashMap<String, Vec<String>>,
+    args: impl Iterator<Item = &'a String>,
+) -> Vec<String> {
+    let mut res = vec![];
+    'args: for arg in args {
+        let mut arg = arg.to_owned();
+        while let Some(start) = arg.find("${") {
+            let Some(end) = arg[start..].find('}') else {
+                continue 'args;
+            };
+            let Some(value) = vars.get(&arg[start + 2..start + end]) else {
+                continue 'args;
+            };
+            arg.replace_range(start..start + end + 1, &value.join(";"));
+        }
+        if arg.contains("$<") {
+            continue;
+        }
+        res.extend(
+            arg.split(';')
+                .filter(|x| !x.is_empty())
+                .map(|x| x.to_owned()),
+        );
+    }
+    res
+}
+
+/// Makes a path relative to the current directory,
+/// paths going outside of it are not handled.
+fn normalize(path: &str) -> Option<PathBuf> {
+    use std::path::Component;
+    let mut res = PathBuf::new();
+    for c in std::path::Path::new(path).components() {
+        match c {
+            Component::CurDir => (),
+            Component::Normal(x) => res.push(x),
+            _ => return None,
+        }
+    }
+    Some(res)
+}
+
+#[derive(Debug, Clone)]
+pub struct MD {
+    pub(crate) metrics: DefaultMetrics,
+}
+
+/// A directory of a CMake project,
+/// it is a module if it contains a CMakeLists.txt
+pub struct CMakeModuleAcc {
+    pub(crate) name: String,
+    pub(crate) children_names: Vec<LabelIdentifier>,
+    pub(crate) children: Vec<NodeIdentifier>,
+    pub(crate) metrics: DefaultMetrics,
+    pub(crate) targets: Vec<Target>,
+    pub(crate) sub_modules: Option<Vec<PathBuf>>,
+    pub(crate) main_dirs: Option<Vec<PathBuf>>,
+}
+
+impl From<String> for CMakeModuleAcc {
+    fn from(name: String) -> Self {
+        Self::new(name)
+    }
+}
+
+impl CMakeModuleAcc {
+    pub(crate) fn new(name: String) -> Self {
+        Self {
+            name,
+            children_names: Default::default(),
+            children: Default::default(),
+            metrics: Default::default(),
+            targets: Default::default(),
+            sub_modules: None,
+            main_dirs: None,
+        }
+    }
+    pub(crate) fn with_content(
+        name: String,
+        sub_modules: Vec<PathBuf>,
+        main_dirs: Vec<PathBuf>,
+    ) -> Self {
+        Self {
+            name,
+            children_names: Default::default(),
+            children: Default::default(),
+            metrics: Default::default(),
+            targets: Default::default(),
+            sub_modules: if sub_modules.is_empty() {
+                None
+            } else {
+                Some(sub_modules)
+            },
+            main_dirs: if main_dirs.is_empty() {
+                None
+            } else {
+                Some(main_dirs)
+            },
+        }
+    }
+}
+
+impl CMakeModuleAcc {
+    pub(crate) fn push_cmakelists(&mut self, name: LabelIdentifier, full_node: CMakeLists) {
+        self.children.push(full_node.compressed_node);
+        self.children_names.push(name);
+        // directories containing the sources of targets
+        let mut main_dirs: Vec<PathBuf> = vec![];
+        for s in full_node.targets.iter().flat_map(|x| &x.sources) {
+            match s.parent() {
+                Some(p) if p.components().next().is_some() && !main_dirs.iter().any(|x| x == p) => {
+                    main_dirs.push(p.to_owned())
+                }
+                _ => (),
+            }
+        }
+        self.main_dirs = Some(main_dirs);
+        self.sub_modules = Some(full_node.submodules.iter().map(|x| x.into()).collect());
+        self.targets = full_node.targets;
+        self.metrics.acc(full_node.metrics);
+    }
+    pub fn push_submodule(&mut self, name: LabelIdentifier, full_node: (NodeIdentifier, MD)) {
+        self.children.push(full_node.0);
+        self.children_names.push(name);
+        self.metrics.acc(full_node.1.metrics);
+    }
+    pub(crate) fn push_source_file(
+        &mut self,
+        name: LabelIdentifier,
+        full_node: cpp_tree_gen::Local,
+    ) {
+        self.children.push(full_node.compressed_node);
+        self.children_names.push(name);
+        self.metrics.acc(SubTreeMetrics {
+            hashs: full_node.metrics.hashs,
+            size: full_node.metrics.size,
+            height: full_node.metrics.height,
+            size_no_spaces: full_node.metrics.size_no_spaces,
+        });
+    }
+    pub(crate) fn push_source_directory(
+        &mut self,
+        name: LabelIdentifier,
+        full_node: cpp_tree_gen::Local,
+    ) {
+        self.children.push(full_node.compressed_node);
+        self.children_names.push(name);
+        self.metrics.acc(SubTreeMetrics {
+            hashs: full_node.metrics.hashs,
+            size: full_node.metrics.size,
+            height: full_node.metrics.height,
+            size_no_spaces: full_node.metrics.size_no_spaces,
+        });
+    }
+}
+
+impl hyper_ast::tree_gen::Accumulator for CMakeModuleAcc {
+    type Node = (LabelIdentifier, (NodeIdentifier, MD));
+    fn push(&mut self, (name, full_node): Self::Node) {
+        self.push_submodule(name, full_node)
+    }
+}
+
+impl Accumulator for CMakeModuleAcc {
+    type Unlabeled = (NodeIdentifier, MD);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn targets_and_subdirectories() {
+        let text = r#"
+cmake_minimum_required(VERSION 3.10)
+project(demo CXX)
+
+# the core library
+set(CORE_SOURCES
+    src/core/a.cpp
+    "src/core/b.cpp" # second one
+)
+list(APPEND CORE_SOURCES ${CMAKE_CURRENT_SOURCE_DIR}/src/core/c.cpp)
+add_library(core STATIC ${CORE_SOURCES})
+target_sources(core PRIVATE src/util/u.cpp $<$<CONFIG:Debug>:src/debug.cpp>)
+
+add_executable(demo main.cpp)
+add_library(ext::lib ALIAS core)
+if(BUILD_TESTING)
+    add_subdirectory(tests)
+endif()
+add_subdirectory(third_party/fmt EXCLUDE_FROM_ALL)
+#[[ add_subdirectory(old) ]]
+"#;
+        let (submodules, targets) = interpret(parse_commands(text));
+        assert_eq!(submodules, vec!["tests", "third_party/fmt"]);
+        assert_eq!(
+            targets,
+            vec![
+                Target {
+                    name: "core".to_owned(),
+                    kind: TargetKind::Library,
+                    sources: vec![
+                        "src/core/a.cpp".into(),
+                        "src/core/b.cpp".into(),
+                        "src/core/c.cpp".into(),
+                        "src/util/u.cpp".into(),
+                    ],
+                },
+                Target {
+                    name: "demo".to_owned(),
+                    kind: TargetKind::Executable,
+                    sources: vec!["main.cpp".into()],
+                },
+            ]
+        );
+    }
+}
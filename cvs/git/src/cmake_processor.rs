@@ -0,0 +1,601 @@
+use std::{
+    iter::Peekable,
+    path::{Components, PathBuf},
+};
+
+use git2::{Oid, Repository};
+use hyper_ast::{
+    filter::BloomSize,
+    hashed::{self, IndexingHashBuilder, MetaDataHashsBuilder},
+    store::{
+        defaults::NodeIdentifier,
+        nodes::legion::{compo, compo::CS, NodeStore},
+    },
+    tree_gen::SubTreeMetrics,
+    types::LabelStore,
+};
+use hyper_ast_gen_ts_cpp::types::Type;
+use hyper_ast_gen_ts_java::legion_with_refs::{eq_node, hash32};
+use hyper_ast_gen_ts_xml::legion::XmlTreeGen;
+
+use crate::{
+    cmake::{CMakeModuleAcc, Targets, MD},
+    cpp_processor::CppProc,
+    git::BasicGitObject,
+    preprocessed::RepositoryProcessor,
+    processing::{erased::ParametrizedCommitProc2, CacheHolding, InFiles, ObjectName},
+    Processor, SimpleStores,
+};
+
+/// RMS: Resursive Module Search, ie. also look for CMakeLists.txt in directories that are not added as sub directories
+/// FFWD: Fast ForWarD to cpp directories without looking at cmake stuff
+pub struct CMakeProcessor<'a, 'b, 'c, const RMS: bool, const FFWD: bool, Acc> {
+    prepro: &'b mut RepositoryProcessor,
+    repository: &'a Repository,
+    stack: Vec<(Oid, Vec<BasicGitObject>, Acc)>,
+    dir_path: &'c mut Peekable<Components<'c>>,
+    handle: crate::processing::erased::ParametrizedCommitProcessor2Handle<CMakeProc>,
+    cpp_handle: crate::processing::erased::ParametrizedCommitProcessor2Handle<CppProc>,
+}
+
+impl<'a, 'b, 'c, const RMS: bool, const FFWD: bool, Acc: From<String>>
+    CMakeProcessor<'a, 'b, 'c, RMS, FFWD, Acc>
+{
+    pub fn new(
+        repository: &'a Repository,
+        prepro: &'b mut RepositoryProcessor,
+        mut dir_path: &'c mut Peekable<Components<'c>>,
+        name: &[u8],
+        oid: git2::Oid,
+    ) -> Self {
+        let h = prepro
+            .processing_systems
+            .mut_or_default::<CMakeProcessorHolder>();
+        let handle =
+            <CMakeProc as crate::processing::erased::CommitProcExt>::register_param(h, Parameter);
+        let h = prepro
+            .processing_systems
+            .mut_or_default::<crate::cpp_processor::CppProcessorHolder>();
+        let cpp_handle = <CppProc as crate::processing::erased::CommitProcExt>::register_param(
+            h,
+            crate::cpp_processor::Parameter,
+        );
+        let tree = repository.find_tree(oid).unwrap();
+        let prepared = prepare_dir_exploration(tree, &mut dir_path);
+        let name = std::str::from_utf8(&name).unwrap().to_string();
+        let stack = vec![(oid, prepared, Acc::from(name))];
+        Self {
+            stack,
+            repository,
+            prepro,
+            dir_path,
+            handle,
+            cpp_handle,
+        }
+    }
+}
+
+impl<'a, 'b, 'c, const RMS: bool, const FFWD: bool> Processor<CMakeModuleAcc>
+    for CMakeProcessor<'a, 'b, 'c, RMS, FFWD, CMakeModuleAcc>
+{
+    fn pre(&mut self, current_dir: BasicGitObject) {
+        match current_dir {
+            BasicGitObject::Tree(oid, name) => {
+                if let Some(s) = self.dir_path.peek() {
+                    if name
+                        .as_bytes()
+                        .eq(std::ffi::OsStr::as_os_str_bytes(s.as_os_str()))
+                    {
+                        self.dir_path.next();
+                        self.stack.last_mut().expect("never empty").1.clear();
+                        let tree = self.repository.find_tree(oid).unwrap();
+                        let prepared = prepare_dir_exploration(tree, &mut self.dir_path);
+                        self.stack.push((
+                            oid,
+                            prepared,
+                            CMakeModuleAcc::new(name.try_into().unwrap()),
+                        ));
+                    }
+                    return;
+                }
+                if let Some(already) = self
+                    .prepro
+                    .processing_systems
+                    .mut_or_default::<CMakeProcessorHolder>()
+                    .get_caches_mut()
+                    .object_map
+                    .get(&oid)
+                {
+                    // reinit already computed node for post order
+                    let full_node = already.clone();
+
+                    let w = &mut self.stack.last_mut().unwrap().2;
+                    let name = self.prepro.intern_object_name(name);
+                    assert!(!w.children_names.contains(&name));
+                    w.push_submodule(name, full_node);
+                    return;
+                }
+                log::debug!("cmake tree {:?}", name.try_str());
+                let parent_acc = &mut self.stack.last_mut().unwrap().2;
+                let helper = CMakeModuleHelper::from((parent_acc, &name));
+                let tree = self.repository.find_tree(oid).unwrap();
+                let is_module = helper.submodules.0
+                    || RMS
+                        && tree
+                            .get_name(std::str::from_utf8(CMAKELISTS).unwrap())
+                            .is_some();
+                if !FFWD
+                    && (is_module
+                        || !helper.submodules.1.is_empty()
+                        || !helper.source_directories.1.is_empty())
+                {
+                    // handle as cmake module or search further inside
+                    let prepared = prepare_dir_exploration(tree, &mut self.dir_path);
+                    self.stack.push((oid, prepared, helper.into()));
+                } else {
+                    // handle as source dir, even when not refered by a target eg. include/
+                    let (name, (full_node, _)) = self.prepro.help_handle_cpp_folder(
+                        &self.repository,
+                        &mut self.dir_path,
+                        oid,
+                        &name,
+                    );
+                    let parent_acc = &mut self.stack.last_mut().unwrap().2;
+                    assert!(!parent_acc.children_names.contains(&name));
+                    parent_acc.push_source_directory(name, full_node);
+                }
+            }
+            BasicGitObject::Blob(oid, name) => {
+                if FFWD {
+                    return;
+                }
+                if self.dir_path.peek().is_some() {
+                    return;
+                }
+                if crate::processing::file_sys::CMakeLists::matches(&name) {
+                    self.prepro
+                        .help_handle_cmakelists(
+                            oid,
+                            &mut self.stack.last_mut().unwrap().2,
+                            name,
+                            &self.repository,
+                            self.handle.into(),
+                        )
+                        .unwrap();
+                } else if crate::processing::file_sys::Cpp::matches(&name) {
+                    self.prepro
+                        .help_handle_cpp_file3(
+                            oid,
+                            &mut self.stack.last_mut().unwrap().2,
+                            &name,
+                            self.repository,
+                            self.cpp_handle,
+                        )
+                        .unwrap();
+                } else {
+                    log::debug!("not cpp source file {:?}", name.try_str());
+                }
+            }
+        }
+    }
+    fn post(&mut self, oid: Oid, acc: CMakeModuleAcc) -> Option<(NodeIdentifier, MD)> {
+        let name = acc.name.clone();
+        let full_node = make(acc, self.prepro.main_stores_mut());
+        self.prepro
+            .processing_systems
+            .mut_or_default::<CMakeProcessorHolder>()
+            .get_caches_mut()
+            .object_map
+            .insert(oid, full_node.clone());
+
+        let name = self.prepro.main_stores.label_store.get_or_insert(name);
+        if self.stack.is_empty() {
+            Some(full_node)
+        } else {
+            let w = &mut self.stack.last_mut().unwrap().2;
+            assert!(
+                !w.children_names.contains(&name),
+                "{:?} {:?}",
+                w.children_names,
+                name
+            );
+            w.push_submodule(name, full_node);
+            None
+        }
+    }
+
+    fn stack(&mut self) -> &mut Vec<(Oid, Vec<BasicGitObject>, CMakeModuleAcc)> {
+        &mut self.stack
+    }
+}
+
+pub(crate) fn make(acc: CMakeModuleAcc, stores: &mut SimpleStores) -> (NodeIdentifier, MD) {
+    let dir_hash: u32 = hash32(&Type::Directory);
+    let hashs = acc.metrics.hashs;
+    let size = acc.metrics.size + 1;
+    let height = acc.metrics.height + 1;
+    let size_no_spaces = acc.metrics.size_no_spaces + 1;
+    let hbuilder = hashed::Builder::new(hashs, &dir_hash, &acc.name, size_no_spaces);
+    let hashable = hbuilder.most_discriminating();
+    let label = stores.label_store.get_or_insert(acc.name.clone());
+
+    let eq = eq_node(&Type::Directory, Some(&label), &acc.children);
+    let insertion = stores.node_store.prepare_insertion(&hashable, eq);
+    let hashs = hbuilder.build();
+    let node_id = if let Some(id) = insertion.occupied_id() {
+        id
+    } else {
+        log::info!("make cmake {} {}", &acc.name, acc.children.len());
+        let vacant = insertion.vacant();
+        assert_eq!(acc.children_names.len(), acc.children.len());
+        NodeStore::insert_after_prepare(
+            vacant,
+            (
+                Type::Directory,
+                label,
+                hashs,
+                compo::Size(size),
+                compo::Height(height),
+                compo::SizeNoSpaces(size_no_spaces),
+                CS(acc.children_names.into_boxed_slice()),
+                CS(acc.children.into_boxed_slice()),
+                BloomSize::Much,
+                Targets(acc.targets.into_boxed_slice()),
+            ),
+        )
+    };
+
+    let metrics = SubTreeMetrics {
+        size,
+        height,
+        hashs,
+        size_no_spaces,
+    };
+
+    let full_node = (node_id.clone(), MD { metrics });
+    full_node
+}
+
+impl RepositoryProcessor {
+    fn help_handle_cmakelists(
+        &mut self,
+        oid: Oid,
+        parent_acc: &mut CMakeModuleAcc,
+        name: ObjectName,
+        repository: &Repository,
+        parameters: crate::processing::erased::ParametrizedCommitProcessor2Handle<CMakeListsProc>,
+    ) -> Result<(), crate::ParseErr> {
+        let x = self
+            .processing_systems
+            .caching_blob_handler::<crate::processing::file_sys::CMakeLists>()
+            .handle(oid, repository, &name, parameters, |c, n, t| {
+                crate::cmake::handle_cmakelists_file(
+                    &mut XmlTreeGen {
+                        line_break: "\n".as_bytes().to_vec(),
+                        stores: &mut self.main_stores,
+                    },
+                    n,
+                    t,
+                )
+                .map_err(|_| crate::ParseErr::IllFormed)
+            })?;
+        let name = self.intern_object_name(&name);
+        assert!(!parent_acc.children_names.contains(&name));
+        parent_acc.push_cmakelists(name, x);
+        Ok(())
+    }
+}
+
+const CMAKELISTS: &[u8] = b"CMakeLists.txt";
+
+struct CMakeModuleHelper {
+    name: String,
+    submodules: (bool, Vec<PathBuf>),
+    source_directories: (bool, Vec<PathBuf>),
+}
+
+impl From<(&mut CMakeModuleAcc, &ObjectName)> for CMakeModuleHelper {
+    fn from((parent_acc, name): (&mut CMakeModuleAcc, &ObjectName)) -> Self {
+        let process = |mut v: &mut Option<Vec<PathBuf>>| {
+            let mut v = drain_filter_strip(&mut v, name.as_bytes());
+            let c = v.extract_if(|x| x.components().next().is_none()).count();
+            (c > 0, v)
+        };
+        Self {
+            name: name.try_into().unwrap(),
+            submodules: process(&mut parent_acc.sub_modules),
+            source_directories: process(&mut parent_acc.main_dirs),
+        }
+    }
+}
+
+impl From<CMakeModuleHelper> for CMakeModuleAcc {
+    fn from(helper: CMakeModuleHelper) -> Self {
+        CMakeModuleAcc::with_content(
+            helper.name,
+            helper.submodules.1,
+            helper.source_directories.1,
+        )
+    }
+}
+
+fn drain_filter_strip(v: &mut Option<Vec<PathBuf>>, name: &[u8]) -> Vec<PathBuf> {
+    let mut new_sub_modules = vec![];
+    let name = std::str::from_utf8(&name).unwrap();
+    if let Some(sub_modules) = v {
+        sub_modules
+            .extract_if(|x| x.starts_with(name))
+            .for_each(|x| {
+                let x = x.strip_prefix(name).unwrap().to_owned();
+                new_sub_modules.push(x);
+            });
+    }
+    new_sub_modules
+}
+
+/// sometimes order of files/dirs can be important, similarly to order of statement
+/// exploration order for example
+pub(crate) fn prepare_dir_exploration(
+    tree: git2::Tree,
+    dir_path: &mut Peekable<Components>,
+) -> Vec<BasicGitObject> {
+    let mut children_objects: Vec<BasicGitObject> = tree
+        .iter()
+        .map(TryInto::try_into)
+        .filter_map(|x| x.ok())
+        .collect();
+    if dir_path.peek().is_none() {
+        let p = children_objects.iter().position(|x| match x {
+            BasicGitObject::Blob(_, n) => crate::processing::file_sys::CMakeLists::matches(n),
+            _ => false,
+        });
+        if let Some(p) = p {
+            children_objects.swap(0, p); // priority to config file processing
+            children_objects.reverse(); // we use it like a stack
+        }
+    }
+    children_objects
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct Parameter;
+
+// # CMakeLists.txt
+
+impl From<crate::processing::erased::ParametrizedCommitProcessor2Handle<CMakeProc>>
+    for crate::processing::erased::ParametrizedCommitProcessor2Handle<CMakeListsProc>
+{
+    fn from(
+        value: crate::processing::erased::ParametrizedCommitProcessor2Handle<CMakeProc>,
+    ) -> Self {
+        crate::processing::erased::ParametrizedCommitProcessor2Handle(
+            value.0,
+            std::marker::PhantomData,
+        )
+    }
+}
+struct CMakeListsProcessorHolder(Option<CMakeListsProc>);
+impl Default for CMakeListsProcessorHolder {
+    fn default() -> Self {
+        Self(Some(CMakeListsProc(Parameter, Default::default())))
+    }
+}
+struct CMakeListsProc(Parameter, crate::processing::caches::CMakeLists);
+impl crate::processing::erased::Parametrized for CMakeListsProcessorHolder {
+    type T = Parameter;
+    fn register_param(
+        &mut self,
+        t: Self::T,
+    ) -> crate::processing::erased::ParametrizedCommitProcessorHandle {
+        let l = self.0.iter().position(|x| &x.0 == &t).unwrap_or_else(|| {
+            self.0 = Some(CMakeListsProc(t, Default::default()));
+            0
+        });
+        use crate::processing::erased::ConfigParametersHandle;
+        use crate::processing::erased::ParametrizedCommitProc;
+        use crate::processing::erased::ParametrizedCommitProcessorHandle;
+        ParametrizedCommitProcessorHandle(self.erased_handle(), ConfigParametersHandle(l))
+    }
+}
+// TODO should not have to impl this trait
+impl crate::processing::erased::CommitProc for CMakeListsProc {
+    fn process_root_tree(
+        &mut self,
+        repository: &git2::Repository,
+        tree_oid: &git2::Oid,
+    ) -> hyper_ast::store::defaults::NodeIdentifier {
+        unimplemented!()
+    }
+
+    fn prepare_processing(
+        &self,
+        repository: &git2::Repository,
+        commit_builder: crate::preprocessed::CommitBuilder,
+    ) -> Box<dyn crate::processing::erased::PreparedCommitProc> {
+        unimplemented!()
+    }
+
+    fn get_commit(&self, commit_oid: git2::Oid) -> Option<&crate::Commit> {
+        unimplemented!()
+    }
+}
+
+impl crate::processing::erased::CommitProcExt for CMakeListsProc {
+    type Holder = CMakeListsProcessorHolder;
+}
+impl crate::processing::erased::ParametrizedCommitProc2 for CMakeListsProcessorHolder {
+    type Proc = CMakeListsProc;
+
+    fn with_parameters_mut(
+        &mut self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &mut Self::Proc {
+        assert_eq!(0, parameters.0);
+        self.0.as_mut().unwrap()
+    }
+
+    fn with_parameters(
+        &self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &Self::Proc {
+        assert_eq!(0, parameters.0);
+        self.0.as_ref().unwrap()
+    }
+}
+impl CacheHolding<crate::processing::caches::CMakeLists> for CMakeListsProc {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::CMakeLists {
+        &mut self.1
+    }
+    fn get_caches(&self) -> &crate::processing::caches::CMakeLists {
+        &self.1
+    }
+}
+impl CacheHolding<crate::processing::caches::CMakeLists> for CMakeListsProcessorHolder {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::CMakeLists {
+        &mut self.0.as_mut().unwrap().1
+    }
+    fn get_caches(&self) -> &crate::processing::caches::CMakeLists {
+        &self.0.as_ref().unwrap().1
+    }
+}
+
+// # CMake
+#[derive(Default)]
+pub(crate) struct CMakeProcessorHolder(Option<CMakeProc>);
+pub(crate) struct CMakeProc {
+    parameter: Parameter,
+    cache: crate::processing::caches::CMake,
+    commits: std::collections::HashMap<git2::Oid, crate::Commit>,
+}
+impl crate::processing::erased::Parametrized for CMakeProcessorHolder {
+    type T = Parameter;
+    fn register_param(
+        &mut self,
+        t: Self::T,
+    ) -> crate::processing::erased::ParametrizedCommitProcessorHandle {
+        let l = self
+            .0
+            .iter()
+            .position(|x| &x.parameter == &t)
+            .unwrap_or_else(|| {
+                self.0 = Some(CMakeProc {
+                    parameter: t,
+                    cache: Default::default(),
+                    commits: Default::default(),
+                });
+                0
+            });
+        use crate::processing::erased::ConfigParametersHandle;
+        use crate::processing::erased::ParametrizedCommitProc;
+        use crate::processing::erased::ParametrizedCommitProcessorHandle;
+        ParametrizedCommitProcessorHandle(self.erased_handle(), ConfigParametersHandle(l))
+    }
+}
+struct PreparedCMakeCommitProc<'repo> {
+    repository: &'repo git2::Repository,
+    commit_builder: crate::preprocessed::CommitBuilder,
+}
+impl<'repo> crate::processing::erased::PreparedCommitProc for PreparedCMakeCommitProc<'repo> {
+    fn process(
+        self: Box<PreparedCMakeCommitProc<'repo>>,
+        prepro: &mut RepositoryProcessor,
+    ) -> hyper_ast::store::defaults::NodeIdentifier {
+        let dir_path = PathBuf::from("");
+        let mut dir_path = dir_path.components().peekable();
+        let name = b"";
+        let root_full_node = CMakeProcessor::<true, false, CMakeModuleAcc>::new(
+            self.repository,
+            prepro,
+            &mut dir_path,
+            name,
+            self.commit_builder.tree_oid(),
+        )
+        .process();
+        let h = prepro
+            .processing_systems
+            .mut_or_default::<CMakeProcessorHolder>();
+        let handle =
+            <CMakeProc as crate::processing::erased::CommitProcExt>::register_param(h, Parameter);
+        let commit_oid = self.commit_builder.commit_oid();
+        let commit = self.commit_builder.finish(root_full_node.0);
+        h.with_parameters_mut(handle.0)
+            .commits
+            .insert(commit_oid, commit);
+        root_full_node.0
+    }
+}
+impl crate::processing::erased::CommitProc for CMakeProc {
+    fn process_root_tree(
+        &mut self,
+        repository: &git2::Repository,
+        tree_oid: &git2::Oid,
+    ) -> hyper_ast::store::defaults::NodeIdentifier {
+        unimplemented!("see reason in the java proc")
+    }
+
+    fn prepare_processing<'repo>(
+        &self,
+        repository: &'repo git2::Repository,
+        commit_builder: crate::preprocessed::CommitBuilder,
+    ) -> Box<dyn crate::processing::erased::PreparedCommitProc + 'repo> {
+        Box::new(PreparedCMakeCommitProc {
+            repository,
+            commit_builder,
+        })
+    }
+
+    fn get_commit(&self, commit_oid: git2::Oid) -> Option<&crate::Commit> {
+        self.commits.get(&commit_oid)
+    }
+}
+
+impl CMakeProcessorHolder {
+    pub(crate) fn commits(&self) -> Option<&std::collections::HashMap<git2::Oid, crate::Commit>> {
+        self.0.as_ref().map(|x| &x.commits)
+    }
+}
+impl CMakeProc {
+    pub(crate) fn commits_mut(
+        &mut self,
+    ) -> &mut std::collections::HashMap<git2::Oid, crate::Commit> {
+        &mut self.commits
+    }
+}
+impl crate::processing::erased::CommitProcExt for CMakeProc {
+    type Holder = CMakeProcessorHolder;
+}
+impl crate::processing::erased::ParametrizedCommitProc2 for CMakeProcessorHolder {
+    type Proc = CMakeProc;
+
+    fn with_parameters_mut(
+        &mut self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &mut Self::Proc {
+        assert_eq!(0, parameters.0);
+        self.0.as_mut().unwrap()
+    }
+
+    fn with_parameters(
+        &self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &Self::Proc {
+        assert_eq!(0, parameters.0);
+        self.0.as_ref().unwrap()
+    }
+}
+impl CacheHolding<crate::processing::caches::CMake> for CMakeProc {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::CMake {
+        &mut self.cache
+    }
+    fn get_caches(&self) -> &crate::processing::caches::CMake {
+        &self.cache
+    }
+}
+impl CacheHolding<crate::processing::caches::CMake> for CMakeProcessorHolder {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::CMake {
+        &mut self.0.as_mut().unwrap().cache
+    }
+    fn get_caches(&self) -> &crate::processing::caches::CMake {
+        &self.0.as_ref().unwrap().cache
+    }
+}
@@ -8,6 +8,7 @@ use hyper_ast::{
         defaults::{LabelIdentifier, NodeIdentifier},
         nodes::legion::PendingInsert,
     },
+    tree_gen::derived::DerivedMetrics,
     types::LabelStore,
 };
 use hyper_ast_gen_ts_cpp::{
@@ -21,7 +22,10 @@ use crate::{
     git::BasicGitObject,
     make::MakeModuleAcc,
     preprocessed::{IsSkippedAna, RepositoryProcessor},
-    processing::{erased::CommitProcExt, CacheHolding, InFiles, ObjectName},
+    processing::{
+        erased::{CommitProcExt, ParametrizedCommitProc2},
+        CacheHolding, InFiles, ObjectName,
+    },
     Processor, SimpleStores,
 };
 
@@ -160,6 +164,8 @@ pub(crate) struct CppProc {
     parameter: Parameter,
     cache: crate::processing::caches::Cpp,
     commits: std::collections::HashMap<git2::Oid, crate::Commit>,
+    /// see [`RepositoryProcessor::cpp_derived_metrics_mut`]
+    derived: DerivedMetrics<Type>,
 }
 impl crate::processing::erased::Parametrized for CppProcessorHolder {
     type T = Parameter;
@@ -178,6 +184,7 @@ impl crate::processing::erased::Parametrized for CppProcessorHolder {
                     parameter: t,
                     cache: Default::default(),
                     commits: Default::default(),
+                    derived: Default::default(),
                 });
                 l
             });
@@ -265,15 +272,16 @@ impl RepositoryProcessor {
                 } else {
                     "\n".as_bytes().to_vec()
                 };
+                let proc = c
+                    .mut_or_default::<CppProcessorHolder>()
+                    .with_parameters_mut(parameters.0);
                 crate::cpp::handle_cpp_file(
-                    &mut cpp_gen::CppTreeGen {
-                        line_break,
-                        stores: &mut self.main_stores,
-                        md_cache: &mut c
-                            .mut_or_default::<CppProcessorHolder>()
-                            .get_caches_mut()
-                            .md_cache, //cpp_md_cache,
-                    },
+                    &mut cpp_gen::CppTreeGen::<crate::TStore>::new(
+                        &mut self.main_stores,
+                        &mut proc.cache.md_cache,
+                    )
+                    .with_line_break(line_break)
+                    .with_derived(&proc.derived),
                     n,
                     t,
                 )
@@ -315,6 +323,22 @@ impl RepositoryProcessor {
         Ok(())
     }
 
+    #[cfg(feature = "cmake")]
+    pub(crate) fn help_handle_cpp_file3(
+        &mut self,
+        oid: Oid,
+        parent: &mut crate::cmake::CMakeModuleAcc,
+        name: &ObjectName,
+        repository: &Repository,
+        parameters: crate::processing::erased::ParametrizedCommitProcessor2Handle<CppProc>,
+    ) -> Result<(), crate::ParseErr> {
+        let (full_node, _) = self.handle_cpp_blob(oid, name, repository, parameters)?;
+        let name = self.intern_object_name(name);
+        assert!(!parent.children_names.contains(&name));
+        parent.push_source_file(name, full_node);
+        Ok(())
+    }
+
     pub(crate) fn handle_cpp_directory<'b, 'd: 'b>(
         &mut self,
         repository: &Repository,
@@ -330,6 +354,16 @@ impl RepositoryProcessor {
         CppProcessor::<CppAcc>::new(repository, self, dir_path, name, oid, &handle).process()
     }
 
+    /// Metrics computed on each generated C++ node, see [`DerivedMetrics`].
+    /// Register them before processing commits, files already processed are not updated.
+    pub fn cpp_derived_metrics_mut(&mut self) -> &mut DerivedMetrics<Type> {
+        let h = self
+            .processing_systems
+            .mut_or_default::<CppProcessorHolder>();
+        let handle = CppProc::register_param(h, Parameter);
+        &mut h.with_parameters_mut(handle.0).derived
+    }
+
     pub(crate) fn help_handle_cpp_folder<'a, 'b, 'c, 'd: 'c>(
         &'a mut self,
         repository: &'b Repository,
@@ -80,6 +80,136 @@ pub fn all_commits_from_head(repository: &Repository) -> Revwalk {
     // return walk;
 }
 
+/// The uncommitted states of a local checkout, see [`synthetic_commit`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkingState {
+    /// the staged changes
+    Index,
+    /// the files on disk, ie. the staged and unstaged changes plus the untracked files that are not ignored
+    WorkDir,
+}
+
+/// Writes the given state of the checkout as a commit whose parent is HEAD,
+/// like `git stash create` neither references nor the index are updated.
+///
+/// The new objects are only kept in memory, in a backend added once to the object database of `repository`,
+/// they stay available as long as `repository` is open but are never written on disk.
+///
+/// The signature is fixed, thus the same state on top of the same HEAD always gives the same commit,
+/// and already processed commits can be looked up.
+pub fn synthetic_commit(repository: &Repository, state: WorkingState) -> Result<Oid, git2::Error> {
+    add_in_memory_backend(repository)?;
+    // a copy of the index, modifying the one of the repository would be visible to the user
+    let mut index = git2::Index::new()?;
+    for entry in repository.index()?.iter() {
+        index.add(&entry)?;
+    }
+    if state == WorkingState::WorkDir {
+        add_working_dir(repository, &mut index)?;
+    }
+    let tree = index.write_tree_to(repository)?;
+    let tree = repository.find_tree(tree)?;
+    let parents = match repository.head() {
+        Ok(head) => vec![head.peel_to_commit()?],
+        Err(err) if err.code() == git2::ErrorCode::UnbornBranch => vec![],
+        Err(err) => return Err(err),
+    };
+    let parents: Vec<_> = parents.iter().collect();
+    let sig = git2::Signature::new("hyperast", "hyperast@localhost", &git2::Time::new(0, 0))?;
+    let message = match state {
+        WorkingState::Index => "index",
+        WorkingState::WorkDir => "working directory",
+    };
+    repository.commit(None, &sig, &sig, message, &tree, &parents)
+}
+
+/// Written in the in-memory backend when adding it, its presence tells that the backend was already added.
+const IN_MEMORY_MARKER: &[u8] = b"objects of the synthetic commits of hyperast";
+
+/// Adds an in-memory backend to the object database of `repository`, unless it was already added,
+/// otherwise each synthetic commit would add another backend to search.
+fn add_in_memory_backend(repository: &Repository) -> Result<(), git2::Error> {
+    let odb = repository.odb()?;
+    if odb.exists(Oid::hash_object(git2::ObjectType::Blob, IN_MEMORY_MARKER)?) {
+        return Ok(());
+    }
+    // higher than the priorities of the default backends, so that writes go in memory
+    odb.add_new_mempack_backend(1000)?;
+    odb.write(git2::ObjectType::Blob, IN_MEMORY_MARKER)?;
+    Ok(())
+}
+
+/// Puts in `index` the files of the working directory that differ from it,
+/// untracked files included, ignored ones excluded.
+fn add_working_dir(repository: &Repository, index: &mut git2::Index) -> Result<(), git2::Error> {
+    let workdir = repository
+        .workdir()
+        .ok_or_else(|| git2::Error::from_str("no working directory in a bare repository"))?;
+    let mut options = git2::StatusOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .include_ignored(false);
+    let io_err = |err: std::io::Error| git2::Error::from_str(&err.to_string());
+    for status in repository.statuses(Some(&mut options))?.iter() {
+        let s = status.status();
+        let path = status
+            .path()
+            .ok_or_else(|| git2::Error::from_str("path of file is not valid utf-8"))?;
+        if s.contains(git2::Status::WT_DELETED) {
+            index.remove_path(Path::new(path))?;
+            continue;
+        }
+        if !s.intersects(
+            git2::Status::WT_NEW
+                | git2::Status::WT_MODIFIED
+                | git2::Status::WT_TYPECHANGE
+                | git2::Status::WT_RENAMED,
+        ) {
+            continue;
+        }
+        let file = workdir.join(path);
+        let meta = std::fs::symlink_metadata(&file).map_err(io_err)?;
+        let (content, mode) = if meta.file_type().is_symlink() {
+            let target = std::fs::read_link(&file).map_err(io_err)?;
+            (target.to_string_lossy().into_owned().into_bytes(), 0o120000)
+        } else {
+            (std::fs::read(&file).map_err(io_err)?, file_mode(&meta))
+        };
+        let id = repository.blob(&content)?;
+        index.add(&git2::IndexEntry {
+            ctime: git2::IndexTime::new(0, 0),
+            mtime: git2::IndexTime::new(0, 0),
+            dev: 0,
+            ino: 0,
+            mode,
+            uid: 0,
+            gid: 0,
+            file_size: content.len() as u32,
+            id,
+            flags: 0,
+            flags_extended: 0,
+            path: path.as_bytes().to_vec(),
+        })?;
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn file_mode(meta: &std::fs::Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    if meta.permissions().mode() & 0o111 != 0 {
+        0o100755
+    } else {
+        0o100644
+    }
+}
+
+#[cfg(not(unix))]
+fn file_mode(_meta: &std::fs::Metadata) -> u32 {
+    0o100644
+}
+
 pub struct Url {
     protocol: String,
     domain: String,
@@ -0,0 +1,285 @@
+use std::path::PathBuf;
+
+use hyper_ast::{
+    store::defaults::{LabelIdentifier, NodeIdentifier},
+    tree_gen::SubTreeMetrics,
+};
+use hyper_ast_gen_ts_java::legion_with_refs as java_tree_gen;
+use hyper_ast_gen_ts_xml::legion::XmlTreeGen;
+
+use crate::{
+    processing::ObjectName, Accumulator, DefaultMetrics, TStore, PROPAGATE_ERROR_ON_BAD_CST_NODE,
+};
+
+pub(crate) fn handle_settings_file<'a>(
+    tree_gen: &mut XmlTreeGen<'a, TStore>,
+    name: &ObjectName,
+    text: &'a [u8],
+) -> Result<GradleSettings, ()> {
+    let x = make_placeholder(tree_gen, name, b"<settings></settings>")?;
+    let text = std::str::from_utf8(text).map_err(|_| ())?;
+    let x = GradleSettings {
+        compressed_node: x.compressed_node,
+        metrics: x.metrics,
+        subprojects: parse_includes(text),
+    };
+    Ok(x)
+}
+
+pub(crate) fn handle_build_file<'a>(
+    tree_gen: &mut XmlTreeGen<'a, TStore>,
+    name: &ObjectName,
+    text: &'a [u8],
+) -> Result<GradleBuild, ()> {
+    let x = make_placeholder(tree_gen, name, b"<build></build>")?;
+    // TODO look for sourceSets blocks, for now only the conventional layout is considered
+    let x = GradleBuild {
+        compressed_node: x.compressed_node,
+        metrics: x.metrics,
+        source_dirs: vec!["src/main/java".to_owned()],
+        test_source_dirs: vec!["src/test/java".to_owned()],
+    };
+    Ok(x)
+}
+
+// TODO use a groovy/kotlin generator, for now gradle scripts are placeholders like Makefiles
+fn make_placeholder<'a>(
+    tree_gen: &mut XmlTreeGen<'a, TStore>,
+    name: &ObjectName,
+    placeholder: &'static [u8],
+) -> Result<hyper_ast_gen_ts_xml::legion::Local, ()> {
+    let tree = match XmlTreeGen::<TStore>::tree_sitter_parse(placeholder) {
+        Ok(tree) => tree,
+        Err(tree) => {
+            log::warn!("bad CST");
+            log::debug!("{:?}", name.try_str());
+            log::debug!("{}", tree.root_node().to_sexp());
+            if PROPAGATE_ERROR_ON_BAD_CST_NODE {
+                return Err(());
+            } else {
+                tree
+            }
+        }
+    };
+    Ok(tree_gen
+        .generate_file(name.as_bytes(), placeholder, tree.walk())
+        .local)
+}
+
+/// Extracts the directories of the subprojects declared in a settings.gradle(.kts),
+/// ie. the arguments of `include` in both the groovy and the kotlin dsl,
+/// eg. `include ':app', ':libs:core'` gives `app` and `libs/core`.
+///
+/// Project directories changed with `project(...).projectDir` are not considered.
+fn parse_includes(text: &str) -> Vec<String> {
+    let mut res = vec![];
+    let mut in_include = false;
+    for line in text.lines() {
+        let line = line.trim();
+        let line = if in_include {
+            line
+        } else if let Some(rest) = line.strip_prefix("include") {
+            if !rest.starts_with(|c: char| c.is_whitespace() || c == '(') {
+                continue;
+            }
+            rest
+        } else {
+            continue;
+        };
+        let line = line.split("//").next().unwrap_or_default();
+        let mut parts = line.split(|c| c == '\'' || c == '"');
+        parts.next();
+        while let Some(project) = parts.next() {
+            let project = project.trim_start_matches(':');
+            if !project.is_empty() {
+                res.push(project.replace(':', "/"));
+            }
+            parts.next();
+        }
+        // groovy lists can continue on the next line after a comma
+        let line = line.trim_end();
+        in_include = line.ends_with(',') || line.ends_with('(');
+    }
+    res
+}
+
+#[derive(Debug, Clone)]
+pub struct GradleSettings {
+    pub compressed_node: NodeIdentifier,
+    pub metrics: DefaultMetrics,
+    subprojects: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct GradleBuild {
+    pub compressed_node: NodeIdentifier,
+    pub metrics: DefaultMetrics,
+    source_dirs: Vec<String>,
+    test_source_dirs: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MD {
+    pub(crate) metrics: DefaultMetrics,
+}
+
+/// A directory of a gradle build,
+/// either a project ie. it contains a build script,
+/// or a plain directory containing projects.
+pub struct GradleModuleAcc {
+    pub(crate) name: String,
+    pub(crate) children_names: Vec<LabelIdentifier>,
+    pub(crate) children: Vec<NodeIdentifier>,
+    pub(crate) metrics: DefaultMetrics,
+    pub(crate) sub_modules: Option<Vec<PathBuf>>,
+    pub(crate) main_dirs: Option<Vec<PathBuf>>,
+    pub(crate) test_dirs: Option<Vec<PathBuf>>,
+}
+
+impl From<String> for GradleModuleAcc {
+    fn from(name: String) -> Self {
+        Self::new(name)
+    }
+}
+
+impl GradleModuleAcc {
+    pub(crate) fn new(name: String) -> Self {
+        Self {
+            name,
+            children_names: Default::default(),
+            children: Default::default(),
+            metrics: Default::default(),
+            sub_modules: None,
+            main_dirs: None,
+            test_dirs: None,
+        }
+    }
+    pub(crate) fn with_content(
+        name: String,
+        sub_modules: Vec<PathBuf>,
+        main_dirs: Vec<PathBuf>,
+        test_dirs: Vec<PathBuf>,
+    ) -> Self {
+        Self {
+            name,
+            children_names: Default::default(),
+            children: Default::default(),
+            metrics: Default::default(),
+            sub_modules: if sub_modules.is_empty() {
+                None
+            } else {
+                Some(sub_modules)
+            },
+            main_dirs: if main_dirs.is_empty() {
+                None
+            } else {
+                Some(main_dirs)
+            },
+            test_dirs: if test_dirs.is_empty() {
+                None
+            } else {
+                Some(test_dirs)
+            },
+        }
+    }
+}
+
+impl GradleModuleAcc {
+    pub(crate) fn push_settings(&mut self, name: LabelIdentifier, full_node: GradleSettings) {
+        assert!(!self.children_names.contains(&name));
+        self.children.push(full_node.compressed_node);
+        self.children_names.push(name);
+        self.sub_modules
+            .get_or_insert_with(Default::default)
+            .extend(full_node.subprojects.iter().map(|x| x.into()));
+        self.metrics.acc(full_node.metrics);
+    }
+    pub(crate) fn push_build_script(&mut self, name: LabelIdentifier, full_node: GradleBuild) {
+        assert!(!self.children_names.contains(&name));
+        self.children.push(full_node.compressed_node);
+        self.children_names.push(name);
+        self.main_dirs = Some(full_node.source_dirs.iter().map(|x| x.into()).collect());
+        self.test_dirs = Some(
+            full_node
+                .test_source_dirs
+                .iter()
+                .map(|x| x.into())
+                .collect(),
+        );
+        self.metrics.acc(full_node.metrics);
+    }
+    pub fn push_submodule(&mut self, name: LabelIdentifier, full_node: (NodeIdentifier, MD)) {
+        self.children.push(full_node.0);
+        self.children_names.push(name);
+        self.metrics.acc(full_node.1.metrics);
+    }
+    pub(crate) fn push_source_directory(
+        &mut self,
+        name: LabelIdentifier,
+        full_node: java_tree_gen::Local,
+    ) {
+        self.children.push(full_node.compressed_node);
+        self.children_names.push(name);
+        self.metrics.acc(SubTreeMetrics {
+            hashs: full_node.metrics.hashs,
+            size: full_node.metrics.size,
+            height: full_node.metrics.height,
+            size_no_spaces: full_node.metrics.size_no_spaces,
+        });
+    }
+    pub(crate) fn push_test_source_directory(
+        &mut self,
+        name: LabelIdentifier,
+        full_node: java_tree_gen::Local,
+    ) {
+        self.children.push(full_node.compressed_node);
+        self.children_names.push(name);
+        self.metrics.acc(SubTreeMetrics {
+            hashs: full_node.metrics.hashs,
+            size: full_node.metrics.size,
+            height: full_node.metrics.height,
+            size_no_spaces: full_node.metrics.size_no_spaces,
+        });
+    }
+}
+
+impl hyper_ast::tree_gen::Accumulator for GradleModuleAcc {
+    type Node = (LabelIdentifier, (NodeIdentifier, MD));
+    fn push(&mut self, (name, full_node): Self::Node) {
+        self.push_submodule(name, full_node)
+    }
+}
+
+impl Accumulator for GradleModuleAcc {
+    type Unlabeled = (NodeIdentifier, MD);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_includes() {
+        let groovy = r#"
+rootProject.name = 'demo'
+include ':app', ':libs:core' // the cli is not built
+include 'docs'
+includeBuild 'plugins'
+include ':tools:a',
+        ':tools:b'
+"#;
+        assert_eq!(
+            parse_includes(groovy),
+            vec!["app", "libs/core", "docs", "tools/a", "tools/b"]
+        );
+        let kotlin = r#"
+rootProject.name = "demo"
+include("app")
+include(
+    ":libs:core",
+    ":libs:io",
+)
+"#;
+        assert_eq!(parse_includes(kotlin), vec!["app", "libs/core", "libs/io"]);
+    }
+}
@@ -0,0 +1,649 @@
+use std::{
+    iter::Peekable,
+    path::{Components, PathBuf},
+};
+
+use git2::{Oid, Repository};
+use hyper_ast::{store::defaults::NodeIdentifier, types::LabelStore};
+use hyper_ast_gen_ts_xml::{legion::XmlTreeGen, types::Type};
+
+use crate::{
+    git::BasicGitObject,
+    gradle::{GradleModuleAcc, MD},
+    preprocessed::RepositoryProcessor,
+    processing::{erased::ParametrizedCommitProc2, CacheHolding, InFiles, ObjectName},
+    Processor, SimpleStores,
+};
+
+/// RMS: Resursive Module Search, ie. also look for projects that are not included in the settings
+/// FFWD: Fast ForWarD to java directories without looking at gradle stuff
+pub struct GradleProcessor<'a, 'b, 'c, const RMS: bool, const FFWD: bool, Acc> {
+    prepro: &'b mut RepositoryProcessor,
+    repository: &'a Repository,
+    stack: Vec<(Oid, Vec<BasicGitObject>, Acc)>,
+    dir_path: &'c mut Peekable<Components<'c>>,
+    handle: crate::processing::erased::ParametrizedCommitProcessor2Handle<GradleProc>,
+}
+
+impl<'a, 'b, 'c, const RMS: bool, const FFWD: bool, Acc: From<String>>
+    GradleProcessor<'a, 'b, 'c, RMS, FFWD, Acc>
+{
+    pub fn new(
+        repository: &'a Repository,
+        prepro: &'b mut RepositoryProcessor,
+        mut dir_path: &'c mut Peekable<Components<'c>>,
+        name: &[u8],
+        oid: git2::Oid,
+    ) -> Self {
+        let h = prepro
+            .processing_systems
+            .mut_or_default::<GradleProcessorHolder>();
+        let handle =
+            <GradleProc as crate::processing::erased::CommitProcExt>::register_param(h, Parameter);
+        let tree = repository.find_tree(oid).unwrap();
+        let prepared = prepare_dir_exploration(tree, &mut dir_path);
+        let name = std::str::from_utf8(&name).unwrap().to_string();
+        let stack = vec![(oid, prepared, Acc::from(name))];
+        Self {
+            stack,
+            repository,
+            prepro,
+            dir_path,
+            handle,
+        }
+    }
+}
+
+impl<'a, 'b, 'c, const RMS: bool, const FFWD: bool> Processor<GradleModuleAcc>
+    for GradleProcessor<'a, 'b, 'c, RMS, FFWD, GradleModuleAcc>
+{
+    fn pre(&mut self, current_dir: BasicGitObject) {
+        match current_dir {
+            BasicGitObject::Tree(oid, name) => {
+                self.handle_tree_cached(name, oid);
+            }
+            BasicGitObject::Blob(oid, name) => {
+                if FFWD {
+                    return;
+                }
+                if self.dir_path.peek().is_some() {
+                    return;
+                }
+                if crate::processing::file_sys::GradleSettings::matches(&name) {
+                    self.prepro
+                        .help_handle_gradle_settings(
+                            oid,
+                            &mut self.stack.last_mut().unwrap().2,
+                            name,
+                            &self.repository,
+                            self.handle.into(),
+                        )
+                        .unwrap()
+                } else if crate::processing::file_sys::GradleBuild::matches(&name) {
+                    self.prepro
+                        .help_handle_gradle_build(
+                            oid,
+                            &mut self.stack.last_mut().unwrap().2,
+                            name,
+                            &self.repository,
+                            self.handle.into(),
+                        )
+                        .unwrap()
+                }
+            }
+        }
+    }
+    fn post(&mut self, oid: Oid, acc: GradleModuleAcc) -> Option<(NodeIdentifier, MD)> {
+        let name = acc.name.clone();
+        let full_node = make(acc, self.prepro.main_stores_mut());
+        self.prepro
+            .processing_systems
+            .mut_or_default::<GradleProcessorHolder>()
+            .get_caches_mut()
+            .object_map
+            .insert(oid, full_node.clone());
+
+        let name = self.prepro.intern_label(&name);
+        if self.stack.is_empty() {
+            Some(full_node)
+        } else {
+            let w = &mut self.stack.last_mut().unwrap().2;
+            assert!(
+                !w.children_names.contains(&name),
+                "{:?} {:?}",
+                w.children_names,
+                name
+            );
+            w.push_submodule(name, full_node);
+            None
+        }
+    }
+
+    fn stack(&mut self) -> &mut Vec<(Oid, Vec<BasicGitObject>, GradleModuleAcc)> {
+        &mut self.stack
+    }
+}
+
+impl<'a, 'b, 'c, const RMS: bool, const FFWD: bool>
+    GradleProcessor<'a, 'b, 'c, RMS, FFWD, GradleModuleAcc>
+{
+    fn handle_tree_cached(&mut self, name: ObjectName, oid: Oid) {
+        if let Some(s) = self.dir_path.peek() {
+            if name
+                .as_bytes()
+                .eq(std::ffi::OsStr::as_os_str_bytes(s.as_os_str()))
+            {
+                self.dir_path.next();
+                self.stack.last_mut().expect("never empty").1.clear();
+                let tree = self.repository.find_tree(oid).unwrap();
+                let prepared = prepare_dir_exploration(tree, &mut self.dir_path);
+                self.stack.push((
+                    oid,
+                    prepared,
+                    GradleModuleAcc::new(name.try_into().unwrap()),
+                ));
+            }
+            return;
+        }
+        if let Some(already) = self
+            .prepro
+            .processing_systems
+            .mut_or_default::<GradleProcessorHolder>()
+            .get_caches_mut()
+            .object_map
+            .get(&oid)
+        {
+            // reinit already computed node for post order
+            let full_node = already.clone();
+
+            let w = &mut self.stack.last_mut().unwrap().2;
+            let name = self.prepro.intern_object_name(&name);
+            assert!(!w.children_names.contains(&name));
+            w.push_submodule(name, full_node);
+            return;
+        }
+        log::debug!("gradle tree {:?}", name.try_str());
+        let parent_acc = &mut self.stack.last_mut().unwrap().2;
+        if FFWD {
+            let (name, (full_node, _)) = self.prepro.help_handle_java_folder(
+                &self.repository,
+                &mut self.dir_path,
+                oid,
+                &name,
+            );
+            assert!(!parent_acc.children_names.contains(&name));
+            parent_acc.push_source_directory(name, full_node);
+            return;
+        }
+        let helper = GradleModuleHelper::from((parent_acc, &name));
+        if helper.source_directories.0 || helper.test_source_directories.0 {
+            // handle as source dir
+            let (name, (full_node, _)) =
+                self.prepro
+                    .help_handle_java_folder(&self.repository, self.dir_path, oid, &name);
+            let parent_acc = &mut self.stack.last_mut().unwrap().2;
+            assert!(!parent_acc.children_names.contains(&name));
+            if helper.source_directories.0 {
+                parent_acc.push_source_directory(name, full_node);
+            } else {
+                parent_acc.push_test_source_directory(name, full_node);
+            }
+        } else if helper.submodules.0
+            || !helper.submodules.1.is_empty()
+            || !helper.source_directories.1.is_empty()
+            || !helper.test_source_directories.1.is_empty()
+            || RMS
+        {
+            // handle as included project or search further inside
+            let tree = self.repository.find_tree(oid).unwrap();
+            let prepared = prepare_dir_exploration(tree, &mut self.dir_path);
+            self.stack.push((oid, prepared, helper.into()));
+        }
+    }
+}
+
+/// Gradle projects are typed as maven directories,
+/// thus iterating over modules and reference analysis handle them like maven modules.
+pub(crate) fn make(acc: GradleModuleAcc, stores: &mut SimpleStores) -> (NodeIdentifier, MD) {
+    use hyper_ast::{
+        filter::BloomSize,
+        hashed::{self, IndexingHashBuilder, MetaDataHashsBuilder},
+        store::nodes::legion::{compo, compo::CS, NodeStore},
+        tree_gen::SubTreeMetrics,
+    };
+    use hyper_ast_gen_ts_java::legion_with_refs::{eq_node, hash32};
+    let dir_hash: u32 = hash32(&Type::MavenDirectory);
+    let hashs = acc.metrics.hashs;
+    let size = acc.metrics.size + 1;
+    let height = acc.metrics.height + 1;
+    let size_no_spaces = acc.metrics.size_no_spaces + 1;
+    let hbuilder = hashed::Builder::new(hashs, &dir_hash, &acc.name, size_no_spaces);
+    let hashable = hbuilder.most_discriminating();
+    let label = stores.label_store.get_or_insert(acc.name.clone());
+
+    let eq = eq_node(&Type::MavenDirectory, Some(&label), &acc.children);
+    let insertion = stores.node_store.prepare_insertion(&hashable, eq);
+    let hashs = hbuilder.build();
+    let node_id = if let Some(id) = insertion.occupied_id() {
+        id
+    } else {
+        log::info!("make gradle {} {}", &acc.name, acc.children.len());
+        let vacant = insertion.vacant();
+        assert_eq!(acc.children_names.len(), acc.children.len());
+        NodeStore::insert_after_prepare(
+            vacant,
+            (
+                Type::MavenDirectory,
+                label,
+                hashs,
+                compo::Size(size),
+                compo::Height(height),
+                compo::SizeNoSpaces(size_no_spaces),
+                CS(acc.children_names.into_boxed_slice()),
+                CS(acc.children.into_boxed_slice()),
+                BloomSize::Much,
+            ),
+        )
+    };
+
+    let metrics = SubTreeMetrics {
+        size,
+        height,
+        hashs,
+        size_no_spaces,
+    };
+
+    let full_node = (node_id.clone(), MD { metrics });
+    full_node
+}
+
+impl RepositoryProcessor {
+    fn help_handle_gradle_settings(
+        &mut self,
+        oid: Oid,
+        parent_acc: &mut GradleModuleAcc,
+        name: ObjectName,
+        repository: &Repository,
+        parameters: crate::processing::erased::ParametrizedCommitProcessor2Handle<GradleScriptProc>,
+    ) -> Result<(), crate::ParseErr> {
+        let x = self
+            .processing_systems
+            .caching_blob_handler::<crate::processing::file_sys::GradleSettings>()
+            .handle(oid, repository, &name, parameters, |c, n, t| {
+                crate::gradle::handle_settings_file(
+                    &mut XmlTreeGen {
+                        line_break: "\n".as_bytes().to_vec(),
+                        stores: &mut self.main_stores,
+                    },
+                    n,
+                    t,
+                )
+                .map_err(|_| crate::ParseErr::IllFormed)
+            })?;
+        let name = self.intern_object_name(&name);
+        assert!(!parent_acc.children_names.contains(&name));
+        parent_acc.push_settings(name, x);
+        Ok(())
+    }
+
+    fn help_handle_gradle_build(
+        &mut self,
+        oid: Oid,
+        parent_acc: &mut GradleModuleAcc,
+        name: ObjectName,
+        repository: &Repository,
+        parameters: crate::processing::erased::ParametrizedCommitProcessor2Handle<GradleScriptProc>,
+    ) -> Result<(), crate::ParseErr> {
+        let x = self
+            .processing_systems
+            .caching_blob_handler::<crate::processing::file_sys::GradleBuild>()
+            .handle(oid, repository, &name, parameters, |c, n, t| {
+                crate::gradle::handle_build_file(
+                    &mut XmlTreeGen {
+                        line_break: "\n".as_bytes().to_vec(),
+                        stores: &mut self.main_stores,
+                    },
+                    n,
+                    t,
+                )
+                .map_err(|_| crate::ParseErr::IllFormed)
+            })?;
+        let name = self.intern_object_name(&name);
+        assert!(!parent_acc.children_names.contains(&name));
+        parent_acc.push_build_script(name, x);
+        Ok(())
+    }
+}
+
+struct GradleModuleHelper {
+    name: String,
+    submodules: (bool, Vec<PathBuf>),
+    source_directories: (bool, Vec<PathBuf>),
+    test_source_directories: (bool, Vec<PathBuf>),
+}
+
+impl From<(&mut GradleModuleAcc, &ObjectName)> for GradleModuleHelper {
+    fn from((parent_acc, name): (&mut GradleModuleAcc, &ObjectName)) -> Self {
+        let process = |mut v: &mut Option<Vec<PathBuf>>| {
+            let mut v = drain_filter_strip(&mut v, name.as_bytes());
+            let c = v.extract_if(|x| x.components().next().is_none()).count();
+            (c > 0, v)
+        };
+        Self {
+            name: name.try_into().unwrap(),
+            submodules: process(&mut parent_acc.sub_modules),
+            source_directories: process(&mut parent_acc.main_dirs),
+            test_source_directories: process(&mut parent_acc.test_dirs),
+        }
+    }
+}
+
+impl From<GradleModuleHelper> for GradleModuleAcc {
+    fn from(helper: GradleModuleHelper) -> Self {
+        GradleModuleAcc::with_content(
+            helper.name,
+            helper.submodules.1,
+            helper.source_directories.1,
+            helper.test_source_directories.1,
+        )
+    }
+}
+
+fn drain_filter_strip(v: &mut Option<Vec<PathBuf>>, name: &[u8]) -> Vec<PathBuf> {
+    let mut new_sub_modules = vec![];
+    let name = std::str::from_utf8(&name).unwrap();
+    if let Some(sub_modules) = v {
+        sub_modules
+            .extract_if(|x| x.starts_with(name))
+            .for_each(|x| {
+                let x = x.strip_prefix(name).unwrap().to_owned();
+                new_sub_modules.push(x);
+            });
+    }
+    new_sub_modules
+}
+
+/// sometimes order of files/dirs can be important, similarly to order of statement
+/// exploration order for example
+pub(crate) fn prepare_dir_exploration(
+    tree: git2::Tree,
+    dir_path: &mut Peekable<Components>,
+) -> Vec<BasicGitObject> {
+    let mut children_objects: Vec<BasicGitObject> = tree
+        .iter()
+        .map(TryInto::try_into)
+        .filter_map(|x| x.ok())
+        .collect();
+    if dir_path.peek().is_none() {
+        // priority to the settings then to the build script, before any directory
+        use crate::processing::file_sys::{GradleBuild, GradleSettings};
+        children_objects.sort_by_key(|x| match x {
+            BasicGitObject::Blob(_, n) if GradleSettings::matches(n) => 0,
+            BasicGitObject::Blob(_, n) if GradleBuild::matches(n) => 1,
+            _ => 2,
+        });
+        children_objects.reverse(); // we use it like a stack
+    }
+    children_objects
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct Parameter;
+
+// # settings.gradle and build.gradle
+
+impl From<crate::processing::erased::ParametrizedCommitProcessor2Handle<GradleProc>>
+    for crate::processing::erased::ParametrizedCommitProcessor2Handle<GradleScriptProc>
+{
+    fn from(
+        value: crate::processing::erased::ParametrizedCommitProcessor2Handle<GradleProc>,
+    ) -> Self {
+        crate::processing::erased::ParametrizedCommitProcessor2Handle(
+            value.0,
+            std::marker::PhantomData,
+        )
+    }
+}
+struct GradleScriptProcessorHolder(Option<GradleScriptProc>);
+impl Default for GradleScriptProcessorHolder {
+    fn default() -> Self {
+        Self(Some(GradleScriptProc {
+            parameter: Parameter,
+            settings_cache: Default::default(),
+            build_cache: Default::default(),
+        }))
+    }
+}
+/// Both kinds of gradle scripts are cached by the same processor
+struct GradleScriptProc {
+    parameter: Parameter,
+    settings_cache: crate::processing::caches::GradleSettings,
+    build_cache: crate::processing::caches::GradleBuild,
+}
+impl crate::processing::erased::Parametrized for GradleScriptProcessorHolder {
+    type T = Parameter;
+    fn register_param(
+        &mut self,
+        t: Self::T,
+    ) -> crate::processing::erased::ParametrizedCommitProcessorHandle {
+        let l = self
+            .0
+            .iter()
+            .position(|x| &x.parameter == &t)
+            .unwrap_or_else(|| {
+                self.0 = Some(GradleScriptProc {
+                    parameter: t,
+                    settings_cache: Default::default(),
+                    build_cache: Default::default(),
+                });
+                0
+            });
+        use crate::processing::erased::ConfigParametersHandle;
+        use crate::processing::erased::ParametrizedCommitProc;
+        use crate::processing::erased::ParametrizedCommitProcessorHandle;
+        ParametrizedCommitProcessorHandle(self.erased_handle(), ConfigParametersHandle(l))
+    }
+}
+// TODO should not have to impl this trait
+impl crate::processing::erased::CommitProc for GradleScriptProc {
+    fn process_root_tree(
+        &mut self,
+        repository: &git2::Repository,
+        tree_oid: &git2::Oid,
+    ) -> hyper_ast::store::defaults::NodeIdentifier {
+        unimplemented!()
+    }
+
+    fn prepare_processing(
+        &self,
+        repository: &git2::Repository,
+        commit_builder: crate::preprocessed::CommitBuilder,
+    ) -> Box<dyn crate::processing::erased::PreparedCommitProc> {
+        unimplemented!()
+    }
+
+    fn get_commit(&self, commit_oid: git2::Oid) -> Option<&crate::Commit> {
+        unimplemented!()
+    }
+}
+
+impl crate::processing::erased::CommitProcExt for GradleScriptProc {
+    type Holder = GradleScriptProcessorHolder;
+}
+impl crate::processing::erased::ParametrizedCommitProc2 for GradleScriptProcessorHolder {
+    type Proc = GradleScriptProc;
+
+    fn with_parameters_mut(
+        &mut self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &mut Self::Proc {
+        assert_eq!(0, parameters.0);
+        self.0.as_mut().unwrap()
+    }
+
+    fn with_parameters(
+        &self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &Self::Proc {
+        assert_eq!(0, parameters.0);
+        self.0.as_ref().unwrap()
+    }
+}
+impl CacheHolding<crate::processing::caches::GradleSettings> for GradleScriptProc {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::GradleSettings {
+        &mut self.settings_cache
+    }
+    fn get_caches(&self) -> &crate::processing::caches::GradleSettings {
+        &self.settings_cache
+    }
+}
+impl CacheHolding<crate::processing::caches::GradleBuild> for GradleScriptProc {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::GradleBuild {
+        &mut self.build_cache
+    }
+    fn get_caches(&self) -> &crate::processing::caches::GradleBuild {
+        &self.build_cache
+    }
+}
+
+// # Gradle
+#[derive(Default)]
+pub struct GradleProcessorHolder(Option<GradleProc>);
+pub struct GradleProc {
+    parameter: Parameter,
+    cache: crate::processing::caches::Gradle,
+    commits: std::collections::HashMap<git2::Oid, crate::Commit>,
+}
+impl crate::processing::erased::Parametrized for GradleProcessorHolder {
+    type T = Parameter;
+    fn register_param(
+        &mut self,
+        t: Self::T,
+    ) -> crate::processing::erased::ParametrizedCommitProcessorHandle {
+        let l = self
+            .0
+            .iter()
+            .position(|x| &x.parameter == &t)
+            .unwrap_or_else(|| {
+                self.0 = Some(GradleProc {
+                    parameter: t,
+                    cache: Default::default(),
+                    commits: Default::default(),
+                });
+                0
+            });
+        use crate::processing::erased::ConfigParametersHandle;
+        use crate::processing::erased::ParametrizedCommitProc;
+        use crate::processing::erased::ParametrizedCommitProcessorHandle;
+        ParametrizedCommitProcessorHandle(self.erased_handle(), ConfigParametersHandle(l))
+    }
+}
+
+struct PreparedGradleCommitProc<'repo> {
+    repository: &'repo git2::Repository,
+    commit_builder: crate::preprocessed::CommitBuilder,
+}
+impl<'repo> crate::processing::erased::PreparedCommitProc for PreparedGradleCommitProc<'repo> {
+    fn process(
+        self: Box<PreparedGradleCommitProc<'repo>>,
+        prepro: &mut RepositoryProcessor,
+    ) -> hyper_ast::store::defaults::NodeIdentifier {
+        let dir_path = PathBuf::from("");
+        let mut dir_path = dir_path.components().peekable();
+        let name = b"";
+        let root_full_node = GradleProcessor::<true, false, GradleModuleAcc>::new(
+            self.repository,
+            prepro,
+            &mut dir_path,
+            name,
+            self.commit_builder.tree_oid(),
+        )
+        .process();
+        let h = prepro
+            .processing_systems
+            .mut_or_default::<GradleProcessorHolder>();
+        let handle =
+            <GradleProc as crate::processing::erased::CommitProcExt>::register_param(h, Parameter);
+        let commit_oid = self.commit_builder.commit_oid();
+        let commit = self.commit_builder.finish(root_full_node.0);
+        h.with_parameters_mut(handle.0)
+            .commits
+            .insert(commit_oid, commit);
+        root_full_node.0
+    }
+}
+impl crate::processing::erased::CommitProc for GradleProc {
+    fn process_root_tree(
+        &mut self,
+        repository: &git2::Repository,
+        tree_oid: &git2::Oid,
+    ) -> hyper_ast::store::defaults::NodeIdentifier {
+        unimplemented!("see reason in the java proc")
+    }
+
+    fn prepare_processing<'repo>(
+        &self,
+        repository: &'repo git2::Repository,
+        commit_builder: crate::preprocessed::CommitBuilder,
+    ) -> Box<dyn crate::processing::erased::PreparedCommitProc + 'repo> {
+        Box::new(PreparedGradleCommitProc {
+            repository,
+            commit_builder,
+        })
+    }
+
+    fn get_commit(&self, commit_oid: git2::Oid) -> Option<&crate::Commit> {
+        self.commits.get(&commit_oid)
+    }
+}
+
+impl GradleProcessorHolder {
+    pub(crate) fn commits(&self) -> Option<&std::collections::HashMap<git2::Oid, crate::Commit>> {
+        self.0.as_ref().map(|x| &x.commits)
+    }
+}
+impl GradleProc {
+    pub(crate) fn commits_mut(
+        &mut self,
+    ) -> &mut std::collections::HashMap<git2::Oid, crate::Commit> {
+        &mut self.commits
+    }
+}
+impl crate::processing::erased::CommitProcExt for GradleProc {
+    type Holder = GradleProcessorHolder;
+}
+impl crate::processing::erased::ParametrizedCommitProc2 for GradleProcessorHolder {
+    type Proc = GradleProc;
+
+    fn with_parameters_mut(
+        &mut self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &mut Self::Proc {
+        assert_eq!(0, parameters.0);
+        self.0.as_mut().unwrap()
+    }
+
+    fn with_parameters(
+        &self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &Self::Proc {
+        assert_eq!(0, parameters.0);
+        self.0.as_ref().unwrap()
+    }
+}
+impl CacheHolding<crate::processing::caches::Gradle> for GradleProc {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::Gradle {
+        &mut self.cache
+    }
+    fn get_caches(&self) -> &crate::processing::caches::Gradle {
+        &self.cache
+    }
+}
+impl CacheHolding<crate::processing::caches::Gradle> for GradleProcessorHolder {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::Gradle {
+        &mut self.0.as_mut().unwrap().cache
+    }
+    fn get_caches(&self) -> &crate::processing::caches::Gradle {
+        &self.0.as_ref().unwrap().cache
+    }
+}
@@ -17,7 +17,19 @@ pub(crate) fn handle_java_file<'stores, 'cache, 'b: 'stores>(
     name: &ObjectName,
     text: &'b [u8],
 ) -> Result<java_tree_gen::FNode, ()> {
-    let tree = match java_tree_gen::JavaTreeGen::<TStore>::tree_sitter_parse(text) {
+    let tree = java_tree_gen::JavaTreeGen::<TStore>::tree_sitter_parse(text);
+    handle_java_tree(tree_gen, name, text, tree)
+}
+
+/// Same as [`handle_java_file`] but with an already parsed `text`,
+/// eg. by [`crate::parallel::parse_blobs`].
+pub(crate) fn handle_java_tree<'stores, 'cache, 'b: 'stores>(
+    tree_gen: &mut java_tree_gen::JavaTreeGen<'stores, 'cache, TStore>,
+    name: &ObjectName,
+    text: &'b [u8],
+    tree: crate::parallel::Parsed,
+) -> Result<java_tree_gen::FNode, ()> {
+    let tree = match tree {
         Ok(tree) => tree,
         Err(tree) => {
             log::warn!("bad CST");
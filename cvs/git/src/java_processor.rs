@@ -9,7 +9,7 @@ use hyper_ast::{
         defaults::{LabelIdentifier, NodeIdentifier},
         nodes::legion::{compo, compo::CS, NodeStore, PendingInsert},
     },
-    tree_gen::SubTreeMetrics,
+    tree_gen::{derived::DerivedMetrics, SubTreeMetrics},
     types::LabelStore,
 };
 use hyper_ast_gen_ts_java::types::Type;
@@ -347,6 +347,8 @@ pub(crate) struct JavaProc {
     parameter: Parameter,
     cache: crate::processing::caches::Java,
     commits: std::collections::HashMap<git2::Oid, crate::Commit>,
+    /// see [`RepositoryProcessor::java_derived_metrics_mut`]
+    derived: DerivedMetrics<Type>,
 }
 impl crate::processing::erased::Parametrized for JavaProcessorHolder {
     type T = Parameter;
@@ -365,6 +367,7 @@ impl crate::processing::erased::Parametrized for JavaProcessorHolder {
                     parameter: t,
                     cache: Default::default(),
                     commits: Default::default(),
+                    derived: Default::default(),
                 });
                 l
             });
@@ -455,15 +458,15 @@ impl RepositoryProcessor {
         } else {
             "\n".as_bytes().to_vec()
         };
-        java_tree_gen::JavaTreeGen {
-            line_break,
-            stores: &mut self.main_stores,
-            md_cache: &mut self
-                .processing_systems
-                .mut_or_default::<JavaProcessorHolder>()
-                .get_caches_mut()
-                .md_cache, //java_md_cache,
-        }
+        let JavaProc { cache, derived, .. } = self
+            .processing_systems
+            .mut_or_default::<JavaProcessorHolder>()
+            .0
+            .as_mut()
+            .unwrap();
+        java_tree_gen::JavaTreeGen::new(&mut self.main_stores, &mut cache.md_cache)
+            .with_line_break(line_break)
+            .with_derived(derived)
     }
 
     pub(crate) fn help_handle_java_folder<'a, 'b, 'c, 'd: 'c>(
@@ -493,17 +496,23 @@ impl RepositoryProcessor {
                 } else {
                     "\n".as_bytes().to_vec()
                 };
-                crate::java::handle_java_file(
-                    &mut java_tree_gen::JavaTreeGen {
-                        line_break,
-                        stores: &mut self.main_stores,
-                        md_cache: &mut c
-                            .mut_or_default::<JavaProcessorHolder>()
-                            .get_caches_mut()
-                            .md_cache, //java_md_cache,
-                    },
+                let proc = c
+                    .mut_or_default::<JavaProcessorHolder>()
+                    .with_parameters_mut(parameters.0);
+                let caches = &mut proc.cache;
+                let tree = caches.parsed.remove(&oid).unwrap_or_else(|| {
+                    java_tree_gen::JavaTreeGen::<crate::TStore>::tree_sitter_parse(t)
+                });
+                crate::java::handle_java_tree(
+                    &mut java_tree_gen::JavaTreeGen::new(
+                        &mut self.main_stores,
+                        &mut caches.md_cache,
+                    )
+                    .with_line_break(line_break)
+                    .with_derived(&proc.derived),
                     n,
                     t,
+                    tree,
                 )
                 .map_err(|_| crate::ParseErr::IllFormed)
                 .map(|x| (x.local.clone(), false))
@@ -572,6 +581,27 @@ impl RepositoryProcessor {
         let handle = JavaProc::register_param(h, Parameter);
         JavaProcessor::<JavaAcc>::new(repository, self, dir_path, name, oid, &handle).process()
     }
+
+    /// Java files parsed in advance, they are taken by [`Self::handle_java_blob`] instead of parsing again.
+    pub(crate) fn parsed_java_files(
+        &mut self,
+    ) -> &mut crate::processing::caches::OidMap<crate::parallel::Parsed> {
+        let h = self
+            .processing_systems
+            .mut_or_default::<JavaProcessorHolder>();
+        let handle = JavaProc::register_param(h, Parameter);
+        &mut h.with_parameters_mut(handle.0).cache.parsed
+    }
+
+    /// Metrics computed on each generated Java node, see [`DerivedMetrics`].
+    /// Register them before processing commits, files already processed are not updated.
+    pub fn java_derived_metrics_mut(&mut self) -> &mut DerivedMetrics<Type> {
+        let h = self
+            .processing_systems
+            .mut_or_default::<JavaProcessorHolder>();
+        let handle = JavaProc::register_param(h, Parameter);
+        &mut h.with_parameters_mut(handle.0).derived
+    }
 }
 
 // TODO try to separate processing from caching from git
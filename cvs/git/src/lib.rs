@@ -3,22 +3,49 @@
 #![feature(trait_upcasting)]
 #![feature(os_str_bytes)]
 pub mod allrefs;
+pub mod cargo;
+pub mod cmake;
 pub mod cpp;
 pub mod git;
+pub mod gradle;
 pub mod java;
 pub mod make;
 pub mod maven;
-
+pub mod npm;
+pub mod pip;
+pub mod python;
+pub mod rust;
+pub mod ts;
+
+#[cfg(any(feature = "ts", feature = "python", feature = "rust"))]
+pub mod basic_processor;
+#[cfg(feature = "cargo")]
+pub mod cargo_processor;
+#[cfg(feature = "cmake")]
+pub mod cmake_processor;
 #[cfg(feature = "cpp")]
 pub mod cpp_processor;
+#[cfg(feature = "gradle")]
+pub mod gradle_processor;
 #[cfg(feature = "java")]
 pub mod java_processor;
 #[cfg(feature = "make")]
 pub mod make_processor;
 #[cfg(feature = "maven")]
 pub mod maven_processor;
+#[cfg(feature = "npm")]
+pub mod npm_processor;
+#[cfg(feature = "pip")]
+pub mod pip_processor;
+#[cfg(feature = "python")]
+pub mod python_processor;
+#[cfg(feature = "rust")]
+pub mod rust_processor;
+#[cfg(feature = "ts")]
+pub mod ts_processor;
 pub mod multi_preprocessed;
 pub mod no_space;
+pub mod parallel;
 /// for now only tested on maven repositories with a pom in root.
 pub mod preprocessed;
 pub mod processing;
@@ -64,6 +91,35 @@ impl Commit {
         self.memory_used
     }
 }
+/// The components of the stores used to persist repositories processed by this crate,
+/// ie. the generic ones and the types of each supported language.
+pub fn persist_registry() -> hyper_ast::store::nodes::legion::persist::PersistRegistry {
+    let mut r = hyper_ast::store::nodes::legion::persist::PersistRegistry::new();
+    #[cfg(feature = "java")]
+    r.register::<hyper_ast_gen_ts_java::types::Type>("java::Type");
+    #[cfg(feature = "cpp")]
+    r.register::<hyper_ast_gen_ts_cpp::types::Type>("cpp::Type");
+    // the build systems also make xml nodes, eg. as placeholders of their manifests
+    #[cfg(any(
+        feature = "maven",
+        feature = "gradle",
+        feature = "cmake",
+        feature = "npm",
+        feature = "pip",
+        feature = "cargo"
+    ))]
+    r.register::<hyper_ast_gen_ts_xml::types::Type>("xml::Type");
+    #[cfg(feature = "cmake")]
+    r.register::<cmake::Targets>("cmake::Targets");
+    #[cfg(feature = "ts")]
+    r.register::<hyper_ast_gen_ts_typescript::types::Type>("ts::Type");
+    #[cfg(feature = "python")]
+    r.register::<hyper_ast_gen_ts_python::types::Type>("python::Type");
+    #[cfg(feature = "rust")]
+    r.register::<hyper_ast_gen_ts_rust::types::Type>("rust::Type");
+    r
+}
+
 trait Accumulator: hyper_ast::tree_gen::Accumulator<Node = (LabelIdentifier, Self::Unlabeled)> {
     type Unlabeled;
     // fn push(&mut self, name: LabelIdentifier, full_node: Self::Node);
@@ -117,6 +173,12 @@ mod type_store {
     use hyper_ast_gen_ts_cpp::types::CppEnabledTypeStore;
     #[cfg(feature = "java")]
     use hyper_ast_gen_ts_java::types::JavaEnabledTypeStore;
+    #[cfg(feature = "python")]
+    use hyper_ast_gen_ts_python::types::PythonEnabledTypeStore;
+    #[cfg(feature = "rust")]
+    use hyper_ast_gen_ts_rust::types::RustEnabledTypeStore;
+    #[cfg(feature = "ts")]
+    use hyper_ast_gen_ts_typescript::types::TsEnabledTypeStore;
     #[cfg(feature = "maven")]
     use hyper_ast_gen_ts_xml::types::XmlEnabledTypeStore;
 
@@ -127,6 +189,9 @@ mod type_store {
         Maven = 0,
         Java = 1,
         Cpp = 2,
+        Ts = 3,
+        Python = 4,
+        Rust = 5,
     }
 
     impl Default for TStore {
@@ -150,6 +215,18 @@ mod type_store {
                 let t = *t as u16;
                 let t = <hyper_ast_gen_ts_cpp::types::Cpp as hyper_ast::types::Lang<_>>::make(t);
                 From::<&'static (dyn HyperType)>::from(t)
+            } else if let Ok(t) = n.get_component::<hyper_ast_gen_ts_typescript::types::Type>() {
+                let t = *t as u16;
+                let t = <hyper_ast_gen_ts_typescript::types::Ts as hyper_ast::types::Lang<_>>::make(t);
+                From::<&'static (dyn HyperType)>::from(t)
+            } else if let Ok(t) = n.get_component::<hyper_ast_gen_ts_python::types::Type>() {
+                let t = *t as u16;
+                let t = <hyper_ast_gen_ts_python::types::Python as hyper_ast::types::Lang<_>>::make(t);
+                From::<&'static (dyn HyperType)>::from(t)
+            } else if let Ok(t) = n.get_component::<hyper_ast_gen_ts_rust::types::Type>() {
+                let t = *t as u16;
+                let t = <hyper_ast_gen_ts_rust::types::Rust as hyper_ast::types::Lang<_>>::make(t);
+                From::<&'static (dyn HyperType)>::from(t)
             } else if let Ok(t) = n.get_component::<hyper_ast_gen_ts_xml::types::Type>() {
                 let t = *t as u16;
                 let t = <hyper_ast_gen_ts_xml::types::Xml as hyper_ast::types::Lang<_>>::make(t);
@@ -168,6 +245,12 @@ mod type_store {
                 From::<&'static (dyn LangRef<AnyType>)>::from(&hyper_ast_gen_ts_java::types::Java)
             } else if let Ok(t) = n.get_component::<hyper_ast_gen_ts_cpp::types::Type>() {
                 From::<&'static (dyn LangRef<AnyType>)>::from(&hyper_ast_gen_ts_cpp::types::Cpp)
+            } else if let Ok(t) = n.get_component::<hyper_ast_gen_ts_typescript::types::Type>() {
+                From::<&'static (dyn LangRef<AnyType>)>::from(&hyper_ast_gen_ts_typescript::types::Ts)
+            } else if let Ok(t) = n.get_component::<hyper_ast_gen_ts_python::types::Type>() {
+                From::<&'static (dyn LangRef<AnyType>)>::from(&hyper_ast_gen_ts_python::types::Python)
+            } else if let Ok(t) = n.get_component::<hyper_ast_gen_ts_rust::types::Type>() {
+                From::<&'static (dyn LangRef<AnyType>)>::from(&hyper_ast_gen_ts_rust::types::Rust)
             } else if let Ok(t) = n.get_component::<hyper_ast_gen_ts_xml::types::Type>() {
                 From::<&'static (dyn LangRef<AnyType>)>::from(&hyper_ast_gen_ts_xml::types::Xml)
             } else {
@@ -195,6 +278,30 @@ mod type_store {
                 );
                 let ty = *t as u16;
                 TypeIndex { lang, ty }
+            } else if let Ok(t) = n.get_component::<hyper_ast_gen_ts_typescript::types::Type>() {
+                let t = *t as u16;
+                let t = <hyper_ast_gen_ts_typescript::types::Ts as hyper_ast::types::Lang<_>>::make(t);
+                let lang = hyper_ast::types::LangRef::<hyper_ast_gen_ts_typescript::types::Type>::name(
+                    &hyper_ast_gen_ts_typescript::types::Ts,
+                );
+                let ty = *t as u16;
+                TypeIndex { lang, ty }
+            } else if let Ok(t) = n.get_component::<hyper_ast_gen_ts_python::types::Type>() {
+                let t = *t as u16;
+                let t = <hyper_ast_gen_ts_python::types::Python as hyper_ast::types::Lang<_>>::make(t);
+                let lang = hyper_ast::types::LangRef::<hyper_ast_gen_ts_python::types::Type>::name(
+                    &hyper_ast_gen_ts_python::types::Python,
+                );
+                let ty = *t as u16;
+                TypeIndex { lang, ty }
+            } else if let Ok(t) = n.get_component::<hyper_ast_gen_ts_rust::types::Type>() {
+                let t = *t as u16;
+                let t = <hyper_ast_gen_ts_rust::types::Rust as hyper_ast::types::Lang<_>>::make(t);
+                let lang = hyper_ast::types::LangRef::<hyper_ast_gen_ts_rust::types::Type>::name(
+                    &hyper_ast_gen_ts_rust::types::Rust,
+                );
+                let ty = *t as u16;
+                TypeIndex { lang, ty }
             } else if let Ok(t) = n.get_component::<hyper_ast_gen_ts_xml::types::Type>() {
                 let t = *t as u16;
                 let t = <hyper_ast_gen_ts_xml::types::Xml as hyper_ast::types::Lang<_>>::make(t);
@@ -491,10 +598,145 @@ mod type_store {
         }
     }
 
+    impl<'a> TypeStore<HashedNodeRef<'a, hyper_ast_gen_ts_typescript::types::TIdN<NodeIdentifier>>>
+        for TStore
+    {
+        type Ty = hyper_ast_gen_ts_typescript::types::Type;
+        const MASK: TypeInternalSize = 0b1000_0000_0000_0000;
+
+        fn resolve_type(
+            &self,
+            n: &HashedNodeRef<'a, hyper_ast_gen_ts_typescript::types::TIdN<NodeIdentifier>>,
+        ) -> Self::Ty {
+            todo!()
+        }
+
+        fn resolve_lang(
+            &self,
+            n: &HashedNodeRef<'a, hyper_ast_gen_ts_typescript::types::TIdN<NodeIdentifier>>,
+        ) -> hyper_ast::types::LangWrapper<Self::Ty> {
+            todo!()
+        }
+
+        type Marshaled = TypeIndex;
+
+        fn marshal_type(
+            &self,
+            n: &HashedNodeRef<'a, hyper_ast_gen_ts_typescript::types::TIdN<NodeIdentifier>>,
+        ) -> Self::Marshaled {
+            todo!()
+        }
+    }
+    impl<'a>
+        TsEnabledTypeStore<HashedNodeRef<'a, hyper_ast_gen_ts_typescript::types::TIdN<NodeIdentifier>>>
+        for TStore
+    {
+        const LANG: u16 = 0;
+
+        fn _intern(l: u16, t: u16) -> Self::Ty {
+            hyper_ast_gen_ts_typescript::types::Type::resolve(t)
+        }
+
+        fn resolve(&self, t: Self::Ty) -> hyper_ast_gen_ts_typescript::types::Type {
+            todo!()
+        }
+    }
+
+    impl<'a> TypeStore<HashedNodeRef<'a, hyper_ast_gen_ts_python::types::TIdN<NodeIdentifier>>>
+        for TStore
+    {
+        type Ty = hyper_ast_gen_ts_python::types::Type;
+        const MASK: TypeInternalSize = 0b1000_0000_0000_0000;
+
+        fn resolve_type(
+            &self,
+            n: &HashedNodeRef<'a, hyper_ast_gen_ts_python::types::TIdN<NodeIdentifier>>,
+        ) -> Self::Ty {
+            todo!()
+        }
+
+        fn resolve_lang(
+            &self,
+            n: &HashedNodeRef<'a, hyper_ast_gen_ts_python::types::TIdN<NodeIdentifier>>,
+        ) -> hyper_ast::types::LangWrapper<Self::Ty> {
+            todo!()
+        }
+
+        type Marshaled = TypeIndex;
+
+        fn marshal_type(
+            &self,
+            n: &HashedNodeRef<'a, hyper_ast_gen_ts_python::types::TIdN<NodeIdentifier>>,
+        ) -> Self::Marshaled {
+            todo!()
+        }
+    }
+    impl<'a>
+        PythonEnabledTypeStore<HashedNodeRef<'a, hyper_ast_gen_ts_python::types::TIdN<NodeIdentifier>>>
+        for TStore
+    {
+        const LANG: u16 = 0;
+
+        fn _intern(l: u16, t: u16) -> Self::Ty {
+            hyper_ast_gen_ts_python::types::Type::resolve(t)
+        }
+
+        fn resolve(&self, t: Self::Ty) -> hyper_ast_gen_ts_python::types::Type {
+            todo!()
+        }
+    }
+
+    impl<'a> TypeStore<HashedNodeRef<'a, hyper_ast_gen_ts_rust::types::TIdN<NodeIdentifier>>>
+        for TStore
+    {
+        type Ty = hyper_ast_gen_ts_rust::types::Type;
+        const MASK: TypeInternalSize = 0b1000_0000_0000_0000;
+
+        fn resolve_type(
+            &self,
+            n: &HashedNodeRef<'a, hyper_ast_gen_ts_rust::types::TIdN<NodeIdentifier>>,
+        ) -> Self::Ty {
+            todo!()
+        }
+
+        fn resolve_lang(
+            &self,
+            n: &HashedNodeRef<'a, hyper_ast_gen_ts_rust::types::TIdN<NodeIdentifier>>,
+        ) -> hyper_ast::types::LangWrapper<Self::Ty> {
+            todo!()
+        }
+
+        type Marshaled = TypeIndex;
+
+        fn marshal_type(
+            &self,
+            n: &HashedNodeRef<'a, hyper_ast_gen_ts_rust::types::TIdN<NodeIdentifier>>,
+        ) -> Self::Marshaled {
+            todo!()
+        }
+    }
+    impl<'a>
+        RustEnabledTypeStore<HashedNodeRef<'a, hyper_ast_gen_ts_rust::types::TIdN<NodeIdentifier>>>
+        for TStore
+    {
+        const LANG: u16 = 0;
+
+        fn _intern(l: u16, t: u16) -> Self::Ty {
+            hyper_ast_gen_ts_rust::types::Type::resolve(t)
+        }
+
+        fn resolve(&self, t: Self::Ty) -> hyper_ast_gen_ts_rust::types::Type {
+            todo!()
+        }
+    }
+
     #[derive(Clone, Copy, Debug)]
     pub enum MultiType {
         Java(hyper_ast_gen_ts_java::types::Type),
         Cpp(hyper_ast_gen_ts_cpp::types::Type),
+        Ts(hyper_ast_gen_ts_typescript::types::Type),
+        Python(hyper_ast_gen_ts_python::types::Type),
+        Rust(hyper_ast_gen_ts_rust::types::Type),
         Xml(hyper_ast_gen_ts_xml::types::Type),
     }
 
@@ -505,6 +747,9 @@ mod type_store {
             match (self, other) {
                 (MultiType::Java(s), MultiType::Java(o)) => s == o,
                 (MultiType::Cpp(s), MultiType::Cpp(o)) => s == o,
+                (MultiType::Ts(s), MultiType::Ts(o)) => s == o,
+                (MultiType::Python(s), MultiType::Python(o)) => s == o,
+                (MultiType::Rust(s), MultiType::Rust(o)) => s == o,
                 (MultiType::Xml(s), MultiType::Xml(o)) => s == o,
                 _ => false,
             }
@@ -516,6 +761,9 @@ mod type_store {
             match self {
                 MultiType::Java(t) => t.hash(state),
                 MultiType::Cpp(t) => t.hash(state),
+                MultiType::Ts(t) => t.hash(state),
+                MultiType::Python(t) => t.hash(state),
+                MultiType::Rust(t) => t.hash(state),
                 MultiType::Xml(t) => t.hash(state),
             }
         }
@@ -525,6 +773,9 @@ mod type_store {
             match self {
                 MultiType::Java(t) => std::fmt::Display::fmt(t, f),
                 MultiType::Cpp(t) => std::fmt::Display::fmt(t, f),
+                MultiType::Ts(t) => std::fmt::Display::fmt(t, f),
+                MultiType::Python(t) => std::fmt::Display::fmt(t, f),
+                MultiType::Rust(t) => std::fmt::Display::fmt(t, f),
                 MultiType::Xml(t) => std::fmt::Display::fmt(t, f),
             }
         }
@@ -535,6 +786,9 @@ mod type_store {
             match self {
                 MultiType::Java(t) => t.is_file(),
                 MultiType::Cpp(t) => t.is_file(),
+                MultiType::Ts(t) => t.is_file(),
+                MultiType::Python(t) => t.is_file(),
+                MultiType::Rust(t) => t.is_file(),
                 MultiType::Xml(t) => t.is_file(),
             }
         }
@@ -543,6 +797,9 @@ mod type_store {
             match self {
                 MultiType::Java(t) => t.is_file(),
                 MultiType::Cpp(t) => t.is_file(),
+                MultiType::Ts(t) => t.is_file(),
+                MultiType::Python(t) => t.is_file(),
+                MultiType::Rust(t) => t.is_file(),
                 MultiType::Xml(t) => t.is_file(),
             }
         }
@@ -551,6 +808,9 @@ mod type_store {
             match self {
                 MultiType::Java(t) => t.is_spaces(),
                 MultiType::Cpp(t) => t.is_spaces(),
+                MultiType::Ts(t) => t.is_spaces(),
+                MultiType::Python(t) => t.is_spaces(),
+                MultiType::Rust(t) => t.is_spaces(),
                 MultiType::Xml(t) => t.is_spaces(),
             }
         }
@@ -559,6 +819,9 @@ mod type_store {
             match self {
                 MultiType::Java(t) => t.is_syntax(),
                 MultiType::Cpp(t) => t.is_syntax(),
+                MultiType::Ts(t) => t.is_syntax(),
+                MultiType::Python(t) => t.is_syntax(),
+                MultiType::Rust(t) => t.is_syntax(),
                 MultiType::Xml(t) => t.is_syntax(),
             }
         }
@@ -567,6 +830,9 @@ mod type_store {
             match self {
                 MultiType::Java(t) => t.as_shared(),
                 MultiType::Cpp(t) => t.as_shared(),
+                MultiType::Ts(t) => t.as_shared(),
+                MultiType::Python(t) => t.as_shared(),
+                MultiType::Rust(t) => t.as_shared(),
                 MultiType::Xml(t) => t.as_shared(),
             }
         }
@@ -575,6 +841,9 @@ mod type_store {
             match self {
                 MultiType::Java(t) => t.as_any(),
                 MultiType::Cpp(t) => t.as_any(),
+                MultiType::Ts(t) => t.as_any(),
+                MultiType::Python(t) => t.as_any(),
+                MultiType::Rust(t) => t.as_any(),
                 MultiType::Xml(t) => t.as_any(),
             }
         }
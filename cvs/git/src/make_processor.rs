@@ -645,6 +645,16 @@ impl crate::processing::erased::CommitProc for MakeProc {
     }
 }
 
+impl MakeProcessorHolder {
+    pub(crate) fn commits(&self) -> Option<&std::collections::HashMap<git2::Oid, crate::Commit>> {
+        self.0.as_ref().map(|x| &x.commits)
+    }
+}
+impl MakeProc {
+    pub(crate) fn commits_mut(&mut self) -> &mut std::collections::HashMap<git2::Oid, crate::Commit> {
+        &mut self.commits
+    }
+}
 impl crate::processing::erased::CommitProcExt for MakeProc {
     type Holder = MakeProcessorHolder;
 }
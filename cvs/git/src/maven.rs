@@ -166,10 +166,9 @@ impl<'a> IterMavenModules2<'a> {
                                 "f name: {:?}",
                                 self.stores.label_store.resolve(n.get_label_unchecked())
                             );
-                            self.stores
-                                .label_store
-                                .resolve(n.get_label_unchecked())
-                                .eq("pom.xml")
+                            is_module_config_file(
+                                self.stores.label_store.resolve(n.get_label_unchecked()),
+                            )
                         } else {
                             false
                         }
@@ -192,6 +191,12 @@ impl<'a> IterMavenModules2<'a> {
     }
 }
 
+/// Config files identifying a module,
+/// gradle projects are also made of maven directories, see [`crate::gradle_processor`].
+fn is_module_config_file(name: &str) -> bool {
+    name == "pom.xml" || name == "build.gradle" || name == "build.gradle.kts"
+}
+
 #[derive(Debug, Clone)]
 pub struct MD {
     pub(crate) metrics: DefaultMetrics,
@@ -494,10 +499,9 @@ impl<'a, T: TreePath<NodeIdentifier>> IterMavenModules<'a, T> {
                                 "f name: {:?}",
                                 self.stores.label_store.resolve(n.get_label_unchecked())
                             );
-                            self.stores
-                                .label_store
-                                .resolve(n.get_label_unchecked())
-                                .eq("pom.xml")
+                            is_module_config_file(
+                                self.stores.label_store.resolve(n.get_label_unchecked()),
+                            )
                         } else {
                             false
                         }
@@ -3,8 +3,19 @@ use std::{
     marker::PhantomData,
 };
 
+use std::io::{self, Read, Write};
+
 use git2::Repository;
-use hyper_ast::store::nodes::DefaultNodeIdentifier as NodeIdentifier;
+use hyper_ast::store::{
+    nodes::{
+        legion::persist::{LoadedIds, SavedIds},
+        DefaultNodeIdentifier as NodeIdentifier,
+    },
+    persist::{
+        invalid_data, read_header, read_u128, read_u32, read_u64, write_header, write_u128,
+        write_u32, write_u64,
+    },
+};
 
 use crate::{
     git::{all_commits_between, Repo},
@@ -32,8 +43,12 @@ pub struct PreProcessedRepositories {
 #[derive(Default)]
 pub struct CommitsPerSys {
     pub maven: HashMap<git2::Oid, Commit>,
+    pub gradle: HashMap<git2::Oid, Commit>,
     pub make: HashMap<git2::Oid, Commit>,
+    pub cmake: HashMap<git2::Oid, Commit>,
     pub npm: HashMap<git2::Oid, Commit>,
+    pub pip: HashMap<git2::Oid, Commit>,
+    pub cargo: HashMap<git2::Oid, Commit>,
     pub any: HashMap<git2::Oid, Commit>,
 }
 
@@ -49,13 +64,106 @@ impl CommitsPerSys {
     pub fn accessCommits<'a>(&'a self, sys: &RepoConfig) -> &'a HashMap<git2::Oid, Commit> {
         match sys {
             RepoConfig::JavaMaven => &self.maven,
+            RepoConfig::JavaGradle => &self.gradle,
             RepoConfig::CppMake => &self.make,
+            RepoConfig::CppCMake => &self.cmake,
             RepoConfig::TsNpm => &self.npm,
+            RepoConfig::PythonPip => &self.pip,
+            RepoConfig::RustCargo => &self.cargo,
             RepoConfig::Any => &self.any,
         }
     }
 }
 
+const COMMITS_MAGIC: &[u8; 4] = b"HAcs";
+
+impl CommitsPerSys {
+    /// `ids` must come from the node store holding the roots of the commits.
+    pub fn save<W: Write>(&self, w: &mut W, ids: &SavedIds) -> io::Result<()> {
+        write_header(w, COMMITS_MAGIC)?;
+        for commits in [
+            &self.maven,
+            &self.gradle,
+            &self.make,
+            &self.cmake,
+            &self.npm,
+            &self.pip,
+            &self.cargo,
+            &self.any,
+        ] {
+            write_u64(w, commits.len() as u64)?;
+            for (oid, commit) in commits {
+                w.write_all(oid.as_bytes())?;
+                commit.save(w, ids)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn load<R: Read>(r: &mut R, ids: &LoadedIds) -> io::Result<Self> {
+        read_header(r, COMMITS_MAGIC)?;
+        let mut res = Self::default();
+        for commits in [
+            &mut res.maven,
+            &mut res.gradle,
+            &mut res.make,
+            &mut res.cmake,
+            &mut res.npm,
+            &mut res.pip,
+            &mut res.cargo,
+            &mut res.any,
+        ] {
+            let len = read_u64(r)?;
+            for _ in 0..len {
+                let oid = read_oid(r)?;
+                commits.insert(oid, Commit::load(r, ids)?);
+            }
+        }
+        Ok(res)
+    }
+}
+
+fn read_oid<R: Read>(r: &mut R) -> io::Result<git2::Oid> {
+    let mut b = [0; 20];
+    r.read_exact(&mut b)?;
+    git2::Oid::from_bytes(&b).map_err(invalid_data)
+}
+
+impl Commit {
+    fn save<W: Write>(&self, w: &mut W, ids: &SavedIds) -> io::Result<()> {
+        write_u32(w, self.parents.len() as u32)?;
+        for p in &self.parents {
+            w.write_all(p.as_bytes())?;
+        }
+        write_u128(w, self.processing_time)?;
+        write_u64(w, self.memory_used.bytes() as u64)?;
+        let root = ids
+            .get(&self.ast_root)
+            .ok_or_else(|| invalid_data("root of commit not in store"))?;
+        write_u64(w, root)?;
+        w.write_all(self.tree_oid.as_bytes())
+    }
+
+    fn load<R: Read>(r: &mut R, ids: &LoadedIds) -> io::Result<Self> {
+        let len = read_u32(r)?;
+        let parents = (0..len).map(|_| read_oid(r)).collect::<io::Result<_>>()?;
+        let processing_time = read_u128(r)?;
+        let memory_used = (read_u64(r)? as isize).into();
+        let root = read_u64(r)?;
+        let ast_root = ids
+            .get(root)
+            .ok_or_else(|| invalid_data("root of commit not in store"))?;
+        let tree_oid = read_oid(r)?;
+        Ok(Self {
+            parents,
+            processing_time,
+            memory_used,
+            ast_root,
+            tree_oid,
+        })
+    }
+}
+
 pub(crate) struct CommitBuilder<'prepro, 'repo, Sys, CP: CommitProcessor<Sys>> {
     pub commits: &'prepro mut HashMap<git2::Oid, Commit>,
     pub processor: &'prepro mut CP,
@@ -149,6 +257,16 @@ impl PreProcessedRepositories {
                     config: h.register_param(crate::maven_processor::Parameter),
                 }
             }
+            RepoConfig::JavaGradle => {
+                let h = self
+                    .processor
+                    .processing_systems
+                    .mut_or_default::<crate::gradle_processor::GradleProcessorHolder>();
+                ConfiguredRepoHandle2 {
+                    spec: repo,
+                    config: h.register_param(crate::gradle_processor::Parameter),
+                }
+            }
             RepoConfig::CppMake => {
                 let h = self
                     .processor
@@ -159,6 +277,46 @@ impl PreProcessedRepositories {
                     config: h.register_param(crate::make_processor::Parameter),
                 }
             }
+            RepoConfig::CppCMake => {
+                let h = self
+                    .processor
+                    .processing_systems
+                    .mut_or_default::<crate::cmake_processor::CMakeProcessorHolder>();
+                ConfiguredRepoHandle2 {
+                    spec: repo,
+                    config: h.register_param(crate::cmake_processor::Parameter),
+                }
+            }
+            RepoConfig::TsNpm => {
+                let h = self
+                    .processor
+                    .processing_systems
+                    .mut_or_default::<crate::npm_processor::NpmProcessorHolder>();
+                ConfiguredRepoHandle2 {
+                    spec: repo,
+                    config: h.register_param(crate::npm_processor::Parameter),
+                }
+            }
+            RepoConfig::PythonPip => {
+                let h = self
+                    .processor
+                    .processing_systems
+                    .mut_or_default::<crate::pip_processor::PipProcessorHolder>();
+                ConfiguredRepoHandle2 {
+                    spec: repo,
+                    config: h.register_param(crate::pip_processor::Parameter),
+                }
+            }
+            RepoConfig::RustCargo => {
+                let h = self
+                    .processor
+                    .processing_systems
+                    .mut_or_default::<crate::cargo_processor::CargoProcessorHolder>();
+                ConfiguredRepoHandle2 {
+                    spec: repo,
+                    config: h.register_param(crate::cargo_processor::Parameter),
+                }
+            }
             _ => todo!(),
         };
 
@@ -191,6 +349,14 @@ impl PreProcessedRepositories {
             .pre_process_with_limit(repository, before, after, limit)
     }
 
+    pub fn pre_process_working_dir(
+        &mut self,
+        repository: &mut ConfiguredRepo2,
+        state: crate::git::WorkingState,
+    ) -> Result<git2::Oid, git2::Error> {
+        self.processor.pre_process_working_dir(repository, state)
+    }
+
     pub fn pre_process_with_config2(
         &mut self,
         repository: &mut ConfiguredRepo2,
@@ -201,6 +367,34 @@ impl PreProcessedRepositories {
         self.processor.pre_process(repository, before, after)
     }
 
+    /// Same as [`Self::pre_process_with_config2`],
+    /// but the files of many commits are parsed concurrently by `workers` threads.
+    #[cfg(feature = "java")]
+    pub fn pre_process_with_config2_parallel(
+        &mut self,
+        repository: &mut ConfiguredRepo2,
+        before: &str,
+        after: &str,
+        workers: usize,
+    ) -> Result<Vec<git2::Oid>, git2::Error> {
+        assert!(!before.is_empty());
+        self.processor
+            .pre_process_parallel(repository, before, after, usize::MAX, workers)
+    }
+
+    #[cfg(feature = "java")]
+    pub fn pre_process_with_limit_parallel(
+        &mut self,
+        repository: &mut ConfiguredRepo2,
+        before: &str,
+        after: &str,
+        limit: usize,
+        workers: usize,
+    ) -> Result<Vec<git2::Oid>, git2::Error> {
+        self.processor
+            .pre_process_parallel(repository, before, after, limit, workers)
+    }
+
     fn pre_process_with_config(
         &mut self,
         repository: &mut ConfiguredRepo,
@@ -290,6 +484,172 @@ impl PreProcessedRepositories {
         Ok(processing_ordered_commits)
     }
 
+    /// Gathers the commits processed so far by each build system.
+    pub fn commits_per_sys(&self) -> CommitsPerSys {
+        let mut res = CommitsPerSys::default();
+        let systems = &self.processor.processing_systems;
+        if let Some(commits) = systems
+            .get::<crate::maven_processor::MavenProcessorHolder>()
+            .and_then(|h| h.commits())
+        {
+            res.maven = commits.clone();
+        }
+        if let Some(commits) = systems
+            .get::<crate::gradle_processor::GradleProcessorHolder>()
+            .and_then(|h| h.commits())
+        {
+            res.gradle = commits.clone();
+        }
+        if let Some(commits) = systems
+            .get::<crate::make_processor::MakeProcessorHolder>()
+            .and_then(|h| h.commits())
+        {
+            res.make = commits.clone();
+        }
+        if let Some(commits) = systems
+            .get::<crate::cmake_processor::CMakeProcessorHolder>()
+            .and_then(|h| h.commits())
+        {
+            res.cmake = commits.clone();
+        }
+        if let Some(commits) = systems
+            .get::<crate::npm_processor::NpmProcessorHolder>()
+            .and_then(|h| h.commits())
+        {
+            res.npm = commits.clone();
+        }
+        if let Some(commits) = systems
+            .get::<crate::pip_processor::PipProcessorHolder>()
+            .and_then(|h| h.commits())
+        {
+            res.pip = commits.clone();
+        }
+        if let Some(commits) = systems
+            .get::<crate::cargo_processor::CargoProcessorHolder>()
+            .and_then(|h| h.commits())
+        {
+            res.cargo = commits.clone();
+        }
+        res
+    }
+
+    /// Persists the main stores and the processed commits.
+    /// Caches are not persisted, they are filled again when processing new commits.
+    pub fn save<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        let ids = self
+            .processor
+            .main_stores
+            .save(w, &crate::persist_registry())?;
+        self.commits_per_sys().save(w, &ids)
+    }
+
+    /// Loads what was written by [`PreProcessedRepositories::save`],
+    /// repositories still need to be registered with [`PreProcessedRepositories::register_config`].
+    pub fn load<R: Read>(r: &mut R) -> io::Result<(Self, CommitsPerSys)> {
+        let (main_stores, ids) = SimpleStores::load(r, &crate::persist_registry())?;
+        let commits = CommitsPerSys::load(r, &ids)?;
+        let mut res = Self::default();
+        res.processor.main_stores = main_stores;
+        res.restore_commits(&commits);
+        Ok((res, commits))
+    }
+
+    /// Only keeps the `retained` commits and the nodes reachable from them.
+    /// Caches are purged as they could reference removed nodes.
+    pub fn gc(&mut self, retained: &[git2::Oid]) -> hyper_ast::store::nodes::legion::gc::GcStats {
+        let mut commits = self.commits_per_sys();
+        for c in [
+            &mut commits.maven,
+            &mut commits.gradle,
+            &mut commits.make,
+            &mut commits.cmake,
+            &mut commits.npm,
+            &mut commits.pip,
+            &mut commits.cargo,
+            &mut commits.any,
+        ] {
+            c.retain(|oid, _| retained.contains(oid));
+        }
+        self.processor.purge_caches();
+        self.restore_commits(&commits);
+        let roots = [
+            &commits.maven,
+            &commits.gradle,
+            &commits.make,
+            &commits.cmake,
+            &commits.npm,
+            &commits.pip,
+            &commits.cargo,
+            &commits.any,
+        ]
+            .into_iter()
+            .flat_map(|x| x.values())
+            .map(|c| c.ast_root);
+        self.processor.main_stores.node_store.gc(roots)
+    }
+
+    /// Puts back `commits` into their processors,
+    /// processors are registered even without commits so that configs stay valid.
+    fn restore_commits(&mut self, commits: &CommitsPerSys) {
+        use crate::processing::erased::{Parametrized, ParametrizedCommitProc2};
+        let h = self
+            .processor
+            .processing_systems
+            .mut_or_default::<crate::maven_processor::MavenProcessorHolder>();
+        let handle = h.register_param(crate::maven_processor::Parameter);
+        h.with_parameters_mut(handle.1)
+            .commits_mut()
+            .extend(commits.maven.iter().map(|(k, v)| (*k, v.clone())));
+        let h = self
+            .processor
+            .processing_systems
+            .mut_or_default::<crate::gradle_processor::GradleProcessorHolder>();
+        let handle = h.register_param(crate::gradle_processor::Parameter);
+        h.with_parameters_mut(handle.1)
+            .commits_mut()
+            .extend(commits.gradle.iter().map(|(k, v)| (*k, v.clone())));
+        let h = self
+            .processor
+            .processing_systems
+            .mut_or_default::<crate::make_processor::MakeProcessorHolder>();
+        let handle = h.register_param(crate::make_processor::Parameter);
+        h.with_parameters_mut(handle.1)
+            .commits_mut()
+            .extend(commits.make.iter().map(|(k, v)| (*k, v.clone())));
+        let h = self
+            .processor
+            .processing_systems
+            .mut_or_default::<crate::cmake_processor::CMakeProcessorHolder>();
+        let handle = h.register_param(crate::cmake_processor::Parameter);
+        h.with_parameters_mut(handle.1)
+            .commits_mut()
+            .extend(commits.cmake.iter().map(|(k, v)| (*k, v.clone())));
+        let h = self
+            .processor
+            .processing_systems
+            .mut_or_default::<crate::npm_processor::NpmProcessorHolder>();
+        let handle = h.register_param(crate::npm_processor::Parameter);
+        h.with_parameters_mut(handle.1)
+            .commits_mut()
+            .extend(commits.npm.iter().map(|(k, v)| (*k, v.clone())));
+        let h = self
+            .processor
+            .processing_systems
+            .mut_or_default::<crate::pip_processor::PipProcessorHolder>();
+        let handle = h.register_param(crate::pip_processor::Parameter);
+        h.with_parameters_mut(handle.1)
+            .commits_mut()
+            .extend(commits.pip.iter().map(|(k, v)| (*k, v.clone())));
+        let h = self
+            .processor
+            .processing_systems
+            .mut_or_default::<crate::cargo_processor::CargoProcessorHolder>();
+        let handle = h.register_param(crate::cargo_processor::Parameter);
+        h.with_parameters_mut(handle.1)
+            .commits_mut()
+            .extend(commits.cargo.iter().map(|(k, v)| (*k, v.clone())));
+    }
+
     pub fn make(
         acc: MavenModuleAcc,
         stores: &mut SimpleStores,
@@ -123,6 +123,27 @@ impl<'a> types::Typed for NoSpaceWrapper<'a, MIdN<NodeIdentifier>> {
             let t = *t as u16;
             let t = <hyper_ast_gen_ts_cpp::types::Cpp as hyper_ast::types::Lang<_>>::make(t);
             MultiType::Cpp(*t)
+        } else if let Ok(t) = self
+            .inner
+            .get_component::<hyper_ast_gen_ts_typescript::types::Type>()
+        {
+            let t = *t as u16;
+            let t = <hyper_ast_gen_ts_typescript::types::Ts as hyper_ast::types::Lang<_>>::make(t);
+            MultiType::Ts(*t)
+        } else if let Ok(t) = self
+            .inner
+            .get_component::<hyper_ast_gen_ts_python::types::Type>()
+        {
+            let t = *t as u16;
+            let t = <hyper_ast_gen_ts_python::types::Python as hyper_ast::types::Lang<_>>::make(t);
+            MultiType::Python(*t)
+        } else if let Ok(t) = self
+            .inner
+            .get_component::<hyper_ast_gen_ts_rust::types::Type>()
+        {
+            let t = *t as u16;
+            let t = <hyper_ast_gen_ts_rust::types::Rust as hyper_ast::types::Lang<_>>::make(t);
+            MultiType::Rust(*t)
         } else if let Ok(t) = self
             .inner
             .get_component::<hyper_ast_gen_ts_xml::types::Type>()
@@ -154,6 +175,27 @@ impl<'a> types::Typed for NoSpaceWrapper<'a, NodeIdentifier> {
             let t = *t as u16;
             let t = <hyper_ast_gen_ts_cpp::types::Cpp as hyper_ast::types::Lang<_>>::make(t);
             MultiType::Cpp(*t)
+        } else if let Ok(t) = self
+            .inner
+            .get_component::<hyper_ast_gen_ts_typescript::types::Type>()
+        {
+            let t = *t as u16;
+            let t = <hyper_ast_gen_ts_typescript::types::Ts as hyper_ast::types::Lang<_>>::make(t);
+            MultiType::Ts(*t)
+        } else if let Ok(t) = self
+            .inner
+            .get_component::<hyper_ast_gen_ts_python::types::Type>()
+        {
+            let t = *t as u16;
+            let t = <hyper_ast_gen_ts_python::types::Python as hyper_ast::types::Lang<_>>::make(t);
+            MultiType::Python(*t)
+        } else if let Ok(t) = self
+            .inner
+            .get_component::<hyper_ast_gen_ts_rust::types::Type>()
+        {
+            let t = *t as u16;
+            let t = <hyper_ast_gen_ts_rust::types::Rust as hyper_ast::types::Lang<_>>::make(t);
+            MultiType::Rust(*t)
         } else if let Ok(t) = self
             .inner
             .get_component::<hyper_ast_gen_ts_xml::types::Type>()
@@ -0,0 +1,197 @@
+use std::path::PathBuf;
+
+use hyper_ast::{
+    store::defaults::{LabelIdentifier, NodeIdentifier},
+    tree_gen::SubTreeMetrics,
+};
+use hyper_ast_gen_ts_typescript::legion as ts_tree_gen;
+use hyper_ast_gen_ts_xml::legion::XmlTreeGen;
+
+use crate::{
+    processing::ObjectName, Accumulator, DefaultMetrics, TStore, PROPAGATE_ERROR_ON_BAD_CST_NODE,
+};
+
+pub(crate) fn handle_package_json_file<'a>(
+    tree_gen: &mut XmlTreeGen<'a, TStore>,
+    name: &ObjectName,
+    text: &'a [u8],
+) -> Result<PackageJson, ()> {
+    let content: PackageJsonContent = match serde_json::from_slice(text) {
+        Ok(content) => content,
+        Err(err) => {
+            log::warn!("bad package.json: {}", err);
+            if PROPAGATE_ERROR_ON_BAD_CST_NODE {
+                return Err(());
+            } else {
+                Default::default()
+            }
+        }
+    };
+    // TODO use a json generator, for now package.json files are placeholders like Makefiles
+    let tree = match XmlTreeGen::<TStore>::tree_sitter_parse(b"<package></package>") {
+        Ok(tree) => tree,
+        Err(tree) => {
+            log::warn!("bad CST");
+            log::debug!("{:?}", name.try_str());
+            log::debug!("{}", tree.root_node().to_sexp());
+            if PROPAGATE_ERROR_ON_BAD_CST_NODE {
+                return Err(());
+            } else {
+                tree
+            }
+        }
+    };
+    let x = tree_gen
+        .generate_file(name.as_bytes(), b"<package></package>", tree.walk())
+        .local;
+    let x = PackageJson {
+        compressed_node: x.compressed_node,
+        metrics: x.metrics,
+        workspaces: content.workspaces.into_globs(),
+    };
+    Ok(x)
+}
+
+#[derive(serde::Deserialize, Default)]
+struct PackageJsonContent {
+    #[serde(default)]
+    workspaces: Workspaces,
+}
+
+/// npm and yarn use a list of globs, yarn also accepts them in a `packages` field
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum Workspaces {
+    Globs(Vec<String>),
+    Packages {
+        #[serde(default)]
+        packages: Vec<String>,
+    },
+}
+
+impl Default for Workspaces {
+    fn default() -> Self {
+        Self::Globs(vec![])
+    }
+}
+
+impl Workspaces {
+    fn into_globs(self) -> Vec<String> {
+        match self {
+            Workspaces::Globs(x) => x,
+            Workspaces::Packages { packages } => packages,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PackageJson {
+    pub compressed_node: NodeIdentifier,
+    pub metrics: DefaultMetrics,
+    workspaces: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MD {
+    pub(crate) metrics: DefaultMetrics,
+}
+
+pub struct NpmModuleAcc {
+    pub(crate) name: String,
+    pub(crate) children_names: Vec<LabelIdentifier>,
+    pub(crate) children: Vec<NodeIdentifier>,
+    pub(crate) metrics: DefaultMetrics,
+    /// globs of the workspaces still to be matched, relative to this directory
+    pub(crate) sub_modules: Option<Vec<PathBuf>>,
+}
+
+impl From<String> for NpmModuleAcc {
+    fn from(name: String) -> Self {
+        Self::new(name)
+    }
+}
+
+impl NpmModuleAcc {
+    pub(crate) fn new(name: String) -> Self {
+        Self {
+            name,
+            children_names: Default::default(),
+            children: Default::default(),
+            metrics: Default::default(),
+            sub_modules: None,
+        }
+    }
+    pub(crate) fn with_content(name: String, sub_modules: Vec<PathBuf>) -> Self {
+        Self {
+            name,
+            children_names: Default::default(),
+            children: Default::default(),
+            metrics: Default::default(),
+            sub_modules: if sub_modules.is_empty() {
+                None
+            } else {
+                Some(sub_modules)
+            },
+        }
+    }
+}
+
+impl NpmModuleAcc {
+    pub(crate) fn push_package_json(&mut self, name: LabelIdentifier, full_node: PackageJson) {
+        self.children.push(full_node.compressed_node);
+        self.children_names.push(name);
+        let workspaces = full_node
+            .workspaces
+            .iter()
+            .map(|x| x.trim_start_matches("./").into());
+        self.sub_modules
+            .get_or_insert_with(Default::default)
+            .extend(workspaces);
+        self.metrics.acc(full_node.metrics);
+    }
+    pub fn push_submodule(&mut self, name: LabelIdentifier, full_node: (NodeIdentifier, MD)) {
+        self.children.push(full_node.0);
+        self.children_names.push(name);
+        self.metrics.acc(full_node.1.metrics);
+    }
+    pub(crate) fn push_source_file(
+        &mut self,
+        name: LabelIdentifier,
+        full_node: ts_tree_gen::Local,
+        skiped_ana: bool,
+    ) {
+        self.children.push(full_node.compressed_node);
+        self.children_names.push(name);
+        self.metrics.acc(SubTreeMetrics {
+            hashs: full_node.metrics.hashs,
+            size: full_node.metrics.size,
+            height: full_node.metrics.height,
+            size_no_spaces: full_node.metrics.size_no_spaces,
+        });
+    }
+    pub(crate) fn push_source_directory(
+        &mut self,
+        name: LabelIdentifier,
+        full_node: ts_tree_gen::Local,
+    ) {
+        self.children.push(full_node.compressed_node);
+        self.children_names.push(name);
+        self.metrics.acc(SubTreeMetrics {
+            hashs: full_node.metrics.hashs,
+            size: full_node.metrics.size,
+            height: full_node.metrics.height,
+            size_no_spaces: full_node.metrics.size_no_spaces,
+        });
+    }
+}
+
+impl hyper_ast::tree_gen::Accumulator for NpmModuleAcc {
+    type Node = (LabelIdentifier, (NodeIdentifier, MD));
+    fn push(&mut self, (name, full_node): Self::Node) {
+        self.push_submodule(name, full_node)
+    }
+}
+
+impl Accumulator for NpmModuleAcc {
+    type Unlabeled = (NodeIdentifier, MD);
+}
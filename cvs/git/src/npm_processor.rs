@@ -0,0 +1,612 @@
+use std::{
+    iter::Peekable,
+    path::{Components, PathBuf},
+};
+
+use git2::{Oid, Repository};
+use hyper_ast::{
+    filter::BloomSize,
+    hashed::{self, IndexingHashBuilder, MetaDataHashsBuilder},
+    store::{
+        defaults::NodeIdentifier,
+        nodes::legion::{compo, compo::CS, NodeStore},
+    },
+    tree_gen::SubTreeMetrics,
+    types::LabelStore,
+};
+use hyper_ast_gen_ts_java::legion_with_refs::hash32;
+use hyper_ast_gen_ts_typescript::{legion::eq_node, types::Type};
+
+use crate::{
+    git::BasicGitObject,
+    npm::{NpmModuleAcc, MD},
+    preprocessed::RepositoryProcessor,
+    processing::{erased::ParametrizedCommitProc2, CacheHolding, InFiles, ObjectName},
+    ts_processor::TsProc,
+    Processor, SimpleStores,
+};
+
+/// RMS: Resursive Module Search, ie. also look for packages that are not declared as workspaces
+/// FFWD: Fast ForWarD to ts directories without looking at npm stuff
+pub struct NpmProcessor<'a, 'b, 'c, const RMS: bool, const FFWD: bool, Acc> {
+    prepro: &'b mut RepositoryProcessor,
+    repository: &'a Repository,
+    stack: Vec<(Oid, Vec<BasicGitObject>, Acc)>,
+    dir_path: &'c mut Peekable<Components<'c>>,
+    handle: crate::processing::erased::ParametrizedCommitProcessor2Handle<NpmProc>,
+    ts_handle: crate::processing::erased::ParametrizedCommitProcessor2Handle<TsProc>,
+}
+
+impl<'a, 'b, 'c, const RMS: bool, const FFWD: bool, Acc: From<String>>
+    NpmProcessor<'a, 'b, 'c, RMS, FFWD, Acc>
+{
+    pub fn new(
+        repository: &'a Repository,
+        prepro: &'b mut RepositoryProcessor,
+        mut dir_path: &'c mut Peekable<Components<'c>>,
+        name: &[u8],
+        oid: git2::Oid,
+    ) -> Self {
+        let h = prepro
+            .processing_systems
+            .mut_or_default::<NpmProcessorHolder>();
+        let handle =
+            <NpmProc as crate::processing::erased::CommitProcExt>::register_param(h, Parameter);
+        let h = prepro
+            .processing_systems
+            .mut_or_default::<crate::ts_processor::TsProcessorHolder>();
+        let ts_handle = <TsProc as crate::processing::erased::CommitProcExt>::register_param(
+            h,
+            crate::ts_processor::Parameter,
+        );
+        let tree = repository.find_tree(oid).unwrap();
+        let prepared = prepare_dir_exploration(tree, &mut dir_path);
+        let name = std::str::from_utf8(&name).unwrap().to_string();
+        let stack = vec![(oid, prepared, Acc::from(name))];
+        Self {
+            stack,
+            repository,
+            prepro,
+            dir_path,
+            handle,
+            ts_handle,
+        }
+    }
+}
+
+impl<'a, 'b, 'c, const RMS: bool, const FFWD: bool> Processor<NpmModuleAcc>
+    for NpmProcessor<'a, 'b, 'c, RMS, FFWD, NpmModuleAcc>
+{
+    fn pre(&mut self, current_dir: BasicGitObject) {
+        match current_dir {
+            BasicGitObject::Tree(oid, name) => {
+                if let Some(s) = self.dir_path.peek() {
+                    if name
+                        .as_bytes()
+                        .eq(std::ffi::OsStr::as_os_str_bytes(s.as_os_str()))
+                    {
+                        self.dir_path.next();
+                        self.stack.last_mut().expect("never empty").1.clear();
+                        let tree = self.repository.find_tree(oid).unwrap();
+                        let prepared = prepare_dir_exploration(tree, &mut self.dir_path);
+                        self.stack.push((
+                            oid,
+                            prepared,
+                            NpmModuleAcc::new(name.try_into().unwrap()),
+                        ));
+                    }
+                    return;
+                }
+                if crate::processing::file_sys::NodeModules::matches(&name) {
+                    log::debug!("skip {:?}", name.try_str());
+                    return;
+                }
+                if let Some(already) = self
+                    .prepro
+                    .processing_systems
+                    .mut_or_default::<NpmProcessorHolder>()
+                    .get_caches_mut()
+                    .object_map
+                    .get(&oid)
+                {
+                    // reinit already computed node for post order
+                    let full_node = already.clone();
+
+                    let w = &mut self.stack.last_mut().unwrap().2;
+                    let name = self.prepro.intern_object_name(name);
+                    assert!(!w.children_names.contains(&name));
+                    w.push_submodule(name, full_node);
+                    return;
+                }
+                log::debug!("npm tree {:?}", name.try_str());
+                let parent_acc = &mut self.stack.last_mut().unwrap().2;
+                let (is_package, sub_modules) =
+                    match_workspaces(&parent_acc.sub_modules, name.try_str().unwrap());
+                let tree = self.repository.find_tree(oid).unwrap();
+                let is_package = is_package
+                    || RMS
+                        && tree
+                            .get_name(std::str::from_utf8(PACKAGE_JSON).unwrap())
+                            .is_some();
+                if is_package || !sub_modules.is_empty() {
+                    // handle as npm package or search further inside
+                    let prepared = prepare_dir_exploration(tree, &mut self.dir_path);
+                    let acc = NpmModuleAcc::with_content(name.try_into().unwrap(), sub_modules);
+                    self.stack.push((oid, prepared, acc));
+                } else {
+                    // handle as source dir
+                    let (name, (full_node, _)) = self
+                        .prepro
+                        .help_handle_basic_folder::<crate::processing::file_sys::Ts>(
+                            &self.repository,
+                            &mut self.dir_path,
+                            oid,
+                            &name,
+                        );
+                    let parent_acc = &mut self.stack.last_mut().unwrap().2;
+                    assert!(!parent_acc.children_names.contains(&name));
+                    parent_acc.push_source_directory(name, full_node);
+                }
+            }
+            BasicGitObject::Blob(oid, name) => {
+                if FFWD {
+                    return;
+                }
+                if self.dir_path.peek().is_some() {
+                    return;
+                }
+                if crate::processing::file_sys::PackageJson::matches(&name) {
+                    self.prepro
+                        .help_handle_package_json(
+                            oid,
+                            &mut self.stack.last_mut().unwrap().2,
+                            name,
+                            &self.repository,
+                            self.handle.into(),
+                        )
+                        .unwrap();
+                } else if crate::processing::file_sys::Ts::matches(&name) {
+                    let (name, (full_node, skiped_ana)) = self
+                        .prepro
+                        .help_handle_basic_file(oid, &name, self.repository, self.ts_handle)
+                        .unwrap();
+                    let parent_acc = &mut self.stack.last_mut().unwrap().2;
+                    assert!(!parent_acc.children_names.contains(&name));
+                    parent_acc.push_source_file(name, full_node, skiped_ana);
+                } else {
+                    log::debug!("not ts source file {:?}", name.try_str());
+                }
+            }
+        }
+    }
+    fn post(&mut self, oid: Oid, acc: NpmModuleAcc) -> Option<(NodeIdentifier, MD)> {
+        let name = acc.name.clone();
+        let full_node = make(acc, self.prepro.main_stores_mut());
+        self.prepro
+            .processing_systems
+            .mut_or_default::<NpmProcessorHolder>()
+            .get_caches_mut()
+            .object_map
+            .insert(oid, full_node.clone());
+
+        let name = self.prepro.main_stores.label_store.get_or_insert(name);
+        if self.stack.is_empty() {
+            Some(full_node)
+        } else {
+            let w = &mut self.stack.last_mut().unwrap().2;
+            assert!(
+                !w.children_names.contains(&name),
+                "{:?} {:?}",
+                w.children_names,
+                name
+            );
+            w.push_submodule(name, full_node);
+            None
+        }
+    }
+
+    fn stack(&mut self) -> &mut Vec<(Oid, Vec<BasicGitObject>, NpmModuleAcc)> {
+        &mut self.stack
+    }
+}
+
+pub(crate) fn make(acc: NpmModuleAcc, stores: &mut SimpleStores) -> (NodeIdentifier, MD) {
+    let dir_hash: u32 = hash32(&Type::Directory);
+    let hashs = acc.metrics.hashs;
+    let size = acc.metrics.size + 1;
+    let height = acc.metrics.height + 1;
+    let size_no_spaces = acc.metrics.size_no_spaces + 1;
+    let hbuilder = hashed::Builder::new(hashs, &dir_hash, &acc.name, size_no_spaces);
+    let hashable = hbuilder.most_discriminating();
+    let label = stores.label_store.get_or_insert(acc.name.clone());
+
+    let eq = eq_node(&Type::Directory, Some(&label), &acc.children);
+    let insertion = stores.node_store.prepare_insertion(&hashable, eq);
+    let hashs = hbuilder.build();
+    let node_id = if let Some(id) = insertion.occupied_id() {
+        id
+    } else {
+        log::info!("make npm {} {}", &acc.name, acc.children.len());
+        let vacant = insertion.vacant();
+        assert_eq!(acc.children_names.len(), acc.children.len());
+        NodeStore::insert_after_prepare(
+            vacant,
+            (
+                Type::Directory,
+                label,
+                hashs,
+                compo::Size(size),
+                compo::Height(height),
+                compo::SizeNoSpaces(size_no_spaces),
+                CS(acc.children_names.into_boxed_slice()),
+                CS(acc.children.into_boxed_slice()),
+                BloomSize::Much,
+            ),
+        )
+    };
+
+    let metrics = SubTreeMetrics {
+        size,
+        height,
+        hashs,
+        size_no_spaces,
+    };
+
+    (node_id, MD { metrics })
+}
+
+use hyper_ast_gen_ts_xml::legion::XmlTreeGen;
+impl RepositoryProcessor {
+    fn help_handle_package_json(
+        &mut self,
+        oid: Oid,
+        parent_acc: &mut NpmModuleAcc,
+        name: ObjectName,
+        repository: &Repository,
+        parameters: crate::processing::erased::ParametrizedCommitProcessor2Handle<PackageJsonProc>,
+    ) -> Result<(), crate::ParseErr> {
+        let x = self
+            .processing_systems
+            .caching_blob_handler::<crate::processing::file_sys::PackageJson>()
+            .handle(oid, repository, &name, parameters, |c, n, t| {
+                crate::npm::handle_package_json_file(
+                    &mut XmlTreeGen {
+                        line_break: "\n".as_bytes().to_vec(),
+                        stores: &mut self.main_stores,
+                    },
+                    n,
+                    t,
+                )
+                .map_err(|_| crate::ParseErr::IllFormed)
+            })?;
+        let name = self.intern_object_name(&name);
+        assert!(!parent_acc.children_names.contains(&name));
+        parent_acc.push_package_json(name, x);
+        Ok(())
+    }
+}
+
+const PACKAGE_JSON: &[u8] = b"package.json";
+
+/// Matches the workspaces globs of a parent package against one of its directories,
+/// returns if the directory is a package and the globs remaining to be matched inside it.
+fn match_workspaces(sub_modules: &Option<Vec<PathBuf>>, name: &str) -> (bool, Vec<PathBuf>) {
+    let mut is_package = false;
+    let mut remaining = vec![];
+    for x in sub_modules.iter().flatten() {
+        let mut components = x.components();
+        let Some(first) = components.next() else {
+            continue;
+        };
+        if !glob_match(&first.as_os_str().to_string_lossy(), name) {
+            continue;
+        }
+        let rest = components.as_path();
+        if rest.components().next().is_none() {
+            is_package = true;
+        } else {
+            remaining.push(rest.to_owned());
+        }
+    }
+    (is_package, remaining)
+}
+
+/// only handles the `*` wildcard, `**` being considered as `*`
+fn glob_match(pattern: &str, name: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == name,
+        Some((prefix, suffix)) => {
+            let suffix = suffix.trim_start_matches('*');
+            name.len() >= prefix.len() + suffix.len()
+                && name.starts_with(prefix)
+                && (suffix.contains('*') || name.ends_with(suffix))
+        }
+    }
+}
+
+/// sometimes order of files/dirs can be important, similarly to order of statement
+/// exploration order for example
+pub(crate) fn prepare_dir_exploration(
+    tree: git2::Tree,
+    dir_path: &mut Peekable<Components>,
+) -> Vec<BasicGitObject> {
+    let mut children_objects: Vec<BasicGitObject> = tree
+        .iter()
+        .map(TryInto::try_into)
+        .filter_map(|x| x.ok())
+        .collect();
+    if dir_path.peek().is_none() {
+        let p = children_objects.iter().position(|x| match x {
+            BasicGitObject::Blob(_, n) => crate::processing::file_sys::PackageJson::matches(n),
+            _ => false,
+        });
+        if let Some(p) = p {
+            children_objects.swap(0, p); // priority to config file processing
+            children_objects.reverse(); // we use it like a stack
+        }
+    }
+    children_objects
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct Parameter;
+
+// # package.json
+
+impl From<crate::processing::erased::ParametrizedCommitProcessor2Handle<NpmProc>>
+    for crate::processing::erased::ParametrizedCommitProcessor2Handle<PackageJsonProc>
+{
+    fn from(value: crate::processing::erased::ParametrizedCommitProcessor2Handle<NpmProc>) -> Self {
+        crate::processing::erased::ParametrizedCommitProcessor2Handle(
+            value.0,
+            std::marker::PhantomData,
+        )
+    }
+}
+struct PackageJsonProcessorHolder(Option<PackageJsonProc>);
+impl Default for PackageJsonProcessorHolder {
+    fn default() -> Self {
+        Self(Some(PackageJsonProc(Parameter, Default::default())))
+    }
+}
+struct PackageJsonProc(Parameter, crate::processing::caches::PackageJson);
+impl crate::processing::erased::Parametrized for PackageJsonProcessorHolder {
+    type T = Parameter;
+    fn register_param(
+        &mut self,
+        t: Self::T,
+    ) -> crate::processing::erased::ParametrizedCommitProcessorHandle {
+        let l = self.0.iter().position(|x| &x.0 == &t).unwrap_or_else(|| {
+            self.0 = Some(PackageJsonProc(t, Default::default()));
+            0
+        });
+        use crate::processing::erased::ConfigParametersHandle;
+        use crate::processing::erased::ParametrizedCommitProc;
+        use crate::processing::erased::ParametrizedCommitProcessorHandle;
+        ParametrizedCommitProcessorHandle(self.erased_handle(), ConfigParametersHandle(l))
+    }
+}
+// TODO should not have to impl this trait
+impl crate::processing::erased::CommitProc for PackageJsonProc {
+    fn process_root_tree(
+        &mut self,
+        repository: &git2::Repository,
+        tree_oid: &git2::Oid,
+    ) -> hyper_ast::store::defaults::NodeIdentifier {
+        unimplemented!()
+    }
+
+    fn prepare_processing(
+        &self,
+        repository: &git2::Repository,
+        commit_builder: crate::preprocessed::CommitBuilder,
+    ) -> Box<dyn crate::processing::erased::PreparedCommitProc> {
+        unimplemented!()
+    }
+
+    fn get_commit(&self, commit_oid: git2::Oid) -> Option<&crate::Commit> {
+        unimplemented!()
+    }
+}
+
+impl crate::processing::erased::CommitProcExt for PackageJsonProc {
+    type Holder = PackageJsonProcessorHolder;
+}
+impl crate::processing::erased::ParametrizedCommitProc2 for PackageJsonProcessorHolder {
+    type Proc = PackageJsonProc;
+
+    fn with_parameters_mut(
+        &mut self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &mut Self::Proc {
+        assert_eq!(0, parameters.0);
+        self.0.as_mut().unwrap()
+    }
+
+    fn with_parameters(
+        &self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &Self::Proc {
+        assert_eq!(0, parameters.0);
+        self.0.as_ref().unwrap()
+    }
+}
+impl CacheHolding<crate::processing::caches::PackageJson> for PackageJsonProc {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::PackageJson {
+        &mut self.1
+    }
+    fn get_caches(&self) -> &crate::processing::caches::PackageJson {
+        &self.1
+    }
+}
+impl CacheHolding<crate::processing::caches::PackageJson> for PackageJsonProcessorHolder {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::PackageJson {
+        &mut self.0.as_mut().unwrap().1
+    }
+    fn get_caches(&self) -> &crate::processing::caches::PackageJson {
+        &self.0.as_ref().unwrap().1
+    }
+}
+
+// # Npm
+#[derive(Default)]
+pub(crate) struct NpmProcessorHolder(Option<NpmProc>);
+pub(crate) struct NpmProc {
+    parameter: Parameter,
+    cache: crate::processing::caches::Npm,
+    commits: std::collections::HashMap<git2::Oid, crate::Commit>,
+}
+impl crate::processing::erased::Parametrized for NpmProcessorHolder {
+    type T = Parameter;
+    fn register_param(
+        &mut self,
+        t: Self::T,
+    ) -> crate::processing::erased::ParametrizedCommitProcessorHandle {
+        let l = self
+            .0
+            .iter()
+            .position(|x| &x.parameter == &t)
+            .unwrap_or_else(|| {
+                self.0 = Some(NpmProc {
+                    parameter: t,
+                    cache: Default::default(),
+                    commits: Default::default(),
+                });
+                0
+            });
+        use crate::processing::erased::ConfigParametersHandle;
+        use crate::processing::erased::ParametrizedCommitProc;
+        use crate::processing::erased::ParametrizedCommitProcessorHandle;
+        ParametrizedCommitProcessorHandle(self.erased_handle(), ConfigParametersHandle(l))
+    }
+}
+struct PreparedNpmCommitProc<'repo> {
+    repository: &'repo git2::Repository,
+    commit_builder: crate::preprocessed::CommitBuilder,
+}
+impl<'repo> crate::processing::erased::PreparedCommitProc for PreparedNpmCommitProc<'repo> {
+    fn process(
+        self: Box<PreparedNpmCommitProc<'repo>>,
+        prepro: &mut RepositoryProcessor,
+    ) -> hyper_ast::store::defaults::NodeIdentifier {
+        let dir_path = PathBuf::from("");
+        let mut dir_path = dir_path.components().peekable();
+        let name = b"";
+        let root_full_node = NpmProcessor::<true, false, NpmModuleAcc>::new(
+            self.repository,
+            prepro,
+            &mut dir_path,
+            name,
+            self.commit_builder.tree_oid(),
+        )
+        .process();
+        let h = prepro
+            .processing_systems
+            .mut_or_default::<NpmProcessorHolder>();
+        let handle =
+            <NpmProc as crate::processing::erased::CommitProcExt>::register_param(h, Parameter);
+        let commit_oid = self.commit_builder.commit_oid();
+        let commit = self.commit_builder.finish(root_full_node.0);
+        h.with_parameters_mut(handle.0)
+            .commits
+            .insert(commit_oid, commit);
+        root_full_node.0
+    }
+}
+impl crate::processing::erased::CommitProc for NpmProc {
+    fn process_root_tree(
+        &mut self,
+        repository: &git2::Repository,
+        tree_oid: &git2::Oid,
+    ) -> hyper_ast::store::defaults::NodeIdentifier {
+        unimplemented!("see reason in the java proc")
+    }
+
+    fn prepare_processing<'repo>(
+        &self,
+        repository: &'repo git2::Repository,
+        commit_builder: crate::preprocessed::CommitBuilder,
+    ) -> Box<dyn crate::processing::erased::PreparedCommitProc + 'repo> {
+        Box::new(PreparedNpmCommitProc {
+            repository,
+            commit_builder,
+        })
+    }
+
+    fn get_commit(&self, commit_oid: git2::Oid) -> Option<&crate::Commit> {
+        self.commits.get(&commit_oid)
+    }
+}
+
+impl NpmProcessorHolder {
+    pub(crate) fn commits(&self) -> Option<&std::collections::HashMap<git2::Oid, crate::Commit>> {
+        self.0.as_ref().map(|x| &x.commits)
+    }
+}
+impl NpmProc {
+    pub(crate) fn commits_mut(
+        &mut self,
+    ) -> &mut std::collections::HashMap<git2::Oid, crate::Commit> {
+        &mut self.commits
+    }
+}
+impl crate::processing::erased::CommitProcExt for NpmProc {
+    type Holder = NpmProcessorHolder;
+}
+impl crate::processing::erased::ParametrizedCommitProc2 for NpmProcessorHolder {
+    type Proc = NpmProc;
+
+    fn with_parameters_mut(
+        &mut self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &mut Self::Proc {
+        assert_eq!(0, parameters.0);
+        self.0.as_mut().unwrap()
+    }
+
+    fn with_parameters(
+        &self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &Self::Proc {
+        assert_eq!(0, parameters.0);
+        self.0.as_ref().unwrap()
+    }
+}
+impl CacheHolding<crate::processing::caches::Npm> for NpmProc {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::Npm {
+        &mut self.cache
+    }
+    fn get_caches(&self) -> &crate::processing::caches::Npm {
+        &self.cache
+    }
+}
+impl CacheHolding<crate::processing::caches::Npm> for NpmProcessorHolder {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::Npm {
+        &mut self.0.as_mut().unwrap().cache
+    }
+    fn get_caches(&self) -> &crate::processing::caches::Npm {
+        &self.0.as_ref().unwrap().cache
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn workspaces_globs() {
+        let globs = Some(vec!["packages/*".into(), "apps/web".into(), "tools".into()]);
+        assert_eq!(match_workspaces(&globs, "tools"), (true, vec![]));
+        assert_eq!(
+            match_workspaces(&globs, "packages"),
+            (false, vec![PathBuf::from("*")])
+        );
+        assert_eq!(
+            match_workspaces(&Some(vec!["*".into()]), "core"),
+            (true, vec![])
+        );
+        assert_eq!(match_workspaces(&globs, "docs"), (false, vec![]));
+        assert!(glob_match("pkg-*", "pkg-a"));
+        assert!(!glob_match("pkg-*", "lib-a"));
+    }
+}
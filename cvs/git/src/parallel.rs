@@ -0,0 +1,112 @@
+//! Parsing the files of many commits concurrently.
+//!
+//! Building hyperASTs needs an exclusive access to the single [`crate::SimpleStores`],
+//! but most of the time spent on a new file goes into its parsing, which is independent of the stores.
+//! So the blobs of a batch of commits are first parsed by a pool of workers, keyed by their oid,
+//! then the commits are processed sequentially as usual, taking the parsed trees instead of parsing again
+//! (see [`crate::preprocessed::RepositoryProcessor::pre_process_parallel`]).
+use std::{
+    collections::HashSet,
+    path::Path,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use git2::{ObjectType, Oid, Repository};
+
+use crate::processing::ObjectName;
+
+/// The result of a tree-sitter parser, the tree is an error if it contains error nodes.
+pub type Parsed = Result<tree_sitter::Tree, tree_sitter::Tree>;
+
+/// Number of commits whose files are parsed before building them,
+/// it bounds the number of parsed trees held in memory.
+pub const BATCH_SIZE: usize = 100;
+
+/// Finds the blobs that were not explored by previous batches.
+///
+/// Subtrees are mostly shared between commits,
+/// so an already seen tree is not explored again.
+#[derive(Default)]
+pub struct Prefetcher {
+    seen: HashSet<Oid>,
+}
+
+impl Prefetcher {
+    pub fn new_blobs(
+        &mut self,
+        repository: &Repository,
+        commits: &[Oid],
+        matches: impl Fn(&ObjectName) -> bool,
+    ) -> Result<Vec<Oid>, git2::Error> {
+        let mut res = vec![];
+        for oid in commits {
+            let tree = repository.find_commit(*oid)?.tree()?;
+            if self.seen.insert(tree.id()) {
+                self.explore(repository, &tree, &matches, &mut res)?;
+            }
+        }
+        Ok(res)
+    }
+
+    fn explore(
+        &mut self,
+        repository: &Repository,
+        tree: &git2::Tree,
+        matches: &impl Fn(&ObjectName) -> bool,
+        out: &mut Vec<Oid>,
+    ) -> Result<(), git2::Error> {
+        for entry in tree.iter() {
+            let oid = entry.id();
+            if !self.seen.insert(oid) {
+                continue;
+            }
+            match entry.kind() {
+                Some(ObjectType::Tree) => {
+                    let tree = repository.find_tree(oid)?;
+                    self.explore(repository, &tree, matches, out)?;
+                }
+                Some(ObjectType::Blob) if matches(&entry.name_bytes().into()) => out.push(oid),
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parses `blobs` on `workers` threads,
+/// each one opens its own handle on the repository at `path` as [`Repository`] is not [`Sync`].
+pub fn parse_blobs(
+    path: &Path,
+    blobs: &[Oid],
+    workers: usize,
+    parse: fn(&[u8]) -> Parsed,
+) -> Vec<(Oid, Parsed)> {
+    let next = AtomicUsize::new(0);
+    std::thread::scope(|s| {
+        let handles: Vec<_> = (0..workers.max(1))
+            .map(|_| {
+                s.spawn(|| {
+                    let Ok(repository) = Repository::open(path) else {
+                        return vec![];
+                    };
+                    let mut res = vec![];
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(oid) = blobs.get(i) else {
+                            break;
+                        };
+                        // the blob is parsed again during the sequential processing if it could not be read here
+                        if let Ok(blob) = repository.find_blob(*oid) {
+                            res.push((*oid, parse(blob.content())));
+                        }
+                    }
+                    res
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().unwrap())
+            .collect()
+    })
+}
@@ -16,11 +16,21 @@ use hyper_ast_gen_ts_java::impact::partial_analysis::PartialAnalysis;
 use log::info;
 
 use crate::{
-    git::{all_commits_between, retrieve_commit},
+    cargo::CargoModuleAcc,
+    cargo_processor::CargoProcessor,
+    cmake::CMakeModuleAcc,
+    cmake_processor::CMakeProcessor,
+    git::{all_commits_between, retrieve_commit, synthetic_commit, WorkingState},
+    gradle::GradleModuleAcc,
+    gradle_processor::GradleProcessor,
     make::MakeModuleAcc,
     make_processor::MakeProcessor,
     maven::MavenModuleAcc,
     maven_processor::MavenProcessor,
+    npm::NpmModuleAcc,
+    npm_processor::NpmProcessor,
+    pip::PipModuleAcc,
+    pip_processor::PipProcessor,
     processing::{
         erased::ParametrizedCommitProcessorHandle, file_sys, CacheHolding, ConfiguredRepo,
         ConfiguredRepo2,
@@ -143,6 +153,18 @@ impl PreProcessedRepository {
         self.processor.purge_caches()
     }
 
+    /// Only keeps the `retained` commits and the nodes reachable from them.
+    /// Caches are purged as they could reference removed nodes.
+    pub fn gc(
+        &mut self,
+        retained: &[git2::Oid],
+    ) -> hyper_ast::store::nodes::legion::gc::GcStats {
+        self.commits.retain(|oid, _| retained.contains(oid));
+        self.processor.purge_caches();
+        let roots = self.commits.values().map(|c| c.ast_root);
+        self.processor.main_stores.node_store.gc(roots)
+    }
+
     pub fn child_by_name(&self, d: NodeIdentifier, name: &str) -> Option<NodeIdentifier> {
         self.processor.child_by_name(d, name)
     }
@@ -239,6 +261,79 @@ impl RepositoryProcessor {
             .collect();
         Ok(r)
     }
+
+    /// Same as [`Self::pre_process_with_limit`],
+    /// but the files of each batch of commits are first parsed concurrently by `workers` threads,
+    /// see [`crate::parallel`].
+    ///
+    /// For now only java files are parsed in advance.
+    #[cfg(feature = "java")]
+    pub fn pre_process_parallel(
+        &mut self,
+        repository: &mut ConfiguredRepo2,
+        before: &str,
+        after: &str,
+        limit: usize,
+        workers: usize,
+    ) -> Result<Vec<git2::Oid>, git2::Error> {
+        use crate::parallel::{parse_blobs, Prefetcher, BATCH_SIZE};
+        use crate::processing::InFiles;
+        let commits = all_commits_between(&repository.repo, before, after)?
+            .take(limit)
+            .collect::<Result<Vec<_>, _>>()?;
+        log::info!("commits to process: {}", commits.len());
+        let mut prefetcher = Prefetcher::default();
+        for batch in commits.chunks(BATCH_SIZE) {
+            let blobs = prefetcher.new_blobs(&repository.repo, batch, file_sys::Java::matches)?;
+            if !blobs.is_empty() {
+                let parsed = parse_blobs(
+                    repository.repo.path(),
+                    &blobs,
+                    workers,
+                    hyper_ast_gen_ts_java::legion_with_refs::tree_sitter_parse,
+                );
+                self.parsed_java_files().extend(parsed);
+            }
+            for &oid in batch {
+                let builder = crate::preprocessed::CommitBuilder::start(&repository.repo, oid);
+                let commit_processor = self
+                    .processing_systems
+                    .by_id_mut(&repository.config.0)
+                    .unwrap()
+                    .get_mut(repository.config.1);
+                commit_processor
+                    .prepare_processing(&repository.repo, builder)
+                    .process(self);
+            }
+            if !blobs.is_empty() {
+                // files that were not needed, eg. outside of source directories
+                self.parsed_java_files().clear();
+            }
+        }
+        Ok(commits)
+    }
+
+    /// Processes the staged changes or the whole working directory of a local checkout,
+    /// as a synthetic commit on top of HEAD (see [`crate::git::synthetic_commit`]).
+    ///
+    /// Files and directories that did not change are taken from the caches filled by previously processed commits.
+    pub fn pre_process_working_dir(
+        &mut self,
+        repository: &mut ConfiguredRepo2,
+        state: WorkingState,
+    ) -> Result<git2::Oid, git2::Error> {
+        let oid = synthetic_commit(&repository.repo, state)?;
+        let builder = crate::preprocessed::CommitBuilder::start(&repository.repo, oid);
+        let commit_processor = self
+            .processing_systems
+            .by_id_mut(&repository.config.0)
+            .unwrap()
+            .get_mut(repository.config.1);
+        commit_processor
+            .prepare_processing(&repository.repo, builder)
+            .process(self);
+        Ok(oid)
+    }
 }
 #[cfg(feature = "maven_java")]
 impl PreProcessedRepository {
@@ -418,6 +513,24 @@ impl PreProcessedRepository {
         self.commits.insert(oid.clone(), c);
         oid
     }
+
+    /// see [`RepositoryProcessor::pre_process_working_dir`]
+    pub fn pre_process_working_dir(
+        &mut self,
+        repository: &mut Repository,
+        dir_path: &str,
+        state: WorkingState,
+    ) -> Result<git2::Oid, git2::Error> {
+        let oid = synthetic_commit(repository, state)?;
+        let c = CommitProcessor::<file_sys::Maven>::handle_commit::<false>(
+            &mut self.processor,
+            &repository,
+            dir_path,
+            oid,
+        );
+        self.commits.insert(oid.clone(), c);
+        Ok(oid)
+    }
 }
 
 #[cfg(feature = "java")]
@@ -514,6 +627,291 @@ impl PreProcessedRepository {
     }
 }
 
+#[cfg(feature = "npm_ts")]
+impl PreProcessedRepository {
+    pub fn pre_process_npm_project_with_limit(
+        &mut self,
+        repository: &mut Repository,
+        before: &str,
+        after: &str,
+        dir_path: &str,
+        limit: usize,
+    ) -> Vec<git2::Oid> {
+        log::info!(
+            "commits to process: {:?}",
+            all_commits_between(&repository, before, after).map(|x| x.count())
+        );
+        let mut processing_ordered_commits = vec![];
+        let rw = all_commits_between(&repository, before, after);
+        let Ok(rw) = rw else {
+            dbg!(rw.err());
+            return vec![]
+        };
+        rw
+            // .skip(1500)release-1.0.0 refs/tags/release-3.3.2-RC4
+            .take(limit) // TODO make a variable
+            .for_each(|oid| {
+                let oid = oid.unwrap();
+                let c = CommitProcessor::<file_sys::Npm>::handle_commit::<false>(
+                    &mut self.processor,
+                    &repository,
+                    dir_path,
+                    oid,
+                );
+                processing_ordered_commits.push(oid.clone());
+                self.commits.insert(oid.clone(), c);
+            });
+        processing_ordered_commits
+    }
+
+    // TODO auto detect and selectect processor,
+    // TODO pass processor as dyn param
+    pub fn pre_process_npm_project(
+        &mut self,
+        repository: &mut Repository,
+        ref_or_commit: &str,
+        dir_path: &str,
+    ) -> git2::Oid {
+        let oid = retrieve_commit(repository, ref_or_commit).unwrap().id();
+        let c = CommitProcessor::<file_sys::Npm>::handle_commit::<false>(
+            &mut self.processor,
+            &repository,
+            dir_path,
+            oid,
+        );
+        self.commits.insert(oid.clone(), c);
+        oid
+    }
+}
+
+#[cfg(feature = "pip_python")]
+impl PreProcessedRepository {
+    pub fn pre_process_pip_project_with_limit(
+        &mut self,
+        repository: &mut Repository,
+        before: &str,
+        after: &str,
+        dir_path: &str,
+        limit: usize,
+    ) -> Vec<git2::Oid> {
+        log::info!(
+            "commits to process: {:?}",
+            all_commits_between(&repository, before, after).map(|x| x.count())
+        );
+        let mut processing_ordered_commits = vec![];
+        let rw = all_commits_between(&repository, before, after);
+        let Ok(rw) = rw else {
+            dbg!(rw.err());
+            return vec![]
+        };
+        rw
+            // .skip(1500)release-1.0.0 refs/tags/release-3.3.2-RC4
+            .take(limit) // TODO make a variable
+            .for_each(|oid| {
+                let oid = oid.unwrap();
+                let c = CommitProcessor::<file_sys::Pip>::handle_commit::<false>(
+                    &mut self.processor,
+                    &repository,
+                    dir_path,
+                    oid,
+                );
+                processing_ordered_commits.push(oid.clone());
+                self.commits.insert(oid.clone(), c);
+            });
+        processing_ordered_commits
+    }
+
+    // TODO auto detect and selectect processor,
+    // TODO pass processor as dyn param
+    pub fn pre_process_pip_project(
+        &mut self,
+        repository: &mut Repository,
+        ref_or_commit: &str,
+        dir_path: &str,
+    ) -> git2::Oid {
+        let oid = retrieve_commit(repository, ref_or_commit).unwrap().id();
+        let c = CommitProcessor::<file_sys::Pip>::handle_commit::<false>(
+            &mut self.processor,
+            &repository,
+            dir_path,
+            oid,
+        );
+        self.commits.insert(oid.clone(), c);
+        oid
+    }
+}
+
+#[cfg(feature = "cargo_rust")]
+impl PreProcessedRepository {
+    pub fn pre_process_cargo_project_with_limit(
+        &mut self,
+        repository: &mut Repository,
+        before: &str,
+        after: &str,
+        dir_path: &str,
+        limit: usize,
+    ) -> Vec<git2::Oid> {
+        log::info!(
+            "commits to process: {:?}",
+            all_commits_between(&repository, before, after).map(|x| x.count())
+        );
+        let mut processing_ordered_commits = vec![];
+        let rw = all_commits_between(&repository, before, after);
+        let Ok(rw) = rw else {
+            dbg!(rw.err());
+            return vec![]
+        };
+        rw
+            // .skip(1500)release-1.0.0 refs/tags/release-3.3.2-RC4
+            .take(limit) // TODO make a variable
+            .for_each(|oid| {
+                let oid = oid.unwrap();
+                let c = CommitProcessor::<file_sys::Cargo>::handle_commit::<false>(
+                    &mut self.processor,
+                    &repository,
+                    dir_path,
+                    oid,
+                );
+                processing_ordered_commits.push(oid.clone());
+                self.commits.insert(oid.clone(), c);
+            });
+        processing_ordered_commits
+    }
+
+    // TODO auto detect and selectect processor,
+    // TODO pass processor as dyn param
+    pub fn pre_process_cargo_project(
+        &mut self,
+        repository: &mut Repository,
+        ref_or_commit: &str,
+        dir_path: &str,
+    ) -> git2::Oid {
+        let oid = retrieve_commit(repository, ref_or_commit).unwrap().id();
+        let c = CommitProcessor::<file_sys::Cargo>::handle_commit::<false>(
+            &mut self.processor,
+            &repository,
+            dir_path,
+            oid,
+        );
+        self.commits.insert(oid.clone(), c);
+        oid
+    }
+}
+
+#[cfg(feature = "gradle_java")]
+impl PreProcessedRepository {
+    pub fn pre_process_gradle_project_with_limit(
+        &mut self,
+        repository: &mut Repository,
+        before: &str,
+        after: &str,
+        dir_path: &str,
+        limit: usize,
+    ) -> Vec<git2::Oid> {
+        log::info!(
+            "commits to process: {:?}",
+            all_commits_between(&repository, before, after).map(|x| x.count())
+        );
+        let mut processing_ordered_commits = vec![];
+        let rw = all_commits_between(&repository, before, after);
+        let Ok(rw) = rw else {
+            dbg!(rw.err());
+            return vec![]
+        };
+        rw
+            // .skip(1500)release-1.0.0 refs/tags/release-3.3.2-RC4
+            .take(limit) // TODO make a variable
+            .for_each(|oid| {
+                let oid = oid.unwrap();
+                let c = CommitProcessor::<file_sys::Gradle>::handle_commit::<false>(
+                    &mut self.processor,
+                    &repository,
+                    dir_path,
+                    oid,
+                );
+                processing_ordered_commits.push(oid.clone());
+                self.commits.insert(oid.clone(), c);
+            });
+        processing_ordered_commits
+    }
+
+    // TODO auto detect and selectect processor,
+    // TODO pass processor as dyn param
+    pub fn pre_process_gradle_project(
+        &mut self,
+        repository: &mut Repository,
+        ref_or_commit: &str,
+        dir_path: &str,
+    ) -> git2::Oid {
+        let oid = retrieve_commit(repository, ref_or_commit).unwrap().id();
+        let c = CommitProcessor::<file_sys::Gradle>::handle_commit::<false>(
+            &mut self.processor,
+            &repository,
+            dir_path,
+            oid,
+        );
+        self.commits.insert(oid.clone(), c);
+        oid
+    }
+}
+
+#[cfg(feature = "cmake_cpp")]
+impl PreProcessedRepository {
+    pub fn pre_process_cmake_project_with_limit(
+        &mut self,
+        repository: &mut Repository,
+        before: &str,
+        after: &str,
+        dir_path: &str,
+        limit: usize,
+    ) -> Vec<git2::Oid> {
+        log::info!(
+            "commits to process: {:?}",
+            all_commits_between(&repository, before, after).map(|x| x.count())
+        );
+        let mut processing_ordered_commits = vec![];
+        let rw = all_commits_between(&repository, before, after);
+        let Ok(rw) = rw else {
+            dbg!(rw.err());
+            return vec![]
+        };
+        rw
+            // .skip(1500)release-1.0.0 refs/tags/release-3.3.2-RC4
+            .take(limit) // TODO make a variable
+            .for_each(|oid| {
+                let oid = oid.unwrap();
+                let c = CommitProcessor::<file_sys::CMake>::handle_commit::<false>(
+                    &mut self.processor,
+                    &repository,
+                    dir_path,
+                    oid,
+                );
+                processing_ordered_commits.push(oid.clone());
+                self.commits.insert(oid.clone(), c);
+            });
+        processing_ordered_commits
+    }
+
+    // TODO auto detect and selectect processor,
+    // TODO pass processor as dyn param
+    pub fn pre_process_cmake_project(
+        &mut self,
+        repository: &mut Repository,
+        ref_or_commit: &str,
+        dir_path: &str,
+    ) -> git2::Oid {
+        let oid = retrieve_commit(repository, ref_or_commit).unwrap().id();
+        let c = CommitProcessor::<file_sys::CMake>::handle_commit::<false>(
+            &mut self.processor,
+            &repository,
+            dir_path,
+            oid,
+        );
+        self.commits.insert(oid.clone(), c);
+        oid
+    }
+}
+
 pub(crate) trait CommitProcessor<Sys> {
     type Module: IdHolder<Id = NodeIdentifier>;
     /// How to handle a module in a commit eg. maven modules, cargo crate.
@@ -712,17 +1110,17 @@ impl CommitProcessor<file_sys::Any> for RepositoryProcessor {
 }
 
 /// plan to work on all languges of the family of typesript ie. ts, js, tsx, jsx
-/// - [ ] ts
-/// - [ ] js
-/// - [ ] tsx
-/// - [ ] jsx
+/// - [x] ts
+/// - [x] js
+/// - [x] tsx
+/// - [x] jsx
 /// - [ ] d.ts
 /// - [ ] various transpiler configs
 ///   - [ ] babel
 ///   - [ ] ts
 #[cfg(feature = "npm")]
 impl CommitProcessor<file_sys::Npm> for RepositoryProcessor {
-    type Module = (NodeIdentifier, DefaultMetrics);
+    type Module = (NodeIdentifier, crate::npm::MD);
     fn handle_module<'a, 'b, const RMS: bool>(
         &mut self,
         repository: &'a Repository,
@@ -730,12 +1128,79 @@ impl CommitProcessor<file_sys::Npm> for RepositoryProcessor {
         name: &[u8],
         oid: git2::Oid,
     ) -> Self::Module {
-        todo!()
-        // let root_full_node = NpmProcessor::<RMS, FFWD, NpmModuleAcc>::new(repository, self, dir_path, name, oid)
-        //     .process();
-        // // self.object_map_make
-        // //     .insert(commit_oid, root_full_node.clone());
-        // root_full_node.0
+        let root_full_node =
+            NpmProcessor::<RMS, false, NpmModuleAcc>::new(repository, self, dir_path, name, oid)
+                .process();
+        root_full_node
+    }
+}
+
+#[cfg(feature = "pip")]
+impl CommitProcessor<file_sys::Pip> for RepositoryProcessor {
+    type Module = (NodeIdentifier, crate::pip::MD);
+    fn handle_module<'a, 'b, const RMS: bool>(
+        &mut self,
+        repository: &'a Repository,
+        dir_path: &'b mut Peekable<Components<'b>>,
+        name: &[u8],
+        oid: git2::Oid,
+    ) -> Self::Module {
+        let root_full_node =
+            PipProcessor::<RMS, false, PipModuleAcc>::new(repository, self, dir_path, name, oid)
+                .process();
+        root_full_node
+    }
+}
+
+#[cfg(feature = "gradle")]
+impl CommitProcessor<file_sys::Gradle> for RepositoryProcessor {
+    type Module = (NodeIdentifier, crate::gradle::MD);
+    fn handle_module<'a, 'b, const RMS: bool>(
+        &mut self,
+        repository: &'a Repository,
+        dir_path: &'b mut Peekable<Components<'b>>,
+        name: &[u8],
+        oid: git2::Oid,
+    ) -> Self::Module {
+        let root_full_node = GradleProcessor::<RMS, false, GradleModuleAcc>::new(
+            repository, self, dir_path, name, oid,
+        )
+        .process();
+        root_full_node
+    }
+}
+
+#[cfg(feature = "cmake")]
+impl CommitProcessor<file_sys::CMake> for RepositoryProcessor {
+    type Module = (NodeIdentifier, crate::cmake::MD);
+    fn handle_module<'a, 'b, const RMS: bool>(
+        &mut self,
+        repository: &'a Repository,
+        dir_path: &'b mut Peekable<Components<'b>>,
+        name: &[u8],
+        oid: git2::Oid,
+    ) -> Self::Module {
+        let root_full_node =
+            CMakeProcessor::<RMS, false, CMakeModuleAcc>::new(repository, self, dir_path, name, oid)
+                .process();
+        root_full_node
+    }
+}
+
+#[cfg(feature = "cargo")]
+impl CommitProcessor<file_sys::Cargo> for RepositoryProcessor {
+    type Module = (NodeIdentifier, crate::cargo::MD);
+    fn handle_module<'a, 'b, const RMS: bool>(
+        &mut self,
+        repository: &'a Repository,
+        dir_path: &'b mut Peekable<Components<'b>>,
+        name: &[u8],
+        oid: git2::Oid,
+    ) -> Self::Module {
+        let root_full_node =
+            CargoProcessor::<RMS, false, CargoModuleAcc>::new(repository, self, dir_path, name, oid)
+                .process();
+        root_full_node
     }
 }
 
@@ -6,8 +6,12 @@ pub use erased::ParametrizedCommitProcessorHandle;
 
 pub enum BuildSystem {
     Maven,
+    Gradle,
     Make,
+    CMake,
     Npm,
+    Pip,
+    Cargo,
     None,
 }
 
@@ -15,13 +19,19 @@ enum Language {
     Java,
     Cpp,
     Ts,
+    Python,
+    Rust,
     Xml,
 }
 
 pub enum ProcessingConfig<P> {
     JavaMaven { limit: usize, dir_path: P },
+    JavaGradle { limit: usize, dir_path: P },
     CppMake { limit: usize, dir_path: P },
+    CppCMake { limit: usize, dir_path: P },
     TsNpm { limit: usize, dir_path: P },
+    PythonPip { limit: usize, dir_path: P },
+    RustCargo { limit: usize, dir_path: P },
     Any { limit: usize, dir_path: P },
 }
 
@@ -30,8 +40,12 @@ pub enum ProcessingConfig<P> {
 #[derive(serde::Deserialize, Clone, Copy, Debug, Hash, PartialEq, Eq)]
 pub enum RepoConfig {
     CppMake,
+    CppCMake,
     JavaMaven,
+    JavaGradle,
     TsNpm,
+    PythonPip,
+    RustCargo,
     Any,
 }
 
@@ -42,12 +56,22 @@ impl std::str::FromStr for RepoConfig {
         Ok(match s {
             "Cpp" => Self::CppMake,
             "cpp" => Self::CppMake,
+            "CMake" => Self::CppCMake,
+            "cmake" => Self::CppCMake,
             "Java" => Self::JavaMaven,
             "java" => Self::JavaMaven,
+            "Gradle" => Self::JavaGradle,
+            "gradle" => Self::JavaGradle,
             "typescript" => Self::TsNpm,
             "javascript" => Self::TsNpm,
             "Ts" => Self::TsNpm,
             "ts" => Self::TsNpm,
+            "Python" => Self::PythonPip,
+            "python" => Self::PythonPip,
+            "py" => Self::PythonPip,
+            "Rust" => Self::RustCargo,
+            "rust" => Self::RustCargo,
+            "rs" => Self::RustCargo,
             "any" => Self::Any,
             x => return Err(format!("'{}' is not anvailable config", x))
         })
@@ -61,11 +85,30 @@ impl From<&RepoConfig> for ProcessingConfig<&'static str> {
                 limit: 3,
                 dir_path: "",
             },
+            RepoConfig::CppCMake => Self::CppCMake {
+                limit: 3,
+                dir_path: "",
+            },
             RepoConfig::JavaMaven => Self::JavaMaven {
                 limit: 3,
                 dir_path: "",
             },
-            RepoConfig::TsNpm => todo!(),
+            RepoConfig::JavaGradle => Self::JavaGradle {
+                limit: 3,
+                dir_path: "",
+            },
+            RepoConfig::TsNpm => Self::TsNpm {
+                limit: 3,
+                dir_path: "",
+            },
+            RepoConfig::PythonPip => Self::PythonPip {
+                limit: 3,
+                dir_path: "",
+            },
+            RepoConfig::RustCargo => Self::RustCargo {
+                limit: 3,
+                dir_path: "",
+            },
             RepoConfig::Any => todo!(),
         }
     }
@@ -257,6 +300,8 @@ pub(crate) mod caches {
     pub struct Java {
         pub(crate) md_cache: hyper_ast_gen_ts_java::legion_with_refs::MDCache,
         pub object_map: NamedMap<(hyper_ast_gen_ts_java::legion_with_refs::Local, IsSkippedAna)>,
+        /// files parsed in advance, see [`crate::parallel`]
+        pub(crate) parsed: OidMap<crate::parallel::Parsed>,
     }
 
     impl super::ObjectMapper for Java {
@@ -341,6 +386,185 @@ pub(crate) mod caches {
         }
     }
 
+    #[cfg(feature = "cmake")]
+    #[derive(Default)]
+    pub struct CMake {
+        pub object_map: OidMap<(NodeIdentifier, crate::cmake::MD)>,
+    }
+
+    #[cfg(feature = "cmake")]
+    #[derive(Default)]
+    pub struct CMakeLists {
+        pub object_map: OidMap<crate::cmake::CMakeLists>,
+    }
+
+    #[cfg(feature = "cmake")]
+    impl super::ObjectMapper for CMakeLists {
+        type K = git2::Oid;
+
+        type V = crate::cmake::CMakeLists;
+
+        fn get(&self, key: &Self::K) -> Option<&Self::V> {
+            self.object_map.get(key)
+        }
+
+        fn insert(&mut self, key: Self::K, value: Self::V) -> Option<Self::V> {
+            self.object_map.insert(key, value)
+        }
+    }
+
+    /// the caches of the languages processed by [`crate::basic_processor::BasicProcessor`]
+    #[cfg(any(feature = "ts", feature = "python", feature = "rust"))]
+    #[derive(Default)]
+    pub struct Basic {
+        pub(crate) md_cache: hyper_ast::tree_gen::basic::MDCache,
+        pub object_map: NamedMap<(hyper_ast::tree_gen::basic::Local, IsSkippedAna)>,
+    }
+
+    #[cfg(any(feature = "ts", feature = "python", feature = "rust"))]
+    impl super::ObjectMapper for Basic {
+        type K = (git2::Oid, ObjectName);
+
+        type V = (hyper_ast::tree_gen::basic::Local, IsSkippedAna);
+
+        fn get(&self, key: &Self::K) -> Option<&Self::V> {
+            self.object_map.get(key)
+        }
+
+        fn insert(&mut self, key: Self::K, value: Self::V) -> Option<Self::V> {
+            self.object_map.insert(key, value)
+        }
+    }
+
+    #[cfg(feature = "npm")]
+    #[derive(Default)]
+    pub struct Npm {
+        pub object_map: OidMap<(NodeIdentifier, crate::npm::MD)>,
+    }
+
+    #[cfg(feature = "npm")]
+    #[derive(Default)]
+    pub struct PackageJson {
+        pub object_map: OidMap<crate::npm::PackageJson>,
+    }
+
+    #[cfg(feature = "npm")]
+    impl super::ObjectMapper for PackageJson {
+        type K = git2::Oid;
+
+        type V = crate::npm::PackageJson;
+
+        fn get(&self, key: &Self::K) -> Option<&Self::V> {
+            self.object_map.get(key)
+        }
+
+        fn insert(&mut self, key: Self::K, value: Self::V) -> Option<Self::V> {
+            self.object_map.insert(key, value)
+        }
+    }
+
+    #[cfg(feature = "pip")]
+    #[derive(Default)]
+    pub struct Pip {
+        pub object_map: OidMap<(NodeIdentifier, crate::pip::MD)>,
+    }
+
+    #[cfg(feature = "pip")]
+    #[derive(Default)]
+    pub struct PyProject {
+        pub object_map: OidMap<crate::pip::PyProject>,
+    }
+
+    #[cfg(feature = "pip")]
+    impl super::ObjectMapper for PyProject {
+        type K = git2::Oid;
+
+        type V = crate::pip::PyProject;
+
+        fn get(&self, key: &Self::K) -> Option<&Self::V> {
+            self.object_map.get(key)
+        }
+
+        fn insert(&mut self, key: Self::K, value: Self::V) -> Option<Self::V> {
+            self.object_map.insert(key, value)
+        }
+    }
+
+    #[cfg(feature = "cargo")]
+    #[derive(Default)]
+    pub struct Cargo {
+        pub object_map: OidMap<(NodeIdentifier, crate::cargo::MD)>,
+    }
+
+    #[cfg(feature = "cargo")]
+    #[derive(Default)]
+    pub struct CargoToml {
+        pub object_map: OidMap<crate::cargo::CargoToml>,
+    }
+
+    #[cfg(feature = "cargo")]
+    impl super::ObjectMapper for CargoToml {
+        type K = git2::Oid;
+
+        type V = crate::cargo::CargoToml;
+
+        fn get(&self, key: &Self::K) -> Option<&Self::V> {
+            self.object_map.get(key)
+        }
+
+        fn insert(&mut self, key: Self::K, value: Self::V) -> Option<Self::V> {
+            self.object_map.insert(key, value)
+        }
+    }
+
+    #[cfg(feature = "gradle")]
+    #[derive(Default)]
+    pub struct Gradle {
+        pub object_map: OidMap<(NodeIdentifier, crate::gradle::MD)>,
+    }
+
+    #[cfg(feature = "gradle")]
+    #[derive(Default)]
+    pub struct GradleSettings {
+        pub object_map: OidMap<crate::gradle::GradleSettings>,
+    }
+
+    #[cfg(feature = "gradle")]
+    impl super::ObjectMapper for GradleSettings {
+        type K = git2::Oid;
+
+        type V = crate::gradle::GradleSettings;
+
+        fn get(&self, key: &Self::K) -> Option<&Self::V> {
+            self.object_map.get(key)
+        }
+
+        fn insert(&mut self, key: Self::K, value: Self::V) -> Option<Self::V> {
+            self.object_map.insert(key, value)
+        }
+    }
+
+    #[cfg(feature = "gradle")]
+    #[derive(Default)]
+    pub struct GradleBuild {
+        pub object_map: OidMap<crate::gradle::GradleBuild>,
+    }
+
+    #[cfg(feature = "gradle")]
+    impl super::ObjectMapper for GradleBuild {
+        type K = git2::Oid;
+
+        type V = crate::gradle::GradleBuild;
+
+        fn get(&self, key: &Self::K) -> Option<&Self::V> {
+            self.object_map.get(key)
+        }
+
+        fn insert(&mut self, key: Self::K, value: Self::V) -> Option<Self::V> {
+            self.object_map.insert(key, value)
+        }
+    }
+
     // // any
     // pub object_map_any: OidMap<(NodeIdentifier, DefaultMetrics)>,
     // // maven
@@ -410,6 +634,48 @@ pub mod file_sys {
         }
     }
 
+    /// The gradle scheme https://docs.gradle.org/current/userguide/multi_project_builds.html ,
+    /// made of projects declared in a settings.gradle(.kts) at the root of the build.
+    /// Each project has a build script (build.gradle or build.gradle.kts)
+    /// and by convention the same source sets layout as maven ie. src/main/java/ and src/test/java/
+    #[cfg(feature = "gradle")]
+    pub struct Gradle;
+
+    #[cfg(feature = "gradle")]
+    impl CachesHolding for Gradle {
+        type Caches = super::caches::Gradle;
+    }
+
+    #[cfg(feature = "gradle")]
+    pub struct GradleSettings;
+
+    #[cfg(feature = "gradle")]
+    impl CachesHolding for GradleSettings {
+        type Caches = super::caches::GradleSettings;
+    }
+
+    #[cfg(feature = "gradle")]
+    impl super::InFiles for GradleSettings {
+        fn matches(name: &ObjectName) -> bool {
+            name.0.eq(b"settings.gradle") || name.0.eq(b"settings.gradle.kts")
+        }
+    }
+
+    #[cfg(feature = "gradle")]
+    pub struct GradleBuild;
+
+    #[cfg(feature = "gradle")]
+    impl CachesHolding for GradleBuild {
+        type Caches = super::caches::GradleBuild;
+    }
+
+    #[cfg(feature = "gradle")]
+    impl super::InFiles for GradleBuild {
+        fn matches(name: &ObjectName) -> bool {
+            name.0.eq(b"build.gradle") || name.0.eq(b"build.gradle.kts")
+        }
+    }
+
     /// The java scheme,
     /// made of packages and modules https://docs.oracle.com/javase/specs/jls/se11/html/jls-7.html
     #[cfg(feature = "maven")]
@@ -448,6 +714,32 @@ pub mod file_sys {
         }
     }
 
+    /// The cmake scheme,
+    /// a CMakeLists.txt declares targets made of source files,
+    /// and adds other directories with their own CMakeLists.txt using add_subdirectory.
+    #[cfg(feature = "cmake")]
+    pub struct CMake;
+
+    #[cfg(feature = "cmake")]
+    impl CachesHolding for CMake {
+        type Caches = super::caches::CMake;
+    }
+
+    #[cfg(feature = "cmake")]
+    pub struct CMakeLists;
+
+    #[cfg(feature = "cmake")]
+    impl CachesHolding for CMakeLists {
+        type Caches = super::caches::CMakeLists;
+    }
+
+    #[cfg(feature = "cmake")]
+    impl super::InFiles for CMakeLists {
+        fn matches(name: &ObjectName) -> bool {
+            name.0.eq(b"CMakeLists.txt")
+        }
+    }
+
     #[cfg(feature = "cpp")]
     pub struct Cpp;
 
@@ -471,6 +763,163 @@ pub mod file_sys {
     /// or is a collection of packages that contains a packages/ directory where each package is located
     #[cfg(feature = "npm")]
     pub struct Npm;
+
+    #[cfg(feature = "npm")]
+    impl CachesHolding for Npm {
+        type Caches = super::caches::Npm;
+    }
+
+    #[cfg(feature = "npm")]
+    pub struct PackageJson;
+
+    #[cfg(feature = "npm")]
+    impl CachesHolding for PackageJson {
+        type Caches = super::caches::PackageJson;
+    }
+
+    #[cfg(feature = "npm")]
+    impl super::InFiles for PackageJson {
+        fn matches(name: &ObjectName) -> bool {
+            name.0.eq(b"package.json")
+        }
+    }
+
+    /// Installed dependencies of an npm package, not part of the sources
+    #[cfg(feature = "ts")]
+    pub struct NodeModules;
+
+    #[cfg(feature = "ts")]
+    impl super::InFiles for NodeModules {
+        fn matches(name: &ObjectName) -> bool {
+            name.0.eq(b"node_modules")
+        }
+    }
+
+    #[cfg(feature = "ts")]
+    pub struct Ts;
+
+    #[cfg(feature = "ts")]
+    impl CachesHolding for Ts {
+        type Caches = super::caches::Basic;
+    }
+
+    #[cfg(feature = "ts")]
+    impl super::InFiles for Ts {
+        fn matches(name: &ObjectName) -> bool {
+            name.0.ends_with(b".ts")
+                || name.0.ends_with(b".tsx")
+                || name.0.ends_with(b".mts")
+                || name.0.ends_with(b".cts")
+                || name.0.ends_with(b".js")
+                || name.0.ends_with(b".jsx")
+                || name.0.ends_with(b".mjs")
+                || name.0.ends_with(b".cjs")
+        }
+    }
+
+    /// The python scheme,
+    /// a project is a directory with a pyproject.toml or a setup.py,
+    /// its python packages are the directories containing an __init__.py
+    #[cfg(feature = "pip")]
+    pub struct Pip;
+
+    #[cfg(feature = "pip")]
+    impl CachesHolding for Pip {
+        type Caches = super::caches::Pip;
+    }
+
+    #[cfg(feature = "pip")]
+    pub struct PyProject;
+
+    #[cfg(feature = "pip")]
+    impl CachesHolding for PyProject {
+        type Caches = super::caches::PyProject;
+    }
+
+    #[cfg(feature = "pip")]
+    impl super::InFiles for PyProject {
+        fn matches(name: &ObjectName) -> bool {
+            name.0.eq(b"pyproject.toml")
+        }
+    }
+
+    /// Bytecode compiled by the interpreter, not part of the sources
+    #[cfg(feature = "python")]
+    pub struct PyCache;
+
+    #[cfg(feature = "python")]
+    impl super::InFiles for PyCache {
+        fn matches(name: &ObjectName) -> bool {
+            name.0.eq(b"__pycache__")
+        }
+    }
+
+    #[cfg(feature = "python")]
+    pub struct Python;
+
+    #[cfg(feature = "python")]
+    impl CachesHolding for Python {
+        type Caches = super::caches::Basic;
+    }
+
+    #[cfg(feature = "python")]
+    impl super::InFiles for Python {
+        fn matches(name: &ObjectName) -> bool {
+            name.0.ends_with(b".py") || name.0.ends_with(b".pyi")
+        }
+    }
+
+    /// The rust scheme,
+    /// a crate is a directory with a Cargo.toml,
+    /// a workspace lists its member crates in its Cargo.toml
+    #[cfg(feature = "cargo")]
+    pub struct Cargo;
+
+    #[cfg(feature = "cargo")]
+    impl CachesHolding for Cargo {
+        type Caches = super::caches::Cargo;
+    }
+
+    #[cfg(feature = "cargo")]
+    pub struct CargoToml;
+
+    #[cfg(feature = "cargo")]
+    impl CachesHolding for CargoToml {
+        type Caches = super::caches::CargoToml;
+    }
+
+    #[cfg(feature = "cargo")]
+    impl super::InFiles for CargoToml {
+        fn matches(name: &ObjectName) -> bool {
+            name.0.eq(b"Cargo.toml")
+        }
+    }
+
+    /// Build outputs of cargo, not part of the sources
+    #[cfg(feature = "rust")]
+    pub struct Target;
+
+    #[cfg(feature = "rust")]
+    impl super::InFiles for Target {
+        fn matches(name: &ObjectName) -> bool {
+            name.0.eq(b"target")
+        }
+    }
+
+    #[cfg(feature = "rust")]
+    pub struct Rust;
+
+    #[cfg(feature = "rust")]
+    impl CachesHolding for Rust {
+        type Caches = super::caches::Basic;
+    }
+
+    #[cfg(feature = "rust")]
+    impl super::InFiles for Rust {
+        fn matches(name: &ObjectName) -> bool {
+            name.0.ends_with(b".rs")
+        }
+    }
 }
 
 impl crate::preprocessed::RepositoryProcessor {
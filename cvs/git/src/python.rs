@@ -0,0 +1,25 @@
+use crate::{processing::ObjectName, TStore, PROPAGATE_ERROR_ON_BAD_CST_NODE};
+
+use hyper_ast_gen_ts_python::legion as python_tree_gen;
+
+pub(crate) fn handle_python_file<'stores, 'cache, 'b: 'stores>(
+    tree_gen: &mut python_tree_gen::PythonTreeGen<'stores, 'cache, TStore>,
+    name: &ObjectName,
+    text: &'b [u8],
+) -> Result<python_tree_gen::FNode, ()> {
+    let tree = python_tree_gen::tree_sitter_parse(text);
+    let tree = match tree {
+        Ok(tree) => tree,
+        Err(tree) => {
+            log::warn!("bad CST");
+            log::debug!("{:?}", name.try_str());
+            log::debug!("{}", tree.root_node().to_sexp());
+            if PROPAGATE_ERROR_ON_BAD_CST_NODE {
+                return Err(());
+            } else {
+                tree
+            }
+        }
+    };
+    Ok(tree_gen.generate_file(name.as_bytes(), text, tree.walk().into()))
+}
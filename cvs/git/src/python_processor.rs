@@ -0,0 +1,28 @@
+//! Python directories are processed by the [`crate::basic_processor`]
+use hyper_ast::tree_gen::basic::{BasicTreeGen, FNode};
+use hyper_ast_gen_ts_python::types::{Python, Type};
+
+use crate::{
+    basic_processor::{BasicFiles, BasicProc, BasicProcessorHolder},
+    processing::{file_sys, ObjectName},
+    TStore,
+};
+
+pub use crate::basic_processor::Parameter;
+
+pub(crate) type PythonProcessorHolder = BasicProcessorHolder<file_sys::Python>;
+pub(crate) type PythonProc = BasicProc<file_sys::Python>;
+
+impl BasicFiles for file_sys::Python {
+    type Lang = Python;
+    type Skipped = file_sys::PyCache;
+    const DIRECTORY: Type = Type::Directory;
+
+    fn handle_file<'stores, 'cache, 'b: 'stores>(
+        tree_gen: &mut BasicTreeGen<'stores, 'cache, Python, TStore>,
+        name: &ObjectName,
+        text: &'b [u8],
+    ) -> Result<FNode, ()> {
+        crate::python::handle_python_file(tree_gen, name, text)
+    }
+}
@@ -0,0 +1,28 @@
+//! Rust directories are processed by the [`crate::basic_processor`]
+use hyper_ast::tree_gen::basic::{BasicTreeGen, FNode};
+use hyper_ast_gen_ts_rust::types::{Rust, Type};
+
+use crate::{
+    basic_processor::{BasicFiles, BasicProc, BasicProcessorHolder},
+    processing::{file_sys, ObjectName},
+    TStore,
+};
+
+pub use crate::basic_processor::Parameter;
+
+pub(crate) type RustProcessorHolder = BasicProcessorHolder<file_sys::Rust>;
+pub(crate) type RustProc = BasicProc<file_sys::Rust>;
+
+impl BasicFiles for file_sys::Rust {
+    type Lang = Rust;
+    type Skipped = file_sys::Target;
+    const DIRECTORY: Type = Type::Directory;
+
+    fn handle_file<'stores, 'cache, 'b: 'stores>(
+        tree_gen: &mut BasicTreeGen<'stores, 'cache, Rust, TStore>,
+        name: &ObjectName,
+        text: &'b [u8],
+    ) -> Result<FNode, ()> {
+        crate::rust::handle_rust_file(tree_gen, name, text)
+    }
+}
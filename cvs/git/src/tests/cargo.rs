@@ -0,0 +1,114 @@
+use std::path::Path;
+
+use git2::{Repository, Signature};
+
+use crate::{
+    git::{Forge, Repo},
+    multi_preprocessed::PreProcessedRepositories,
+    processing::{ConfiguredRepo2, RepoConfig},
+};
+
+const WORKSPACE: &str = r#"[workspace]
+members = ["crates/*", "cli"]
+"#;
+
+const A_MANIFEST: &str = r#"[package]
+name = "a"
+version = "0.1.0"
+edition = "2021"
+"#;
+
+const A_LIB: &str = r#"mod walk;
+
+pub use walk::walk;
+"#;
+
+const A_WALK: &str = r#"use std::path::{Path, PathBuf};
+
+pub fn walk(root: &Path) -> Vec<PathBuf> {
+    std::fs::read_dir(root)
+        .into_iter()
+        .flatten()
+        .filter_map(|x| x.ok().map(|x| x.path()))
+        .collect()
+}
+"#;
+
+const CLI_MAIN: &str = r#"fn main() {
+    println!("{:?}", a::walk(".".as_ref()));
+}
+"#;
+
+fn write(root: &Path, path: &str, content: &str) {
+    let path = root.join(path);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+fn init_repo(path: &Path) -> Repository {
+    if path.exists() {
+        std::fs::remove_dir_all(path).unwrap();
+    }
+    let repo = Repository::init(path).unwrap();
+    write(path, "Cargo.toml", WORKSPACE);
+    write(path, "crates/a/Cargo.toml", A_MANIFEST);
+    write(path, "crates/a/src/lib.rs", A_LIB);
+    write(path, "crates/a/src/walk.rs", A_WALK);
+    write(path, "cli/Cargo.toml", &A_MANIFEST.replace("\"a\"", "\"cli\""));
+    write(path, "cli/src/main.rs", CLI_MAIN);
+    write(path, "target/debug/cli", "\u{0}\u{1}");
+    {
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("test", "test@example.org").unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "test", &tree, &[])
+            .unwrap();
+    }
+    repo
+}
+
+#[test]
+fn process_cargo_workspace() {
+    let path = std::env::temp_dir().join("hyperast_cargo_workspace");
+    let repo = init_repo(&path);
+    let spec = Repo {
+        forge: Forge::Github,
+        user: "local".to_string(),
+        name: "cargo_workspace".to_string(),
+    };
+
+    let mut preprocessed = PreProcessedRepositories::default();
+    let handle = preprocessed.register_config(spec, RepoConfig::RustCargo);
+    let mut configured = ConfiguredRepo2 {
+        spec: handle.spec,
+        repo,
+        config: handle.config,
+    };
+    let head = preprocessed
+        .pre_process_with_limit(&mut configured, "", "", 10)
+        .unwrap()[0];
+    let root = preprocessed
+        .get_commit(&configured.config, &head)
+        .unwrap()
+        .ast_root;
+    let child = |d, path: &str| {
+        path.split('/')
+            .try_fold(d, |d, name| preprocessed.processor.child_by_name(d, name))
+    };
+    assert!(child(root, "Cargo.toml").is_some());
+    assert!(child(root, "crates/a/Cargo.toml").is_some());
+    assert!(child(root, "crates/a/src/lib.rs").is_some());
+    assert!(child(root, "crates/a/src/walk.rs").is_some());
+    assert!(child(root, "cli/src/main.rs").is_some());
+    // build outputs are not part of the sources
+    assert!(child(root, "target").is_none());
+
+    let commits = preprocessed.commits_per_sys().cargo;
+    assert_eq!(Some(root), commits.get(&head).map(|c| c.ast_root));
+
+    std::fs::remove_dir_all(path).unwrap();
+}
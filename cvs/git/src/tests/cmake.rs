@@ -0,0 +1,147 @@
+use std::path::Path;
+
+use git2::{Repository, Signature};
+
+use crate::{
+    cmake::TargetKind,
+    git::{Forge, Repo},
+    multi_preprocessed::PreProcessedRepositories,
+    processing::{ConfiguredRepo2, RepoConfig},
+};
+
+const ROOT_LISTS: &str = r#"cmake_minimum_required(VERSION 3.10)
+project(demo CXX)
+
+add_subdirectory(lib)
+add_executable(demo app/main.cpp)
+target_link_libraries(demo PRIVATE core)
+"#;
+
+const LIB_LISTS: &str = r#"set(SOURCES src/core.cpp)
+add_library(core STATIC ${SOURCES})
+target_include_directories(core PUBLIC include)
+"#;
+
+const MAIN: &str = r#"#include "core.h"
+
+int main() {
+    return core::run();
+}
+"#;
+
+const CORE_H: &str = r#"namespace core {
+int run();
+}
+"#;
+
+const CORE: &str = r#"#include "core.h"
+
+namespace core {
+int run() { return 0; }
+}
+"#;
+
+fn write(root: &Path, path: &str, content: &str) {
+    let path = root.join(path);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+fn init_repo(path: &Path) -> Repository {
+    if path.exists() {
+        std::fs::remove_dir_all(path).unwrap();
+    }
+    let repo = Repository::init(path).unwrap();
+    write(path, "CMakeLists.txt", ROOT_LISTS);
+    write(path, "app/main.cpp", MAIN);
+    write(path, "lib/CMakeLists.txt", LIB_LISTS);
+    write(path, "lib/include/core.h", CORE_H);
+    write(path, "lib/src/core.cpp", CORE);
+    {
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("test", "test@example.org").unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "test", &tree, &[])
+            .unwrap();
+    }
+    repo
+}
+
+#[test]
+fn process_cmake_project() {
+    let path = std::env::temp_dir().join("hyperast_cmake_project");
+    let repo = init_repo(&path);
+    let spec = Repo {
+        forge: Forge::Github,
+        user: "local".to_string(),
+        name: "cmake_project".to_string(),
+    };
+
+    let mut preprocessed = PreProcessedRepositories::default();
+    let handle = preprocessed.register_config(spec, RepoConfig::CppCMake);
+    let mut configured = ConfiguredRepo2 {
+        spec: handle.spec,
+        repo,
+        config: handle.config,
+    };
+    let head = preprocessed
+        .pre_process_with_limit(&mut configured, "", "", 10)
+        .unwrap()[0];
+    let root = preprocessed
+        .get_commit(&configured.config, &head)
+        .unwrap()
+        .ast_root;
+    let child = |d, path: &str| {
+        path.split('/')
+            .try_fold(d, |d, name| preprocessed.processor.child_by_name(d, name))
+    };
+    assert!(child(root, "CMakeLists.txt").is_some());
+    assert!(child(root, "app/main.cpp").is_some());
+    assert!(child(root, "lib/CMakeLists.txt").is_some());
+    assert!(child(root, "lib/include/core.h").is_some());
+    assert!(child(root, "lib/src/core.cpp").is_some());
+
+    // targets are kept at the level of the directory declaring them
+    let lib = child(root, "lib").unwrap();
+    let targets = |stores: &crate::SimpleStores, id| {
+        let node = stores.node_store.resolve(id);
+        let targets = node.get_component::<crate::cmake::Targets>().unwrap();
+        targets
+            .0
+            .iter()
+            .map(|x| (x.name.clone(), x.kind))
+            .collect::<Vec<_>>()
+    };
+    let stores = &preprocessed.processor.main_stores;
+    assert_eq!(
+        targets(stores, root),
+        vec![("demo".to_string(), TargetKind::Executable)]
+    );
+    assert_eq!(
+        targets(stores, lib),
+        vec![("core".to_string(), TargetKind::Library)]
+    );
+
+    // and persisted with the other components
+    let mut buf = vec![];
+    preprocessed.save(&mut buf).unwrap();
+    let (loaded, _) = PreProcessedRepositories::load(&mut buf.as_slice()).unwrap();
+    let stores = &loaded.processor.main_stores;
+    assert_eq!(
+        targets(stores, root),
+        vec![("demo".to_string(), TargetKind::Executable)]
+    );
+    assert_eq!(
+        targets(stores, lib),
+        vec![("core".to_string(), TargetKind::Library)]
+    );
+
+    let commits = preprocessed.commits_per_sys().cmake;
+    assert_eq!(Some(root), commits.get(&head).map(|c| c.ast_root));
+
+    std::fs::remove_dir_all(path).unwrap();
+}
@@ -22,11 +22,7 @@ fn run(text: &[u8]) {
         node_store: NodeStore::new(),
     };
     let mut md_cache = Default::default();
-    let mut java_tree_gen = java_tree_gen::JavaTreeGen {
-        line_break: "\n".as_bytes().to_vec(),
-        stores: &mut stores,
-        md_cache: &mut md_cache,
-    };
+    let mut java_tree_gen = java_tree_gen::JavaTreeGen::new(&mut stores, &mut md_cache);
     let a = handle_java_file(&mut java_tree_gen, &b"A.java".into(), text).unwrap();
 
     let mut ana = PartialAnalysis::default(); //&mut commits[0].meta_data.0;
@@ -0,0 +1,57 @@
+use crate::{
+    git::{Forge, Repo},
+    multi_preprocessed::PreProcessedRepositories,
+    processing::{ConfiguredRepo2, RepoConfig},
+};
+
+use super::persistence::init_repo;
+
+#[test]
+fn gc_keeps_retained_commits() {
+    let path = std::env::temp_dir().join("hyperast_gc_retained");
+    let repo = init_repo(&path);
+    let spec = Repo {
+        forge: Forge::Github,
+        user: "local".to_string(),
+        name: "gc".to_string(),
+    };
+
+    let mut preprocessed = PreProcessedRepositories::default();
+    let handle = preprocessed.register_config(spec, RepoConfig::JavaMaven);
+    let mut configured = ConfiguredRepo2 {
+        spec: handle.spec,
+        repo,
+        config: handle.config,
+    };
+    let oids = preprocessed
+        .pre_process_with_limit(&mut configured, "", "", 10)
+        .unwrap();
+    assert_eq!(2, oids.len());
+
+    let config = configured.config;
+    let serialize = |preprocessed: &PreProcessedRepositories, oid| {
+        let commit = preprocessed.get_commit(&config, oid).unwrap();
+        hyper_ast::nodes::SyntaxSerializer::new(&preprocessed.processor.main_stores, commit.ast_root)
+            .to_string()
+    };
+    let expected: Vec<_> = oids.iter().map(|oid| serialize(&preprocessed, oid)).collect();
+
+    let len = preprocessed.processor.main_stores.node_store.len();
+    // the most recent commit comes first
+    let stats = preprocessed.gc(&oids[..1]);
+    assert!(stats.removed > 0);
+    assert!(stats.freed > 0);
+    assert_eq!(len - stats.removed, preprocessed.processor.main_stores.node_store.len());
+    assert!(preprocessed.get_commit(&config, &oids[1]).is_none());
+    assert_eq!(expected[0], serialize(&preprocessed, &oids[0]));
+
+    // evicted nodes are built again
+    preprocessed
+        .pre_process_with_limit(&mut configured, "", "", 10)
+        .unwrap();
+    for (oid, expected) in oids.iter().zip(expected) {
+        assert_eq!(expected, serialize(&preprocessed, oid));
+    }
+
+    std::fs::remove_dir_all(path).unwrap();
+}
@@ -0,0 +1,126 @@
+use std::path::Path;
+
+use git2::{Repository, Signature};
+use hyper_ast::position::{StructuralPosition, TreePath};
+
+use crate::{
+    git::{Forge, Repo},
+    maven::IterMavenModules,
+    multi_preprocessed::PreProcessedRepositories,
+    processing::{ConfiguredRepo2, RepoConfig},
+};
+
+const SETTINGS: &str = r#"rootProject.name = 'demo'
+include 'app', ':lib:core'
+"#;
+
+const APP_BUILD: &str = r#"plugins {
+    id 'application'
+}
+
+dependencies {
+    implementation project(':lib:core')
+}
+"#;
+
+const CORE_BUILD: &str = r#"plugins {
+    `java-library`
+}
+"#;
+
+const A: &str = r#"package app;
+
+import core.B;
+
+public class A {
+    public static void main(String[] args) {
+        new B().run();
+    }
+}
+"#;
+
+const B: &str = r#"package core;
+
+public class B {
+    public void run() {}
+}
+"#;
+
+fn write(root: &Path, path: &str, content: &str) {
+    let path = root.join(path);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+fn init_repo(path: &Path) -> Repository {
+    if path.exists() {
+        std::fs::remove_dir_all(path).unwrap();
+    }
+    let repo = Repository::init(path).unwrap();
+    write(path, "settings.gradle", SETTINGS);
+    write(path, "app/build.gradle", APP_BUILD);
+    write(path, "app/src/main/java/app/A.java", A);
+    write(path, "lib/core/build.gradle.kts", CORE_BUILD);
+    write(path, "lib/core/src/main/java/core/B.java", B);
+    {
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("test", "test@example.org").unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "test", &tree, &[])
+            .unwrap();
+    }
+    repo
+}
+
+#[test]
+fn process_gradle_build() {
+    let path = std::env::temp_dir().join("hyperast_gradle_build");
+    let repo = init_repo(&path);
+    let spec = Repo {
+        forge: Forge::Github,
+        user: "local".to_string(),
+        name: "gradle_build".to_string(),
+    };
+
+    let mut preprocessed = PreProcessedRepositories::default();
+    let handle = preprocessed.register_config(spec, RepoConfig::JavaGradle);
+    let mut configured = ConfiguredRepo2 {
+        spec: handle.spec,
+        repo,
+        config: handle.config,
+    };
+    let head = preprocessed
+        .pre_process_with_limit(&mut configured, "", "", 10)
+        .unwrap()[0];
+    let root = preprocessed
+        .get_commit(&configured.config, &head)
+        .unwrap()
+        .ast_root;
+    let child = |d, path: &str| {
+        path.split('/')
+            .try_fold(d, |d, name| preprocessed.processor.child_by_name(d, name))
+    };
+    assert!(child(root, "settings.gradle").is_some());
+    assert!(child(root, "app/build.gradle").is_some());
+    assert!(child(root, "app/src/main/java/app/A.java").is_some());
+    assert!(child(root, "lib/core/build.gradle.kts").is_some());
+    assert!(child(root, "lib/core/src/main/java/core/B.java").is_some());
+
+    // gradle projects are iterated like maven modules
+    let stores = &preprocessed.processor.main_stores;
+    let modules: Vec<_> = IterMavenModules::new(stores, StructuralPosition::new(root), root)
+        .map(|x| *x.node().unwrap())
+        .collect();
+    assert_eq!(modules.len(), 2, "{:?}", modules);
+    assert!(modules.contains(&child(root, "app").unwrap()));
+    assert!(modules.contains(&child(root, "lib/core").unwrap()));
+
+    let commits = preprocessed.commits_per_sys().gradle;
+    assert_eq!(Some(root), commits.get(&head).map(|c| c.ast_root));
+
+    std::fs::remove_dir_all(path).unwrap();
+}
@@ -1,7 +1,17 @@
+pub mod cargo;
+pub mod cmake;
 pub mod direct_type_ref;
 #[cfg(test)]
 pub mod extends_package_local;
+pub mod gc;
+pub mod gradle;
+pub mod npm;
 pub mod obj_creation;
+pub mod parallel;
+pub mod persistence;
+pub mod pip;
+pub mod snapshot;
+pub mod working_dir;
 
 use crate::{git::fetch_github_repository, preprocessed::PreProcessedRepository};
 use hyper_ast_gen_ts_java::impact::element::RefsEnum;
@@ -0,0 +1,108 @@
+use std::path::Path;
+
+use git2::{Repository, Signature};
+
+use crate::{
+    git::{Forge, Repo},
+    multi_preprocessed::PreProcessedRepositories,
+    processing::{ConfiguredRepo2, RepoConfig},
+};
+
+const ROOT_PACKAGE: &str = r#"{
+  "name": "root",
+  "private": true,
+  "workspaces": ["packages/*"]
+}
+"#;
+
+const A_PACKAGE: &str = r#"{
+  "name": "a",
+  "main": "src/index.ts"
+}
+"#;
+
+const A_INDEX: &str = r#"import { b } from "./b";
+
+export function a(x: number): number {
+  return b(x) + 1;
+}
+"#;
+
+const A_B: &str = r#"export const b = (x: number) => x * 2;
+"#;
+
+const A_VIEW: &str = r#"export const View = () => <div className="a">{"a"}</div>;
+"#;
+
+fn write(root: &Path, path: &str, content: &str) {
+    let path = root.join(path);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+fn init_repo(path: &Path) -> Repository {
+    if path.exists() {
+        std::fs::remove_dir_all(path).unwrap();
+    }
+    let repo = Repository::init(path).unwrap();
+    write(path, "package.json", ROOT_PACKAGE);
+    write(path, "packages/a/package.json", A_PACKAGE);
+    write(path, "packages/a/src/index.ts", A_INDEX);
+    write(path, "packages/a/src/b.ts", A_B);
+    write(path, "packages/a/src/view.jsx", A_VIEW);
+    write(path, "node_modules/c/index.js", "module.exports = 42;\n");
+    {
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("test", "test@example.org").unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "test", &tree, &[])
+            .unwrap();
+    }
+    repo
+}
+
+#[test]
+fn process_npm_workspace() {
+    let path = std::env::temp_dir().join("hyperast_npm_workspace");
+    let repo = init_repo(&path);
+    let spec = Repo {
+        forge: Forge::Github,
+        user: "local".to_string(),
+        name: "npm_workspace".to_string(),
+    };
+
+    let mut preprocessed = PreProcessedRepositories::default();
+    let handle = preprocessed.register_config(spec, RepoConfig::TsNpm);
+    let mut configured = ConfiguredRepo2 {
+        spec: handle.spec,
+        repo,
+        config: handle.config,
+    };
+    let head = preprocessed
+        .pre_process_with_limit(&mut configured, "", "", 10)
+        .unwrap()[0];
+    let root = preprocessed
+        .get_commit(&configured.config, &head)
+        .unwrap()
+        .ast_root;
+    let child = |d, path: &str| {
+        path.split('/')
+            .try_fold(d, |d, name| preprocessed.processor.child_by_name(d, name))
+    };
+    assert!(child(root, "package.json").is_some());
+    assert!(child(root, "packages/a/package.json").is_some());
+    assert!(child(root, "packages/a/src/index.ts").is_some());
+    assert!(child(root, "packages/a/src/b.ts").is_some());
+    assert!(child(root, "packages/a/src/view.jsx").is_some());
+    // installed dependencies are not part of the sources
+    assert!(child(root, "node_modules").is_none());
+
+    let commits = preprocessed.commits_per_sys().npm;
+    assert_eq!(Some(root), commits.get(&head).map(|c| c.ast_root));
+
+    std::fs::remove_dir_all(path).unwrap();
+}
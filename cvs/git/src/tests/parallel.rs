@@ -0,0 +1,73 @@
+use hyper_ast::{
+    hashed::SyntaxNodeHashs,
+    types::{IterableChildren, WithChildren, WithStats},
+};
+
+use crate::{
+    git::{Forge, Repo},
+    multi_preprocessed::PreProcessedRepositories,
+    processing::{ConfiguredRepo2, RepoConfig},
+};
+
+use super::persistence::{init_repo, refs_of};
+
+#[test]
+fn parallel_same_as_sequential() {
+    let path = std::env::temp_dir().join("hyperast_parallel");
+    init_repo(&path);
+    let spec = Repo {
+        forge: Forge::Github,
+        user: "local".to_string(),
+        name: "parallel".to_string(),
+    };
+    let process = |parallel: bool| {
+        let mut preprocessed = PreProcessedRepositories::default();
+        let handle = preprocessed.register_config(spec.clone(), RepoConfig::JavaMaven);
+        let mut configured = ConfiguredRepo2 {
+            spec: handle.spec,
+            repo: git2::Repository::open(&path).unwrap(),
+            config: handle.config,
+        };
+        let oids = if parallel {
+            preprocessed.pre_process_with_limit_parallel(&mut configured, "", "", 10, 4)
+        } else {
+            preprocessed.pre_process_with_limit(&mut configured, "", "", 10)
+        }
+        .unwrap();
+        // parsed trees are either taken or dropped, none is kept after processing
+        assert!(preprocessed.processor.parsed_java_files().is_empty());
+        let stores = &preprocessed.processor.main_stores;
+        let commits = oids
+            .iter()
+            .map(|oid| {
+                let root = preprocessed
+                    .get_commit(&configured.config, oid)
+                    .unwrap()
+                    .ast_root;
+                let text = hyper_ast::nodes::SyntaxSerializer::new(stores, root).to_string();
+                // what the text does not show
+                let mut metadata = vec![];
+                let mut stack = vec![root];
+                while let Some(id) = stack.pop() {
+                    let node = stores.node_store.resolve(id);
+                    metadata.push(format!(
+                        "{} {} {:?} {}",
+                        node.size(),
+                        node.height(),
+                        node.get_component::<SyntaxNodeHashs<u32>>().ok(),
+                        refs_of(&node),
+                    ));
+                    if let Some(cs) = node.children() {
+                        stack.extend(cs.iter_children().copied());
+                    }
+                }
+                (*oid, text, metadata)
+            })
+            .collect::<Vec<_>>();
+        (commits, stores.node_store.len())
+    };
+    let sequential = process(false);
+    let parallel = process(true);
+    assert_eq!(2, parallel.0.len());
+    assert_eq!(sequential, parallel);
+}
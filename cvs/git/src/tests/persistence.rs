@@ -0,0 +1,274 @@
+use std::{collections::HashSet, path::Path};
+
+use git2::{Oid, Repository, Signature};
+use hyper_ast::{
+    store::nodes::legion::{HashedNodeRef, NodeIdentifier},
+    types::{IterableChildren, WithChildren},
+};
+
+use crate::{
+    git::{Forge, Repo},
+    multi_preprocessed::PreProcessedRepositories,
+    processing::{ConfiguredRepo2, RepoConfig},
+};
+
+pub(super) const POM: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<project>
+    <modelVersion>4.0.0</modelVersion>
+    <groupId>org.example</groupId>
+    <artifactId>persistence</artifactId>
+    <version>1.0</version>
+</project>
+"#;
+
+pub(super) const A_JAVA: &str = r#"package org.example;
+
+public class A {
+    int f(int x) {
+        if (x > 0) {
+            return x;
+        }
+        return new B().g(x);
+    }
+}
+"#;
+
+pub(super) const B_JAVA: &str = r#"package org.example;
+
+class B {
+    int g(int x) {
+        return -x;
+    }
+}
+"#;
+
+fn tree(repo: &Repository, files: &[(&str, &str)], dirs: &[(&str, Oid)]) -> Oid {
+    let mut builder = repo.treebuilder(None).unwrap();
+    for (name, content) in files {
+        let blob = repo.blob(content.as_bytes()).unwrap();
+        builder.insert(name, blob, 0o100644).unwrap();
+    }
+    for (name, oid) in dirs {
+        builder.insert(name, *oid, 0o040000).unwrap();
+    }
+    builder.write().unwrap()
+}
+
+fn commit(repo: &Repository, files: &[(&str, &str)], parents: &[Oid]) -> Oid {
+    let java = tree(repo, files, &[]);
+    let example = tree(repo, &[], &[("example", java)]);
+    let org = tree(repo, &[], &[("org", example)]);
+    let main = tree(repo, &[], &[("java", org)]);
+    let src_main = tree(repo, &[], &[("main", main)]);
+    let root = tree(repo, &[("pom.xml", POM)], &[("src", src_main)]);
+    let root = repo.find_tree(root).unwrap();
+    let sig = Signature::now("test", "test@example.org").unwrap();
+    let parents: Vec<_> = parents
+        .iter()
+        .map(|x| repo.find_commit(*x).unwrap())
+        .collect();
+    let parents: Vec<_> = parents.iter().collect();
+    repo.commit(Some("HEAD"), &sig, &sig, "test", &root, &parents)
+        .unwrap()
+}
+
+pub(super) fn init_repo(path: &Path) -> Repository {
+    if path.exists() {
+        std::fs::remove_dir_all(path).unwrap();
+    }
+    let repo = Repository::init_bare(path).unwrap();
+    let first = commit(&repo, &[("A.java", A_JAVA)], &[]);
+    commit(&repo, &[("A.java", A_JAVA), ("B.java", B_JAVA)], &[first]);
+    repo
+}
+
+#[test]
+fn round_trip_processed_commits() {
+    let path = std::env::temp_dir().join("hyperast_persistence_round_trip");
+    let repo = init_repo(&path);
+    let spec = Repo {
+        forge: Forge::Github,
+        user: "local".to_string(),
+        name: "persistence".to_string(),
+    };
+
+    let mut preprocessed = PreProcessedRepositories::default();
+    let handle = preprocessed.register_config(spec, RepoConfig::JavaMaven);
+    let mut configured = ConfiguredRepo2 {
+        spec: handle.spec,
+        repo,
+        config: handle.config,
+    };
+    let oids = preprocessed
+        .pre_process_with_limit(&mut configured, "", "", 10)
+        .unwrap();
+    assert_eq!(2, oids.len());
+
+    let mut buf = vec![];
+    preprocessed.save(&mut buf).unwrap();
+    let (mut loaded, commits) = PreProcessedRepositories::load(&mut buf.as_slice()).unwrap();
+    assert_eq!(2, commits.maven.len());
+    let loaded_handle = loaded.register_config(configured.spec.clone(), RepoConfig::JavaMaven);
+
+    for oid in &oids {
+        let expected = preprocessed.get_commit(&configured.config, oid).unwrap();
+        let actual = loaded.get_commit(&loaded_handle.config, oid).unwrap();
+        assert_eq!(expected.parents, actual.parents);
+        assert_eq!(expected.tree_oid, actual.tree_oid);
+        let expected = hyper_ast::nodes::SyntaxSerializer::new(
+            &preprocessed.processor.main_stores,
+            expected.ast_root,
+        )
+        .to_string();
+        let actual =
+            hyper_ast::nodes::SyntaxSerializer::new(&loaded.processor.main_stores, actual.ast_root)
+                .to_string();
+        assert_eq!(expected, actual);
+    }
+
+    // processing another commit after loading must give the same result as without persisting
+    let before: HashSet<_> = oids
+        .iter()
+        .map(|oid| {
+            preprocessed
+                .get_commit(&configured.config, oid)
+                .unwrap()
+                .ast_root
+        })
+        .collect();
+    let before = reachable(&preprocessed.processor.main_stores, before);
+    let loaded_before: HashSet<_> = oids
+        .iter()
+        .map(|oid| {
+            loaded
+                .get_commit(&loaded_handle.config, oid)
+                .unwrap()
+                .ast_root
+        })
+        .collect();
+    let loaded_before = reachable(&loaded.processor.main_stores, loaded_before);
+    let head = configured.repo.head().unwrap().target().unwrap();
+    let third = commit(
+        &configured.repo,
+        &[("A.java", A2_JAVA), ("B.java", B_JAVA)],
+        &[head],
+    );
+    let processed = preprocessed
+        .pre_process_with_limit(&mut configured, "", "", 1)
+        .unwrap();
+    assert_eq!(vec![third], processed);
+    let mut loaded_configured = ConfiguredRepo2 {
+        spec: loaded_handle.spec,
+        repo: Repository::open_bare(&path).unwrap(),
+        config: loaded_handle.config,
+    };
+    let processed = loaded
+        .pre_process_with_limit(&mut loaded_configured, "", "", 1)
+        .unwrap();
+    assert_eq!(vec![third], processed);
+    let expected = preprocessed
+        .get_commit(&configured.config, &third)
+        .unwrap()
+        .ast_root;
+    let actual = loaded
+        .get_commit(&loaded_configured.config, &third)
+        .unwrap()
+        .ast_root;
+    let mut reused = 0;
+    let mut stack = vec![(expected, actual)];
+    while let Some((expected, actual)) = stack.pop() {
+        let expected_node = preprocessed
+            .processor
+            .main_stores
+            .node_store
+            .resolve(expected);
+        let actual_node = loaded.processor.main_stores.node_store.resolve(actual);
+        assert_eq!(
+            before.contains(&expected),
+            loaded_before.contains(&actual),
+            "reused nodes differ"
+        );
+        if before.contains(&expected) {
+            reused += 1;
+        }
+        assert_eq!(refs_of(&expected_node), refs_of(&actual_node));
+        let expected_cs: Vec<_> = expected_node
+            .children()
+            .map(|cs| cs.iter_children().copied().collect())
+            .unwrap_or_default();
+        let actual_cs: Vec<_> = actual_node
+            .children()
+            .map(|cs| cs.iter_children().copied().collect())
+            .unwrap_or_default();
+        assert_eq!(expected_cs.len(), actual_cs.len());
+        stack.extend(expected_cs.into_iter().zip(actual_cs));
+    }
+    // eg. the unchanged B.java and method f of A
+    assert!(reused > 0);
+
+    // the dedup table must still be usable
+    loaded.save(&mut vec![]).unwrap();
+
+    std::fs::remove_dir_all(path).unwrap();
+}
+
+const A2_JAVA: &str = r#"package org.example;
+
+public class A {
+    int f(int x) {
+        if (x > 0) {
+            return x;
+        }
+        return new B().g(x);
+    }
+
+    int h(int x) {
+        return f(x) + 1;
+    }
+}
+"#;
+
+pub(super) fn reachable(
+    stores: &crate::SimpleStores,
+    roots: HashSet<NodeIdentifier>,
+) -> HashSet<NodeIdentifier> {
+    let mut r = HashSet::new();
+    let mut stack: Vec<_> = roots.into_iter().collect();
+    while let Some(id) = stack.pop() {
+        if !r.insert(id) {
+            continue;
+        }
+        if let Some(cs) = stores.node_store.resolve(id).children() {
+            stack.extend(cs.iter_children().copied());
+        }
+    }
+    r
+}
+
+/// The reference bloom filter of a node, as text.
+pub(super) fn refs_of(node: &HashedNodeRef<NodeIdentifier>) -> String {
+    use hyper_ast::filter::{Bloom, BloomSize};
+    macro_rules! bloom {
+        ( $($t:ty),* ) => {$(
+            if let Ok(b) = node.get_component::<Bloom<&'static [u8], $t>>() {
+                return format!("{:?}", b);
+            }
+        )*};
+    }
+    bloom!(u16, u32, u64, [u64; 2], [u64; 4], [u64; 8], [u64; 16], [u64; 32], [u64; 64]);
+    match node.get_component::<BloomSize>() {
+        Ok(BloomSize::None) => "none".to_string(),
+        Ok(BloomSize::Much) => "much".to_string(),
+        _ => "missing".to_string(),
+    }
+}
+
+#[test]
+fn reject_other_versions() {
+    let stores = crate::SimpleStores::default();
+    let mut buf = vec![];
+    stores.save(&mut buf, &crate::persist_registry()).unwrap();
+    // version follows the 4 bytes of magic
+    buf[4] = buf[4].wrapping_add(1);
+    assert!(crate::SimpleStores::load(&mut buf.as_slice(), &crate::persist_registry()).is_err());
+}
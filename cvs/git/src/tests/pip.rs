@@ -0,0 +1,103 @@
+use std::path::Path;
+
+use git2::{Repository, Signature};
+
+use crate::{
+    git::{Forge, Repo},
+    multi_preprocessed::PreProcessedRepositories,
+    processing::{ConfiguredRepo2, RepoConfig},
+};
+
+const PYPROJECT: &str = r#"[project]
+name = "a"
+version = "0.1.0"
+"#;
+
+const SETUP: &str = r#"from setuptools import setup
+
+setup(name="b", packages=["b"])
+"#;
+
+const A_INIT: &str = r#"from .walk import walk
+"#;
+
+const A_WALK: &str = r#"import os
+
+
+def walk(root: str = "."):
+    for d, _, files in os.walk(root):
+        yield from (f"{d}/{f}" for f in files)
+"#;
+
+fn write(root: &Path, path: &str, content: &str) {
+    let path = root.join(path);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+fn init_repo(path: &Path) -> Repository {
+    if path.exists() {
+        std::fs::remove_dir_all(path).unwrap();
+    }
+    let repo = Repository::init(path).unwrap();
+    write(path, "pyproject.toml", PYPROJECT);
+    write(path, "src/a/__init__.py", A_INIT);
+    write(path, "src/a/walk.py", A_WALK);
+    write(path, "src/a/__pycache__/walk.cpython-311.pyc", "\u{0}\u{1}");
+    write(path, "libs/b/setup.py", SETUP);
+    write(path, "libs/b/b/__init__.py", "");
+    {
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("test", "test@example.org").unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "test", &tree, &[])
+            .unwrap();
+    }
+    repo
+}
+
+#[test]
+fn process_python_projects() {
+    let path = std::env::temp_dir().join("hyperast_pip_projects");
+    let repo = init_repo(&path);
+    let spec = Repo {
+        forge: Forge::Github,
+        user: "local".to_string(),
+        name: "pip_projects".to_string(),
+    };
+
+    let mut preprocessed = PreProcessedRepositories::default();
+    let handle = preprocessed.register_config(spec, RepoConfig::PythonPip);
+    let mut configured = ConfiguredRepo2 {
+        spec: handle.spec,
+        repo,
+        config: handle.config,
+    };
+    let head = preprocessed
+        .pre_process_with_limit(&mut configured, "", "", 10)
+        .unwrap()[0];
+    let root = preprocessed
+        .get_commit(&configured.config, &head)
+        .unwrap()
+        .ast_root;
+    let child = |d, path: &str| {
+        path.split('/')
+            .try_fold(d, |d, name| preprocessed.processor.child_by_name(d, name))
+    };
+    assert!(child(root, "pyproject.toml").is_some());
+    assert!(child(root, "src/a/__init__.py").is_some());
+    assert!(child(root, "src/a/walk.py").is_some());
+    assert!(child(root, "libs/b/setup.py").is_some());
+    assert!(child(root, "libs/b/b/__init__.py").is_some());
+    // compiled bytecode is not part of the sources
+    assert!(child(root, "src/a/__pycache__").is_none());
+
+    let commits = preprocessed.commits_per_sys().pip;
+    assert_eq!(Some(root), commits.get(&head).map(|c| c.ast_root));
+
+    std::fs::remove_dir_all(path).unwrap();
+}
@@ -0,0 +1,73 @@
+use hyper_ast::{
+    store::{
+        labels::LabelStore,
+        nodes::DefaultNodeStore as NodeStore,
+        snapshot::{self, Snapshot},
+    },
+    types::{
+        AnyType, HyperType, LabelStore as _, Labeled, LangRef, TypeStore as _, Typed, WithChildren,
+    },
+};
+use hyper_ast_gen_ts_java::{
+    legion_with_refs::{self, JavaTreeGen},
+    types::Java,
+};
+use hyper_ast_gen_ts_xml::{legion::XmlTreeGen, types::Xml};
+
+use crate::{SimpleStores, TStore};
+
+static JAVA: &str = "class A { int f() { return 1; } }";
+static POM: &str = "<project><modelVersion>4.0.0</modelVersion></project>";
+
+#[test]
+fn snapshot_of_two_languages() {
+    let mut stores = SimpleStores {
+        label_store: LabelStore::new(),
+        type_store: TStore::default(),
+        node_store: NodeStore::new(),
+    };
+    let mut md_cache = Default::default();
+    let mut java_tree_gen = JavaTreeGen::new(&mut stores, &mut md_cache);
+    let tree = legion_with_refs::tree_sitter_parse(JAVA.as_bytes()).unwrap_or_else(|t| t);
+    let java = java_tree_gen
+        .generate_file(b"A.java", JAVA.as_bytes(), tree.walk())
+        .local
+        .compressed_node;
+    let mut xml_tree_gen = XmlTreeGen {
+        line_break: "\n".as_bytes().to_vec(),
+        stores: &mut stores,
+    };
+    let tree = XmlTreeGen::<TStore>::tree_sitter_parse(POM.as_bytes()).unwrap_or_else(|t| t);
+    let pom = xml_tree_gen
+        .generate_file(b"pom.xml", POM.as_bytes(), tree.walk())
+        .local
+        .compressed_node;
+
+    let mut bytes = vec![];
+    snapshot::freeze(&stores, &[java, pom], &mut bytes).unwrap();
+    // in another order than the one of the snapshot
+    let langs: &[&'static dyn LangRef<AnyType>] = &[&Xml, &Java];
+    let snapshot = Snapshot::open(bytes, langs).unwrap();
+
+    let s_roots = snapshot.roots();
+    let ty = |x| snapshot.try_resolve(x).unwrap().get_type();
+    let is_java = |t: AnyType| t.as_any().is::<hyper_ast_gen_ts_java::types::Type>();
+    let is_xml = |t: AnyType| t.as_any().is::<hyper_ast_gen_ts_xml::types::Type>();
+    assert!(is_java(ty(s_roots[0])));
+    assert!(is_xml(ty(s_roots[1])));
+
+    let mut stack = vec![(java, s_roots[0]), (pom, s_roots[1])];
+    while let Some((id, s_id)) = stack.pop() {
+        let node = stores.node_store.resolve(id);
+        let s_node = snapshot.try_resolve(s_id).unwrap();
+        assert_eq!(stores.type_store.resolve_type(&node), s_node.get_type());
+        assert_eq!(
+            node.try_get_label().map(|l| stores.label_store.resolve(l)),
+            s_node.try_get_label().map(|l| snapshot.resolve_label(l)),
+        );
+        assert_eq!(node.child_count(), s_node.child_count());
+        for i in 0..node.child_count() {
+            stack.push((node.child(&i).unwrap(), s_node.child(&i).unwrap()));
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use std::path::Path;
+
+use git2::{Repository, Signature};
+
+use crate::{
+    git::{synthetic_commit, Forge, Repo, WorkingState},
+    multi_preprocessed::PreProcessedRepositories,
+    processing::{ConfiguredRepo2, RepoConfig},
+};
+
+use super::persistence::{A_JAVA, B_JAVA, POM};
+
+const JAVA_DIR: &str = "src/main/java/org/example";
+
+fn write(root: &Path, path: &str, content: &str) {
+    let path = root.join(path);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+/// a checkout with A.java committed
+fn init_checkout(path: &Path) -> Repository {
+    if path.exists() {
+        std::fs::remove_dir_all(path).unwrap();
+    }
+    let repo = Repository::init(path).unwrap();
+    write(path, "pom.xml", POM);
+    write(path, &format!("{}/A.java", JAVA_DIR), A_JAVA);
+    {
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("test", "test@example.org").unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "test", &tree, &[])
+            .unwrap();
+    }
+    repo
+}
+
+#[test]
+fn process_index_and_working_dir() {
+    let path = std::env::temp_dir().join("hyperast_working_dir");
+    let repo = init_checkout(&path);
+    let spec = Repo {
+        forge: Forge::Github,
+        user: "local".to_string(),
+        name: "working_dir".to_string(),
+    };
+
+    let mut preprocessed = PreProcessedRepositories::default();
+    let handle = preprocessed.register_config(spec, RepoConfig::JavaMaven);
+    let mut configured = ConfiguredRepo2 {
+        spec: handle.spec,
+        repo,
+        config: handle.config,
+    };
+    let head = preprocessed
+        .pre_process_with_limit(&mut configured, "", "", 10)
+        .unwrap()[0];
+    let config = configured.config;
+    let root = |preprocessed: &PreProcessedRepositories, oid| {
+        preprocessed.get_commit(&config, oid).unwrap().ast_root
+    };
+
+    // nothing staged, same code as HEAD
+    let index = preprocessed
+        .pre_process_working_dir(&mut configured, WorkingState::Index)
+        .unwrap();
+    assert_ne!(head, index);
+    assert_eq!(
+        vec![head],
+        preprocessed.get_commit(&config, &index).unwrap().parents
+    );
+    assert_eq!(root(&preprocessed, &head), root(&preprocessed, &index));
+
+    // B.java is untracked, only in the working directory
+    write(&path, &format!("{}/B.java", JAVA_DIR), B_JAVA);
+    let index2 = preprocessed
+        .pre_process_working_dir(&mut configured, WorkingState::Index)
+        .unwrap();
+    assert_eq!(index, index2);
+    let work_dir = preprocessed
+        .pre_process_working_dir(&mut configured, WorkingState::WorkDir)
+        .unwrap();
+    assert_ne!(root(&preprocessed, &head), root(&preprocessed, &work_dir));
+    let file = |preprocessed: &PreProcessedRepositories, oid, name| {
+        let mut d = root(preprocessed, oid);
+        for name in JAVA_DIR.split('/').chain([name]) {
+            d = preprocessed.processor.child_by_name(d, name)?;
+        }
+        Some(d)
+    };
+    assert!(file(&preprocessed, &head, "B.java").is_none());
+    assert!(file(&preprocessed, &work_dir, "B.java").is_some());
+    // unchanged files are shared
+    assert_eq!(
+        file(&preprocessed, &head, "A.java"),
+        file(&preprocessed, &work_dir, "A.java")
+    );
+
+    // the checkout itself is left untouched
+    let status = configured
+        .repo
+        .status_file(Path::new(&format!("{}/B.java", JAVA_DIR)))
+        .unwrap();
+    assert_eq!(git2::Status::WT_NEW, status);
+    assert_eq!(head, configured.repo.head().unwrap().target().unwrap());
+    let b = Path::new(&format!("{}/B.java", JAVA_DIR)).to_owned();
+    assert!(configured.repo.index().unwrap().get_path(&b, 0).is_none());
+    // nor the object database on disk
+    let reopened = Repository::open(&path).unwrap();
+    assert!(reopened.index().unwrap().get_path(&b, 0).is_none());
+    assert!(reopened.find_commit(work_dir).is_err());
+    assert!(reopened.find_commit(index).is_err());
+
+    std::fs::remove_dir_all(path).unwrap();
+}
+
+#[test]
+fn synthetic_commit_twice() {
+    let path = std::env::temp_dir().join("hyperast_synthetic_commit_twice");
+    let repo = init_checkout(&path);
+    let b = format!("{}/B.java", JAVA_DIR);
+    write(&path, &b, B_JAVA);
+    let first = synthetic_commit(&repo, WorkingState::WorkDir).unwrap();
+    // the in-memory backend is only added by the first call
+    let second = synthetic_commit(&repo, WorkingState::WorkDir).unwrap();
+    assert_eq!(first, second);
+    write(&path, &b, &format!("{}// changed\n", B_JAVA));
+    let third = synthetic_commit(&repo, WorkingState::WorkDir).unwrap();
+    assert_ne!(first, third);
+    assert!(repo.find_commit(first).is_ok());
+    assert!(repo.find_commit(third).is_ok());
+    // still written in memory
+    let reopened = Repository::open(&path).unwrap();
+    assert!(reopened.find_commit(first).is_err());
+    assert!(reopened.find_commit(third).is_err());
+
+    std::fs::remove_dir_all(path).unwrap();
+}
@@ -0,0 +1,35 @@
+use crate::{processing::ObjectName, TStore, PROPAGATE_ERROR_ON_BAD_CST_NODE};
+
+use hyper_ast_gen_ts_typescript::legion as ts_tree_gen;
+
+pub(crate) fn handle_ts_file<'stores, 'cache, 'b: 'stores>(
+    tree_gen: &mut ts_tree_gen::TsTreeGen<'stores, 'cache, TStore>,
+    name: &ObjectName,
+    text: &'b [u8],
+) -> Result<ts_tree_gen::FNode, ()> {
+    // jsx can only be parsed by the tsx grammar,
+    // but the tsx grammar misparses the `<T>x` casts of plain typescript
+    let name_bytes = name.as_bytes();
+    let tree = if [&b".ts"[..], b".mts", b".cts"]
+        .iter()
+        .any(|ext| name_bytes.ends_with(ext))
+    {
+        ts_tree_gen::tree_sitter_parse(text)
+    } else {
+        ts_tree_gen::tree_sitter_parse_tsx(text)
+    };
+    let tree = match tree {
+        Ok(tree) => tree,
+        Err(tree) => {
+            log::warn!("bad CST");
+            log::debug!("{:?}", name.try_str());
+            log::debug!("{}", tree.root_node().to_sexp());
+            if PROPAGATE_ERROR_ON_BAD_CST_NODE {
+                return Err(());
+            } else {
+                tree
+            }
+        }
+    };
+    Ok(tree_gen.generate_file(name.as_bytes(), text, tree.walk().into()))
+}
@@ -0,0 +1,28 @@
+//! Typescript directories are processed by the [`crate::basic_processor`]
+use hyper_ast::tree_gen::basic::{BasicTreeGen, FNode};
+use hyper_ast_gen_ts_typescript::types::{Ts, Type};
+
+use crate::{
+    basic_processor::{BasicFiles, BasicProc, BasicProcessorHolder},
+    processing::{file_sys, ObjectName},
+    TStore,
+};
+
+pub use crate::basic_processor::Parameter;
+
+pub(crate) type TsProcessorHolder = BasicProcessorHolder<file_sys::Ts>;
+pub(crate) type TsProc = BasicProc<file_sys::Ts>;
+
+impl BasicFiles for file_sys::Ts {
+    type Lang = Ts;
+    type Skipped = file_sys::NodeModules;
+    const DIRECTORY: Type = Type::Directory;
+
+    fn handle_file<'stores, 'cache, 'b: 'stores>(
+        tree_gen: &mut BasicTreeGen<'stores, 'cache, Ts, TStore>,
+        name: &ObjectName,
+        text: &'b [u8],
+    ) -> Result<FNode, ()> {
+        crate::ts::handle_ts_file(tree_gen, name, text)
+    }
+}
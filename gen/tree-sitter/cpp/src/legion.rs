@@ -22,9 +22,10 @@ use hyper_ast::{
         nodes::DefaultNodeStore as NodeStore,
     },
     tree_gen::{
-        compute_indentation, get_spacing, has_final_space, parser::Node as _, AccIndentation,
-        Accumulator, BasicAccumulator, BasicGlobalData, GlobalData, Parents, SpacedGlobalData,
-        Spaces, SubTreeMetrics, TextedGlobalData, TreeGen, ZippedTreeGen,
+        compute_indentation, derived::DerivedMetrics, get_spacing, has_final_space,
+        parser::Node as _, AccIndentation, Accumulator, BasicAccumulator, BasicGlobalData,
+        GlobalData, Parents, SpacedGlobalData, Spaces, SubTreeMetrics, TextedGlobalData, TreeGen,
+        ZippedTreeGen,
     },
     types::LabelStore as _,
 };
@@ -37,8 +38,12 @@ pub struct CppTreeGen<'store, 'cache, TS> {
     pub line_break: Vec<u8>,
     pub stores: &'store mut SimpleStores<TS>,
     pub md_cache: &'cache mut MDCache,
+    /// computed on each generated node, see [`DerivedMetrics`]
+    pub derived: &'cache DerivedMetrics<Type>,
 }
 
+static NO_DERIVED_METRICS: DerivedMetrics<Type> = DerivedMetrics::new();
+
 pub type MDCache = HashMap<NodeIdentifier, MD>;
 
 // NOTE only keep compute intensive metadata (where space/time tradeoff is worth storing)
@@ -388,9 +393,21 @@ impl<'store, 'cache, TS: CppEnabledTypeStore<HashedNodeRef<'store, TIdN<NodeIden
             line_break: "\n".as_bytes().to_vec(),
             stores,
             md_cache,
+            derived: &NO_DERIVED_METRICS,
         }
     }
 
+    pub fn with_line_break(mut self, line_break: Vec<u8>) -> Self {
+        self.line_break = line_break;
+        self
+    }
+
+    /// computes the registered metrics on each generated node
+    pub fn with_derived(mut self, derived: &'cache DerivedMetrics<Type>) -> Self {
+        self.derived = derived;
+        self
+    }
+
     pub fn tree_sitter_parse(text: &[u8]) -> Result<tree_sitter::Tree, tree_sitter::Tree> {
         let mut parser = tree_sitter::Parser::new();
         let language = tree_sitter_cpp::language();
@@ -574,6 +591,11 @@ impl<'stores, 'cache, TS: CppEnabledTypeStore<HashedNodeRef<'stores, TIdN<NodeId
                 ana,
             }
         };
+        self.derived.compute(
+            &mut self.stores.node_store,
+            &self.stores.label_store,
+            local.compressed_node,
+        );
 
         let full_node = FullNode {
             global: global.into(),
@@ -78,11 +78,7 @@ int main(int argl, int* argv) {
     println!("{:#?}", tree.root_node().to_sexp());
     let mut stores = SimpleStores::default();
     let mut md_cache = Default::default();
-    let mut tree_gen = CppTreeGen {
-        line_break: "\n".as_bytes().to_vec(),
-        stores: &mut stores,
-        md_cache: &mut md_cache,
-    };
+    let mut tree_gen = CppTreeGen::new(&mut stores, &mut md_cache);
     let x = tree_gen.generate_file(b"", text, tree.walk()).local;
     // print_tree_syntax(&stores.node_store, &stores.label_store, &x.compressed_node);
     // println!("{}", tree.root_node().to_sexp());
@@ -104,11 +100,7 @@ fn cpp_def_bl_test() {
     println!("{:#?}", tree.root_node().to_sexp());
     let mut stores = SimpleStores::default();
     let mut md_cache = Default::default();
-    let mut tree_gen = CppTreeGen {
-        line_break: "\n".as_bytes().to_vec(),
-        stores: &mut stores,
-        md_cache: &mut md_cache,
-    };
+    let mut tree_gen = CppTreeGen::new(&mut stores, &mut md_cache);
     let x = tree_gen.generate_file(b"", text, tree.walk()).local;
     println!(
         "{}",
@@ -136,11 +128,7 @@ fn cpp_char_literal_test() {
     println!("{:#?}", tree.root_node().to_sexp());
     let mut stores = SimpleStores::default();
     let mut md_cache = Default::default();
-    let mut tree_gen = CppTreeGen {
-        line_break: "\n".as_bytes().to_vec(),
-        stores: &mut stores,
-        md_cache: &mut md_cache,
-    };
+    let mut tree_gen = CppTreeGen::new(&mut stores, &mut md_cache);
     let x = tree_gen.generate_file(b"", text, tree.walk()).local;
     let entity = x.compressed_node;
     println!(
@@ -185,11 +173,7 @@ fn cpp_asm_test() {
     println!("{:#?}", tree.root_node().to_sexp());
     let mut stores = SimpleStores::default();
     let mut md_cache = Default::default();
-    let mut tree_gen = CppTreeGen {
-        line_break: "\n".as_bytes().to_vec(),
-        stores: &mut stores,
-        md_cache: &mut md_cache,
-    };
+    let mut tree_gen = CppTreeGen::new(&mut stores, &mut md_cache);
     let x = tree_gen.generate_file(b"", text, tree.walk()).local;
     println!(
         "{}",
@@ -224,11 +208,7 @@ fn cpp_op_test() {
     println!("{:#?}", tree.root_node().to_sexp());
     let mut stores = SimpleStores::default();
     let mut md_cache = Default::default();
-    let mut tree_gen = CppTreeGen {
-        line_break: "\n".as_bytes().to_vec(),
-        stores: &mut stores,
-        md_cache: &mut md_cache,
-    };
+    let mut tree_gen = CppTreeGen::new(&mut stores, &mut md_cache);
     let x = tree_gen.generate_file(b"", text, tree.walk()).local;
     println!(
         "{}",
@@ -276,11 +256,7 @@ fn cpp_3_test() {
     println!("{:#?}", tree.root_node().to_sexp());
     let mut stores = SimpleStores::default();
     let mut md_cache = Default::default();
-    let mut tree_gen = CppTreeGen {
-        line_break: "\n".as_bytes().to_vec(),
-        stores: &mut stores,
-        md_cache: &mut md_cache,
-    };
+    let mut tree_gen = CppTreeGen::new(&mut stores, &mut md_cache);
     let x = tree_gen.generate_file(b"", text, tree.walk()).local;
     println!(
         "{}",
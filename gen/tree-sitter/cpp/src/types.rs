@@ -119,6 +119,31 @@ mod legion_impls {
             }
         }
     }
+
+    impl hyper_ast::store::nodes::legion::persist::Persist for Type {
+        fn save(
+            &self,
+            w: &mut dyn std::io::Write,
+            _: &hyper_ast::store::nodes::legion::persist::SavedIds,
+        ) -> std::io::Result<()> {
+            hyper_ast::store::persist::write_u16(w, *self as u16)
+        }
+
+        fn load(
+            r: &mut dyn std::io::Read,
+            _: &hyper_ast::store::nodes::legion::persist::LoadedIds,
+        ) -> std::io::Result<Self> {
+            let t = hyper_ast::store::persist::read_u16(r)?;
+            if t < COUNT {
+                Ok(Type::resolve(t))
+            } else {
+                Err(hyper_ast::store::persist::invalid_data(format!(
+                    "invalid type {}",
+                    t
+                )))
+            }
+        }
+    }
 }
 
 pub trait CppEnabledTypeStore<T>: TypeStore<T> {
@@ -270,11 +295,9 @@ impl Cpp {
 
 impl LangRef<AnyType> for Cpp {
     fn make(&self, t: u16) -> &'static AnyType {
-        panic!()
-        // &From::<&'static dyn HyperType>::from(&S_T_L[t as usize])
+        &ANY_T_L[t as usize]
     }
     fn to_u16(&self, t: AnyType) -> u16 {
-        // t as u16
         let t = t.as_any().downcast_ref::<Type>().unwrap();
         *t as u16
     }
@@ -2698,3 +2721,5 @@ const S_T_L: &'static [Type] = &[
     Type::Directory,
     Type::ERROR,
 ];
+
+static ANY_T_L: [AnyType; S_T_L.len()] = hyper_ast::types::any_types(S_T_L);
@@ -11,8 +11,8 @@ use hyper_ast::{
         nodes::legion::{compo::NoSpacesCS, HashedNodeRef, PendingInsert},
     },
     tree_gen::{
-        BasicGlobalData, GlobalData, Parents, SpacedGlobalData, SubTreeMetrics, TextedGlobalData,
-        TreeGen,
+        derived::DerivedMetrics, BasicGlobalData, GlobalData, Parents, SpacedGlobalData,
+        SubTreeMetrics, TextedGlobalData, TreeGen,
     },
     types::{self, AnyType, NodeStoreExt, TypeStore, TypeTrait, WithHashs, WithStats},
     utils::{self},
@@ -55,6 +55,8 @@ use crate::{
 
 pub use crate::impact::element::BulkHasher;
 
+pub mod incremental;
+
 pub fn hash32<T: ?Sized + Hash>(t: &T) -> u32 {
     utils::clamp_u64_to_u32(&utils::hash(t))
 }
@@ -77,8 +79,12 @@ pub struct JavaTreeGen<'stores, 'cache, TS> {
     pub line_break: Vec<u8>,
     pub stores: &'stores mut SimpleStores<TS>,
     pub md_cache: &'cache mut MDCache,
+    /// computed on each generated node, see [`DerivedMetrics`]
+    pub derived: &'cache DerivedMetrics<Type>,
 }
 
+static NO_DERIVED_METRICS: DerivedMetrics<Type> = DerivedMetrics::new();
+
 pub type MDCache = HashMap<NodeIdentifier, MD>;
 
 // NOTE only keep compute intensive metadata (where space/time tradeoff is worth storing)
@@ -87,7 +93,7 @@ pub type MDCache = HashMap<NodeIdentifier, MD>;
 // they can be qualitative metadata .eg a hash or they can be quantitative .eg lines of code
 pub struct MD {
     metrics: SubTreeMetrics<SyntaxNodeHashs<u32>>,
-    ana: Option<PartialAnalysis>,
+    pub(crate) ana: Option<PartialAnalysis>,
     mcc: Mcc,
 }
 
@@ -101,6 +107,35 @@ impl From<Local> for MD {
     }
 }
 
+impl MD {
+    /// Recovers the metadata of a node from its components,
+    /// eg. when the store was loaded from disk but the cache was not.
+    /// The partial analysis is not stored in nodes, it must be given.
+    fn from_store(
+        node_store: &NodeStore,
+        id: NodeIdentifier,
+        ana: Option<PartialAnalysis>,
+    ) -> Self {
+        let node = node_store.resolve(id);
+        let hashs = SyntaxNodeHashs {
+            structt: WithHashs::hash(&node, &SyntaxNodeHashsKinds::Struct),
+            label: WithHashs::hash(&node, &SyntaxNodeHashsKinds::Label),
+            syntax: WithHashs::hash(&node, &SyntaxNodeHashsKinds::Syntax),
+        };
+        let metrics = SubTreeMetrics {
+            size: node.size().to_u32().unwrap(),
+            height: node.height().to_u32().unwrap(),
+            size_no_spaces: node.size_no_spaces().to_u32().unwrap(),
+            hashs,
+        };
+        let kind = *node.get_component::<Type>().unwrap();
+        let mcc = node
+            .get_component::<Mcc>()
+            .map_or(Mcc::new(&kind), |x| x.clone());
+        MD { metrics, ana, mcc }
+    }
+}
+
 pub type Global<'a> = SpacedGlobalData<'a>;
 
 #[derive(Debug, Clone)]
@@ -375,9 +410,21 @@ impl<'stores, 'cache, TS: JavaEnabledTypeStore<HashedNodeRef<'stores, TIdN<NodeI
             line_break: "\n".as_bytes().to_vec(),
             stores,
             md_cache,
+            derived: &NO_DERIVED_METRICS,
         }
     }
 
+    pub fn with_line_break(mut self, line_break: Vec<u8>) -> Self {
+        self.line_break = line_break;
+        self
+    }
+
+    /// computes the registered metrics on each generated node
+    pub fn with_derived(mut self, derived: &'cache DerivedMetrics<Type>) -> Self {
+        self.derived = derived;
+        self
+    }
+
     pub fn tree_sitter_parse(text: &[u8]) -> Result<tree_sitter::Tree, tree_sitter::Tree> {
         let mut parser = tree_sitter::Parser::new();
         let language = tree_sitter_java::language();
@@ -543,7 +590,26 @@ impl<'stores, 'cache, TS: JavaEnabledTypeStore<HashedNodeRef<'stores, TIdN<NodeI
         let insertion = node_store.prepare_insertion(&hashable, eq);
 
         let local = if let Some(compressed_node) = insertion.occupied_id() {
-            let md = self.md_cache.get(&compressed_node).unwrap();
+            let md = match self.md_cache.entry(compressed_node) {
+                std::collections::hash_map::Entry::Occupied(md) => md.into_mut(),
+                std::collections::hash_map::Entry::Vacant(md) => {
+                    // the node comes from a loaded store, its partial analysis was not persisted,
+                    // so it is made again from the children, as for a new node
+                    let ana = make_partial_ana(
+                        acc.simple.kind,
+                        acc.ana,
+                        label,
+                        &acc.simple.children,
+                        label_store,
+                        &insertion,
+                    );
+                    md.insert(MD::from_store(
+                        &self.stores.node_store,
+                        compressed_node,
+                        ana,
+                    ))
+                }
+            };
             let ana = md.ana.clone();
             let metrics = md.metrics;
             let mcc = md.mcc.clone();
@@ -697,6 +763,11 @@ impl<'stores, 'cache, TS: JavaEnabledTypeStore<HashedNodeRef<'stores, TIdN<NodeI
                 mcc,
             }
         };
+        self.derived.compute(
+            &mut self.stores.node_store,
+            &self.stores.label_store,
+            local.compressed_node,
+        );
 
         let full_node = FullNode {
             global: global.into(),
@@ -1106,7 +1177,10 @@ where
             let insertion = node_store.prepare_insertion(&hashable, eq);
 
             let local = if let Some(id) = insertion.occupied_id() {
-                let md = self.md_cache.get(&id).unwrap();
+                let md = self
+                    .md_cache
+                    .entry(id)
+                    .or_insert_with(|| MD::from_store(&self.stores.node_store, id, None));
                 let ana = md.ana.clone();
                 let metrics = md.metrics;
                 let mcc = md.mcc.clone();
@@ -0,0 +1,338 @@
+//! Incremental generation of java files, eg. to follow the edits made in an editor.
+//!
+//! Tree-sitter reparses an edited file by reusing the unchanged parts of the previous syntax tree,
+//! here we do the same with the HyperAST:
+//! the subtrees outside of the edited and changed ranges are not built again,
+//! only the nodes along the edited spine are.
+//!
+//! ```ignore
+//! let tree = legion_with_refs::tree_sitter_parse(text).unwrap_or_else(|t| t);
+//! let (full_node, file) = IncrementalFile::generate(&mut java_tree_gen, b"A.java", text, tree);
+//! // after some edit of the text
+//! let (full_node, file) = file.update(&mut java_tree_gen, b"A.java", new_text, &edit);
+//! ```
+
+use std::{collections::BTreeMap, ops::Range};
+
+use hyper_ast::{
+    full::FullNode,
+    store::nodes::legion::HashedNodeRef,
+    tree_gen::{
+        get_spacing, has_final_space, parser::Node as _, AccIndentation, Accumulator,
+        BasicGlobalData, Parents, TextedGlobalData, TreeGen, ZippedTreeGen,
+    },
+};
+
+use crate::{
+    types::{JavaEnabledTypeStore, TIdN},
+    TNode,
+};
+
+use super::{Acc, Global, JavaTreeGen, Local, NodeIdentifier, TTreeCursor};
+
+/// Locals of the nodes of a file, by byte range and type.
+type Locals = BTreeMap<(usize, usize, u16), Local>;
+
+/// What needs to be kept from a generation to incrementally handle the next one.
+pub struct IncrementalFile {
+    tree: tree_sitter::Tree,
+    locals: Locals,
+    built: usize,
+    reused: usize,
+}
+
+impl IncrementalFile {
+    /// Generates the whole file, like [`JavaTreeGen::generate_file`], while keeping what is needed to handle future edits.
+    pub fn generate<'stores, 'cache, TS>(
+        java_tree_gen: &mut JavaTreeGen<'stores, 'cache, TS>,
+        name: &[u8],
+        text: &'stores [u8],
+        tree: tree_sitter::Tree,
+    ) -> (FullNode<BasicGlobalData, Local>, Self)
+    where
+        TS: JavaEnabledTypeStore<HashedNodeRef<'stores, TIdN<NodeIdentifier>>>,
+    {
+        Self::gen(java_tree_gen, name, text, tree, Default::default(), vec![])
+    }
+
+    /// Applies `edit` to the previous syntax tree then reparses and generates `text`,
+    /// subtrees that were neither edited nor changed are reused from the previous generation.
+    ///
+    /// `text` is the whole file after the edit.
+    pub fn update<'stores, 'cache, TS>(
+        self,
+        java_tree_gen: &mut JavaTreeGen<'stores, 'cache, TS>,
+        name: &[u8],
+        text: &'stores [u8],
+        edit: &tree_sitter::InputEdit,
+    ) -> (FullNode<BasicGlobalData, Local>, Self)
+    where
+        TS: JavaEnabledTypeStore<HashedNodeRef<'stores, TIdN<NodeIdentifier>>>,
+    {
+        let mut old = self.tree;
+        old.edit(edit);
+        let tree = tree_sitter_reparse(text, &old);
+        let mut changed: Vec<_> = old
+            .changed_ranges(&tree)
+            .map(|r| r.start_byte..r.end_byte)
+            .collect();
+        // changed ranges only account for structural changes, eg. not for a renamed identifier
+        changed.push(edit.start_byte..edit.new_end_byte);
+        let previous = shift(self.locals, edit);
+        Self::gen(java_tree_gen, name, text, tree, previous, changed)
+    }
+
+    pub fn tree(&self) -> &tree_sitter::Tree {
+        &self.tree
+    }
+
+    /// Number of nodes built by the last generation, spaces excluded.
+    pub fn built(&self) -> usize {
+        self.built
+    }
+
+    /// Number of subtrees taken from the previous generation by the last one.
+    pub fn reused(&self) -> usize {
+        self.reused
+    }
+
+    fn gen<'stores, 'cache, TS>(
+        java_tree_gen: &mut JavaTreeGen<'stores, 'cache, TS>,
+        name: &[u8],
+        text: &'stores [u8],
+        tree: tree_sitter::Tree,
+        previous: Locals,
+        changed: Vec<Range<usize>>,
+    ) -> (FullNode<BasicGlobalData, Local>, Self)
+    where
+        TS: JavaEnabledTypeStore<HashedNodeRef<'stores, TIdN<NodeIdentifier>>>,
+    {
+        let mut gen = IncrementalJavaTreeGen {
+            inner: java_tree_gen,
+            previous,
+            changed,
+            recorded: Default::default(),
+            built: 0,
+            reused: 0,
+        };
+        let full_node = gen.generate_file(name, text, tree.walk());
+        let file = Self {
+            tree,
+            locals: gen.recorded,
+            built: gen.built,
+            reused: gen.reused,
+        };
+        (full_node, file)
+    }
+}
+
+/// Same as [`super::tree_sitter_parse`] but reusing `old`, that must already be edited.
+/// Syntax errors are kept in the tree, editors often make files temporarily invalid.
+pub fn tree_sitter_reparse(text: &[u8], old: &tree_sitter::Tree) -> tree_sitter::Tree {
+    let mut parser = tree_sitter::Parser::new();
+    let language = tree_sitter_java::language();
+    parser.set_language(language).unwrap();
+    parser.parse(text, Some(old)).unwrap()
+}
+
+/// Moves the locals after the edit, drops the ones touching it.
+fn shift(locals: Locals, edit: &tree_sitter::InputEdit) -> Locals {
+    let delta = edit.new_end_byte as isize - edit.old_end_byte as isize;
+    locals
+        .into_iter()
+        .filter_map(|((start, end, kind), local)| {
+            if end < edit.start_byte {
+                Some(((start, end, kind), local))
+            } else if start > edit.old_end_byte {
+                let start = (start as isize + delta) as usize;
+                let end = (end as isize + delta) as usize;
+                Some(((start, end, kind), local))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+fn touches(a: &Range<usize>, b: &Range<usize>) -> bool {
+    a.start <= b.end && b.start <= a.end
+}
+
+struct IncrementalJavaTreeGen<'gen, 'stores, 'cache, TS> {
+    inner: &'gen mut JavaTreeGen<'stores, 'cache, TS>,
+    previous: Locals,
+    changed: Vec<Range<usize>>,
+    recorded: Locals,
+    built: usize,
+    reused: usize,
+}
+
+impl<'gen, 'stores, 'cache, TS> IncrementalJavaTreeGen<'gen, 'stores, 'cache, TS>
+where
+    TS: JavaEnabledTypeStore<HashedNodeRef<'stores, TIdN<NodeIdentifier>>>,
+{
+    fn reusable(&self, key: &(usize, usize, u16)) -> Option<&Local> {
+        let range = key.0..key.1;
+        if self.changed.iter().any(|r| touches(r, &range)) {
+            return None;
+        }
+        self.previous.get(key)
+    }
+
+    /// the descendants of a reused node stay valid
+    fn record_reused(&mut self, key: (usize, usize, u16), local: Local) {
+        let descendants = self
+            .previous
+            .range((key.0, 0, 0)..(key.1, 0, 0))
+            .filter(|((_, end, _), _)| *end <= key.1)
+            .map(|(k, v)| (*k, v.clone()))
+            .collect::<Vec<_>>();
+        self.recorded.extend(descendants);
+        self.recorded.insert(key, local);
+    }
+
+    /// see [`JavaTreeGen::generate_file`]
+    fn generate_file(
+        &mut self,
+        name: &[u8],
+        text: &'stores [u8],
+        cursor: tree_sitter::TreeCursor,
+    ) -> FullNode<BasicGlobalData, Local> {
+        let mut global = Global::from(TextedGlobalData::new(Default::default(), text));
+        let mut init = self.init_val(text, &TNode(cursor.node()));
+        let mut xx = TTreeCursor(cursor);
+
+        let spacing = get_spacing(
+            init.padding_start,
+            init.start_byte,
+            text,
+            init.indentation(),
+        );
+        if let Some(spacing) = spacing {
+            global.down();
+            init.start_byte = 0;
+            init.push(FullNode {
+                global: global.into(),
+                local: self.inner.make_spacing(spacing),
+            });
+            global.right();
+        }
+        let mut stack = init.into();
+
+        self.gen(text, &mut stack, &mut xx, &mut global);
+
+        let mut acc = stack.finalize();
+
+        if has_final_space(&0, global.sum_byte_length(), text) {
+            let spacing = get_spacing(
+                global.sum_byte_length(),
+                text.len(),
+                text,
+                acc.indentation(),
+            );
+            if let Some(spacing) = spacing {
+                global.right();
+                acc.push(FullNode {
+                    global: global.into(),
+                    local: self.inner.make_spacing(spacing),
+                });
+            }
+        }
+        let label = Some(std::str::from_utf8(name).unwrap().to_owned());
+        self.make(&mut global, acc, label)
+    }
+}
+
+impl<'gen, 'stores, 'cache, TS> TreeGen for IncrementalJavaTreeGen<'gen, 'stores, 'cache, TS>
+where
+    TS: JavaEnabledTypeStore<HashedNodeRef<'stores, TIdN<NodeIdentifier>>>,
+{
+    type Acc = Acc;
+    type Global = Global<'stores>;
+
+    fn make(
+        &mut self,
+        global: &mut Self::Global,
+        acc: Self::Acc,
+        label: Option<String>,
+    ) -> <Self::Acc as Accumulator>::Node {
+        self.inner.make(global, acc, label)
+    }
+}
+
+impl<'gen, 'stores, 'cache, TS> ZippedTreeGen for IncrementalJavaTreeGen<'gen, 'stores, 'cache, TS>
+where
+    TS: JavaEnabledTypeStore<HashedNodeRef<'stores, TIdN<NodeIdentifier>>>,
+{
+    type Stores = <JavaTreeGen<'stores, 'cache, TS> as ZippedTreeGen>::Stores;
+    type Text = [u8];
+    type Node<'b> = TNode<'b>;
+    type TreeCursor<'b> = TTreeCursor<'b>;
+
+    fn stores(&mut self) -> &mut Self::Stores {
+        self.inner.stores()
+    }
+
+    fn init_val(&mut self, text: &[u8], node: &Self::Node<'_>) -> Self::Acc {
+        self.inner.init_val(text, node)
+    }
+
+    fn pre_skippable(
+        &mut self,
+        text: &Self::Text,
+        node: &Self::Node<'_>,
+        stack: &Parents<Self::Acc>,
+        global: &mut Self::Global,
+        skip: &mut bool,
+    ) -> Option<Self::Acc> {
+        let acc = self.pre(text, node, stack, global);
+        let key = (node.start_byte(), node.end_byte(), acc.simple.kind as u16);
+        // the whole subtree will be taken from the previous generation in post
+        *skip = self.reusable(&key).is_some();
+        Some(acc)
+    }
+
+    fn pre(
+        &mut self,
+        text: &[u8],
+        node: &Self::Node<'_>,
+        stack: &Parents<Self::Acc>,
+        global: &mut Self::Global,
+    ) -> Self::Acc {
+        self.inner.pre(text, node, stack, global)
+    }
+
+    fn post(
+        &mut self,
+        parent: &mut Self::Acc,
+        global: &mut Self::Global,
+        text: &[u8],
+        acc: Self::Acc,
+    ) -> <Self::Acc as Accumulator>::Node {
+        let key = (acc.start_byte, acc.end_byte, acc.simple.kind as u16);
+        if let Some(local) = self.reusable(&key).cloned() {
+            let spacing = get_spacing(
+                acc.padding_start,
+                acc.start_byte,
+                text,
+                parent.indentation(),
+            );
+            if let Some(spacing) = spacing {
+                parent.push(FullNode {
+                    global: global.into(),
+                    local: self.inner.make_spacing(spacing),
+                });
+            }
+            self.reused += 1;
+            self.record_reused(key, local.clone());
+            return FullNode {
+                global: global.into(),
+                local,
+            };
+        }
+        self.built += 1;
+        let full_node = self.inner.post(parent, global, text, acc);
+        self.recorded.insert(key, full_node.local.clone());
+        full_node
+    }
+}
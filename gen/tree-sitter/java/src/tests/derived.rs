@@ -0,0 +1,114 @@
+use hyper_ast::{
+    store::{
+        labels::LabelStore,
+        nodes::{legion::compo::BytesLen, DefaultNodeStore as NodeStore},
+        SimpleStores,
+    },
+    tree_gen::derived::{DerivedMetric, DerivedMetrics, MetricInput},
+};
+
+use crate::{
+    legion_with_refs::{self, JavaTreeGen, NodeIdentifier},
+    types::{TStore, Type},
+};
+
+/// maximum number of nested blocks
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct NestingDepth(u32);
+
+struct NestingDepthMetric;
+
+impl DerivedMetric<Type> for NestingDepthMetric {
+    type Value = NestingDepth;
+
+    fn compute(&self, node: MetricInput<'_, Type>, children: &[NestingDepth]) -> NestingDepth {
+        let max = children.iter().map(|x| x.0).max().unwrap_or(0);
+        NestingDepth(if node.kind == Type::Block {
+            max + 1
+        } else {
+            max
+        })
+    }
+}
+
+/// bytes of comments and bytes of the subtree
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct CommentRatio {
+    comments: u32,
+    total: u32,
+}
+
+struct CommentRatioMetric;
+
+impl DerivedMetric<Type> for CommentRatioMetric {
+    type Value = CommentRatio;
+
+    fn compute(&self, node: MetricInput<'_, Type>, children: &[CommentRatio]) -> CommentRatio {
+        let total = node.node.get_component::<BytesLen>().map_or(0, |x| x.0);
+        let comments = if node.kind == Type::Comment {
+            total
+        } else {
+            children.iter().map(|x| x.comments).sum()
+        };
+        CommentRatio { comments, total }
+    }
+}
+
+static A: &str = r#"class A {
+    // a comment
+    void f() {
+        if (true) {
+            while (true) {}
+        }
+    }
+}
+"#;
+
+static B: &str = r#"class B {
+    void f() {
+        if (true) {
+            while (true) {}
+        }
+    }
+}
+"#;
+
+fn generate(java_tree_gen: &mut JavaTreeGen<'_, '_, TStore>, text: &'static str) -> NodeIdentifier {
+    let tree = legion_with_refs::tree_sitter_parse(text.as_bytes()).unwrap_or_else(|t| t);
+    java_tree_gen
+        .generate_file(b"A.java", text.as_bytes(), tree.walk())
+        .local
+        .compressed_node
+}
+
+#[test]
+fn derived_metrics_stored_on_nodes() {
+    let mut stores = SimpleStores {
+        label_store: LabelStore::new(),
+        type_store: TStore::default(),
+        node_store: NodeStore::new(),
+    };
+    let mut md_cache = Default::default();
+    // generated before registering the metrics
+    let b = generate(&mut JavaTreeGen::new(&mut stores, &mut md_cache), B);
+    let mut derived = DerivedMetrics::default();
+    derived
+        .register(NestingDepthMetric)
+        .register(CommentRatioMetric);
+    let a = generate(
+        &mut JavaTreeGen::new(&mut stores, &mut md_cache).with_derived(&derived),
+        A,
+    );
+
+    let a = stores.node_store.resolve(a);
+    assert_eq!(
+        a.get_component::<NestingDepth>().ok(),
+        Some(&NestingDepth(3))
+    );
+    let ratio = a.get_component::<CommentRatio>().unwrap();
+    assert_eq!(ratio.comments as usize, "// a comment".len());
+    assert!(ratio.total > ratio.comments);
+    // only the subtrees shared with A got their metrics
+    let b = stores.node_store.resolve(b);
+    assert!(b.get_component::<NestingDepth>().is_err());
+}
@@ -0,0 +1,100 @@
+use hyper_ast::store::{labels::LabelStore, nodes::DefaultNodeStore as NodeStore, SimpleStores};
+use pretty_assertions::assert_eq;
+
+use crate::{
+    legion_with_refs::{self, incremental::IncrementalFile, JavaTreeGen},
+    types::TStore,
+};
+
+static BEFORE: &str = r#"package a;
+
+class A {
+    int f(int x) {
+        return x + 1;
+    }
+
+    void g() {
+        f(2);
+    }
+}
+"#;
+
+static AFTER: &str = r#"package a;
+
+class A {
+    int f(int x) {
+        return x * 2 + 1;
+    }
+
+    void g() {
+        f(2);
+    }
+}
+"#;
+
+fn point(text: &[u8], byte: usize) -> tree_sitter::Point {
+    let before = &text[..byte];
+    let row = before.iter().filter(|x| **x == b'\n').count();
+    let column = byte - before.iter().rposition(|x| *x == b'\n').map_or(0, |i| i + 1);
+    tree_sitter::Point { row, column }
+}
+
+#[test]
+fn incremental_same_as_full() {
+    let mut stores = SimpleStores {
+        label_store: LabelStore::new(),
+        type_store: TStore::default(),
+        node_store: NodeStore::new(),
+    };
+    let mut md_cache = Default::default();
+    let mut java_tree_gen = JavaTreeGen::new(&mut stores, &mut md_cache);
+
+    let before = BEFORE.as_bytes();
+    let tree = legion_with_refs::tree_sitter_parse(before).unwrap_or_else(|t| t);
+    let (_, file) = IncrementalFile::generate(&mut java_tree_gen, b"A.java", before, tree);
+    assert_eq!(file.reused(), 0);
+    let full_built = file.built();
+
+    let after = AFTER.as_bytes();
+    let start_byte = BEFORE.find("x + 1").unwrap() + 1;
+    let edit = tree_sitter::InputEdit {
+        start_byte,
+        old_end_byte: start_byte,
+        new_end_byte: start_byte + " * 2".len(),
+        start_position: point(before, start_byte),
+        old_end_position: point(before, start_byte),
+        new_end_position: point(after, start_byte + " * 2".len()),
+    };
+    let (incremental, file) = file.update(&mut java_tree_gen, b"A.java", after, &edit);
+    // at least the package declaration and method g are reused
+    assert!(file.reused() >= 2, "{}", file.reused());
+    // only the spine of the edit and the new nodes are built
+    assert!(
+        file.built() < full_built,
+        "{} {}",
+        file.built(),
+        full_built
+    );
+
+    let tree = legion_with_refs::tree_sitter_parse(after).unwrap_or_else(|t| t);
+    let full = java_tree_gen.generate_file(b"A.java", after, tree.walk());
+
+    assert_eq!(
+        hyper_ast::nodes::SyntaxSerializer::new(&*java_tree_gen.stores, full.local.compressed_node)
+            .to_string(),
+        hyper_ast::nodes::SyntaxSerializer::new(
+            &*java_tree_gen.stores,
+            incremental.local.compressed_node
+        )
+        .to_string(),
+    );
+    assert_eq!(full.local.compressed_node, incremental.local.compressed_node);
+    assert_eq!(
+        hyper_ast::nodes::TextSerializer::new(
+            &*java_tree_gen.stores,
+            incremental.local.compressed_node
+        )
+        .to_string(),
+        AFTER
+    );
+}
@@ -0,0 +1,107 @@
+use std::collections::HashSet;
+
+use hyper_ast::{
+    filter::BloomSize,
+    store::{
+        labels::{index::find_in_subtrees, DefaultLabelIdentifier, LabelStore},
+        nodes::DefaultNodeStore as NodeStore,
+        SimpleStores,
+    },
+    types::{IterableChildren, LabelStore as _, Labeled, WithChildren},
+};
+
+use crate::{
+    legion_with_refs::{self, JavaTreeGen, NodeIdentifier},
+    types::TStore,
+};
+
+static A: &str = r#"class A {
+    int unused;
+
+    void f(int x) {
+        B.g(x);
+    }
+}
+"#;
+
+/// `root` and all its descendants
+fn descendants(node_store: &NodeStore, root: NodeIdentifier) -> Vec<NodeIdentifier> {
+    let mut r = vec![];
+    let mut stack = vec![root];
+    while let Some(id) = stack.pop() {
+        r.push(id);
+        if let Some(cs) = node_store.resolve(id).children() {
+            stack.extend(cs.iter_children().copied());
+        }
+    }
+    r
+}
+
+/// The nodes under `root` labeled by one of `labels`, exploring the whole tree.
+fn scan(
+    node_store: &NodeStore,
+    root: NodeIdentifier,
+    labels: &[DefaultLabelIdentifier],
+) -> HashSet<NodeIdentifier> {
+    descendants(node_store, root)
+        .into_iter()
+        .filter(|x| {
+            let node = node_store.resolve(*x);
+            node.try_get_label().is_some_and(|l| labels.contains(l))
+        })
+        .collect()
+}
+
+#[test]
+fn find_in_subtrees_skips_unreferenced() {
+    let mut stores = SimpleStores {
+        label_store: LabelStore::new(),
+        type_store: TStore::default(),
+        node_store: NodeStore::new(),
+    };
+    let mut md_cache = Default::default();
+    let tree = legion_with_refs::tree_sitter_parse(A.as_bytes()).unwrap_or_else(|t| t);
+    let root = JavaTreeGen::new(&mut stores, &mut md_cache)
+        .generate_file(b"A.java", A.as_bytes(), tree.walk())
+        .local
+        .compressed_node;
+    // the references of every subtree, thus the ones in the bloom filters of the ancestors of `g`
+    let refs: Vec<_> = md_cache
+        .values()
+        .filter_map(|md| md.ana.as_ref())
+        .flat_map(|ana| ana.solver.iter_refs())
+        .collect();
+    assert!(!refs.is_empty());
+    let node_store = &stores.node_store;
+
+    let g = [stores.label_store.get("g").unwrap()];
+    let expected = scan(node_store, root, &g);
+    assert_eq!(expected.len(), 1);
+    let found = find_in_subtrees(node_store, root, &g, &refs);
+    assert_eq!(found.into_iter().collect::<HashSet<_>>(), expected);
+
+    // subtrees without references are skipped, whatever the references looked for
+    let mut skipped_labeled = 0;
+    for id in descendants(node_store, root) {
+        let node = node_store.resolve(id);
+        if node.child_count() == 0
+            || !matches!(node.get_component::<BloomSize>(), Ok(BloomSize::None))
+        {
+            continue;
+        }
+        let mut labels: Vec<_> = descendants(node_store, id)
+            .into_iter()
+            .filter_map(|x| node_store.resolve(x).try_get_label().copied())
+            .collect();
+        labels.sort();
+        labels.dedup();
+        let found = find_in_subtrees(node_store, id, &labels, &refs);
+        assert!(found.iter().all(|x| *x == id));
+        let all = scan(node_store, id, &labels);
+        skipped_labeled += all.iter().filter(|x| **x != id).count();
+        let found = find_in_subtrees(node_store, id, &labels, &refs[..0]);
+        assert_eq!(found.into_iter().collect::<HashSet<_>>(), all);
+    }
+    // eg. the declarator of `unused`
+    assert!(skipped_labeled > 0);
+}
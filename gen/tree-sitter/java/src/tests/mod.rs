@@ -1,7 +1,17 @@
 pub mod tests_legion_with_refs;
 
+mod incremental;
+
 mod tree_sitter_types_test;
 
+mod query;
+
+mod snapshot;
+
+mod derived;
+
+mod label_index;
+
 // fn f() {
 //     tree_sitter_java::language().node_kind_count();
 // }
@@ -0,0 +1,100 @@
+use hyper_ast::{
+    query::{MemoizedQuery, Query},
+    store::{labels::LabelStore, nodes::DefaultNodeStore as NodeStore, SimpleStores},
+    types::{LabelStore as _, Labeled},
+};
+use pretty_assertions::assert_eq;
+
+use crate::{
+    legion_with_refs::{self, JavaTreeGen},
+    types::TStore,
+};
+
+static BEFORE: &str = r#"package a;
+
+class A {
+    int f(int x) {
+        return x + 1;
+    }
+
+    void g() {
+        f(2);
+    }
+}
+"#;
+
+static AFTER: &str = r#"package a;
+
+class A {
+    int f(int x) {
+        return x + 1;
+    }
+
+    void g() {
+        f(3);
+    }
+
+    void h() {
+        g();
+    }
+}
+"#;
+
+#[test]
+fn query_memoized_across_versions() {
+    let mut stores = SimpleStores {
+        label_store: LabelStore::new(),
+        type_store: TStore::default(),
+        node_store: NodeStore::new(),
+    };
+    let mut md_cache = Default::default();
+    let mut java_tree_gen = JavaTreeGen::new(&mut stores, &mut md_cache);
+    let mut generate = |text: &str| {
+        let tree = legion_with_refs::tree_sitter_parse(text.as_bytes()).unwrap_or_else(|t| t);
+        java_tree_gen
+            .generate_file(b"A.java", text.as_bytes(), tree.walk())
+            .local
+            .compressed_node
+    };
+    let before = generate(BEFORE);
+    let after = generate(AFTER);
+
+    let query = Query::new(
+        r#"
+        ((method_declaration (identifier) @name) (#match? @name "^[fg]$"))
+        (method_invocation (identifier) @callee (argument_list [(decimal_integer_literal) @arg "(" ]))
+        "#,
+    )
+    .unwrap();
+    let name = query.capture_index_for_name("name").unwrap();
+    let mut query = MemoizedQuery::new(query);
+
+    let names = |query: &mut MemoizedQuery<_, _>, root| {
+        let stores = &stores;
+        query
+            .matches(stores, &root)
+            .into_iter()
+            .filter(|m| m.pattern_index == 0)
+            .map(|m| {
+                let c = m.captures.iter().find(|c| c.index == name).unwrap();
+                let l = stores
+                    .node_store
+                    .resolve(c.node)
+                    .get_label_unchecked()
+                    .clone();
+                stores.label_store.resolve(&l).to_string()
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(names(&mut query, before), vec!["f", "g"]);
+    let memoized = query.memoized_count();
+    assert_eq!(names(&mut query, after), vec!["f", "g"]);
+    // the subtree of f is shared, thus it is not queried again
+    let mut fresh = MemoizedQuery::new(query.query().clone());
+    assert_eq!(names(&mut fresh, after), vec!["f", "g"]);
+    assert!(query.memoized_count() < memoized + fresh.memoized_count());
+
+    let matches = query.matches(&stores, &after);
+    let invocations: Vec<_> = matches.iter().filter(|m| m.pattern_index == 1).collect();
+    assert_eq!(invocations.len(), 3);
+}
@@ -0,0 +1,98 @@
+use hyper_ast::{
+    hashed::SyntaxNodeHashsKinds,
+    query::{MemoizedQuery, Query},
+    store::{
+        labels::LabelStore,
+        nodes::DefaultNodeStore as NodeStore,
+        snapshot::{self, Snapshot},
+        SimpleStores,
+    },
+    types::{
+        LabelStore as _, Labeled, LangRef, TypeStore as _, Typed, WithChildren, WithHashs,
+        WithSerialization, WithStats,
+    },
+};
+use pretty_assertions::assert_eq;
+
+use crate::{
+    legion_with_refs::{self, JavaTreeGen},
+    types::{Java, TStore, Type},
+};
+
+static TEXT: &str = r#"package a;
+
+class A {
+    int f(int x) {
+        return x + 1;
+    }
+
+    int g(int x) {
+        return x + 1;
+    }
+}
+"#;
+
+#[test]
+fn snapshot_same_as_stores() {
+    let mut stores = SimpleStores {
+        label_store: LabelStore::new(),
+        type_store: TStore::default(),
+        node_store: NodeStore::new(),
+    };
+    let mut md_cache = Default::default();
+    let mut java_tree_gen = JavaTreeGen::new(&mut stores, &mut md_cache);
+    let tree = legion_with_refs::tree_sitter_parse(TEXT.as_bytes()).unwrap_or_else(|t| t);
+    let root = java_tree_gen
+        .generate_file(b"A.java", TEXT.as_bytes(), tree.walk())
+        .local
+        .compressed_node;
+
+    let mut bytes = vec![];
+    snapshot::freeze(&stores, &[root, root], &mut bytes).unwrap();
+    let langs: &[&'static dyn LangRef<Type>] = &[&Java];
+    let snapshot = Snapshot::open(bytes, langs).unwrap();
+    assert_eq!(snapshot.roots()[0], snapshot.roots()[1]);
+    // the bodies of f and g are shared
+    assert!(snapshot.node_count() < stores.node_store.resolve(root).size());
+
+    let mut stack = vec![(root, snapshot.roots()[0])];
+    while let Some((id, s_id)) = stack.pop() {
+        let node = stores.node_store.resolve(id);
+        let s_node = snapshot.try_resolve(s_id).unwrap();
+        let ty = stores.type_store.resolve_type(&node);
+        assert_eq!(ty.to_string(), s_node.get_type().to_string());
+        assert_eq!(
+            node.try_get_label().map(|l| stores.label_store.resolve(l)),
+            s_node.try_get_label().map(|l| snapshot.resolve_label(l)),
+        );
+        assert_eq!(node.size(), s_node.size());
+        assert_eq!(node.height(), s_node.height());
+        assert_eq!(node.try_bytes_len(), s_node.try_bytes_len());
+        for kind in [
+            SyntaxNodeHashsKinds::Struct,
+            SyntaxNodeHashsKinds::Label,
+            SyntaxNodeHashsKinds::Syntax,
+        ] {
+            assert_eq!(node.hash(&kind), s_node.hash(&kind));
+        }
+        assert_eq!(node.child_count(), s_node.child_count());
+        for i in 0..node.child_count() {
+            stack.push((node.child(&i).unwrap(), s_node.child(&i).unwrap()));
+        }
+    }
+
+    let query = Query::new("(method_declaration (identifier) @name)").unwrap();
+    let names = snapshot.get("f").into_iter().chain(snapshot.get("g"));
+    assert_eq!(names.count(), 2);
+    let matches = MemoizedQuery::new(query).matches(&snapshot, &snapshot.roots()[0]);
+    let names: Vec<_> = matches
+        .iter()
+        .map(|m| {
+            let node = snapshot.try_resolve(m.captures[0].node).unwrap();
+            snapshot
+                .resolve_label(node.get_label_unchecked())
+                .to_string()
+        })
+        .collect();
+    assert_eq!(names, vec!["f", "g"]);
+}
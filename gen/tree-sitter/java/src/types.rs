@@ -127,6 +127,31 @@ mod legion_impls {
             }
         }
     }
+
+    impl hyper_ast::store::nodes::legion::persist::Persist for Type {
+        fn save(
+            &self,
+            w: &mut dyn std::io::Write,
+            _: &hyper_ast::store::nodes::legion::persist::SavedIds,
+        ) -> std::io::Result<()> {
+            hyper_ast::store::persist::write_u16(w, *self as u16)
+        }
+
+        fn load(
+            r: &mut dyn std::io::Read,
+            _: &hyper_ast::store::nodes::legion::persist::LoadedIds,
+        ) -> std::io::Result<Self> {
+            let t = hyper_ast::store::persist::read_u16(r)?;
+            if t < COUNT {
+                Ok(Type::resolve(t))
+            } else {
+                Err(hyper_ast::store::persist::invalid_data(format!(
+                    "invalid type {}",
+                    t
+                )))
+            }
+        }
+    }
 }
 pub trait JavaEnabledTypeStore<T>: TypeStore<T> {}
 
@@ -147,7 +172,7 @@ impl Type {
 }
 
 #[repr(u8)]
-pub(crate) enum TStore {
+pub enum TStore {
     Java = 0,
 }
 
@@ -209,10 +234,11 @@ impl LangRef<Type> for Java {
 }
 impl LangRef<AnyType> for Java {
     fn make(&self, t: u16) -> &'static AnyType {
-        todo!()
+        &ANY_T_L[t as usize]
     }
     fn to_u16(&self, t: AnyType) -> u16 {
-        todo!()
+        let t = t.as_any().downcast_ref::<Type>().unwrap();
+        *t as u16
     }
 
     fn name(&self) -> &'static str {
@@ -2058,3 +2084,5 @@ const S_T_L: &'static [Type] = &[
     Type::Directory,
     Type::ERROR,
 ];
+
+static ANY_T_L: [AnyType; S_T_L.len()] = hyper_ast::types::any_types(S_T_L);
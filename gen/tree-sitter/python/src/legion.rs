@@ -0,0 +1,77 @@
+///! fully compress all subtrees from a python CST
+use std::fmt::Debug;
+
+use crate::TNode;
+
+pub use hyper_ast::tree_gen::basic::{eq_node, FNode, Global, Local, MDCache, PartialAnalysis, MD};
+use hyper_ast::tree_gen::basic::{BasicLang, BasicTreeGen};
+
+use crate::types::{Python, Type};
+
+pub type LabelIdentifier = hyper_ast::store::labels::DefaultLabelIdentifier;
+
+pub type PythonTreeGen<'store, 'cache, TS> = BasicTreeGen<'store, 'cache, Python, TS>;
+
+pub type Acc = hyper_ast::tree_gen::basic::Acc<Type>;
+
+impl BasicLang for Python {
+    type Type = Type;
+    type Node<'a> = TNode<'a>;
+    type TreeCursor<'a> = TTreeCursor<'a>;
+
+    const SPACES: Type = Type::Spaces;
+
+    fn obtain_type(node: &TNode<'_>) -> Type {
+        node.obtain_type()
+    }
+
+    fn is_literal(kind: &Type) -> bool {
+        // including f-strings
+        kind == &Type::String
+    }
+}
+
+#[repr(transparent)]
+pub struct TTreeCursor<'a>(tree_sitter::TreeCursor<'a>);
+
+impl<'a> From<tree_sitter::TreeCursor<'a>> for TTreeCursor<'a> {
+    fn from(cursor: tree_sitter::TreeCursor<'a>) -> Self {
+        Self(cursor)
+    }
+}
+
+impl<'a> Debug for TTreeCursor<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("TTreeCursor")
+            .field(&self.0.node().kind())
+            .finish()
+    }
+}
+impl<'a> hyper_ast::tree_gen::parser::TreeCursor<'a, TNode<'a>> for TTreeCursor<'a> {
+    fn node(&self) -> TNode<'a> {
+        TNode(self.0.node())
+    }
+
+    fn goto_first_child(&mut self) -> bool {
+        self.0.goto_first_child()
+    }
+
+    fn goto_parent(&mut self) -> bool {
+        self.0.goto_parent()
+    }
+
+    fn goto_next_sibling(&mut self) -> bool {
+        self.0.goto_next_sibling()
+    }
+}
+
+pub fn tree_sitter_parse(text: &[u8]) -> Result<tree_sitter::Tree, tree_sitter::Tree> {
+    let mut parser = tree_sitter::Parser::new();
+    parser.set_language(tree_sitter_python::language()).unwrap();
+    let tree = parser.parse(text, None).unwrap();
+    if tree.root_node().has_error() {
+        Err(tree)
+    } else {
+        Ok(tree)
+    }
+}
@@ -0,0 +1,55 @@
+use crate::types::{TStore, Type};
+
+type PythonTreeGen<'store, 'cache> = crate::legion::PythonTreeGen<'store, 'cache, TStore>;
+type SimpleStores = hyper_ast::store::SimpleStores<TStore>;
+
+#[test]
+fn python_simple_test() {
+    let text = {
+        let source_code1 = r#"
+import os
+from typing import List
+
+
+class Walker:
+    """lists files"""
+
+    def __init__(self, root: str = "."):
+        self.root = root
+
+    def walk(self) -> List[str]:
+        res = []
+        for d, _, files in os.walk(self.root):
+            if not files:
+                continue
+            res += [f"{d}/{f}" for f in files]
+        return res
+"#;
+        source_code1.as_bytes()
+    };
+    let tree = match crate::legion::tree_sitter_parse(text) {
+        Ok(t) => t,
+        Err(t) => panic!("{}", t.root_node().to_sexp()),
+    };
+    println!("{:#?}", tree.root_node().to_sexp());
+    let mut stores = SimpleStores::default();
+    let mut md_cache = Default::default();
+    let mut tree_gen = PythonTreeGen::new(&mut stores, &mut md_cache);
+    let x = tree_gen.generate_file(b"", text, tree.walk().into()).local;
+    println!(
+        "{}",
+        hyper_ast::nodes::SyntaxSerializer::new(&stores, x.compressed_node)
+    );
+    let serialized = hyper_ast::nodes::TextSerializer::new(&stores, x.compressed_node).to_string();
+    assert_eq!(std::str::from_utf8(text).unwrap(), serialized);
+}
+
+#[test]
+fn kinds_round_trip() {
+    assert_eq!(Type::from_str("lambda"), Some(Type::Lambda));
+    assert_eq!(Type::from_str_anonymous("lambda"), Some(Type::TS1));
+    assert_eq!(Type::from_str_anonymous("not in"), Some(Type::NotIn));
+    for k in ["module", "function_definition", "ERROR", ":=", "none"] {
+        assert_eq!(Type::from_str(k).unwrap().to_str(), k);
+    }
+}
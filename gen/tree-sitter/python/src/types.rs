@@ -0,0 +1,1339 @@
+use std::fmt::Display;
+
+use hyper_ast::{
+    store::defaults::NodeIdentifier,
+    types::{
+        AnyType, HyperType, Lang, LangRef, LangWrapper, NodeId, TypeStore, TypeTrait, TypedNodeId,
+    },
+};
+
+#[cfg(feature = "legion")]
+mod legion_impls {
+    use super::*;
+
+    use crate::TNode;
+
+    impl<'a> TNode<'a> {
+        /// kind ids are specific to each version of the grammar,
+        /// so types are obtained from the kind names.
+        pub fn obtain_type(&self) -> Type {
+            let k = self.0.kind();
+            let t = if self.0.is_named() {
+                Type::from_str(k)
+            } else {
+                Type::from_str_anonymous(k)
+            };
+            t.unwrap_or_else(|| {
+                log::warn!("unknown python kind {:?}", k);
+                Type::ERROR
+            })
+        }
+    }
+
+    use hyper_ast::{store::nodes::legion::HashedNodeRef, types::TypeIndex};
+
+    impl<'a, TS: PythonEnabledTypeStore<HashedNodeRef<'a, Type>>> From<TS> for Single {
+        fn from(value: TS) -> Self {
+            Self {
+                mask: TS::MASK,
+                lang: TS::LANG,
+            }
+        }
+    }
+
+    impl<'a> TypeStore<HashedNodeRef<'a, TIdN<NodeIdentifier>>> for TStore {
+        type Ty = Type;
+        const MASK: TypeInternalSize = 0b1000_0000_0000_0000;
+        fn resolve_type(&self, n: &HashedNodeRef<'a, TIdN<NodeIdentifier>>) -> Self::Ty {
+            n.get_component::<Type>().unwrap().clone()
+        }
+
+        fn resolve_lang(
+            &self,
+            n: &HashedNodeRef<'a, TIdN<NodeIdentifier>>,
+        ) -> hyper_ast::types::LangWrapper<Self::Ty> {
+            From::<&'static (dyn LangRef<Type>)>::from(&Python)
+        }
+
+        type Marshaled = TypeIndex;
+
+        fn marshal_type(&self, n: &HashedNodeRef<'a, TIdN<NodeIdentifier>>) -> Self::Marshaled {
+            TypeIndex {
+                lang: LangRef::<Type>::name(&Python),
+                ty: *n.get_component::<Type>().unwrap() as u16,
+            }
+        }
+    }
+    impl<'a> PythonEnabledTypeStore<HashedNodeRef<'a, TIdN<NodeIdentifier>>> for TStore {
+        const LANG: TypeInternalSize = Self::Python as u16;
+
+        fn _intern(l: u16, t: u16) -> Self::Ty {
+            todo!()
+        }
+        fn intern(&self, t: Type) -> Self::Ty {
+            t
+        }
+
+        fn resolve(&self, t: Self::Ty) -> Type {
+            t
+        }
+    }
+    impl<'a> TypeStore<HashedNodeRef<'a, NodeIdentifier>> for TStore {
+        type Ty = AnyType;
+        const MASK: TypeInternalSize = 0b1000_0000_0000_0000;
+        fn resolve_type(&self, n: &HashedNodeRef<'a, NodeIdentifier>) -> Self::Ty {
+            From::<&'static (dyn HyperType)>::from(LangRef::<Type>::make(
+                &Python,
+                *n.get_component::<Type>().unwrap() as u16,
+            ))
+        }
+
+        fn resolve_lang(
+            &self,
+            n: &HashedNodeRef<'a, NodeIdentifier>,
+        ) -> hyper_ast::types::LangWrapper<Self::Ty> {
+            From::<&'static (dyn LangRef<AnyType>)>::from(&Python)
+        }
+
+        type Marshaled = TypeIndex;
+
+        fn marshal_type(&self, n: &HashedNodeRef<'a, NodeIdentifier>) -> Self::Marshaled {
+            TypeIndex {
+                lang: LangRef::<Type>::name(&Python),
+                ty: *n.get_component::<Type>().unwrap() as u16,
+            }
+        }
+    }
+
+    impl hyper_ast::store::nodes::legion::persist::Persist for Type {
+        fn save(
+            &self,
+            w: &mut dyn std::io::Write,
+            _: &hyper_ast::store::nodes::legion::persist::SavedIds,
+        ) -> std::io::Result<()> {
+            hyper_ast::store::persist::write_u16(w, *self as u16)
+        }
+
+        fn load(
+            r: &mut dyn std::io::Read,
+            _: &hyper_ast::store::nodes::legion::persist::LoadedIds,
+        ) -> std::io::Result<Self> {
+            let t = hyper_ast::store::persist::read_u16(r)?;
+            if t < COUNT {
+                Ok(Type::resolve(t))
+            } else {
+                Err(hyper_ast::store::persist::invalid_data(format!(
+                    "invalid type {}",
+                    t
+                )))
+            }
+        }
+    }
+}
+
+pub trait PythonEnabledTypeStore<T>: TypeStore<T> {
+    const LANG: u16;
+    fn intern(&self, t: Type) -> Self::Ty {
+        let t = t as u16;
+        Self::_intern(Self::LANG, t)
+    }
+    fn _intern(l: u16, t: u16) -> Self::Ty;
+    fn resolve(&self, t: Self::Ty) -> Type;
+}
+
+impl Type {
+    pub fn resolve(t: u16) -> Self {
+        assert!(t < COUNT);
+        unsafe { std::mem::transmute(t) }
+    }
+}
+
+pub struct Single {
+    mask: TypeInternalSize,
+    lang: TypeInternalSize,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TIdN<IdN>(IdN);
+
+impl<IdN: Clone + Eq + NodeId> NodeId for TIdN<IdN> {
+    type IdN = IdN;
+
+    fn as_id(&self) -> &Self::IdN {
+        &self.0
+    }
+
+    unsafe fn from_id(id: Self::IdN) -> Self {
+        Self(id)
+    }
+
+    unsafe fn from_ref_id(id: &Self::IdN) -> &Self {
+        std::mem::transmute(id)
+    }
+}
+
+impl<IdN: Clone + Eq + NodeId> TypedNodeId for TIdN<IdN> {
+    type Ty = Type;
+}
+
+#[repr(u8)]
+pub(crate) enum TStore {
+    Python = 0,
+}
+
+impl Default for TStore {
+    fn default() -> Self {
+        Self::Python
+    }
+}
+
+type TypeInternalSize = u16;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct T(TypeInternalSize);
+
+/// Python, as parsed by tree-sitter-python
+pub struct Python;
+
+impl Python {
+    const INST: Python = Python;
+}
+
+impl LangRef<AnyType> for Python {
+    fn make(&self, t: u16) -> &'static AnyType {
+        &ANY_T_L[t as usize]
+    }
+    fn to_u16(&self, t: AnyType) -> u16 {
+        let t = t.as_any().downcast_ref::<Type>().unwrap();
+        *t as u16
+    }
+
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Python>()
+    }
+}
+
+impl LangRef<Type> for Python {
+    fn make(&self, t: u16) -> &'static Type {
+        &S_T_L[t as usize]
+    }
+    fn to_u16(&self, t: Type) -> u16 {
+        t as u16
+    }
+
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Python>()
+    }
+}
+
+impl Lang<Type> for Python {
+    fn make(t: u16) -> &'static Type {
+        Python.make(t)
+    }
+    fn to_u16(t: Type) -> u16 {
+        Python.to_u16(t)
+    }
+}
+
+impl HyperType for Type {
+    fn is_directory(&self) -> bool {
+        self == &Type::Directory
+    }
+
+    fn is_file(&self) -> bool {
+        self == &Type::Module
+    }
+
+    fn is_spaces(&self) -> bool {
+        self == &Type::Spaces
+    }
+
+    fn is_syntax(&self) -> bool {
+        self == &Type::LBrace
+            || self == &Type::RBrace
+            || self == &Type::LParen
+            || self == &Type::RParen
+            || self == &Type::LBracket
+            || self == &Type::RBracket
+            || self == &Type::Comma
+            || self == &Type::SemiColon
+            || self == &Type::Colon
+            || self == &Type::Dot
+            || self == &Type::DashGt
+            || self == &Type::Eq
+            || self == &Type::At
+            || self == &Type::DQuote
+            || self == &Type::BQuote
+    }
+
+    fn as_shared(&self) -> hyper_ast::types::Shared {
+        use hyper_ast::types::Shared;
+        match self {
+            Type::ClassDefinition => Shared::TypeDeclaration,
+            Type::Comment => Shared::Comment,
+            Type::Identifier => Shared::Identifier,
+            _ => Shared::Other,
+        }
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn get_lang(&self) -> hyper_ast::types::LangWrapper<Self>
+    where
+        Self: Sized,
+    {
+        From::<&'static (dyn LangRef<Self>)>::from(&Python)
+    }
+}
+impl TypeTrait for Type {
+    type Lang = Python;
+
+    fn is_fork(&self) -> bool {
+        match self {
+            Self::ConditionalExpression => true,
+            Self::IfStatement => true,
+            Self::ElifClause => true,
+            Self::ForStatement => true,
+            Self::WhileStatement => true,
+            Self::ExceptClause => true,
+            Self::ExceptGroupClause => true,
+            Self::CaseClause => true,
+            Self::TryStatement => true,
+            Self::IfClause => true,
+            _ => false,
+        }
+    }
+
+    fn is_literal(&self) -> bool {
+        match self {
+            Self::String => true,
+            Self::ConcatenatedString => true,
+            Self::Integer => true,
+            Self::Float => true,
+            Self::True => true,
+            Self::False => true,
+            Self::None => true,
+            Self::Ellipsis => true,
+            _ => false,
+        }
+    }
+
+    fn is_primitive(&self) -> bool {
+        false
+    }
+
+    fn is_type_declaration(&self) -> bool {
+        self == &Type::ClassDefinition
+    }
+
+    fn is_identifier(&self) -> bool {
+        match self {
+            Self::Identifier => true,
+            Self::KeywordIdentifier => true,
+            Self::DottedName => true,
+            _ => false,
+        }
+    }
+
+    fn is_instance_ref(&self) -> bool {
+        false
+    }
+
+    fn is_type_body(&self) -> bool {
+        false
+    }
+
+    fn is_value_member(&self) -> bool {
+        false
+    }
+
+    fn is_executable_member(&self) -> bool {
+        self == &Type::FunctionDefinition
+    }
+
+    fn is_statement(&self) -> bool {
+        self.is_declarative_statement()
+            || self.is_structural_statement()
+            || self.is_simple_statement()
+            || self.is_block_related()
+    }
+
+    fn is_declarative_statement(&self) -> bool {
+        self == &Type::ImportStatement
+            || self == &Type::ImportFromStatement
+            || self == &Type::FutureImportStatement
+            || self == &Type::GlobalStatement
+            || self == &Type::NonlocalStatement
+            || self == &Type::TypeAliasStatement
+            || self == &Type::ForStatement
+            || self == &Type::WithStatement
+            || self == &Type::ExceptClause
+    }
+
+    fn is_structural_statement(&self) -> bool {
+        self == &Type::IfStatement
+            || self == &Type::WhileStatement
+            || self == &Type::TryStatement
+            || self == &Type::MatchStatement
+            || self == &Type::FinallyClause
+    }
+
+    fn is_block_related(&self) -> bool {
+        self == &Type::Block
+    }
+
+    fn is_simple_statement(&self) -> bool {
+        self == &Type::ExpressionStatement
+            || self == &Type::ReturnStatement
+            || self == &Type::DeleteStatement
+            || self == &Type::RaiseStatement
+            || self == &Type::PassStatement
+            || self == &Type::BreakStatement
+            || self == &Type::ContinueStatement
+            || self == &Type::AssertStatement
+            || self == &Type::PrintStatement
+            || self == &Type::ExecStatement
+    }
+
+    fn is_local_declare(&self) -> bool {
+        self == &Type::Assignment || self == &Type::NamedExpression
+    }
+
+    fn is_parameter(&self) -> bool {
+        self == &Type::TypedParameter
+            || self == &Type::DefaultParameter
+            || self == &Type::TypedDefaultParameter
+            || self == &Type::ListSplatPattern
+            || self == &Type::DictionarySplatPattern
+            || self == &Type::TypeParameter
+    }
+
+    fn is_parameter_list(&self) -> bool {
+        self == &Type::Parameters || self == &Type::LambdaParameters
+    }
+
+    fn is_argument_list(&self) -> bool {
+        self == &Type::ArgumentList
+    }
+
+    fn is_expression(&self) -> bool {
+        self == &Type::ConditionalExpression
+            || self == &Type::BinaryOperator
+            || self == &Type::BooleanOperator
+            || self == &Type::ComparisonOperator
+            || self == &Type::NotOperator
+            || self == &Type::UnaryOperator
+            || self == &Type::Lambda
+            || self == &Type::Call
+            || self == &Type::Attribute
+            || self == &Type::Subscript
+            || self == &Type::ParenthesizedExpression
+            || self == &Type::NamedExpression
+            || self == &Type::Await
+            || self == &Type::ListComprehension
+            || self == &Type::DictionaryComprehension
+            || self == &Type::SetComprehension
+            || self == &Type::GeneratorExpression
+    }
+
+    fn is_comment(&self) -> bool {
+        self == &Type::Comment
+    }
+}
+
+const COUNT: u16 = 215;
+impl Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.to_str())
+    }
+}
+
+#[repr(u16)]
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum Type {
+    End,
+    Import,
+    Dot,
+    From,
+    Future,
+    LParen,
+    RParen,
+    Comma,
+    As,
+    Star,
+    Print,
+    GtGt,
+    Assert,
+    ColonEq,
+    Return,
+    Del,
+    Raise,
+    Pass,
+    Break,
+    Continue,
+    If,
+    Colon,
+    Elif,
+    Else,
+    Match,
+    Case,
+    Async,
+    For,
+    In,
+    While,
+    Try,
+    Except,
+    ExceptStar,
+    Finally,
+    With,
+    Def,
+    DashGt,
+    StarStar,
+    Global,
+    Nonlocal,
+    Exec,
+    TS0,
+    Class,
+    LBracket,
+    RBracket,
+    At,
+    Dash,
+    Underscore,
+    Pipe,
+    LBrace,
+    RBrace,
+    Plus,
+    Not,
+    And,
+    Or,
+    Slash,
+    Percent,
+    SlashSlash,
+    Amp,
+    Caret,
+    LtLt,
+    Tilde,
+    LT,
+    LTEq,
+    EqEq,
+    BangEq,
+    GTEq,
+    GT,
+    LTGT,
+    Is,
+    IsNot,
+    NotIn,
+    TS1,
+    PlusEq,
+    DashEq,
+    StarEq,
+    SlashEq,
+    AtEq,
+    SlashSlashEq,
+    PercentEq,
+    StarStarEq,
+    GtGtEq,
+    LtLtEq,
+    AmpEq,
+    CaretEq,
+    PipeEq,
+    TS2,
+    SemiColon,
+    Eq,
+    DQuote,
+    BQuote,
+    TS3,
+    Module,
+    ImportStatement,
+    ImportPrefix,
+    RelativeImport,
+    FutureImportStatement,
+    ImportFromStatement,
+    AliasedImport,
+    WildcardImport,
+    PrintStatement,
+    Chevron,
+    AssertStatement,
+    ExpressionStatement,
+    NamedExpression,
+    ReturnStatement,
+    DeleteStatement,
+    RaiseStatement,
+    PassStatement,
+    BreakStatement,
+    ContinueStatement,
+    IfStatement,
+    ElifClause,
+    ElseClause,
+    MatchStatement,
+    CaseClause,
+    CasePattern,
+    ForStatement,
+    WhileStatement,
+    TryStatement,
+    ExceptClause,
+    ExceptGroupClause,
+    FinallyClause,
+    WithStatement,
+    WithClause,
+    WithItem,
+    FunctionDefinition,
+    Parameters,
+    LambdaParameters,
+    ListSplat,
+    DictionarySplat,
+    GlobalStatement,
+    NonlocalStatement,
+    ExecStatement,
+    TypeAliasStatement,
+    ClassDefinition,
+    TypeParameter,
+    ParenthesizedListSplat,
+    ArgumentList,
+    DecoratedDefinition,
+    Decorator,
+    Block,
+    ExpressionList,
+    DottedName,
+    AsPattern,
+    AsPatternTarget,
+    ClassPattern,
+    ComplexPattern,
+    DictPattern,
+    KeywordPattern,
+    ListPattern,
+    SplatPattern,
+    TuplePattern,
+    UnionPattern,
+    DefaultParameter,
+    TypedDefaultParameter,
+    ListSplatPattern,
+    DictionarySplatPattern,
+    NotOperator,
+    BooleanOperator,
+    BinaryOperator,
+    UnaryOperator,
+    ComparisonOperator,
+    Lambda,
+    Assignment,
+    AugmentedAssignment,
+    PatternList,
+    Yield,
+    Attribute,
+    Subscript,
+    Slice,
+    Ellipsis,
+    Call,
+    TypedParameter,
+    Type,
+    KeywordArgument,
+    List,
+    Set,
+    Tuple,
+    Dictionary,
+    Pair,
+    ListComprehension,
+    DictionaryComprehension,
+    SetComprehension,
+    GeneratorExpression,
+    ForInClause,
+    IfClause,
+    ConditionalExpression,
+    ConcatenatedString,
+    String,
+    StringStart,
+    StringContent,
+    StringEnd,
+    Interpolation,
+    FormatSpecifier,
+    FormatExpression,
+    EscapeSequence,
+    EscapeInterpolation,
+    TypeConversion,
+    Integer,
+    Float,
+    Identifier,
+    KeywordIdentifier,
+    True,
+    False,
+    None,
+    Await,
+    Comment,
+    ParenthesizedExpression,
+    PositionalSeparator,
+    KeywordSeparator,
+    LineContinuation,
+    Spaces,
+    Directory,
+    ERROR,
+}
+impl Type {
+    pub fn from_str(t: &str) -> Option<Type> {
+        Some(match t {
+            "end" => Type::End,
+            "import" => Type::Import,
+            "." => Type::Dot,
+            "from" => Type::From,
+            "__future__" => Type::Future,
+            "(" => Type::LParen,
+            ")" => Type::RParen,
+            "," => Type::Comma,
+            "as" => Type::As,
+            "*" => Type::Star,
+            "print" => Type::Print,
+            ">>" => Type::GtGt,
+            "assert" => Type::Assert,
+            ":=" => Type::ColonEq,
+            "return" => Type::Return,
+            "del" => Type::Del,
+            "raise" => Type::Raise,
+            "pass" => Type::Pass,
+            "break" => Type::Break,
+            "continue" => Type::Continue,
+            "if" => Type::If,
+            ":" => Type::Colon,
+            "elif" => Type::Elif,
+            "else" => Type::Else,
+            "match" => Type::Match,
+            "case" => Type::Case,
+            "async" => Type::Async,
+            "for" => Type::For,
+            "in" => Type::In,
+            "while" => Type::While,
+            "try" => Type::Try,
+            "except" => Type::Except,
+            "except*" => Type::ExceptStar,
+            "finally" => Type::Finally,
+            "with" => Type::With,
+            "def" => Type::Def,
+            "->" => Type::DashGt,
+            "**" => Type::StarStar,
+            "global" => Type::Global,
+            "nonlocal" => Type::Nonlocal,
+            "exec" => Type::Exec,
+            "class" => Type::Class,
+            "[" => Type::LBracket,
+            "]" => Type::RBracket,
+            "@" => Type::At,
+            "-" => Type::Dash,
+            "_" => Type::Underscore,
+            "|" => Type::Pipe,
+            "{" => Type::LBrace,
+            "}" => Type::RBrace,
+            "+" => Type::Plus,
+            "not" => Type::Not,
+            "and" => Type::And,
+            "or" => Type::Or,
+            "/" => Type::Slash,
+            "%" => Type::Percent,
+            "//" => Type::SlashSlash,
+            "&" => Type::Amp,
+            "^" => Type::Caret,
+            "<<" => Type::LtLt,
+            "~" => Type::Tilde,
+            "<" => Type::LT,
+            "<=" => Type::LTEq,
+            "==" => Type::EqEq,
+            "!=" => Type::BangEq,
+            ">=" => Type::GTEq,
+            ">" => Type::GT,
+            "<>" => Type::LTGT,
+            "is" => Type::Is,
+            "is not" => Type::IsNot,
+            "not in" => Type::NotIn,
+            "+=" => Type::PlusEq,
+            "-=" => Type::DashEq,
+            "*=" => Type::StarEq,
+            "/=" => Type::SlashEq,
+            "@=" => Type::AtEq,
+            "//=" => Type::SlashSlashEq,
+            "%=" => Type::PercentEq,
+            "**=" => Type::StarStarEq,
+            ">>=" => Type::GtGtEq,
+            "<<=" => Type::LtLtEq,
+            "&=" => Type::AmpEq,
+            "^=" => Type::CaretEq,
+            "|=" => Type::PipeEq,
+            ";" => Type::SemiColon,
+            "=" => Type::Eq,
+            "\"" => Type::DQuote,
+            "`" => Type::BQuote,
+            "module" => Type::Module,
+            "import_statement" => Type::ImportStatement,
+            "import_prefix" => Type::ImportPrefix,
+            "relative_import" => Type::RelativeImport,
+            "future_import_statement" => Type::FutureImportStatement,
+            "import_from_statement" => Type::ImportFromStatement,
+            "aliased_import" => Type::AliasedImport,
+            "wildcard_import" => Type::WildcardImport,
+            "print_statement" => Type::PrintStatement,
+            "chevron" => Type::Chevron,
+            "assert_statement" => Type::AssertStatement,
+            "expression_statement" => Type::ExpressionStatement,
+            "named_expression" => Type::NamedExpression,
+            "return_statement" => Type::ReturnStatement,
+            "delete_statement" => Type::DeleteStatement,
+            "raise_statement" => Type::RaiseStatement,
+            "pass_statement" => Type::PassStatement,
+            "break_statement" => Type::BreakStatement,
+            "continue_statement" => Type::ContinueStatement,
+            "if_statement" => Type::IfStatement,
+            "elif_clause" => Type::ElifClause,
+            "else_clause" => Type::ElseClause,
+            "match_statement" => Type::MatchStatement,
+            "case_clause" => Type::CaseClause,
+            "case_pattern" => Type::CasePattern,
+            "for_statement" => Type::ForStatement,
+            "while_statement" => Type::WhileStatement,
+            "try_statement" => Type::TryStatement,
+            "except_clause" => Type::ExceptClause,
+            "except_group_clause" => Type::ExceptGroupClause,
+            "finally_clause" => Type::FinallyClause,
+            "with_statement" => Type::WithStatement,
+            "with_clause" => Type::WithClause,
+            "with_item" => Type::WithItem,
+            "function_definition" => Type::FunctionDefinition,
+            "parameters" => Type::Parameters,
+            "lambda_parameters" => Type::LambdaParameters,
+            "list_splat" => Type::ListSplat,
+            "dictionary_splat" => Type::DictionarySplat,
+            "global_statement" => Type::GlobalStatement,
+            "nonlocal_statement" => Type::NonlocalStatement,
+            "exec_statement" => Type::ExecStatement,
+            "type_alias_statement" => Type::TypeAliasStatement,
+            "class_definition" => Type::ClassDefinition,
+            "type_parameter" => Type::TypeParameter,
+            "parenthesized_list_splat" => Type::ParenthesizedListSplat,
+            "argument_list" => Type::ArgumentList,
+            "decorated_definition" => Type::DecoratedDefinition,
+            "decorator" => Type::Decorator,
+            "block" => Type::Block,
+            "expression_list" => Type::ExpressionList,
+            "dotted_name" => Type::DottedName,
+            "as_pattern" => Type::AsPattern,
+            "as_pattern_target" => Type::AsPatternTarget,
+            "class_pattern" => Type::ClassPattern,
+            "complex_pattern" => Type::ComplexPattern,
+            "dict_pattern" => Type::DictPattern,
+            "keyword_pattern" => Type::KeywordPattern,
+            "list_pattern" => Type::ListPattern,
+            "splat_pattern" => Type::SplatPattern,
+            "tuple_pattern" => Type::TuplePattern,
+            "union_pattern" => Type::UnionPattern,
+            "default_parameter" => Type::DefaultParameter,
+            "typed_default_parameter" => Type::TypedDefaultParameter,
+            "list_splat_pattern" => Type::ListSplatPattern,
+            "dictionary_splat_pattern" => Type::DictionarySplatPattern,
+            "not_operator" => Type::NotOperator,
+            "boolean_operator" => Type::BooleanOperator,
+            "binary_operator" => Type::BinaryOperator,
+            "unary_operator" => Type::UnaryOperator,
+            "comparison_operator" => Type::ComparisonOperator,
+            "lambda" => Type::Lambda,
+            "assignment" => Type::Assignment,
+            "augmented_assignment" => Type::AugmentedAssignment,
+            "pattern_list" => Type::PatternList,
+            "yield" => Type::Yield,
+            "attribute" => Type::Attribute,
+            "subscript" => Type::Subscript,
+            "slice" => Type::Slice,
+            "ellipsis" => Type::Ellipsis,
+            "call" => Type::Call,
+            "typed_parameter" => Type::TypedParameter,
+            "type" => Type::Type,
+            "keyword_argument" => Type::KeywordArgument,
+            "list" => Type::List,
+            "set" => Type::Set,
+            "tuple" => Type::Tuple,
+            "dictionary" => Type::Dictionary,
+            "pair" => Type::Pair,
+            "list_comprehension" => Type::ListComprehension,
+            "dictionary_comprehension" => Type::DictionaryComprehension,
+            "set_comprehension" => Type::SetComprehension,
+            "generator_expression" => Type::GeneratorExpression,
+            "for_in_clause" => Type::ForInClause,
+            "if_clause" => Type::IfClause,
+            "conditional_expression" => Type::ConditionalExpression,
+            "concatenated_string" => Type::ConcatenatedString,
+            "string" => Type::String,
+            "string_start" => Type::StringStart,
+            "string_content" => Type::StringContent,
+            "string_end" => Type::StringEnd,
+            "interpolation" => Type::Interpolation,
+            "format_specifier" => Type::FormatSpecifier,
+            "format_expression" => Type::FormatExpression,
+            "escape_sequence" => Type::EscapeSequence,
+            "escape_interpolation" => Type::EscapeInterpolation,
+            "type_conversion" => Type::TypeConversion,
+            "integer" => Type::Integer,
+            "float" => Type::Float,
+            "identifier" => Type::Identifier,
+            "keyword_identifier" => Type::KeywordIdentifier,
+            "true" => Type::True,
+            "false" => Type::False,
+            "none" => Type::None,
+            "await" => Type::Await,
+            "comment" => Type::Comment,
+            "parenthesized_expression" => Type::ParenthesizedExpression,
+            "positional_separator" => Type::PositionalSeparator,
+            "keyword_separator" => Type::KeywordSeparator,
+            "line_continuation" => Type::LineContinuation,
+            "Spaces" => Type::Spaces,
+            "Directory" => Type::Directory,
+            "ERROR" => Type::ERROR,
+            _ => return None,
+        })
+    }
+    /// tokens can share their kind with a named node eg. the `import` keyword and `import(...)`,
+    /// use this one for anonymous nodes.
+    pub fn from_str_anonymous(t: &str) -> Option<Type> {
+        Some(match t {
+            "type" => Type::TS0,
+            "lambda" => Type::TS1,
+            "yield" => Type::TS2,
+            "await" => Type::TS3,
+            t => return Self::from_str(t),
+        })
+    }
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Type::End => "end",
+            Type::Import => "import",
+            Type::Dot => ".",
+            Type::From => "from",
+            Type::Future => "__future__",
+            Type::LParen => "(",
+            Type::RParen => ")",
+            Type::Comma => ",",
+            Type::As => "as",
+            Type::Star => "*",
+            Type::Print => "print",
+            Type::GtGt => ">>",
+            Type::Assert => "assert",
+            Type::ColonEq => ":=",
+            Type::Return => "return",
+            Type::Del => "del",
+            Type::Raise => "raise",
+            Type::Pass => "pass",
+            Type::Break => "break",
+            Type::Continue => "continue",
+            Type::If => "if",
+            Type::Colon => ":",
+            Type::Elif => "elif",
+            Type::Else => "else",
+            Type::Match => "match",
+            Type::Case => "case",
+            Type::Async => "async",
+            Type::For => "for",
+            Type::In => "in",
+            Type::While => "while",
+            Type::Try => "try",
+            Type::Except => "except",
+            Type::ExceptStar => "except*",
+            Type::Finally => "finally",
+            Type::With => "with",
+            Type::Def => "def",
+            Type::DashGt => "->",
+            Type::StarStar => "**",
+            Type::Global => "global",
+            Type::Nonlocal => "nonlocal",
+            Type::Exec => "exec",
+            Type::TS0 => "type",
+            Type::Class => "class",
+            Type::LBracket => "[",
+            Type::RBracket => "]",
+            Type::At => "@",
+            Type::Dash => "-",
+            Type::Underscore => "_",
+            Type::Pipe => "|",
+            Type::LBrace => "{",
+            Type::RBrace => "}",
+            Type::Plus => "+",
+            Type::Not => "not",
+            Type::And => "and",
+            Type::Or => "or",
+            Type::Slash => "/",
+            Type::Percent => "%",
+            Type::SlashSlash => "//",
+            Type::Amp => "&",
+            Type::Caret => "^",
+            Type::LtLt => "<<",
+            Type::Tilde => "~",
+            Type::LT => "<",
+            Type::LTEq => "<=",
+            Type::EqEq => "==",
+            Type::BangEq => "!=",
+            Type::GTEq => ">=",
+            Type::GT => ">",
+            Type::LTGT => "<>",
+            Type::Is => "is",
+            Type::IsNot => "is not",
+            Type::NotIn => "not in",
+            Type::TS1 => "lambda",
+            Type::PlusEq => "+=",
+            Type::DashEq => "-=",
+            Type::StarEq => "*=",
+            Type::SlashEq => "/=",
+            Type::AtEq => "@=",
+            Type::SlashSlashEq => "//=",
+            Type::PercentEq => "%=",
+            Type::StarStarEq => "**=",
+            Type::GtGtEq => ">>=",
+            Type::LtLtEq => "<<=",
+            Type::AmpEq => "&=",
+            Type::CaretEq => "^=",
+            Type::PipeEq => "|=",
+            Type::TS2 => "yield",
+            Type::SemiColon => ";",
+            Type::Eq => "=",
+            Type::DQuote => "\"",
+            Type::BQuote => "`",
+            Type::TS3 => "await",
+            Type::Module => "module",
+            Type::ImportStatement => "import_statement",
+            Type::ImportPrefix => "import_prefix",
+            Type::RelativeImport => "relative_import",
+            Type::FutureImportStatement => "future_import_statement",
+            Type::ImportFromStatement => "import_from_statement",
+            Type::AliasedImport => "aliased_import",
+            Type::WildcardImport => "wildcard_import",
+            Type::PrintStatement => "print_statement",
+            Type::Chevron => "chevron",
+            Type::AssertStatement => "assert_statement",
+            Type::ExpressionStatement => "expression_statement",
+            Type::NamedExpression => "named_expression",
+            Type::ReturnStatement => "return_statement",
+            Type::DeleteStatement => "delete_statement",
+            Type::RaiseStatement => "raise_statement",
+            Type::PassStatement => "pass_statement",
+            Type::BreakStatement => "break_statement",
+            Type::ContinueStatement => "continue_statement",
+            Type::IfStatement => "if_statement",
+            Type::ElifClause => "elif_clause",
+            Type::ElseClause => "else_clause",
+            Type::MatchStatement => "match_statement",
+            Type::CaseClause => "case_clause",
+            Type::CasePattern => "case_pattern",
+            Type::ForStatement => "for_statement",
+            Type::WhileStatement => "while_statement",
+            Type::TryStatement => "try_statement",
+            Type::ExceptClause => "except_clause",
+            Type::ExceptGroupClause => "except_group_clause",
+            Type::FinallyClause => "finally_clause",
+            Type::WithStatement => "with_statement",
+            Type::WithClause => "with_clause",
+            Type::WithItem => "with_item",
+            Type::FunctionDefinition => "function_definition",
+            Type::Parameters => "parameters",
+            Type::LambdaParameters => "lambda_parameters",
+            Type::ListSplat => "list_splat",
+            Type::DictionarySplat => "dictionary_splat",
+            Type::GlobalStatement => "global_statement",
+            Type::NonlocalStatement => "nonlocal_statement",
+            Type::ExecStatement => "exec_statement",
+            Type::TypeAliasStatement => "type_alias_statement",
+            Type::ClassDefinition => "class_definition",
+            Type::TypeParameter => "type_parameter",
+            Type::ParenthesizedListSplat => "parenthesized_list_splat",
+            Type::ArgumentList => "argument_list",
+            Type::DecoratedDefinition => "decorated_definition",
+            Type::Decorator => "decorator",
+            Type::Block => "block",
+            Type::ExpressionList => "expression_list",
+            Type::DottedName => "dotted_name",
+            Type::AsPattern => "as_pattern",
+            Type::AsPatternTarget => "as_pattern_target",
+            Type::ClassPattern => "class_pattern",
+            Type::ComplexPattern => "complex_pattern",
+            Type::DictPattern => "dict_pattern",
+            Type::KeywordPattern => "keyword_pattern",
+            Type::ListPattern => "list_pattern",
+            Type::SplatPattern => "splat_pattern",
+            Type::TuplePattern => "tuple_pattern",
+            Type::UnionPattern => "union_pattern",
+            Type::DefaultParameter => "default_parameter",
+            Type::TypedDefaultParameter => "typed_default_parameter",
+            Type::ListSplatPattern => "list_splat_pattern",
+            Type::DictionarySplatPattern => "dictionary_splat_pattern",
+            Type::NotOperator => "not_operator",
+            Type::BooleanOperator => "boolean_operator",
+            Type::BinaryOperator => "binary_operator",
+            Type::UnaryOperator => "unary_operator",
+            Type::ComparisonOperator => "comparison_operator",
+            Type::Lambda => "lambda",
+            Type::Assignment => "assignment",
+            Type::AugmentedAssignment => "augmented_assignment",
+            Type::PatternList => "pattern_list",
+            Type::Yield => "yield",
+            Type::Attribute => "attribute",
+            Type::Subscript => "subscript",
+            Type::Slice => "slice",
+            Type::Ellipsis => "ellipsis",
+            Type::Call => "call",
+            Type::TypedParameter => "typed_parameter",
+            Type::Type => "type",
+            Type::KeywordArgument => "keyword_argument",
+            Type::List => "list",
+            Type::Set => "set",
+            Type::Tuple => "tuple",
+            Type::Dictionary => "dictionary",
+            Type::Pair => "pair",
+            Type::ListComprehension => "list_comprehension",
+            Type::DictionaryComprehension => "dictionary_comprehension",
+            Type::SetComprehension => "set_comprehension",
+            Type::GeneratorExpression => "generator_expression",
+            Type::ForInClause => "for_in_clause",
+            Type::IfClause => "if_clause",
+            Type::ConditionalExpression => "conditional_expression",
+            Type::ConcatenatedString => "concatenated_string",
+            Type::String => "string",
+            Type::StringStart => "string_start",
+            Type::StringContent => "string_content",
+            Type::StringEnd => "string_end",
+            Type::Interpolation => "interpolation",
+            Type::FormatSpecifier => "format_specifier",
+            Type::FormatExpression => "format_expression",
+            Type::EscapeSequence => "escape_sequence",
+            Type::EscapeInterpolation => "escape_interpolation",
+            Type::TypeConversion => "type_conversion",
+            Type::Integer => "integer",
+            Type::Float => "float",
+            Type::Identifier => "identifier",
+            Type::KeywordIdentifier => "keyword_identifier",
+            Type::True => "true",
+            Type::False => "false",
+            Type::None => "none",
+            Type::Await => "await",
+            Type::Comment => "comment",
+            Type::ParenthesizedExpression => "parenthesized_expression",
+            Type::PositionalSeparator => "positional_separator",
+            Type::KeywordSeparator => "keyword_separator",
+            Type::LineContinuation => "line_continuation",
+            Type::Spaces => "Spaces",
+            Type::Directory => "Directory",
+            Type::ERROR => "ERROR",
+        }
+    }
+}
+
+const S_T_L: &'static [Type] = &[
+    Type::End,
+    Type::Import,
+    Type::Dot,
+    Type::From,
+    Type::Future,
+    Type::LParen,
+    Type::RParen,
+    Type::Comma,
+    Type::As,
+    Type::Star,
+    Type::Print,
+    Type::GtGt,
+    Type::Assert,
+    Type::ColonEq,
+    Type::Return,
+    Type::Del,
+    Type::Raise,
+    Type::Pass,
+    Type::Break,
+    Type::Continue,
+    Type::If,
+    Type::Colon,
+    Type::Elif,
+    Type::Else,
+    Type::Match,
+    Type::Case,
+    Type::Async,
+    Type::For,
+    Type::In,
+    Type::While,
+    Type::Try,
+    Type::Except,
+    Type::ExceptStar,
+    Type::Finally,
+    Type::With,
+    Type::Def,
+    Type::DashGt,
+    Type::StarStar,
+    Type::Global,
+    Type::Nonlocal,
+    Type::Exec,
+    Type::TS0,
+    Type::Class,
+    Type::LBracket,
+    Type::RBracket,
+    Type::At,
+    Type::Dash,
+    Type::Underscore,
+    Type::Pipe,
+    Type::LBrace,
+    Type::RBrace,
+    Type::Plus,
+    Type::Not,
+    Type::And,
+    Type::Or,
+    Type::Slash,
+    Type::Percent,
+    Type::SlashSlash,
+    Type::Amp,
+    Type::Caret,
+    Type::LtLt,
+    Type::Tilde,
+    Type::LT,
+    Type::LTEq,
+    Type::EqEq,
+    Type::BangEq,
+    Type::GTEq,
+    Type::GT,
+    Type::LTGT,
+    Type::Is,
+    Type::IsNot,
+    Type::NotIn,
+    Type::TS1,
+    Type::PlusEq,
+    Type::DashEq,
+    Type::StarEq,
+    Type::SlashEq,
+    Type::AtEq,
+    Type::SlashSlashEq,
+    Type::PercentEq,
+    Type::StarStarEq,
+    Type::GtGtEq,
+    Type::LtLtEq,
+    Type::AmpEq,
+    Type::CaretEq,
+    Type::PipeEq,
+    Type::TS2,
+    Type::SemiColon,
+    Type::Eq,
+    Type::DQuote,
+    Type::BQuote,
+    Type::TS3,
+    Type::Module,
+    Type::ImportStatement,
+    Type::ImportPrefix,
+    Type::RelativeImport,
+    Type::FutureImportStatement,
+    Type::ImportFromStatement,
+    Type::AliasedImport,
+    Type::WildcardImport,
+    Type::PrintStatement,
+    Type::Chevron,
+    Type::AssertStatement,
+    Type::ExpressionStatement,
+    Type::NamedExpression,
+    Type::ReturnStatement,
+    Type::DeleteStatement,
+    Type::RaiseStatement,
+    Type::PassStatement,
+    Type::BreakStatement,
+    Type::ContinueStatement,
+    Type::IfStatement,
+    Type::ElifClause,
+    Type::ElseClause,
+    Type::MatchStatement,
+    Type::CaseClause,
+    Type::CasePattern,
+    Type::ForStatement,
+    Type::WhileStatement,
+    Type::TryStatement,
+    Type::ExceptClause,
+    Type::ExceptGroupClause,
+    Type::FinallyClause,
+    Type::WithStatement,
+    Type::WithClause,
+    Type::WithItem,
+    Type::FunctionDefinition,
+    Type::Parameters,
+    Type::LambdaParameters,
+    Type::ListSplat,
+    Type::DictionarySplat,
+    Type::GlobalStatement,
+    Type::NonlocalStatement,
+    Type::ExecStatement,
+    Type::TypeAliasStatement,
+    Type::ClassDefinition,
+    Type::TypeParameter,
+    Type::ParenthesizedListSplat,
+    Type::ArgumentList,
+    Type::DecoratedDefinition,
+    Type::Decorator,
+    Type::Block,
+    Type::ExpressionList,
+    Type::DottedName,
+    Type::AsPattern,
+    Type::AsPatternTarget,
+    Type::ClassPattern,
+    Type::ComplexPattern,
+    Type::DictPattern,
+    Type::KeywordPattern,
+    Type::ListPattern,
+    Type::SplatPattern,
+    Type::TuplePattern,
+    Type::UnionPattern,
+    Type::DefaultParameter,
+    Type::TypedDefaultParameter,
+    Type::ListSplatPattern,
+    Type::DictionarySplatPattern,
+    Type::NotOperator,
+    Type::BooleanOperator,
+    Type::BinaryOperator,
+    Type::UnaryOperator,
+    Type::ComparisonOperator,
+    Type::Lambda,
+    Type::Assignment,
+    Type::AugmentedAssignment,
+    Type::PatternList,
+    Type::Yield,
+    Type::Attribute,
+    Type::Subscript,
+    Type::Slice,
+    Type::Ellipsis,
+    Type::Call,
+    Type::TypedParameter,
+    Type::Type,
+    Type::KeywordArgument,
+    Type::List,
+    Type::Set,
+    Type::Tuple,
+    Type::Dictionary,
+    Type::Pair,
+    Type::ListComprehension,
+    Type::DictionaryComprehension,
+    Type::SetComprehension,
+    Type::GeneratorExpression,
+    Type::ForInClause,
+    Type::IfClause,
+    Type::ConditionalExpression,
+    Type::ConcatenatedString,
+    Type::String,
+    Type::StringStart,
+    Type::StringContent,
+    Type::StringEnd,
+    Type::Interpolation,
+    Type::FormatSpecifier,
+    Type::FormatExpression,
+    Type::EscapeSequence,
+    Type::EscapeInterpolation,
+    Type::TypeConversion,
+    Type::Integer,
+    Type::Float,
+    Type::Identifier,
+    Type::KeywordIdentifier,
+    Type::True,
+    Type::False,
+    Type::None,
+    Type::Await,
+    Type::Comment,
+    Type::ParenthesizedExpression,
+    Type::PositionalSeparator,
+    Type::KeywordSeparator,
+    Type::LineContinuation,
+    Type::Spaces,
+    Type::Directory,
+    Type::ERROR,
+];
+
+static ANY_T_L: [AnyType; S_T_L.len()] = hyper_ast::types::any_types(S_T_L);
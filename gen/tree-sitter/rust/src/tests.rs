@@ -0,0 +1,66 @@
+use crate::types::{TStore, Type};
+
+type RustTreeGen<'store, 'cache> = crate::legion::RustTreeGen<'store, 'cache, TStore>;
+type SimpleStores = hyper_ast::store::SimpleStores<TStore>;
+
+#[test]
+fn rust_simple_test() {
+    let text = {
+        let source_code1 = r#"
+use std::collections::HashMap;
+
+/// counts words
+#[derive(Default)]
+pub struct Counter<'a> {
+    words: HashMap<&'a str, usize>,
+}
+
+impl<'a> Counter<'a> {
+    pub fn add(&mut self, text: &'a str) -> &mut Self {
+        for w in text.split_whitespace() {
+            *self.words.entry(w).or_insert(0) += 1;
+        }
+        self
+    }
+
+    fn get(&self, w: &str) -> usize {
+        match self.words.get(w) {
+            Some(x) if *x > 0 => *x,
+            _ => 0,
+        }
+    }
+}
+
+macro_rules! count {
+    ($($t:expr),*) => { Counter::default()$(.add($t))* };
+}
+"#;
+        source_code1.as_bytes()
+    };
+    let tree = match crate::legion::tree_sitter_parse(text) {
+        Ok(t) => t,
+        Err(t) => panic!("{}", t.root_node().to_sexp()),
+    };
+    println!("{:#?}", tree.root_node().to_sexp());
+    let mut stores = SimpleStores::default();
+    let mut md_cache = Default::default();
+    let mut tree_gen = RustTreeGen::new(&mut stores, &mut md_cache);
+    let x = tree_gen.generate_file(b"", text, tree.walk().into()).local;
+    println!(
+        "{}",
+        hyper_ast::nodes::SyntaxSerializer::new(&stores, x.compressed_node)
+    );
+    let serialized = hyper_ast::nodes::TextSerializer::new(&stores, x.compressed_node).to_string();
+    assert_eq!(std::str::from_utf8(text).unwrap(), serialized);
+}
+
+#[test]
+fn kinds_round_trip() {
+    assert_eq!(Type::from_str("block"), Some(Type::Block));
+    assert_eq!(Type::from_str_anonymous("block"), Some(Type::TS0));
+    assert_eq!(Type::from_str("self"), Some(Type::SelfKw));
+    assert_eq!(Type::from_str_anonymous("macro_rules!"), Some(Type::MacroRulesBang));
+    for k in ["source_file", "function_item", "ERROR", "::", "lifetime"] {
+        assert_eq!(Type::from_str(k).unwrap().to_str(), k);
+    }
+}
@@ -0,0 +1,1605 @@
+use std::fmt::Display;
+
+use hyper_ast::{
+    store::defaults::NodeIdentifier,
+    types::{
+        AnyType, HyperType, Lang, LangRef, LangWrapper, NodeId, TypeStore, TypeTrait, TypedNodeId,
+    },
+};
+
+#[cfg(feature = "legion")]
+mod legion_impls {
+    use super::*;
+
+    use crate::TNode;
+
+    impl<'a> TNode<'a> {
+        /// kind ids are specific to each version of the grammar,
+        /// so types are obtained from the kind names.
+        pub fn obtain_type(&self) -> Type {
+            let k = self.0.kind();
+            let t = if self.0.is_named() {
+                Type::from_str(k)
+            } else {
+                Type::from_str_anonymous(k)
+            };
+            t.unwrap_or_else(|| {
+                log::warn!("unknown rust kind {:?}", k);
+                Type::ERROR
+            })
+        }
+    }
+
+    use hyper_ast::{store::nodes::legion::HashedNodeRef, types::TypeIndex};
+
+    impl<'a, TS: RustEnabledTypeStore<HashedNodeRef<'a, Type>>> From<TS> for Single {
+        fn from(value: TS) -> Self {
+            Self {
+                mask: TS::MASK,
+                lang: TS::LANG,
+            }
+        }
+    }
+
+    impl<'a> TypeStore<HashedNodeRef<'a, TIdN<NodeIdentifier>>> for TStore {
+        type Ty = Type;
+        const MASK: TypeInternalSize = 0b1000_0000_0000_0000;
+        fn resolve_type(&self, n: &HashedNodeRef<'a, TIdN<NodeIdentifier>>) -> Self::Ty {
+            n.get_component::<Type>().unwrap().clone()
+        }
+
+        fn resolve_lang(
+            &self,
+            n: &HashedNodeRef<'a, TIdN<NodeIdentifier>>,
+        ) -> hyper_ast::types::LangWrapper<Self::Ty> {
+            From::<&'static (dyn LangRef<Type>)>::from(&Rust)
+        }
+
+        type Marshaled = TypeIndex;
+
+        fn marshal_type(&self, n: &HashedNodeRef<'a, TIdN<NodeIdentifier>>) -> Self::Marshaled {
+            TypeIndex {
+                lang: LangRef::<Type>::name(&Rust),
+                ty: *n.get_component::<Type>().unwrap() as u16,
+            }
+        }
+    }
+    impl<'a> RustEnabledTypeStore<HashedNodeRef<'a, TIdN<NodeIdentifier>>> for TStore {
+        const LANG: TypeInternalSize = Self::Rust as u16;
+
+        fn _intern(l: u16, t: u16) -> Self::Ty {
+            todo!()
+        }
+        fn intern(&self, t: Type) -> Self::Ty {
+            t
+        }
+
+        fn resolve(&self, t: Self::Ty) -> Type {
+            t
+        }
+    }
+    impl<'a> TypeStore<HashedNodeRef<'a, NodeIdentifier>> for TStore {
+        type Ty = AnyType;
+        const MASK: TypeInternalSize = 0b1000_0000_0000_0000;
+        fn resolve_type(&self, n: &HashedNodeRef<'a, NodeIdentifier>) -> Self::Ty {
+            From::<&'static (dyn HyperType)>::from(LangRef::<Type>::make(
+                &Rust,
+                *n.get_component::<Type>().unwrap() as u16,
+            ))
+        }
+
+        fn resolve_lang(
+            &self,
+            n: &HashedNodeRef<'a, NodeIdentifier>,
+        ) -> hyper_ast::types::LangWrapper<Self::Ty> {
+            From::<&'static (dyn LangRef<AnyType>)>::from(&Rust)
+        }
+
+        type Marshaled = TypeIndex;
+
+        fn marshal_type(&self, n: &HashedNodeRef<'a, NodeIdentifier>) -> Self::Marshaled {
+            TypeIndex {
+                lang: LangRef::<Type>::name(&Rust),
+                ty: *n.get_component::<Type>().unwrap() as u16,
+            }
+        }
+    }
+
+    impl hyper_ast::store::nodes::legion::persist::Persist for Type {
+        fn save(
+            &self,
+            w: &mut dyn std::io::Write,
+            _: &hyper_ast::store::nodes::legion::persist::SavedIds,
+        ) -> std::io::Result<()> {
+            hyper_ast::store::persist::write_u16(w, *self as u16)
+        }
+
+        fn load(
+            r: &mut dyn std::io::Read,
+            _: &hyper_ast::store::nodes::legion::persist::LoadedIds,
+        ) -> std::io::Result<Self> {
+            let t = hyper_ast::store::persist::read_u16(r)?;
+            if t < COUNT {
+                Ok(Type::resolve(t))
+            } else {
+                Err(hyper_ast::store::persist::invalid_data(format!(
+                    "invalid type {}",
+                    t
+                )))
+            }
+        }
+    }
+}
+
+pub trait RustEnabledTypeStore<T>: TypeStore<T> {
+    const LANG: u16;
+    fn intern(&self, t: Type) -> Self::Ty {
+        let t = t as u16;
+        Self::_intern(Self::LANG, t)
+    }
+    fn _intern(l: u16, t: u16) -> Self::Ty;
+    fn resolve(&self, t: Self::Ty) -> Type;
+}
+
+impl Type {
+    pub fn resolve(t: u16) -> Self {
+        assert!(t < COUNT);
+        unsafe { std::mem::transmute(t) }
+    }
+}
+
+pub struct Single {
+    mask: TypeInternalSize,
+    lang: TypeInternalSize,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TIdN<IdN>(IdN);
+
+impl<IdN: Clone + Eq + NodeId> NodeId for TIdN<IdN> {
+    type IdN = IdN;
+
+    fn as_id(&self) -> &Self::IdN {
+        &self.0
+    }
+
+    unsafe fn from_id(id: Self::IdN) -> Self {
+        Self(id)
+    }
+
+    unsafe fn from_ref_id(id: &Self::IdN) -> &Self {
+        std::mem::transmute(id)
+    }
+}
+
+impl<IdN: Clone + Eq + NodeId> TypedNodeId for TIdN<IdN> {
+    type Ty = Type;
+}
+
+#[repr(u8)]
+pub(crate) enum TStore {
+    Rust = 0,
+}
+
+impl Default for TStore {
+    fn default() -> Self {
+        Self::Rust
+    }
+}
+
+type TypeInternalSize = u16;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct T(TypeInternalSize);
+
+/// Rust, as parsed by tree-sitter-rust
+pub struct Rust;
+
+impl Rust {
+    const INST: Rust = Rust;
+}
+
+impl LangRef<AnyType> for Rust {
+    fn make(&self, t: u16) -> &'static AnyType {
+        &ANY_T_L[t as usize]
+    }
+    fn to_u16(&self, t: AnyType) -> u16 {
+        let t = t.as_any().downcast_ref::<Type>().unwrap();
+        *t as u16
+    }
+
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Rust>()
+    }
+}
+
+impl LangRef<Type> for Rust {
+    fn make(&self, t: u16) -> &'static Type {
+        &S_T_L[t as usize]
+    }
+    fn to_u16(&self, t: Type) -> u16 {
+        t as u16
+    }
+
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Rust>()
+    }
+}
+
+impl Lang<Type> for Rust {
+    fn make(t: u16) -> &'static Type {
+        Rust.make(t)
+    }
+    fn to_u16(t: Type) -> u16 {
+        Rust.to_u16(t)
+    }
+}
+
+impl HyperType for Type {
+    fn is_directory(&self) -> bool {
+        self == &Type::Directory
+    }
+
+    fn is_file(&self) -> bool {
+        self == &Type::SourceFile
+    }
+
+    fn is_spaces(&self) -> bool {
+        self == &Type::Spaces
+    }
+
+    fn is_syntax(&self) -> bool {
+        self == &Type::LBrace
+            || self == &Type::RBrace
+            || self == &Type::LParen
+            || self == &Type::RParen
+            || self == &Type::LBracket
+            || self == &Type::RBracket
+            || self == &Type::Comma
+            || self == &Type::SemiColon
+            || self == &Type::Colon
+            || self == &Type::ColonColon
+            || self == &Type::Dot
+            || self == &Type::DashGt
+            || self == &Type::EqGt
+            || self == &Type::Eq
+            || self == &Type::Hash
+            || self == &Type::SQuote
+            || self == &Type::DQuote
+    }
+
+    fn as_shared(&self) -> hyper_ast::types::Shared {
+        use hyper_ast::types::Shared;
+        match self {
+            Type::StructItem => Shared::TypeDeclaration,
+            Type::EnumItem => Shared::TypeDeclaration,
+            Type::UnionItem => Shared::TypeDeclaration,
+            Type::TraitItem => Shared::TypeDeclaration,
+            Type::LineComment => Shared::Comment,
+            Type::BlockComment => Shared::Comment,
+            Type::Identifier => Shared::Identifier,
+            _ => Shared::Other,
+        }
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn get_lang(&self) -> hyper_ast::types::LangWrapper<Self>
+    where
+        Self: Sized,
+    {
+        From::<&'static (dyn LangRef<Self>)>::from(&Rust)
+    }
+}
+impl TypeTrait for Type {
+    type Lang = Rust;
+
+    fn is_fork(&self) -> bool {
+        match self {
+            Self::IfExpression => true,
+            Self::MatchArm => true,
+            Self::WhileExpression => true,
+            Self::LoopExpression => true,
+            Self::ForExpression => true,
+            Self::TryExpression => true,
+            _ => false,
+        }
+    }
+
+    fn is_literal(&self) -> bool {
+        match self {
+            Self::StringLiteral => true,
+            Self::RawStringLiteral => true,
+            Self::CharLiteral => true,
+            Self::BooleanLiteral => true,
+            Self::IntegerLiteral => true,
+            Self::FloatLiteral => true,
+            Self::NegativeLiteral => true,
+            _ => false,
+        }
+    }
+
+    fn is_primitive(&self) -> bool {
+        self == &Type::PrimitiveType
+    }
+
+    fn is_type_declaration(&self) -> bool {
+        self == &Type::StructItem
+            || self == &Type::EnumItem
+            || self == &Type::UnionItem
+            || self == &Type::TraitItem
+            || self == &Type::TypeItem
+    }
+
+    fn is_identifier(&self) -> bool {
+        match self {
+            Self::Identifier => true,
+            Self::TypeIdentifier => true,
+            Self::FieldIdentifier => true,
+            Self::ShorthandFieldIdentifier => true,
+            Self::ScopedIdentifier => true,
+            Self::ScopedTypeIdentifier => true,
+            _ => false,
+        }
+    }
+
+    fn is_instance_ref(&self) -> bool {
+        self == &Type::SelfKw
+    }
+
+    fn is_type_body(&self) -> bool {
+        self == &Type::FieldDeclarationList
+            || self == &Type::OrderedFieldDeclarationList
+            || self == &Type::EnumVariantList
+            || self == &Type::DeclarationList
+    }
+
+    fn is_value_member(&self) -> bool {
+        self == &Type::FieldDeclaration
+            || self == &Type::EnumVariant
+            || self == &Type::ConstItem
+            || self == &Type::StaticItem
+    }
+
+    fn is_executable_member(&self) -> bool {
+        self == &Type::FunctionItem || self == &Type::FunctionSignatureItem
+    }
+
+    fn is_statement(&self) -> bool {
+        self.is_declarative_statement()
+            || self.is_structural_statement()
+            || self.is_simple_statement()
+            || self.is_block_related()
+    }
+
+    fn is_declarative_statement(&self) -> bool {
+        self == &Type::LetDeclaration
+            || self == &Type::UseDeclaration
+            || self == &Type::ExternCrateDeclaration
+            || self == &Type::ForExpression
+            || self == &Type::MatchArm
+    }
+
+    fn is_structural_statement(&self) -> bool {
+        self == &Type::IfExpression
+            || self == &Type::WhileExpression
+            || self == &Type::LoopExpression
+            || self == &Type::MatchExpression
+    }
+
+    fn is_block_related(&self) -> bool {
+        self == &Type::Block || self == &Type::UnsafeBlock || self == &Type::AsyncBlock
+    }
+
+    fn is_simple_statement(&self) -> bool {
+        self == &Type::ExpressionStatement
+            || self == &Type::EmptyStatement
+            || self == &Type::ReturnExpression
+            || self == &Type::BreakExpression
+            || self == &Type::ContinueExpression
+            || self == &Type::YieldExpression
+    }
+
+    fn is_local_declare(&self) -> bool {
+        self == &Type::LetDeclaration || self == &Type::LetCondition
+    }
+
+    fn is_parameter(&self) -> bool {
+        self == &Type::Parameter
+            || self == &Type::SelfParameter
+            || self == &Type::VariadicParameter
+            || self == &Type::ConstParameter
+            || self == &Type::ConstrainedTypeParameter
+            || self == &Type::OptionalTypeParameter
+    }
+
+    fn is_parameter_list(&self) -> bool {
+        self == &Type::Parameters
+            || self == &Type::ClosureParameters
+            || self == &Type::TypeParameters
+    }
+
+    fn is_argument_list(&self) -> bool {
+        self == &Type::Arguments || self == &Type::TypeArguments
+    }
+
+    fn is_expression(&self) -> bool {
+        self == &Type::BinaryExpression
+            || self == &Type::UnaryExpression
+            || self == &Type::AssignmentExpression
+            || self == &Type::CompoundAssignmentExpr
+            || self == &Type::TypeCastExpression
+            || self == &Type::CallExpression
+            || self == &Type::MacroInvocation
+            || self == &Type::FieldExpression
+            || self == &Type::IndexExpression
+            || self == &Type::ReferenceExpression
+            || self == &Type::RangeExpression
+            || self == &Type::TryExpression
+            || self == &Type::AwaitExpression
+            || self == &Type::ClosureExpression
+            || self == &Type::StructExpression
+            || self == &Type::ArrayExpression
+            || self == &Type::TupleExpression
+            || self == &Type::ParenthesizedExpression
+            || self == &Type::IfExpression
+            || self == &Type::MatchExpression
+    }
+
+    fn is_comment(&self) -> bool {
+        self == &Type::LineComment || self == &Type::BlockComment
+    }
+}
+
+const COUNT: u16 = 279;
+impl Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.to_str())
+    }
+}
+
+#[repr(u16)]
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum Type {
+    End,
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    LBrace,
+    RBrace,
+    Dollar,
+    Star,
+    Plus,
+    QMark,
+    TS0,
+    Expr,
+    Ident,
+    Item,
+    TS1,
+    Literal,
+    Meta,
+    Pat,
+    Path,
+    Stmt,
+    Tt,
+    Ty,
+    Vis,
+    U8,
+    I8,
+    U16,
+    I16,
+    U32,
+    I32,
+    U64,
+    I64,
+    U128,
+    I128,
+    Isize,
+    Usize,
+    F32,
+    F64,
+    Bool,
+    Str,
+    Char,
+    Dash,
+    Slash,
+    Percent,
+    Caret,
+    Bang,
+    Amp,
+    Pipe,
+    AmpAmp,
+    PipePipe,
+    LtLt,
+    GtGt,
+    PlusEq,
+    DashEq,
+    StarEq,
+    SlashEq,
+    PercentEq,
+    CaretEq,
+    AmpEq,
+    PipeEq,
+    LtLtEq,
+    GtGtEq,
+    Eq,
+    EqEq,
+    BangEq,
+    GT,
+    LT,
+    GTEq,
+    LTEq,
+    At,
+    Underscore,
+    Dot,
+    DotDot,
+    DotDotDot,
+    DotDotEq,
+    Comma,
+    SemiColon,
+    Colon,
+    ColonColon,
+    DashGt,
+    EqGt,
+    Hash,
+    SQuote,
+    As,
+    Async,
+    Await,
+    Break,
+    Const,
+    Continue,
+    Default,
+    Enum,
+    Fn,
+    For,
+    If,
+    Impl,
+    Let,
+    Loop,
+    Match,
+    Mod,
+    Pub,
+    Return,
+    Static,
+    Struct,
+    Trait,
+    Type,
+    Union,
+    Unsafe,
+    Use,
+    Where,
+    While,
+    MacroRulesBang,
+    Extern,
+    Ref,
+    Dyn,
+    In,
+    Else,
+    Move,
+    Mut,
+    Yield,
+    True,
+    False,
+    DQuote,
+    SourceFile,
+    EmptyStatement,
+    ExpressionStatement,
+    MacroDefinition,
+    MacroRule,
+    TokenTreePattern,
+    TokenBindingPattern,
+    TokenRepetitionPattern,
+    FragmentSpecifier,
+    TokenTree,
+    TokenRepetition,
+    AttributeItem,
+    InnerAttributeItem,
+    Attribute,
+    ModItem,
+    ForeignModItem,
+    DeclarationList,
+    StructItem,
+    UnionItem,
+    EnumItem,
+    EnumVariantList,
+    EnumVariant,
+    FieldDeclarationList,
+    FieldDeclaration,
+    OrderedFieldDeclarationList,
+    ExternCrateDeclaration,
+    ConstItem,
+    StaticItem,
+    TypeItem,
+    FunctionItem,
+    FunctionSignatureItem,
+    FunctionModifiers,
+    WhereClause,
+    WherePredicate,
+    ImplItem,
+    TraitItem,
+    AssociatedType,
+    TraitBounds,
+    HigherRankedTraitBound,
+    RemovedTraitBound,
+    TypeParameters,
+    ConstParameter,
+    ConstrainedTypeParameter,
+    OptionalTypeParameter,
+    LetDeclaration,
+    UseDeclaration,
+    ScopedUseList,
+    UseList,
+    UseAsClause,
+    UseWildcard,
+    Parameters,
+    SelfParameter,
+    VariadicParameter,
+    Parameter,
+    ExternModifier,
+    VisibilityModifier,
+    BracketedType,
+    QualifiedType,
+    Lifetime,
+    ArrayType,
+    ForLifetimes,
+    FunctionType,
+    TupleType,
+    UnitType,
+    GenericFunction,
+    GenericType,
+    GenericTypeWithTurbofish,
+    BoundedType,
+    TypeArguments,
+    TypeBinding,
+    ReferenceType,
+    PointerType,
+    EmptyType,
+    AbstractType,
+    DynamicType,
+    MutableSpecifier,
+    MacroInvocation,
+    ScopedIdentifier,
+    ScopedTypeIdentifier,
+    RangeExpression,
+    UnaryExpression,
+    TryExpression,
+    ReferenceExpression,
+    BinaryExpression,
+    AssignmentExpression,
+    CompoundAssignmentExpr,
+    TypeCastExpression,
+    ReturnExpression,
+    YieldExpression,
+    CallExpression,
+    Arguments,
+    ArrayExpression,
+    ParenthesizedExpression,
+    TupleExpression,
+    UnitExpression,
+    StructExpression,
+    FieldInitializerList,
+    ShorthandFieldInitializer,
+    FieldInitializer,
+    BaseFieldInitializer,
+    IfExpression,
+    LetCondition,
+    LetChain,
+    ElseClause,
+    MatchExpression,
+    MatchBlock,
+    MatchArm,
+    MatchPattern,
+    WhileExpression,
+    LoopExpression,
+    ForExpression,
+    ConstBlock,
+    ClosureExpression,
+    ClosureParameters,
+    LoopLabel,
+    BreakExpression,
+    ContinueExpression,
+    IndexExpression,
+    AwaitExpression,
+    FieldExpression,
+    UnsafeBlock,
+    AsyncBlock,
+    Block,
+    TuplePattern,
+    SlicePattern,
+    TupleStructPattern,
+    StructPattern,
+    FieldPattern,
+    RemainingFieldPattern,
+    MutPattern,
+    RangePattern,
+    RefPattern,
+    CapturedPattern,
+    ReferencePattern,
+    OrPattern,
+    NegativeLiteral,
+    StringLiteral,
+    RawStringLiteral,
+    CharLiteral,
+    BooleanLiteral,
+    IntegerLiteral,
+    FloatLiteral,
+    EscapeSequence,
+    LineComment,
+    BlockComment,
+    Identifier,
+    TypeIdentifier,
+    FieldIdentifier,
+    ShorthandFieldIdentifier,
+    PrimitiveType,
+    Metavariable,
+    SelfKw,
+    Super,
+    Crate,
+    Spaces,
+    Directory,
+    ERROR,
+}
+impl Type {
+    pub fn from_str(t: &str) -> Option<Type> {
+        Some(match t {
+            "end" => Type::End,
+            "(" => Type::LParen,
+            ")" => Type::RParen,
+            "[" => Type::LBracket,
+            "]" => Type::RBracket,
+            "{" => Type::LBrace,
+            "}" => Type::RBrace,
+            "$" => Type::Dollar,
+            "*" => Type::Star,
+            "+" => Type::Plus,
+            "?" => Type::QMark,
+            "expr" => Type::Expr,
+            "ident" => Type::Ident,
+            "item" => Type::Item,
+            "literal" => Type::Literal,
+            "meta" => Type::Meta,
+            "pat" => Type::Pat,
+            "path" => Type::Path,
+            "stmt" => Type::Stmt,
+            "tt" => Type::Tt,
+            "ty" => Type::Ty,
+            "vis" => Type::Vis,
+            "u8" => Type::U8,
+            "i8" => Type::I8,
+            "u16" => Type::U16,
+            "i16" => Type::I16,
+            "u32" => Type::U32,
+            "i32" => Type::I32,
+            "u64" => Type::U64,
+            "i64" => Type::I64,
+            "u128" => Type::U128,
+            "i128" => Type::I128,
+            "isize" => Type::Isize,
+            "usize" => Type::Usize,
+            "f32" => Type::F32,
+            "f64" => Type::F64,
+            "bool" => Type::Bool,
+            "str" => Type::Str,
+            "char" => Type::Char,
+            "-" => Type::Dash,
+            "/" => Type::Slash,
+            "%" => Type::Percent,
+            "^" => Type::Caret,
+            "!" => Type::Bang,
+            "&" => Type::Amp,
+            "|" => Type::Pipe,
+            "&&" => Type::AmpAmp,
+            "||" => Type::PipePipe,
+            "<<" => Type::LtLt,
+            ">>" => Type::GtGt,
+            "+=" => Type::PlusEq,
+            "-=" => Type::DashEq,
+            "*=" => Type::StarEq,
+            "/=" => Type::SlashEq,
+            "%=" => Type::PercentEq,
+            "^=" => Type::CaretEq,
+            "&=" => Type::AmpEq,
+            "|=" => Type::PipeEq,
+            "<<=" => Type::LtLtEq,
+            ">>=" => Type::GtGtEq,
+            "=" => Type::Eq,
+            "==" => Type::EqEq,
+            "!=" => Type::BangEq,
+            ">" => Type::GT,
+            "<" => Type::LT,
+            ">=" => Type::GTEq,
+            "<=" => Type::LTEq,
+            "@" => Type::At,
+            "_" => Type::Underscore,
+            "." => Type::Dot,
+            ".." => Type::DotDot,
+            "..." => Type::DotDotDot,
+            "..=" => Type::DotDotEq,
+            "," => Type::Comma,
+            ";" => Type::SemiColon,
+            ":" => Type::Colon,
+            "::" => Type::ColonColon,
+            "->" => Type::DashGt,
+            "=>" => Type::EqGt,
+            "#" => Type::Hash,
+            "'" => Type::SQuote,
+            "as" => Type::As,
+            "async" => Type::Async,
+            "await" => Type::Await,
+            "break" => Type::Break,
+            "const" => Type::Const,
+            "continue" => Type::Continue,
+            "default" => Type::Default,
+            "enum" => Type::Enum,
+            "fn" => Type::Fn,
+            "for" => Type::For,
+            "if" => Type::If,
+            "impl" => Type::Impl,
+            "let" => Type::Let,
+            "loop" => Type::Loop,
+            "match" => Type::Match,
+            "mod" => Type::Mod,
+            "pub" => Type::Pub,
+            "return" => Type::Return,
+            "static" => Type::Static,
+            "struct" => Type::Struct,
+            "trait" => Type::Trait,
+            "type" => Type::Type,
+            "union" => Type::Union,
+            "unsafe" => Type::Unsafe,
+            "use" => Type::Use,
+            "where" => Type::Where,
+            "while" => Type::While,
+            "macro_rules!" => Type::MacroRulesBang,
+            "extern" => Type::Extern,
+            "ref" => Type::Ref,
+            "dyn" => Type::Dyn,
+            "in" => Type::In,
+            "else" => Type::Else,
+            "move" => Type::Move,
+            "mut" => Type::Mut,
+            "yield" => Type::Yield,
+            "true" => Type::True,
+            "false" => Type::False,
+            "\"" => Type::DQuote,
+            "source_file" => Type::SourceFile,
+            "empty_statement" => Type::EmptyStatement,
+            "expression_statement" => Type::ExpressionStatement,
+            "macro_definition" => Type::MacroDefinition,
+            "macro_rule" => Type::MacroRule,
+            "token_tree_pattern" => Type::TokenTreePattern,
+            "token_binding_pattern" => Type::TokenBindingPattern,
+            "token_repetition_pattern" => Type::TokenRepetitionPattern,
+            "fragment_specifier" => Type::FragmentSpecifier,
+            "token_tree" => Type::TokenTree,
+            "token_repetition" => Type::TokenRepetition,
+            "attribute_item" => Type::AttributeItem,
+            "inner_attribute_item" => Type::InnerAttributeItem,
+            "attribute" => Type::Attribute,
+            "mod_item" => Type::ModItem,
+            "foreign_mod_item" => Type::ForeignModItem,
+            "declaration_list" => Type::DeclarationList,
+            "struct_item" => Type::StructItem,
+            "union_item" => Type::UnionItem,
+            "enum_item" => Type::EnumItem,
+            "enum_variant_list" => Type::EnumVariantList,
+            "enum_variant" => Type::EnumVariant,
+            "field_declaration_list" => Type::FieldDeclarationList,
+            "field_declaration" => Type::FieldDeclaration,
+            "ordered_field_declaration_list" => Type::OrderedFieldDeclarationList,
+            "extern_crate_declaration" => Type::ExternCrateDeclaration,
+            "const_item" => Type::ConstItem,
+            "static_item" => Type::StaticItem,
+            "type_item" => Type::TypeItem,
+            "function_item" => Type::FunctionItem,
+            "function_signature_item" => Type::FunctionSignatureItem,
+            "function_modifiers" => Type::FunctionModifiers,
+            "where_clause" => Type::WhereClause,
+            "where_predicate" => Type::WherePredicate,
+            "impl_item" => Type::ImplItem,
+            "trait_item" => Type::TraitItem,
+            "associated_type" => Type::AssociatedType,
+            "trait_bounds" => Type::TraitBounds,
+            "higher_ranked_trait_bound" => Type::HigherRankedTraitBound,
+            "removed_trait_bound" => Type::RemovedTraitBound,
+            "type_parameters" => Type::TypeParameters,
+            "const_parameter" => Type::ConstParameter,
+            "constrained_type_parameter" => Type::ConstrainedTypeParameter,
+            "optional_type_parameter" => Type::OptionalTypeParameter,
+            "let_declaration" => Type::LetDeclaration,
+            "use_declaration" => Type::UseDeclaration,
+            "scoped_use_list" => Type::ScopedUseList,
+            "use_list" => Type::UseList,
+            "use_as_clause" => Type::UseAsClause,
+            "use_wildcard" => Type::UseWildcard,
+            "parameters" => Type::Parameters,
+            "self_parameter" => Type::SelfParameter,
+            "variadic_parameter" => Type::VariadicParameter,
+            "parameter" => Type::Parameter,
+            "extern_modifier" => Type::ExternModifier,
+            "visibility_modifier" => Type::VisibilityModifier,
+            "bracketed_type" => Type::BracketedType,
+            "qualified_type" => Type::QualifiedType,
+            "lifetime" => Type::Lifetime,
+            "array_type" => Type::ArrayType,
+            "for_lifetimes" => Type::ForLifetimes,
+            "function_type" => Type::FunctionType,
+            "tuple_type" => Type::TupleType,
+            "unit_type" => Type::UnitType,
+            "generic_function" => Type::GenericFunction,
+            "generic_type" => Type::GenericType,
+            "generic_type_with_turbofish" => Type::GenericTypeWithTurbofish,
+            "bounded_type" => Type::BoundedType,
+            "type_arguments" => Type::TypeArguments,
+            "type_binding" => Type::TypeBinding,
+            "reference_type" => Type::ReferenceType,
+            "pointer_type" => Type::PointerType,
+            "empty_type" => Type::EmptyType,
+            "abstract_type" => Type::AbstractType,
+            "dynamic_type" => Type::DynamicType,
+            "mutable_specifier" => Type::MutableSpecifier,
+            "macro_invocation" => Type::MacroInvocation,
+            "scoped_identifier" => Type::ScopedIdentifier,
+            "scoped_type_identifier" => Type::ScopedTypeIdentifier,
+            "range_expression" => Type::RangeExpression,
+            "unary_expression" => Type::UnaryExpression,
+            "try_expression" => Type::TryExpression,
+            "reference_expression" => Type::ReferenceExpression,
+            "binary_expression" => Type::BinaryExpression,
+            "assignment_expression" => Type::AssignmentExpression,
+            "compound_assignment_expr" => Type::CompoundAssignmentExpr,
+            "type_cast_expression" => Type::TypeCastExpression,
+            "return_expression" => Type::ReturnExpression,
+            "yield_expression" => Type::YieldExpression,
+            "call_expression" => Type::CallExpression,
+            "arguments" => Type::Arguments,
+            "array_expression" => Type::ArrayExpression,
+            "parenthesized_expression" => Type::ParenthesizedExpression,
+            "tuple_expression" => Type::TupleExpression,
+            "unit_expression" => Type::UnitExpression,
+            "struct_expression" => Type::StructExpression,
+            "field_initializer_list" => Type::FieldInitializerList,
+            "shorthand_field_initializer" => Type::ShorthandFieldInitializer,
+            "field_initializer" => Type::FieldInitializer,
+            "base_field_initializer" => Type::BaseFieldInitializer,
+            "if_expression" => Type::IfExpression,
+            "let_condition" => Type::LetCondition,
+            "let_chain" => Type::LetChain,
+            "else_clause" => Type::ElseClause,
+            "match_expression" => Type::MatchExpression,
+            "match_block" => Type::MatchBlock,
+            "match_arm" => Type::MatchArm,
+            "match_pattern" => Type::MatchPattern,
+            "while_expression" => Type::WhileExpression,
+            "loop_expression" => Type::LoopExpression,
+            "for_expression" => Type::ForExpression,
+            "const_block" => Type::ConstBlock,
+            "closure_expression" => Type::ClosureExpression,
+            "closure_parameters" => Type::ClosureParameters,
+            "loop_label" => Type::LoopLabel,
+            "break_expression" => Type::BreakExpression,
+            "continue_expression" => Type::ContinueExpression,
+            "index_expression" => Type::IndexExpression,
+            "await_expression" => Type::AwaitExpression,
+            "field_expression" => Type::FieldExpression,
+            "unsafe_block" => Type::UnsafeBlock,
+            "async_block" => Type::AsyncBlock,
+            "block" => Type::Block,
+            "tuple_pattern" => Type::TuplePattern,
+            "slice_pattern" => Type::SlicePattern,
+            "tuple_struct_pattern" => Type::TupleStructPattern,
+            "struct_pattern" => Type::StructPattern,
+            "field_pattern" => Type::FieldPattern,
+            "remaining_field_pattern" => Type::RemainingFieldPattern,
+            "mut_pattern" => Type::MutPattern,
+            "range_pattern" => Type::RangePattern,
+            "ref_pattern" => Type::RefPattern,
+            "captured_pattern" => Type::CapturedPattern,
+            "reference_pattern" => Type::ReferencePattern,
+            "or_pattern" => Type::OrPattern,
+            "negative_literal" => Type::NegativeLiteral,
+            "string_literal" => Type::StringLiteral,
+            "raw_string_literal" => Type::RawStringLiteral,
+            "char_literal" => Type::CharLiteral,
+            "boolean_literal" => Type::BooleanLiteral,
+            "integer_literal" => Type::IntegerLiteral,
+            "float_literal" => Type::FloatLiteral,
+            "escape_sequence" => Type::EscapeSequence,
+            "line_comment" => Type::LineComment,
+            "block_comment" => Type::BlockComment,
+            "identifier" => Type::Identifier,
+            "type_identifier" => Type::TypeIdentifier,
+            "field_identifier" => Type::FieldIdentifier,
+            "shorthand_field_identifier" => Type::ShorthandFieldIdentifier,
+            "primitive_type" => Type::PrimitiveType,
+            "metavariable" => Type::Metavariable,
+            "self" => Type::SelfKw,
+            "super" => Type::Super,
+            "crate" => Type::Crate,
+            "Spaces" => Type::Spaces,
+            "Directory" => Type::Directory,
+            "ERROR" => Type::ERROR,
+            _ => return None,
+        })
+    }
+    /// tokens can share their kind with a named node eg. the `block` fragment specifier and blocks,
+    /// use this one for anonymous nodes.
+    pub fn from_str_anonymous(t: &str) -> Option<Type> {
+        Some(match t {
+            "block" => Type::TS0,
+            "lifetime" => Type::TS1,
+            t => return Self::from_str(t),
+        })
+    }
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Type::End => "end",
+            Type::LParen => "(",
+            Type::RParen => ")",
+            Type::LBracket => "[",
+            Type::RBracket => "]",
+            Type::LBrace => "{",
+            Type::RBrace => "}",
+            Type::Dollar => "$",
+            Type::Star => "*",
+            Type::Plus => "+",
+            Type::QMark => "?",
+            Type::TS0 => "block",
+            Type::Expr => "expr",
+            Type::Ident => "ident",
+            Type::Item => "item",
+            Type::TS1 => "lifetime",
+            Type::Literal => "literal",
+            Type::Meta => "meta",
+            Type::Pat => "pat",
+            Type::Path => "path",
+            Type::Stmt => "stmt",
+            Type::Tt => "tt",
+            Type::Ty => "ty",
+            Type::Vis => "vis",
+            Type::U8 => "u8",
+            Type::I8 => "i8",
+            Type::U16 => "u16",
+            Type::I16 => "i16",
+            Type::U32 => "u32",
+            Type::I32 => "i32",
+            Type::U64 => "u64",
+            Type::I64 => "i64",
+            Type::U128 => "u128",
+            Type::I128 => "i128",
+            Type::Isize => "isize",
+            Type::Usize => "usize",
+            Type::F32 => "f32",
+            Type::F64 => "f64",
+            Type::Bool => "bool",
+            Type::Str => "str",
+            Type::Char => "char",
+            Type::Dash => "-",
+            Type::Slash => "/",
+            Type::Percent => "%",
+            Type::Caret => "^",
+            Type::Bang => "!",
+            Type::Amp => "&",
+            Type::Pipe => "|",
+            Type::AmpAmp => "&&",
+            Type::PipePipe => "||",
+            Type::LtLt => "<<",
+            Type::GtGt => ">>",
+            Type::PlusEq => "+=",
+            Type::DashEq => "-=",
+            Type::StarEq => "*=",
+            Type::SlashEq => "/=",
+            Type::PercentEq => "%=",
+            Type::CaretEq => "^=",
+            Type::AmpEq => "&=",
+            Type::PipeEq => "|=",
+            Type::LtLtEq => "<<=",
+            Type::GtGtEq => ">>=",
+            Type::Eq => "=",
+            Type::EqEq => "==",
+            Type::BangEq => "!=",
+            Type::GT => ">",
+            Type::LT => "<",
+            Type::GTEq => ">=",
+            Type::LTEq => "<=",
+            Type::At => "@",
+            Type::Underscore => "_",
+            Type::Dot => ".",
+            Type::DotDot => "..",
+            Type::DotDotDot => "...",
+            Type::DotDotEq => "..=",
+            Type::Comma => ",",
+            Type::SemiColon => ";",
+            Type::Colon => ":",
+            Type::ColonColon => "::",
+            Type::DashGt => "->",
+            Type::EqGt => "=>",
+            Type::Hash => "#",
+            Type::SQuote => "'",
+            Type::As => "as",
+            Type::Async => "async",
+            Type::Await => "await",
+            Type::Break => "break",
+            Type::Const => "const",
+            Type::Continue => "continue",
+            Type::Default => "default",
+            Type::Enum => "enum",
+            Type::Fn => "fn",
+            Type::For => "for",
+            Type::If => "if",
+            Type::Impl => "impl",
+            Type::Let => "let",
+            Type::Loop => "loop",
+            Type::Match => "match",
+            Type::Mod => "mod",
+            Type::Pub => "pub",
+            Type::Return => "return",
+            Type::Static => "static",
+            Type::Struct => "struct",
+            Type::Trait => "trait",
+            Type::Type => "type",
+            Type::Union => "union",
+            Type::Unsafe => "unsafe",
+            Type::Use => "use",
+            Type::Where => "where",
+            Type::While => "while",
+            Type::MacroRulesBang => "macro_rules!",
+            Type::Extern => "extern",
+            Type::Ref => "ref",
+            Type::Dyn => "dyn",
+            Type::In => "in",
+            Type::Else => "else",
+            Type::Move => "move",
+            Type::Mut => "mut",
+            Type::Yield => "yield",
+            Type::True => "true",
+            Type::False => "false",
+            Type::DQuote => "\"",
+            Type::SourceFile => "source_file",
+            Type::EmptyStatement => "empty_statement",
+            Type::ExpressionStatement => "expression_statement",
+            Type::MacroDefinition => "macro_definition",
+            Type::MacroRule => "macro_rule",
+            Type::TokenTreePattern => "token_tree_pattern",
+            Type::TokenBindingPattern => "token_binding_pattern",
+            Type::TokenRepetitionPattern => "token_repetition_pattern",
+            Type::FragmentSpecifier => "fragment_specifier",
+            Type::TokenTree => "token_tree",
+            Type::TokenRepetition => "token_repetition",
+            Type::AttributeItem => "attribute_item",
+            Type::InnerAttributeItem => "inner_attribute_item",
+            Type::Attribute => "attribute",
+            Type::ModItem => "mod_item",
+            Type::ForeignModItem => "foreign_mod_item",
+            Type::DeclarationList => "declaration_list",
+            Type::StructItem => "struct_item",
+            Type::UnionItem => "union_item",
+            Type::EnumItem => "enum_item",
+            Type::EnumVariantList => "enum_variant_list",
+            Type::EnumVariant => "enum_variant",
+            Type::FieldDeclarationList => "field_declaration_list",
+            Type::FieldDeclaration => "field_declaration",
+            Type::OrderedFieldDeclarationList => "ordered_field_declaration_list",
+            Type::ExternCrateDeclaration => "extern_crate_declaration",
+            Type::ConstItem => "const_item",
+            Type::StaticItem => "static_item",
+            Type::TypeItem => "type_item",
+            Type::FunctionItem => "function_item",
+            Type::FunctionSignatureItem => "function_signature_item",
+            Type::FunctionModifiers => "function_modifiers",
+            Type::WhereClause => "where_clause",
+            Type::WherePredicate => "where_predicate",
+            Type::ImplItem => "impl_item",
+            Type::TraitItem => "trait_item",
+            Type::AssociatedType => "associated_type",
+            Type::TraitBounds => "trait_bounds",
+            Type::HigherRankedTraitBound => "higher_ranked_trait_bound",
+            Type::RemovedTraitBound => "removed_trait_bound",
+            Type::TypeParameters => "type_parameters",
+            Type::ConstParameter => "const_parameter",
+            Type::ConstrainedTypeParameter => "constrained_type_parameter",
+            Type::OptionalTypeParameter => "optional_type_parameter",
+            Type::LetDeclaration => "let_declaration",
+            Type::UseDeclaration => "use_declaration",
+            Type::ScopedUseList => "scoped_use_list",
+            Type::UseList => "use_list",
+            Type::UseAsClause => "use_as_clause",
+            Type::UseWildcard => "use_wildcard",
+            Type::Parameters => "parameters",
+            Type::SelfParameter => "self_parameter",
+            Type::VariadicParameter => "variadic_parameter",
+            Type::Parameter => "parameter",
+            Type::ExternModifier => "extern_modifier",
+            Type::VisibilityModifier => "visibility_modifier",
+            Type::BracketedType => "bracketed_type",
+            Type::QualifiedType => "qualified_type",
+            Type::Lifetime => "lifetime",
+            Type::ArrayType => "array_type",
+            Type::ForLifetimes => "for_lifetimes",
+            Type::FunctionType => "function_type",
+            Type::TupleType => "tuple_type",
+            Type::UnitType => "unit_type",
+            Type::GenericFunction => "generic_function",
+            Type::GenericType => "generic_type",
+            Type::GenericTypeWithTurbofish => "generic_type_with_turbofish",
+            Type::BoundedType => "bounded_type",
+            Type::TypeArguments => "type_arguments",
+            Type::TypeBinding => "type_binding",
+            Type::ReferenceType => "reference_type",
+            Type::PointerType => "pointer_type",
+            Type::EmptyType => "empty_type",
+            Type::AbstractType => "abstract_type",
+            Type::DynamicType => "dynamic_type",
+            Type::MutableSpecifier => "mutable_specifier",
+            Type::MacroInvocation => "macro_invocation",
+            Type::ScopedIdentifier => "scoped_identifier",
+            Type::ScopedTypeIdentifier => "scoped_type_identifier",
+            Type::RangeExpression => "range_expression",
+            Type::UnaryExpression => "unary_expression",
+            Type::TryExpression => "try_expression",
+            Type::ReferenceExpression => "reference_expression",
+            Type::BinaryExpression => "binary_expression",
+            Type::AssignmentExpression => "assignment_expression",
+            Type::CompoundAssignmentExpr => "compound_assignment_expr",
+            Type::TypeCastExpression => "type_cast_expression",
+            Type::ReturnExpression => "return_expression",
+            Type::YieldExpression => "yield_expression",
+            Type::CallExpression => "call_expression",
+            Type::Arguments => "arguments",
+            Type::ArrayExpression => "array_expression",
+            Type::ParenthesizedExpression => "parenthesized_expression",
+            Type::TupleExpression => "tuple_expression",
+            Type::UnitExpression => "unit_expression",
+            Type::StructExpression => "struct_expression",
+            Type::FieldInitializerList => "field_initializer_list",
+            Type::ShorthandFieldInitializer => "shorthand_field_initializer",
+            Type::FieldInitializer => "field_initializer",
+            Type::BaseFieldInitializer => "base_field_initializer",
+            Type::IfExpression => "if_expression",
+            Type::LetCondition => "let_condition",
+            Type::LetChain => "let_chain",
+            Type::ElseClause => "else_clause",
+            Type::MatchExpression => "match_expression",
+            Type::MatchBlock => "match_block",
+            Type::MatchArm => "match_arm",
+            Type::MatchPattern => "match_pattern",
+            Type::WhileExpression => "while_expression",
+            Type::LoopExpression => "loop_expression",
+            Type::ForExpression => "for_expression",
+            Type::ConstBlock => "const_block",
+            Type::ClosureExpression => "closure_expression",
+            Type::ClosureParameters => "closure_parameters",
+            Type::LoopLabel => "loop_label",
+            Type::BreakExpression => "break_expression",
+            Type::ContinueExpression => "continue_expression",
+            Type::IndexExpression => "index_expression",
+            Type::AwaitExpression => "await_expression",
+            Type::FieldExpression => "field_expression",
+            Type::UnsafeBlock => "unsafe_block",
+            Type::AsyncBlock => "async_block",
+            Type::Block => "block",
+            Type::TuplePattern => "tuple_pattern",
+            Type::SlicePattern => "slice_pattern",
+            Type::TupleStructPattern => "tuple_struct_pattern",
+            Type::StructPattern => "struct_pattern",
+            Type::FieldPattern => "field_pattern",
+            Type::RemainingFieldPattern => "remaining_field_pattern",
+            Type::MutPattern => "mut_pattern",
+            Type::RangePattern => "range_pattern",
+            Type::RefPattern => "ref_pattern",
+            Type::CapturedPattern => "captured_pattern",
+            Type::ReferencePattern => "reference_pattern",
+            Type::OrPattern => "or_pattern",
+            Type::NegativeLiteral => "negative_literal",
+            Type::StringLiteral => "string_literal",
+            Type::RawStringLiteral => "raw_string_literal",
+            Type::CharLiteral => "char_literal",
+            Type::BooleanLiteral => "boolean_literal",
+            Type::IntegerLiteral => "integer_literal",
+            Type::FloatLiteral => "float_literal",
+            Type::EscapeSequence => "escape_sequence",
+            Type::LineComment => "line_comment",
+            Type::BlockComment => "block_comment",
+            Type::Identifier => "identifier",
+            Type::TypeIdentifier => "type_identifier",
+            Type::FieldIdentifier => "field_identifier",
+            Type::ShorthandFieldIdentifier => "shorthand_field_identifier",
+            Type::PrimitiveType => "primitive_type",
+            Type::Metavariable => "metavariable",
+            Type::SelfKw => "self",
+            Type::Super => "super",
+            Type::Crate => "crate",
+            Type::Spaces => "Spaces",
+            Type::Directory => "Directory",
+            Type::ERROR => "ERROR",
+        }
+    }
+}
+
+const S_T_L: &'static [Type] = &[
+    Type::End,
+    Type::LParen,
+    Type::RParen,
+    Type::LBracket,
+    Type::RBracket,
+    Type::LBrace,
+    Type::RBrace,
+    Type::Dollar,
+    Type::Star,
+    Type::Plus,
+    Type::QMark,
+    Type::TS0,
+    Type::Expr,
+    Type::Ident,
+    Type::Item,
+    Type::TS1,
+    Type::Literal,
+    Type::Meta,
+    Type::Pat,
+    Type::Path,
+    Type::Stmt,
+    Type::Tt,
+    Type::Ty,
+    Type::Vis,
+    Type::U8,
+    Type::I8,
+    Type::U16,
+    Type::I16,
+    Type::U32,
+    Type::I32,
+    Type::U64,
+    Type::I64,
+    Type::U128,
+    Type::I128,
+    Type::Isize,
+    Type::Usize,
+    Type::F32,
+    Type::F64,
+    Type::Bool,
+    Type::Str,
+    Type::Char,
+    Type::Dash,
+    Type::Slash,
+    Type::Percent,
+    Type::Caret,
+    Type::Bang,
+    Type::Amp,
+    Type::Pipe,
+    Type::AmpAmp,
+    Type::PipePipe,
+    Type::LtLt,
+    Type::GtGt,
+    Type::PlusEq,
+    Type::DashEq,
+    Type::StarEq,
+    Type::SlashEq,
+    Type::PercentEq,
+    Type::CaretEq,
+    Type::AmpEq,
+    Type::PipeEq,
+    Type::LtLtEq,
+    Type::GtGtEq,
+    Type::Eq,
+    Type::EqEq,
+    Type::BangEq,
+    Type::GT,
+    Type::LT,
+    Type::GTEq,
+    Type::LTEq,
+    Type::At,
+    Type::Underscore,
+    Type::Dot,
+    Type::DotDot,
+    Type::DotDotDot,
+    Type::DotDotEq,
+    Type::Comma,
+    Type::SemiColon,
+    Type::Colon,
+    Type::ColonColon,
+    Type::DashGt,
+    Type::EqGt,
+    Type::Hash,
+    Type::SQuote,
+    Type::As,
+    Type::Async,
+    Type::Await,
+    Type::Break,
+    Type::Const,
+    Type::Continue,
+    Type::Default,
+    Type::Enum,
+    Type::Fn,
+    Type::For,
+    Type::If,
+    Type::Impl,
+    Type::Let,
+    Type::Loop,
+    Type::Match,
+    Type::Mod,
+    Type::Pub,
+    Type::Return,
+    Type::Static,
+    Type::Struct,
+    Type::Trait,
+    Type::Type,
+    Type::Union,
+    Type::Unsafe,
+    Type::Use,
+    Type::Where,
+    Type::While,
+    Type::MacroRulesBang,
+    Type::Extern,
+    Type::Ref,
+    Type::Dyn,
+    Type::In,
+    Type::Else,
+    Type::Move,
+    Type::Mut,
+    Type::Yield,
+    Type::True,
+    Type::False,
+    Type::DQuote,
+    Type::SourceFile,
+    Type::EmptyStatement,
+    Type::ExpressionStatement,
+    Type::MacroDefinition,
+    Type::MacroRule,
+    Type::TokenTreePattern,
+    Type::TokenBindingPattern,
+    Type::TokenRepetitionPattern,
+    Type::FragmentSpecifier,
+    Type::TokenTree,
+    Type::TokenRepetition,
+    Type::AttributeItem,
+    Type::InnerAttributeItem,
+    Type::Attribute,
+    Type::ModItem,
+    Type::ForeignModItem,
+    Type::DeclarationList,
+    Type::StructItem,
+    Type::UnionItem,
+    Type::EnumItem,
+    Type::EnumVariantList,
+    Type::EnumVariant,
+    Type::FieldDeclarationList,
+    Type::FieldDeclaration,
+    Type::OrderedFieldDeclarationList,
+    Type::ExternCrateDeclaration,
+    Type::ConstItem,
+    Type::StaticItem,
+    Type::TypeItem,
+    Type::FunctionItem,
+    Type::FunctionSignatureItem,
+    Type::FunctionModifiers,
+    Type::WhereClause,
+    Type::WherePredicate,
+    Type::ImplItem,
+    Type::TraitItem,
+    Type::AssociatedType,
+    Type::TraitBounds,
+    Type::HigherRankedTraitBound,
+    Type::RemovedTraitBound,
+    Type::TypeParameters,
+    Type::ConstParameter,
+    Type::ConstrainedTypeParameter,
+    Type::OptionalTypeParameter,
+    Type::LetDeclaration,
+    Type::UseDeclaration,
+    Type::ScopedUseList,
+    Type::UseList,
+    Type::UseAsClause,
+    Type::UseWildcard,
+    Type::Parameters,
+    Type::SelfParameter,
+    Type::VariadicParameter,
+    Type::Parameter,
+    Type::ExternModifier,
+    Type::VisibilityModifier,
+    Type::BracketedType,
+    Type::QualifiedType,
+    Type::Lifetime,
+    Type::ArrayType,
+    Type::ForLifetimes,
+    Type::FunctionType,
+    Type::TupleType,
+    Type::UnitType,
+    Type::GenericFunction,
+    Type::GenericType,
+    Type::GenericTypeWithTurbofish,
+    Type::BoundedType,
+    Type::TypeArguments,
+    Type::TypeBinding,
+    Type::ReferenceType,
+    Type::PointerType,
+    Type::EmptyType,
+    Type::AbstractType,
+    Type::DynamicType,
+    Type::MutableSpecifier,
+    Type::MacroInvocation,
+    Type::ScopedIdentifier,
+    Type::ScopedTypeIdentifier,
+    Type::RangeExpression,
+    Type::UnaryExpression,
+    Type::TryExpression,
+    Type::ReferenceExpression,
+    Type::BinaryExpression,
+    Type::AssignmentExpression,
+    Type::CompoundAssignmentExpr,
+    Type::TypeCastExpression,
+    Type::ReturnExpression,
+    Type::YieldExpression,
+    Type::CallExpression,
+    Type::Arguments,
+    Type::ArrayExpression,
+    Type::ParenthesizedExpression,
+    Type::TupleExpression,
+    Type::UnitExpression,
+    Type::StructExpression,
+    Type::FieldInitializerList,
+    Type::ShorthandFieldInitializer,
+    Type::FieldInitializer,
+    Type::BaseFieldInitializer,
+    Type::IfExpression,
+    Type::LetCondition,
+    Type::LetChain,
+    Type::ElseClause,
+    Type::MatchExpression,
+    Type::MatchBlock,
+    Type::MatchArm,
+    Type::MatchPattern,
+    Type::WhileExpression,
+    Type::LoopExpression,
+    Type::ForExpression,
+    Type::ConstBlock,
+    Type::ClosureExpression,
+    Type::ClosureParameters,
+    Type::LoopLabel,
+    Type::BreakExpression,
+    Type::ContinueExpression,
+    Type::IndexExpression,
+    Type::AwaitExpression,
+    Type::FieldExpression,
+    Type::UnsafeBlock,
+    Type::AsyncBlock,
+    Type::Block,
+    Type::TuplePattern,
+    Type::SlicePattern,
+    Type::TupleStructPattern,
+    Type::StructPattern,
+    Type::FieldPattern,
+    Type::RemainingFieldPattern,
+    Type::MutPattern,
+    Type::RangePattern,
+    Type::RefPattern,
+    Type::CapturedPattern,
+    Type::ReferencePattern,
+    Type::OrPattern,
+    Type::NegativeLiteral,
+    Type::StringLiteral,
+    Type::RawStringLiteral,
+    Type::CharLiteral,
+    Type::BooleanLiteral,
+    Type::IntegerLiteral,
+    Type::FloatLiteral,
+    Type::EscapeSequence,
+    Type::LineComment,
+    Type::BlockComment,
+    Type::Identifier,
+    Type::TypeIdentifier,
+    Type::FieldIdentifier,
+    Type::ShorthandFieldIdentifier,
+    Type::PrimitiveType,
+    Type::Metavariable,
+    Type::SelfKw,
+    Type::Super,
+    Type::Crate,
+    Type::Spaces,
+    Type::Directory,
+    Type::ERROR,
+];
+
+static ANY_T_L: [AnyType; S_T_L.len()] = hyper_ast::types::any_types(S_T_L);
@@ -0,0 +1,63 @@
+use crate::types::{TStore, Type};
+
+type TsTreeGen<'store, 'cache> = crate::legion::TsTreeGen<'store, 'cache, TStore>;
+type SimpleStores = hyper_ast::store::SimpleStores<TStore>;
+
+#[test]
+fn ts_simple_test() {
+    let text = {
+        let source_code1 = r#"
+import { readFile } from "fs";
+
+export function parse(path: string): number[] {
+    const re = /[0-9]+/g;
+    return readFile(path).match(re).map((x) => parseInt(x, 10));
+}
+"#;
+        source_code1.as_bytes()
+    };
+    let tree = match crate::legion::tree_sitter_parse(text) {
+        Ok(t) => t,
+        Err(t) => panic!("{}", t.root_node().to_sexp()),
+    };
+    println!("{:#?}", tree.root_node().to_sexp());
+    let mut stores = SimpleStores::default();
+    let mut md_cache = Default::default();
+    let mut tree_gen = TsTreeGen::new(&mut stores, &mut md_cache);
+    let x = tree_gen.generate_file(b"", text, tree.walk().into()).local;
+    println!(
+        "{}",
+        hyper_ast::nodes::SyntaxSerializer::new(&stores, x.compressed_node)
+    );
+    let serialized = hyper_ast::nodes::TextSerializer::new(&stores, x.compressed_node).to_string();
+    assert_eq!(std::str::from_utf8(text).unwrap(), serialized);
+}
+
+#[test]
+fn jsx_simple_test() {
+    let text = {
+        let source_code1 = r#"const App = () => <div className="app">{`hello ${name}`}</div>;
+"#;
+        source_code1.as_bytes()
+    };
+    let tree = match crate::legion::tree_sitter_parse_tsx(text) {
+        Ok(t) => t,
+        Err(t) => panic!("{}", t.root_node().to_sexp()),
+    };
+    let mut stores = SimpleStores::default();
+    let mut md_cache = Default::default();
+    let mut tree_gen = TsTreeGen::new(&mut stores, &mut md_cache);
+    let x = tree_gen.generate_file(b"", text, tree.walk().into()).local;
+    let serialized = hyper_ast::nodes::TextSerializer::new(&stores, x.compressed_node).to_string();
+    assert_eq!(std::str::from_utf8(text).unwrap(), serialized);
+}
+
+#[test]
+fn kinds_round_trip() {
+    assert_eq!(Type::from_str("import"), Some(Type::Import));
+    assert_eq!(Type::from_str_anonymous("import"), Some(Type::TS0));
+    assert_eq!(Type::from_str_anonymous("=>"), Some(Type::EqGT));
+    for k in ["program", "arrow_function", "ERROR", "${", "number"] {
+        assert_eq!(Type::from_str(k).unwrap().to_str(), k);
+    }
+}
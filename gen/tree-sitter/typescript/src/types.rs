@@ -0,0 +1,1732 @@
+use std::fmt::Display;
+
+use hyper_ast::{
+    store::defaults::NodeIdentifier,
+    types::{
+        AnyType, HyperType, Lang, LangRef, LangWrapper, NodeId, TypeStore, TypeTrait, TypedNodeId,
+    },
+};
+
+#[cfg(feature = "legion")]
+mod legion_impls {
+    use super::*;
+
+    use crate::TNode;
+
+    impl<'a> TNode<'a> {
+        /// kind ids are specific to each version of the grammar,
+        /// so types are obtained from the kind names.
+        pub fn obtain_type(&self) -> Type {
+            let k = self.0.kind();
+            let t = if self.0.is_named() {
+                Type::from_str(k)
+            } else {
+                Type::from_str_anonymous(k)
+            };
+            t.unwrap_or_else(|| {
+                log::warn!("unknown ts kind {:?}", k);
+                Type::ERROR
+            })
+        }
+    }
+
+    use hyper_ast::{store::nodes::legion::HashedNodeRef, types::TypeIndex};
+
+    impl<'a, TS: TsEnabledTypeStore<HashedNodeRef<'a, Type>>> From<TS> for Single {
+        fn from(value: TS) -> Self {
+            Self {
+                mask: TS::MASK,
+                lang: TS::LANG,
+            }
+        }
+    }
+
+    impl<'a> TypeStore<HashedNodeRef<'a, TIdN<NodeIdentifier>>> for TStore {
+        type Ty = Type;
+        const MASK: TypeInternalSize = 0b1000_0000_0000_0000;
+        fn resolve_type(&self, n: &HashedNodeRef<'a, TIdN<NodeIdentifier>>) -> Self::Ty {
+            n.get_component::<Type>().unwrap().clone()
+        }
+
+        fn resolve_lang(
+            &self,
+            n: &HashedNodeRef<'a, TIdN<NodeIdentifier>>,
+        ) -> hyper_ast::types::LangWrapper<Self::Ty> {
+            From::<&'static (dyn LangRef<Type>)>::from(&Ts)
+        }
+
+        type Marshaled = TypeIndex;
+
+        fn marshal_type(&self, n: &HashedNodeRef<'a, TIdN<NodeIdentifier>>) -> Self::Marshaled {
+            TypeIndex {
+                lang: LangRef::<Type>::name(&Ts),
+                ty: *n.get_component::<Type>().unwrap() as u16,
+            }
+        }
+    }
+    impl<'a> TsEnabledTypeStore<HashedNodeRef<'a, TIdN<NodeIdentifier>>> for TStore {
+        const LANG: TypeInternalSize = Self::Ts as u16;
+
+        fn _intern(l: u16, t: u16) -> Self::Ty {
+            todo!()
+        }
+        fn intern(&self, t: Type) -> Self::Ty {
+            t
+        }
+
+        fn resolve(&self, t: Self::Ty) -> Type {
+            t
+        }
+    }
+    impl<'a> TypeStore<HashedNodeRef<'a, NodeIdentifier>> for TStore {
+        type Ty = AnyType;
+        const MASK: TypeInternalSize = 0b1000_0000_0000_0000;
+        fn resolve_type(&self, n: &HashedNodeRef<'a, NodeIdentifier>) -> Self::Ty {
+            From::<&'static (dyn HyperType)>::from(LangRef::<Type>::make(
+                &Ts,
+                *n.get_component::<Type>().unwrap() as u16,
+            ))
+        }
+
+        fn resolve_lang(
+            &self,
+            n: &HashedNodeRef<'a, NodeIdentifier>,
+        ) -> hyper_ast::types::LangWrapper<Self::Ty> {
+            From::<&'static (dyn LangRef<AnyType>)>::from(&Ts)
+        }
+
+        type Marshaled = TypeIndex;
+
+        fn marshal_type(&self, n: &HashedNodeRef<'a, NodeIdentifier>) -> Self::Marshaled {
+            TypeIndex {
+                lang: LangRef::<Type>::name(&Ts),
+                ty: *n.get_component::<Type>().unwrap() as u16,
+            }
+        }
+    }
+
+    impl hyper_ast::store::nodes::legion::persist::Persist for Type {
+        fn save(
+            &self,
+            w: &mut dyn std::io::Write,
+            _: &hyper_ast::store::nodes::legion::persist::SavedIds,
+        ) -> std::io::Result<()> {
+            hyper_ast::store::persist::write_u16(w, *self as u16)
+        }
+
+        fn load(
+            r: &mut dyn std::io::Read,
+            _: &hyper_ast::store::nodes::legion::persist::LoadedIds,
+        ) -> std::io::Result<Self> {
+            let t = hyper_ast::store::persist::read_u16(r)?;
+            if t < COUNT {
+                Ok(Type::resolve(t))
+            } else {
+                Err(hyper_ast::store::persist::invalid_data(format!(
+                    "invalid type {}",
+                    t
+                )))
+            }
+        }
+    }
+}
+
+pub trait TsEnabledTypeStore<T>: TypeStore<T> {
+    const LANG: u16;
+    fn intern(&self, t: Type) -> Self::Ty {
+        let t = t as u16;
+        Self::_intern(Self::LANG, t)
+    }
+    fn _intern(l: u16, t: u16) -> Self::Ty;
+    fn resolve(&self, t: Self::Ty) -> Type;
+}
+
+impl Type {
+    pub fn resolve(t: u16) -> Self {
+        assert!(t < COUNT);
+        unsafe { std::mem::transmute(t) }
+    }
+}
+
+pub struct Single {
+    mask: TypeInternalSize,
+    lang: TypeInternalSize,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TIdN<IdN>(IdN);
+
+impl<IdN: Clone + Eq + NodeId> NodeId for TIdN<IdN> {
+    type IdN = IdN;
+
+    fn as_id(&self) -> &Self::IdN {
+        &self.0
+    }
+
+    unsafe fn from_id(id: Self::IdN) -> Self {
+        Self(id)
+    }
+
+    unsafe fn from_ref_id(id: &Self::IdN) -> &Self {
+        std::mem::transmute(id)
+    }
+}
+
+impl<IdN: Clone + Eq + NodeId> TypedNodeId for TIdN<IdN> {
+    type Ty = Type;
+}
+
+#[repr(u8)]
+pub(crate) enum TStore {
+    Ts = 0,
+}
+
+impl Default for TStore {
+    fn default() -> Self {
+        Self::Ts
+    }
+}
+
+type TypeInternalSize = u16;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct T(TypeInternalSize);
+
+/// TypeScript and JavaScript, as parsed by tree-sitter-typescript
+pub struct Ts;
+
+impl Ts {
+    const INST: Ts = Ts;
+}
+
+impl LangRef<AnyType> for Ts {
+    fn make(&self, t: u16) -> &'static AnyType {
+        &ANY_T_L[t as usize]
+    }
+    fn to_u16(&self, t: AnyType) -> u16 {
+        let t = t.as_any().downcast_ref::<Type>().unwrap();
+        *t as u16
+    }
+
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Ts>()
+    }
+}
+
+impl LangRef<Type> for Ts {
+    fn make(&self, t: u16) -> &'static Type {
+        &S_T_L[t as usize]
+    }
+    fn to_u16(&self, t: Type) -> u16 {
+        t as u16
+    }
+
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Ts>()
+    }
+}
+
+impl Lang<Type> for Ts {
+    fn make(t: u16) -> &'static Type {
+        Ts.make(t)
+    }
+    fn to_u16(t: Type) -> u16 {
+        Ts.to_u16(t)
+    }
+}
+
+impl HyperType for Type {
+    fn is_directory(&self) -> bool {
+        self == &Type::Directory
+    }
+
+    fn is_file(&self) -> bool {
+        self == &Type::Program
+    }
+
+    fn is_spaces(&self) -> bool {
+        self == &Type::Spaces
+    }
+
+    fn is_syntax(&self) -> bool {
+        self == &Type::LBrace
+            || self == &Type::RBrace
+            || self == &Type::LParen
+            || self == &Type::RParen
+            || self == &Type::LBracket
+            || self == &Type::RBracket
+            || self == &Type::Comma
+            || self == &Type::SemiColon
+            || self == &Type::Colon
+            || self == &Type::Dot
+            || self == &Type::QMarkDot
+            || self == &Type::DotDotDot
+            || self == &Type::EqGT
+            || self == &Type::Eq
+            || self == &Type::QMark
+            || self == &Type::LT
+            || self == &Type::GT
+            || self == &Type::DQuote
+            || self == &Type::SQuote
+            || self == &Type::BQuote
+            || self == &Type::DollarLBrace
+            || self == &Type::At
+    }
+
+    fn as_shared(&self) -> hyper_ast::types::Shared {
+        use hyper_ast::types::Shared;
+        match self {
+            Type::ClassDeclaration => Shared::TypeDeclaration,
+            Type::AbstractClassDeclaration => Shared::TypeDeclaration,
+            Type::InterfaceDeclaration => Shared::TypeDeclaration,
+            Type::EnumDeclaration => Shared::TypeDeclaration,
+            Type::TypeAliasDeclaration => Shared::TypeDeclaration,
+            Type::Comment => Shared::Comment,
+            Type::Identifier => Shared::Identifier,
+            Type::PropertyIdentifier => Shared::Identifier,
+            Type::TypeIdentifier => Shared::Identifier,
+            _ => Shared::Other,
+        }
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn get_lang(&self) -> hyper_ast::types::LangWrapper<Self>
+    where
+        Self: Sized,
+    {
+        From::<&'static (dyn LangRef<Self>)>::from(&Ts)
+    }
+}
+impl TypeTrait for Type {
+    type Lang = Ts;
+
+    fn is_fork(&self) -> bool {
+        todo!()
+    }
+
+    fn is_literal(&self) -> bool {
+        todo!()
+    }
+
+    fn is_primitive(&self) -> bool {
+        todo!()
+    }
+
+    fn is_type_declaration(&self) -> bool {
+        todo!()
+    }
+
+    fn is_identifier(&self) -> bool {
+        todo!()
+    }
+
+    fn is_instance_ref(&self) -> bool {
+        todo!()
+    }
+
+    fn is_type_body(&self) -> bool {
+        todo!()
+    }
+
+    fn is_value_member(&self) -> bool {
+        todo!()
+    }
+
+    fn is_executable_member(&self) -> bool {
+        todo!()
+    }
+
+    fn is_statement(&self) -> bool {
+        todo!()
+    }
+
+    fn is_declarative_statement(&self) -> bool {
+        todo!()
+    }
+
+    fn is_structural_statement(&self) -> bool {
+        todo!()
+    }
+
+    fn is_block_related(&self) -> bool {
+        todo!()
+    }
+
+    fn is_simple_statement(&self) -> bool {
+        todo!()
+    }
+
+    fn is_local_declare(&self) -> bool {
+        todo!()
+    }
+
+    fn is_parameter(&self) -> bool {
+        todo!()
+    }
+
+    fn is_parameter_list(&self) -> bool {
+        todo!()
+    }
+
+    fn is_argument_list(&self) -> bool {
+        todo!()
+    }
+
+    fn is_expression(&self) -> bool {
+        todo!()
+    }
+
+    fn is_comment(&self) -> bool {
+        todo!()
+    }
+}
+
+const COUNT: u16 = 328;
+impl Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.to_str())
+    }
+}
+
+#[repr(u16)]
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum Type {
+    End,
+    Export,
+    Star,
+    Default,
+    As,
+    LBrace,
+    RBrace,
+    Comma,
+    From,
+    TS0,
+    Typeof,
+    Type,
+    SemiColon,
+    With,
+    Assert,
+    Var,
+    Let,
+    Const,
+    Bang,
+    Else,
+    If,
+    Switch,
+    For,
+    LParen,
+    RParen,
+    Await,
+    In,
+    Of,
+    While,
+    Do,
+    Try,
+    Return,
+    Break,
+    Continue,
+    Debugger,
+    Throw,
+    Case,
+    Catch,
+    Finally,
+    Yield,
+    Eq,
+    LBracket,
+    RBracket,
+    LT,
+    GT,
+    Slash,
+    Dot,
+    TS1,
+    Async,
+    TS2,
+    EqGT,
+    QMarkDot,
+    New,
+    PlusEq,
+    DashEq,
+    StarEq,
+    SlashEq,
+    PercentEq,
+    CaretEq,
+    AmpEq,
+    PipeEq,
+    GtGtEq,
+    GtGtGtEq,
+    LtLtEq,
+    StarStarEq,
+    AmpAmpEq,
+    PipePipeEq,
+    QMarkQMarkEq,
+    DotDotDot,
+    AmpAmp,
+    PipePipe,
+    GtGt,
+    GtGtGt,
+    LtLt,
+    Amp,
+    Caret,
+    Pipe,
+    Plus,
+    Dash,
+    Percent,
+    StarStar,
+    LTEq,
+    EqEq,
+    EqEqEq,
+    BangEq,
+    BangEqEq,
+    GTEq,
+    QMarkQMark,
+    Instanceof,
+    Tilde,
+    Void,
+    Delete,
+    PlusPlus,
+    DashDash,
+    DQuote,
+    SQuote,
+    BQuote,
+    DollarLBrace,
+    Static,
+    Get,
+    Set,
+    Declare,
+    Namespace,
+    TS3,
+    Global,
+    QMark,
+    Colon,
+    Abstract,
+    Private,
+    Protected,
+    Public,
+    Override,
+    Readonly,
+    Require,
+    Enum,
+    Interface,
+    Implements,
+    Extends,
+    Keyof,
+    Infer,
+    Is,
+    TS4,
+    Any,
+    TS5,
+    Boolean,
+    TS6,
+    Symbol,
+    TS7,
+    Never,
+    Unknown,
+    Unique,
+    LBracePipe,
+    PipeRBrace,
+    DashQMarkColon,
+    PlusQMarkColon,
+    QMarkColon,
+    Target,
+    Meta,
+    At,
+    Accessor,
+    Satisfies,
+    Hash,
+    Program,
+    HashBangLine,
+    ExportStatement,
+    ExportClause,
+    ExportSpecifier,
+    NamespaceExport,
+    Import,
+    ImportStatement,
+    ImportClause,
+    NamespaceImport,
+    NamedImports,
+    ImportSpecifier,
+    ImportRequireClause,
+    ImportAlias,
+    ExpressionStatement,
+    VariableDeclaration,
+    LexicalDeclaration,
+    VariableDeclarator,
+    StatementBlock,
+    ElseClause,
+    IfStatement,
+    SwitchStatement,
+    ForStatement,
+    ForInStatement,
+    WhileStatement,
+    DoStatement,
+    TryStatement,
+    WithStatement,
+    BreakStatement,
+    ContinueStatement,
+    DebuggerStatement,
+    ReturnStatement,
+    ThrowStatement,
+    EmptyStatement,
+    LabeledStatement,
+    SwitchBody,
+    SwitchCase,
+    SwitchDefault,
+    CatchClause,
+    FinallyClause,
+    ParenthesizedExpression,
+    YieldExpression,
+    Object,
+    ObjectPattern,
+    AssignmentPattern,
+    ObjectAssignmentPattern,
+    Array,
+    ArrayPattern,
+    NestedIdentifier,
+    Class,
+    ClassDeclaration,
+    ClassHeritage,
+    ClassBody,
+    ClassStaticBlock,
+    Function,
+    FunctionDeclaration,
+    GeneratorFunction,
+    GeneratorFunctionDeclaration,
+    ArrowFunction,
+    CallExpression,
+    NewExpression,
+    AwaitExpression,
+    MemberExpression,
+    SubscriptExpression,
+    AssignmentExpression,
+    AugmentedAssignmentExpression,
+    SpreadElement,
+    TernaryExpression,
+    BinaryExpression,
+    UnaryExpression,
+    UpdateExpression,
+    SequenceExpression,
+    InstantiationExpression,
+    String,
+    StringFragment,
+    EscapeSequence,
+    Comment,
+    TemplateString,
+    TemplateSubstitution,
+    Regex,
+    RegexPattern,
+    RegexFlags,
+    Number,
+    Identifier,
+    PropertyIdentifier,
+    ShorthandPropertyIdentifier,
+    ShorthandPropertyIdentifierPattern,
+    PrivatePropertyIdentifier,
+    StatementIdentifier,
+    TypeIdentifier,
+    This,
+    Super,
+    True,
+    False,
+    Null,
+    Undefined,
+    Arguments,
+    Decorator,
+    FieldDefinition,
+    PublicFieldDefinition,
+    FormalParameters,
+    RestPattern,
+    MethodDefinition,
+    Pair,
+    PairPattern,
+    ComputedPropertyName,
+    MetaProperty,
+    OptionalChain,
+    NonNullExpression,
+    MethodSignature,
+    AbstractMethodSignature,
+    FunctionSignature,
+    TypeAssertion,
+    AsExpression,
+    SatisfiesExpression,
+    InternalModule,
+    Module,
+    AmbientDeclaration,
+    AbstractClassDeclaration,
+    AccessibilityModifier,
+    OverrideModifier,
+    RequiredParameter,
+    OptionalParameter,
+    TypeAnnotation,
+    OmittingTypeAnnotation,
+    OptingTypeAnnotation,
+    AddingTypeAnnotation,
+    Asserts,
+    AssertsAnnotation,
+    OptionalType,
+    RestType,
+    TypePredicate,
+    TypePredicateAnnotation,
+    TypeQuery,
+    IndexTypeQuery,
+    LookupType,
+    MappedTypeClause,
+    LiteralType,
+    ExistentialType,
+    FlowMaybeType,
+    ParenthesizedType,
+    PredefinedType,
+    TypeArguments,
+    ObjectType,
+    CallSignature,
+    PropertySignature,
+    TypeParameters,
+    TypeParameter,
+    DefaultType,
+    Constraint,
+    ConstructSignature,
+    IndexSignature,
+    ArrayType,
+    TupleType,
+    OptionalTupleParameter,
+    ReadonlyType,
+    UnionType,
+    IntersectionType,
+    FunctionType,
+    ConstructorType,
+    InferType,
+    ConditionalType,
+    GenericType,
+    TemplateLiteralType,
+    TemplateType,
+    EnumDeclaration,
+    EnumBody,
+    EnumAssignment,
+    TypeAliasDeclaration,
+    InterfaceDeclaration,
+    ExtendsClause,
+    ExtendsTypeClause,
+    ImplementsClause,
+    NestedTypeIdentifier,
+    JsxElement,
+    JsxSelfClosingElement,
+    JsxOpeningElement,
+    JsxClosingElement,
+    JsxText,
+    JsxExpression,
+    JsxAttribute,
+    JsxNamespaceName,
+    HtmlCharacterReference,
+    Spaces,
+    Directory,
+    ERROR,
+}
+impl Type {
+    pub fn from_str(t: &str) -> Option<Type> {
+        Some(match t {
+            "end" => Type::End,
+            "export" => Type::Export,
+            "*" => Type::Star,
+            "default" => Type::Default,
+            "as" => Type::As,
+            "{" => Type::LBrace,
+            "}" => Type::RBrace,
+            "," => Type::Comma,
+            "from" => Type::From,
+            "typeof" => Type::Typeof,
+            "type" => Type::Type,
+            ";" => Type::SemiColon,
+            "with" => Type::With,
+            "assert" => Type::Assert,
+            "var" => Type::Var,
+            "let" => Type::Let,
+            "const" => Type::Const,
+            "!" => Type::Bang,
+            "else" => Type::Else,
+            "if" => Type::If,
+            "switch" => Type::Switch,
+            "for" => Type::For,
+            "(" => Type::LParen,
+            ")" => Type::RParen,
+            "await" => Type::Await,
+            "in" => Type::In,
+            "of" => Type::Of,
+            "while" => Type::While,
+            "do" => Type::Do,
+            "try" => Type::Try,
+            "return" => Type::Return,
+            "break" => Type::Break,
+            "continue" => Type::Continue,
+            "debugger" => Type::Debugger,
+            "throw" => Type::Throw,
+            "case" => Type::Case,
+            "catch" => Type::Catch,
+            "finally" => Type::Finally,
+            "yield" => Type::Yield,
+            "=" => Type::Eq,
+            "[" => Type::LBracket,
+            "]" => Type::RBracket,
+            "<" => Type::LT,
+            ">" => Type::GT,
+            "/" => Type::Slash,
+            "." => Type::Dot,
+            "async" => Type::Async,
+            "=>" => Type::EqGT,
+            "?." => Type::QMarkDot,
+            "new" => Type::New,
+            "+=" => Type::PlusEq,
+            "-=" => Type::DashEq,
+            "*=" => Type::StarEq,
+            "/=" => Type::SlashEq,
+            "%=" => Type::PercentEq,
+            "^=" => Type::CaretEq,
+            "&=" => Type::AmpEq,
+            "|=" => Type::PipeEq,
+            ">>=" => Type::GtGtEq,
+            ">>>=" => Type::GtGtGtEq,
+            "<<=" => Type::LtLtEq,
+            "**=" => Type::StarStarEq,
+            "&&=" => Type::AmpAmpEq,
+            "||=" => Type::PipePipeEq,
+            "??=" => Type::QMarkQMarkEq,
+            "..." => Type::DotDotDot,
+            "&&" => Type::AmpAmp,
+            "||" => Type::PipePipe,
+            ">>" => Type::GtGt,
+            ">>>" => Type::GtGtGt,
+            "<<" => Type::LtLt,
+            "&" => Type::Amp,
+            "^" => Type::Caret,
+            "|" => Type::Pipe,
+            "+" => Type::Plus,
+            "-" => Type::Dash,
+            "%" => Type::Percent,
+            "**" => Type::StarStar,
+            "<=" => Type::LTEq,
+            "==" => Type::EqEq,
+            "===" => Type::EqEqEq,
+            "!=" => Type::BangEq,
+            "!==" => Type::BangEqEq,
+            ">=" => Type::GTEq,
+            "??" => Type::QMarkQMark,
+            "instanceof" => Type::Instanceof,
+            "~" => Type::Tilde,
+            "void" => Type::Void,
+            "delete" => Type::Delete,
+            "++" => Type::PlusPlus,
+            "--" => Type::DashDash,
+            "\"" => Type::DQuote,
+            "'" => Type::SQuote,
+            "`" => Type::BQuote,
+            "${" => Type::DollarLBrace,
+            "static" => Type::Static,
+            "get" => Type::Get,
+            "set" => Type::Set,
+            "declare" => Type::Declare,
+            "namespace" => Type::Namespace,
+            "global" => Type::Global,
+            "?" => Type::QMark,
+            ":" => Type::Colon,
+            "abstract" => Type::Abstract,
+            "private" => Type::Private,
+            "protected" => Type::Protected,
+            "public" => Type::Public,
+            "override" => Type::Override,
+            "readonly" => Type::Readonly,
+            "require" => Type::Require,
+            "enum" => Type::Enum,
+            "interface" => Type::Interface,
+            "implements" => Type::Implements,
+            "extends" => Type::Extends,
+            "keyof" => Type::Keyof,
+            "infer" => Type::Infer,
+            "is" => Type::Is,
+            "any" => Type::Any,
+            "boolean" => Type::Boolean,
+            "symbol" => Type::Symbol,
+            "never" => Type::Never,
+            "unknown" => Type::Unknown,
+            "unique" => Type::Unique,
+            "{|" => Type::LBracePipe,
+            "|}" => Type::PipeRBrace,
+            "-?:" => Type::DashQMarkColon,
+            "+?:" => Type::PlusQMarkColon,
+            "?:" => Type::QMarkColon,
+            "target" => Type::Target,
+            "meta" => Type::Meta,
+            "@" => Type::At,
+            "accessor" => Type::Accessor,
+            "satisfies" => Type::Satisfies,
+            "#" => Type::Hash,
+            "program" => Type::Program,
+            "hash_bang_line" => Type::HashBangLine,
+            "export_statement" => Type::ExportStatement,
+            "export_clause" => Type::ExportClause,
+            "export_specifier" => Type::ExportSpecifier,
+            "namespace_export" => Type::NamespaceExport,
+            "import" => Type::Import,
+            "import_statement" => Type::ImportStatement,
+            "import_clause" => Type::ImportClause,
+            "namespace_import" => Type::NamespaceImport,
+            "named_imports" => Type::NamedImports,
+            "import_specifier" => Type::ImportSpecifier,
+            "import_require_clause" => Type::ImportRequireClause,
+            "import_alias" => Type::ImportAlias,
+            "expression_statement" => Type::ExpressionStatement,
+            "variable_declaration" => Type::VariableDeclaration,
+            "lexical_declaration" => Type::LexicalDeclaration,
+            "variable_declarator" => Type::VariableDeclarator,
+            "statement_block" => Type::StatementBlock,
+            "else_clause" => Type::ElseClause,
+            "if_statement" => Type::IfStatement,
+            "switch_statement" => Type::SwitchStatement,
+            "for_statement" => Type::ForStatement,
+            "for_in_statement" => Type::ForInStatement,
+            "while_statement" => Type::WhileStatement,
+            "do_statement" => Type::DoStatement,
+            "try_statement" => Type::TryStatement,
+            "with_statement" => Type::WithStatement,
+            "break_statement" => Type::BreakStatement,
+            "continue_statement" => Type::ContinueStatement,
+            "debugger_statement" => Type::DebuggerStatement,
+            "return_statement" => Type::ReturnStatement,
+            "throw_statement" => Type::ThrowStatement,
+            "empty_statement" => Type::EmptyStatement,
+            "labeled_statement" => Type::LabeledStatement,
+            "switch_body" => Type::SwitchBody,
+            "switch_case" => Type::SwitchCase,
+            "switch_default" => Type::SwitchDefault,
+            "catch_clause" => Type::CatchClause,
+            "finally_clause" => Type::FinallyClause,
+            "parenthesized_expression" => Type::ParenthesizedExpression,
+            "yield_expression" => Type::YieldExpression,
+            "object" => Type::Object,
+            "object_pattern" => Type::ObjectPattern,
+            "assignment_pattern" => Type::AssignmentPattern,
+            "object_assignment_pattern" => Type::ObjectAssignmentPattern,
+            "array" => Type::Array,
+            "array_pattern" => Type::ArrayPattern,
+            "nested_identifier" => Type::NestedIdentifier,
+            "class" => Type::Class,
+            "class_declaration" => Type::ClassDeclaration,
+            "class_heritage" => Type::ClassHeritage,
+            "class_body" => Type::ClassBody,
+            "class_static_block" => Type::ClassStaticBlock,
+            "function" => Type::Function,
+            "function_declaration" => Type::FunctionDeclaration,
+            "generator_function" => Type::GeneratorFunction,
+            "generator_function_declaration" => Type::GeneratorFunctionDeclaration,
+            "arrow_function" => Type::ArrowFunction,
+            "call_expression" => Type::CallExpression,
+            "new_expression" => Type::NewExpression,
+            "await_expression" => Type::AwaitExpression,
+            "member_expression" => Type::MemberExpression,
+            "subscript_expression" => Type::SubscriptExpression,
+            "assignment_expression" => Type::AssignmentExpression,
+            "augmented_assignment_expression" => Type::AugmentedAssignmentExpression,
+            "spread_element" => Type::SpreadElement,
+            "ternary_expression" => Type::TernaryExpression,
+            "binary_expression" => Type::BinaryExpression,
+            "unary_expression" => Type::UnaryExpression,
+            "update_expression" => Type::UpdateExpression,
+            "sequence_expression" => Type::SequenceExpression,
+            "instantiation_expression" => Type::InstantiationExpression,
+            "string" => Type::String,
+            "string_fragment" => Type::StringFragment,
+            "escape_sequence" => Type::EscapeSequence,
+            "comment" => Type::Comment,
+            "template_string" => Type::TemplateString,
+            "template_substitution" => Type::TemplateSubstitution,
+            "regex" => Type::Regex,
+            "regex_pattern" => Type::RegexPattern,
+            "regex_flags" => Type::RegexFlags,
+            "number" => Type::Number,
+            "identifier" => Type::Identifier,
+            "property_identifier" => Type::PropertyIdentifier,
+            "shorthand_property_identifier" => Type::ShorthandPropertyIdentifier,
+            "shorthand_property_identifier_pattern" => Type::ShorthandPropertyIdentifierPattern,
+            "private_property_identifier" => Type::PrivatePropertyIdentifier,
+            "statement_identifier" => Type::StatementIdentifier,
+            "type_identifier" => Type::TypeIdentifier,
+            "this" => Type::This,
+            "super" => Type::Super,
+            "true" => Type::True,
+            "false" => Type::False,
+            "null" => Type::Null,
+            "undefined" => Type::Undefined,
+            "arguments" => Type::Arguments,
+            "decorator" => Type::Decorator,
+            "field_definition" => Type::FieldDefinition,
+            "public_field_definition" => Type::PublicFieldDefinition,
+            "formal_parameters" => Type::FormalParameters,
+            "rest_pattern" => Type::RestPattern,
+            "method_definition" => Type::MethodDefinition,
+            "pair" => Type::Pair,
+            "pair_pattern" => Type::PairPattern,
+            "computed_property_name" => Type::ComputedPropertyName,
+            "meta_property" => Type::MetaProperty,
+            "optional_chain" => Type::OptionalChain,
+            "non_null_expression" => Type::NonNullExpression,
+            "method_signature" => Type::MethodSignature,
+            "abstract_method_signature" => Type::AbstractMethodSignature,
+            "function_signature" => Type::FunctionSignature,
+            "type_assertion" => Type::TypeAssertion,
+            "as_expression" => Type::AsExpression,
+            "satisfies_expression" => Type::SatisfiesExpression,
+            "internal_module" => Type::InternalModule,
+            "module" => Type::Module,
+            "ambient_declaration" => Type::AmbientDeclaration,
+            "abstract_class_declaration" => Type::AbstractClassDeclaration,
+            "accessibility_modifier" => Type::AccessibilityModifier,
+            "override_modifier" => Type::OverrideModifier,
+            "required_parameter" => Type::RequiredParameter,
+            "optional_parameter" => Type::OptionalParameter,
+            "type_annotation" => Type::TypeAnnotation,
+            "omitting_type_annotation" => Type::OmittingTypeAnnotation,
+            "opting_type_annotation" => Type::OptingTypeAnnotation,
+            "adding_type_annotation" => Type::AddingTypeAnnotation,
+            "asserts" => Type::Asserts,
+            "asserts_annotation" => Type::AssertsAnnotation,
+            "optional_type" => Type::OptionalType,
+            "rest_type" => Type::RestType,
+            "type_predicate" => Type::TypePredicate,
+            "type_predicate_annotation" => Type::TypePredicateAnnotation,
+            "type_query" => Type::TypeQuery,
+            "index_type_query" => Type::IndexTypeQuery,
+            "lookup_type" => Type::LookupType,
+            "mapped_type_clause" => Type::MappedTypeClause,
+            "literal_type" => Type::LiteralType,
+            "existential_type" => Type::ExistentialType,
+            "flow_maybe_type" => Type::FlowMaybeType,
+            "parenthesized_type" => Type::ParenthesizedType,
+            "predefined_type" => Type::PredefinedType,
+            "type_arguments" => Type::TypeArguments,
+            "object_type" => Type::ObjectType,
+            "call_signature" => Type::CallSignature,
+            "property_signature" => Type::PropertySignature,
+            "type_parameters" => Type::TypeParameters,
+            "type_parameter" => Type::TypeParameter,
+            "default_type" => Type::DefaultType,
+            "constraint" => Type::Constraint,
+            "construct_signature" => Type::ConstructSignature,
+            "index_signature" => Type::IndexSignature,
+            "array_type" => Type::ArrayType,
+            "tuple_type" => Type::TupleType,
+            "optional_tuple_parameter" => Type::OptionalTupleParameter,
+            "readonly_type" => Type::ReadonlyType,
+            "union_type" => Type::UnionType,
+            "intersection_type" => Type::IntersectionType,
+            "function_type" => Type::FunctionType,
+            "constructor_type" => Type::ConstructorType,
+            "infer_type" => Type::InferType,
+            "conditional_type" => Type::ConditionalType,
+            "generic_type" => Type::GenericType,
+            "template_literal_type" => Type::TemplateLiteralType,
+            "template_type" => Type::TemplateType,
+            "enum_declaration" => Type::EnumDeclaration,
+            "enum_body" => Type::EnumBody,
+            "enum_assignment" => Type::EnumAssignment,
+            "type_alias_declaration" => Type::TypeAliasDeclaration,
+            "interface_declaration" => Type::InterfaceDeclaration,
+            "extends_clause" => Type::ExtendsClause,
+            "extends_type_clause" => Type::ExtendsTypeClause,
+            "implements_clause" => Type::ImplementsClause,
+            "nested_type_identifier" => Type::NestedTypeIdentifier,
+            "jsx_element" => Type::JsxElement,
+            "jsx_self_closing_element" => Type::JsxSelfClosingElement,
+            "jsx_opening_element" => Type::JsxOpeningElement,
+            "jsx_closing_element" => Type::JsxClosingElement,
+            "jsx_text" => Type::JsxText,
+            "jsx_expression" => Type::JsxExpression,
+            "jsx_attribute" => Type::JsxAttribute,
+            "jsx_namespace_name" => Type::JsxNamespaceName,
+            "html_character_reference" => Type::HtmlCharacterReference,
+            "Spaces" => Type::Spaces,
+            "Directory" => Type::Directory,
+            "ERROR" => Type::ERROR,
+            _ => return None,
+        })
+    }
+    /// tokens can share their kind with a named node eg. the `import` keyword and `import(...)`,
+    /// use this one for anonymous nodes.
+    pub fn from_str_anonymous(t: &str) -> Option<Type> {
+        Some(match t {
+            "import" => Type::TS0,
+            "class" => Type::TS1,
+            "function" => Type::TS2,
+            "module" => Type::TS3,
+            "asserts" => Type::TS4,
+            "number" => Type::TS5,
+            "string" => Type::TS6,
+            "object" => Type::TS7,
+            t => return Self::from_str(t),
+        })
+    }
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Type::End => "end",
+            Type::Export => "export",
+            Type::Star => "*",
+            Type::Default => "default",
+            Type::As => "as",
+            Type::LBrace => "{",
+            Type::RBrace => "}",
+            Type::Comma => ",",
+            Type::From => "from",
+            Type::TS0 => "import",
+            Type::Typeof => "typeof",
+            Type::Type => "type",
+            Type::SemiColon => ";",
+            Type::With => "with",
+            Type::Assert => "assert",
+            Type::Var => "var",
+            Type::Let => "let",
+            Type::Const => "const",
+            Type::Bang => "!",
+            Type::Else => "else",
+            Type::If => "if",
+            Type::Switch => "switch",
+            Type::For => "for",
+            Type::LParen => "(",
+            Type::RParen => ")",
+            Type::Await => "await",
+            Type::In => "in",
+            Type::Of => "of",
+            Type::While => "while",
+            Type::Do => "do",
+            Type::Try => "try",
+            Type::Return => "return",
+            Type::Break => "break",
+            Type::Continue => "continue",
+            Type::Debugger => "debugger",
+            Type::Throw => "throw",
+            Type::Case => "case",
+            Type::Catch => "catch",
+            Type::Finally => "finally",
+            Type::Yield => "yield",
+            Type::Eq => "=",
+            Type::LBracket => "[",
+            Type::RBracket => "]",
+            Type::LT => "<",
+            Type::GT => ">",
+            Type::Slash => "/",
+            Type::Dot => ".",
+            Type::TS1 => "class",
+            Type::Async => "async",
+            Type::TS2 => "function",
+            Type::EqGT => "=>",
+            Type::QMarkDot => "?.",
+            Type::New => "new",
+            Type::PlusEq => "+=",
+            Type::DashEq => "-=",
+            Type::StarEq => "*=",
+            Type::SlashEq => "/=",
+            Type::PercentEq => "%=",
+            Type::CaretEq => "^=",
+            Type::AmpEq => "&=",
+            Type::PipeEq => "|=",
+            Type::GtGtEq => ">>=",
+            Type::GtGtGtEq => ">>>=",
+            Type::LtLtEq => "<<=",
+            Type::StarStarEq => "**=",
+            Type::AmpAmpEq => "&&=",
+            Type::PipePipeEq => "||=",
+            Type::QMarkQMarkEq => "??=",
+            Type::DotDotDot => "...",
+            Type::AmpAmp => "&&",
+            Type::PipePipe => "||",
+            Type::GtGt => ">>",
+            Type::GtGtGt => ">>>",
+            Type::LtLt => "<<",
+            Type::Amp => "&",
+            Type::Caret => "^",
+            Type::Pipe => "|",
+            Type::Plus => "+",
+            Type::Dash => "-",
+            Type::Percent => "%",
+            Type::StarStar => "**",
+            Type::LTEq => "<=",
+            Type::EqEq => "==",
+            Type::EqEqEq => "===",
+            Type::BangEq => "!=",
+            Type::BangEqEq => "!==",
+            Type::GTEq => ">=",
+            Type::QMarkQMark => "??",
+            Type::Instanceof => "instanceof",
+            Type::Tilde => "~",
+            Type::Void => "void",
+            Type::Delete => "delete",
+            Type::PlusPlus => "++",
+            Type::DashDash => "--",
+            Type::DQuote => "\"",
+            Type::SQuote => "'",
+            Type::BQuote => "`",
+            Type::DollarLBrace => "${",
+            Type::Static => "static",
+            Type::Get => "get",
+            Type::Set => "set",
+            Type::Declare => "declare",
+            Type::Namespace => "namespace",
+            Type::TS3 => "module",
+            Type::Global => "global",
+            Type::QMark => "?",
+            Type::Colon => ":",
+            Type::Abstract => "abstract",
+            Type::Private => "private",
+            Type::Protected => "protected",
+            Type::Public => "public",
+            Type::Override => "override",
+            Type::Readonly => "readonly",
+            Type::Require => "require",
+            Type::Enum => "enum",
+            Type::Interface => "interface",
+            Type::Implements => "implements",
+            Type::Extends => "extends",
+            Type::Keyof => "keyof",
+            Type::Infer => "infer",
+            Type::Is => "is",
+            Type::TS4 => "asserts",
+            Type::Any => "any",
+            Type::TS5 => "number",
+            Type::Boolean => "boolean",
+            Type::TS6 => "string",
+            Type::Symbol => "symbol",
+            Type::TS7 => "object",
+            Type::Never => "never",
+            Type::Unknown => "unknown",
+            Type::Unique => "unique",
+            Type::LBracePipe => "{|",
+            Type::PipeRBrace => "|}",
+            Type::DashQMarkColon => "-?:",
+            Type::PlusQMarkColon => "+?:",
+            Type::QMarkColon => "?:",
+            Type::Target => "target",
+            Type::Meta => "meta",
+            Type::At => "@",
+            Type::Accessor => "accessor",
+            Type::Satisfies => "satisfies",
+            Type::Hash => "#",
+            Type::Program => "program",
+            Type::HashBangLine => "hash_bang_line",
+            Type::ExportStatement => "export_statement",
+            Type::ExportClause => "export_clause",
+            Type::ExportSpecifier => "export_specifier",
+            Type::NamespaceExport => "namespace_export",
+            Type::Import => "import",
+            Type::ImportStatement => "import_statement",
+            Type::ImportClause => "import_clause",
+            Type::NamespaceImport => "namespace_import",
+            Type::NamedImports => "named_imports",
+            Type::ImportSpecifier => "import_specifier",
+            Type::ImportRequireClause => "import_require_clause",
+            Type::ImportAlias => "import_alias",
+            Type::ExpressionStatement => "expression_statement",
+            Type::VariableDeclaration => "variable_declaration",
+            Type::LexicalDeclaration => "lexical_declaration",
+            Type::VariableDeclarator => "variable_declarator",
+            Type::StatementBlock => "statement_block",
+            Type::ElseClause => "else_clause",
+            Type::IfStatement => "if_statement",
+            Type::SwitchStatement => "switch_statement",
+            Type::ForStatement => "for_statement",
+            Type::ForInStatement => "for_in_statement",
+            Type::WhileStatement => "while_statement",
+            Type::DoStatement => "do_statement",
+            Type::TryStatement => "try_statement",
+            Type::WithStatement => "with_statement",
+            Type::BreakStatement => "break_statement",
+            Type::ContinueStatement => "continue_statement",
+            Type::DebuggerStatement => "debugger_statement",
+            Type::ReturnStatement => "return_statement",
+            Type::ThrowStatement => "throw_statement",
+            Type::EmptyStatement => "empty_statement",
+            Type::LabeledStatement => "labeled_statement",
+            Type::SwitchBody => "switch_body",
+            Type::SwitchCase => "switch_case",
+            Type::SwitchDefault => "switch_default",
+            Type::CatchClause => "catch_clause",
+            Type::FinallyClause => "finally_clause",
+            Type::ParenthesizedExpression => "parenthesized_expression",
+            Type::YieldExpression => "yield_expression",
+            Type::Object => "object",
+            Type::ObjectPattern => "object_pattern",
+            Type::AssignmentPattern => "assignment_pattern",
+            Type::ObjectAssignmentPattern => "object_assignment_pattern",
+            Type::Array => "array",
+            Type::ArrayPattern => "array_pattern",
+            Type::NestedIdentifier => "nested_identifier",
+            Type::Class => "class",
+            Type::ClassDeclaration => "class_declaration",
+            Type::ClassHeritage => "class_heritage",
+            Type::ClassBody => "class_body",
+            Type::ClassStaticBlock => "class_static_block",
+            Type::Function => "function",
+            Type::FunctionDeclaration => "function_declaration",
+            Type::GeneratorFunction => "generator_function",
+            Type::GeneratorFunctionDeclaration => "generator_function_declaration",
+            Type::ArrowFunction => "arrow_function",
+            Type::CallExpression => "call_expression",
+            Type::NewExpression => "new_expression",
+            Type::AwaitExpression => "await_expression",
+            Type::MemberExpression => "member_expression",
+            Type::SubscriptExpression => "subscript_expression",
+            Type::AssignmentExpression => "assignment_expression",
+            Type::AugmentedAssignmentExpression => "augmented_assignment_expression",
+            Type::SpreadElement => "spread_element",
+            Type::TernaryExpression => "ternary_expression",
+            Type::BinaryExpression => "binary_expression",
+            Type::UnaryExpression => "unary_expression",
+            Type::UpdateExpression => "update_expression",
+            Type::SequenceExpression => "sequence_expression",
+            Type::InstantiationExpression => "instantiation_expression",
+            Type::String => "string",
+            Type::StringFragment => "string_fragment",
+            Type::EscapeSequence => "escape_sequence",
+            Type::Comment => "comment",
+            Type::TemplateString => "template_string",
+            Type::TemplateSubstitution => "template_substitution",
+            Type::Regex => "regex",
+            Type::RegexPattern => "regex_pattern",
+            Type::RegexFlags => "regex_flags",
+            Type::Number => "number",
+            Type::Identifier => "identifier",
+            Type::PropertyIdentifier => "property_identifier",
+            Type::ShorthandPropertyIdentifier => "shorthand_property_identifier",
+            Type::ShorthandPropertyIdentifierPattern => "shorthand_property_identifier_pattern",
+            Type::PrivatePropertyIdentifier => "private_property_identifier",
+            Type::StatementIdentifier => "statement_identifier",
+            Type::TypeIdentifier => "type_identifier",
+            Type::This => "this",
+            Type::Super => "super",
+            Type::True => "true",
+            Type::False => "false",
+            Type::Null => "null",
+            Type::Undefined => "undefined",
+            Type::Arguments => "arguments",
+            Type::Decorator => "decorator",
+            Type::FieldDefinition => "field_definition",
+            Type::PublicFieldDefinition => "public_field_definition",
+            Type::FormalParameters => "formal_parameters",
+            Type::RestPattern => "rest_pattern",
+            Type::MethodDefinition => "method_definition",
+            Type::Pair => "pair",
+            Type::PairPattern => "pair_pattern",
+            Type::ComputedPropertyName => "computed_property_name",
+            Type::MetaProperty => "meta_property",
+            Type::OptionalChain => "optional_chain",
+            Type::NonNullExpression => "non_null_expression",
+            Type::MethodSignature => "method_signature",
+            Type::AbstractMethodSignature => "abstract_method_signature",
+            Type::FunctionSignature => "function_signature",
+            Type::TypeAssertion => "type_assertion",
+            Type::AsExpression => "as_expression",
+            Type::SatisfiesExpression => "satisfies_expression",
+            Type::InternalModule => "internal_module",
+            Type::Module => "module",
+            Type::AmbientDeclaration => "ambient_declaration",
+            Type::AbstractClassDeclaration => "abstract_class_declaration",
+            Type::AccessibilityModifier => "accessibility_modifier",
+            Type::OverrideModifier => "override_modifier",
+            Type::RequiredParameter => "required_parameter",
+            Type::OptionalParameter => "optional_parameter",
+            Type::TypeAnnotation => "type_annotation",
+            Type::OmittingTypeAnnotation => "omitting_type_annotation",
+            Type::OptingTypeAnnotation => "opting_type_annotation",
+            Type::AddingTypeAnnotation => "adding_type_annotation",
+            Type::Asserts => "asserts",
+            Type::AssertsAnnotation => "asserts_annotation",
+            Type::OptionalType => "optional_type",
+            Type::RestType => "rest_type",
+            Type::TypePredicate => "type_predicate",
+            Type::TypePredicateAnnotation => "type_predicate_annotation",
+            Type::TypeQuery => "type_query",
+            Type::IndexTypeQuery => "index_type_query",
+            Type::LookupType => "lookup_type",
+            Type::MappedTypeClause => "mapped_type_clause",
+            Type::LiteralType => "literal_type",
+            Type::ExistentialType => "existential_type",
+            Type::FlowMaybeType => "flow_maybe_type",
+            Type::ParenthesizedType => "parenthesized_type",
+            Type::PredefinedType => "predefined_type",
+            Type::TypeArguments => "type_arguments",
+            Type::ObjectType => "object_type",
+            Type::CallSignature => "call_signature",
+            Type::PropertySignature => "property_signature",
+            Type::TypeParameters => "type_parameters",
+            Type::TypeParameter => "type_parameter",
+            Type::DefaultType => "default_type",
+            Type::Constraint => "constraint",
+            Type::ConstructSignature => "construct_signature",
+            Type::IndexSignature => "index_signature",
+            Type::ArrayType => "array_type",
+            Type::TupleType => "tuple_type",
+            Type::OptionalTupleParameter => "optional_tuple_parameter",
+            Type::ReadonlyType => "readonly_type",
+            Type::UnionType => "union_type",
+            Type::IntersectionType => "intersection_type",
+            Type::FunctionType => "function_type",
+            Type::ConstructorType => "constructor_type",
+            Type::InferType => "infer_type",
+            Type::ConditionalType => "conditional_type",
+            Type::GenericType => "generic_type",
+            Type::TemplateLiteralType => "template_literal_type",
+            Type::TemplateType => "template_type",
+            Type::EnumDeclaration => "enum_declaration",
+            Type::EnumBody => "enum_body",
+            Type::EnumAssignment => "enum_assignment",
+            Type::TypeAliasDeclaration => "type_alias_declaration",
+            Type::InterfaceDeclaration => "interface_declaration",
+            Type::ExtendsClause => "extends_clause",
+            Type::ExtendsTypeClause => "extends_type_clause",
+            Type::ImplementsClause => "implements_clause",
+            Type::NestedTypeIdentifier => "nested_type_identifier",
+            Type::JsxElement => "jsx_element",
+            Type::JsxSelfClosingElement => "jsx_self_closing_element",
+            Type::JsxOpeningElement => "jsx_opening_element",
+            Type::JsxClosingElement => "jsx_closing_element",
+            Type::JsxText => "jsx_text",
+            Type::JsxExpression => "jsx_expression",
+            Type::JsxAttribute => "jsx_attribute",
+            Type::JsxNamespaceName => "jsx_namespace_name",
+            Type::HtmlCharacterReference => "html_character_reference",
+            Type::Spaces => "Spaces",
+            Type::Directory => "Directory",
+            Type::ERROR => "ERROR",
+        }
+    }
+}
+
+const S_T_L: &'static [Type] = &[
+    Type::End,
+    Type::Export,
+    Type::Star,
+    Type::Default,
+    Type::As,
+    Type::LBrace,
+    Type::RBrace,
+    Type::Comma,
+    Type::From,
+    Type::TS0,
+    Type::Typeof,
+    Type::Type,
+    Type::SemiColon,
+    Type::With,
+    Type::Assert,
+    Type::Var,
+    Type::Let,
+    Type::Const,
+    Type::Bang,
+    Type::Else,
+    Type::If,
+    Type::Switch,
+    Type::For,
+    Type::LParen,
+    Type::RParen,
+    Type::Await,
+    Type::In,
+    Type::Of,
+    Type::While,
+    Type::Do,
+    Type::Try,
+    Type::Return,
+    Type::Break,
+    Type::Continue,
+    Type::Debugger,
+    Type::Throw,
+    Type::Case,
+    Type::Catch,
+    Type::Finally,
+    Type::Yield,
+    Type::Eq,
+    Type::LBracket,
+    Type::RBracket,
+    Type::LT,
+    Type::GT,
+    Type::Slash,
+    Type::Dot,
+    Type::TS1,
+    Type::Async,
+    Type::TS2,
+    Type::EqGT,
+    Type::QMarkDot,
+    Type::New,
+    Type::PlusEq,
+    Type::DashEq,
+    Type::StarEq,
+    Type::SlashEq,
+    Type::PercentEq,
+    Type::CaretEq,
+    Type::AmpEq,
+    Type::PipeEq,
+    Type::GtGtEq,
+    Type::GtGtGtEq,
+    Type::LtLtEq,
+    Type::StarStarEq,
+    Type::AmpAmpEq,
+    Type::PipePipeEq,
+    Type::QMarkQMarkEq,
+    Type::DotDotDot,
+    Type::AmpAmp,
+    Type::PipePipe,
+    Type::GtGt,
+    Type::GtGtGt,
+    Type::LtLt,
+    Type::Amp,
+    Type::Caret,
+    Type::Pipe,
+    Type::Plus,
+    Type::Dash,
+    Type::Percent,
+    Type::StarStar,
+    Type::LTEq,
+    Type::EqEq,
+    Type::EqEqEq,
+    Type::BangEq,
+    Type::BangEqEq,
+    Type::GTEq,
+    Type::QMarkQMark,
+    Type::Instanceof,
+    Type::Tilde,
+    Type::Void,
+    Type::Delete,
+    Type::PlusPlus,
+    Type::DashDash,
+    Type::DQuote,
+    Type::SQuote,
+    Type::BQuote,
+    Type::DollarLBrace,
+    Type::Static,
+    Type::Get,
+    Type::Set,
+    Type::Declare,
+    Type::Namespace,
+    Type::TS3,
+    Type::Global,
+    Type::QMark,
+    Type::Colon,
+    Type::Abstract,
+    Type::Private,
+    Type::Protected,
+    Type::Public,
+    Type::Override,
+    Type::Readonly,
+    Type::Require,
+    Type::Enum,
+    Type::Interface,
+    Type::Implements,
+    Type::Extends,
+    Type::Keyof,
+    Type::Infer,
+    Type::Is,
+    Type::TS4,
+    Type::Any,
+    Type::TS5,
+    Type::Boolean,
+    Type::TS6,
+    Type::Symbol,
+    Type::TS7,
+    Type::Never,
+    Type::Unknown,
+    Type::Unique,
+    Type::LBracePipe,
+    Type::PipeRBrace,
+    Type::DashQMarkColon,
+    Type::PlusQMarkColon,
+    Type::QMarkColon,
+    Type::Target,
+    Type::Meta,
+    Type::At,
+    Type::Accessor,
+    Type::Satisfies,
+    Type::Hash,
+    Type::Program,
+    Type::HashBangLine,
+    Type::ExportStatement,
+    Type::ExportClause,
+    Type::ExportSpecifier,
+    Type::NamespaceExport,
+    Type::Import,
+    Type::ImportStatement,
+    Type::ImportClause,
+    Type::NamespaceImport,
+    Type::NamedImports,
+    Type::ImportSpecifier,
+    Type::ImportRequireClause,
+    Type::ImportAlias,
+    Type::ExpressionStatement,
+    Type::VariableDeclaration,
+    Type::LexicalDeclaration,
+    Type::VariableDeclarator,
+    Type::StatementBlock,
+    Type::ElseClause,
+    Type::IfStatement,
+    Type::SwitchStatement,
+    Type::ForStatement,
+    Type::ForInStatement,
+    Type::WhileStatement,
+    Type::DoStatement,
+    Type::TryStatement,
+    Type::WithStatement,
+    Type::BreakStatement,
+    Type::ContinueStatement,
+    Type::DebuggerStatement,
+    Type::ReturnStatement,
+    Type::ThrowStatement,
+    Type::EmptyStatement,
+    Type::LabeledStatement,
+    Type::SwitchBody,
+    Type::SwitchCase,
+    Type::SwitchDefault,
+    Type::CatchClause,
+    Type::FinallyClause,
+    Type::ParenthesizedExpression,
+    Type::YieldExpression,
+    Type::Object,
+    Type::ObjectPattern,
+    Type::AssignmentPattern,
+    Type::ObjectAssignmentPattern,
+    Type::Array,
+    Type::ArrayPattern,
+    Type::NestedIdentifier,
+    Type::Class,
+    Type::ClassDeclaration,
+    Type::ClassHeritage,
+    Type::ClassBody,
+    Type::ClassStaticBlock,
+    Type::Function,
+    Type::FunctionDeclaration,
+    Type::GeneratorFunction,
+    Type::GeneratorFunctionDeclaration,
+    Type::ArrowFunction,
+    Type::CallExpression,
+    Type::NewExpression,
+    Type::AwaitExpression,
+    Type::MemberExpression,
+    Type::SubscriptExpression,
+    Type::AssignmentExpression,
+    Type::AugmentedAssignmentExpression,
+    Type::SpreadElement,
+    Type::TernaryExpression,
+    Type::BinaryExpression,
+    Type::UnaryExpression,
+    Type::UpdateExpression,
+    Type::SequenceExpression,
+    Type::InstantiationExpression,
+    Type::String,
+    Type::StringFragment,
+    Type::EscapeSequence,
+    Type::Comment,
+    Type::TemplateString,
+    Type::TemplateSubstitution,
+    Type::Regex,
+    Type::RegexPattern,
+    Type::RegexFlags,
+    Type::Number,
+    Type::Identifier,
+    Type::PropertyIdentifier,
+    Type::ShorthandPropertyIdentifier,
+    Type::ShorthandPropertyIdentifierPattern,
+    Type::PrivatePropertyIdentifier,
+    Type::StatementIdentifier,
+    Type::TypeIdentifier,
+    Type::This,
+    Type::Super,
+    Type::True,
+    Type::False,
+    Type::Null,
+    Type::Undefined,
+    Type::Arguments,
+    Type::Decorator,
+    Type::FieldDefinition,
+    Type::PublicFieldDefinition,
+    Type::FormalParameters,
+    Type::RestPattern,
+    Type::MethodDefinition,
+    Type::Pair,
+    Type::PairPattern,
+    Type::ComputedPropertyName,
+    Type::MetaProperty,
+    Type::OptionalChain,
+    Type::NonNullExpression,
+    Type::MethodSignature,
+    Type::AbstractMethodSignature,
+    Type::FunctionSignature,
+    Type::TypeAssertion,
+    Type::AsExpression,
+    Type::SatisfiesExpression,
+    Type::InternalModule,
+    Type::Module,
+    Type::AmbientDeclaration,
+    Type::AbstractClassDeclaration,
+    Type::AccessibilityModifier,
+    Type::OverrideModifier,
+    Type::RequiredParameter,
+    Type::OptionalParameter,
+    Type::TypeAnnotation,
+    Type::OmittingTypeAnnotation,
+    Type::OptingTypeAnnotation,
+    Type::AddingTypeAnnotation,
+    Type::Asserts,
+    Type::AssertsAnnotation,
+    Type::OptionalType,
+    Type::RestType,
+    Type::TypePredicate,
+    Type::TypePredicateAnnotation,
+    Type::TypeQuery,
+    Type::IndexTypeQuery,
+    Type::LookupType,
+    Type::MappedTypeClause,
+    Type::LiteralType,
+    Type::ExistentialType,
+    Type::FlowMaybeType,
+    Type::ParenthesizedType,
+    Type::PredefinedType,
+    Type::TypeArguments,
+    Type::ObjectType,
+    Type::CallSignature,
+    Type::PropertySignature,
+    Type::TypeParameters,
+    Type::TypeParameter,
+    Type::DefaultType,
+    Type::Constraint,
+    Type::ConstructSignature,
+    Type::IndexSignature,
+    Type::ArrayType,
+    Type::TupleType,
+    Type::OptionalTupleParameter,
+    Type::ReadonlyType,
+    Type::UnionType,
+    Type::IntersectionType,
+    Type::FunctionType,
+    Type::ConstructorType,
+    Type::InferType,
+    Type::ConditionalType,
+    Type::GenericType,
+    Type::TemplateLiteralType,
+    Type::TemplateType,
+    Type::EnumDeclaration,
+    Type::EnumBody,
+    Type::EnumAssignment,
+    Type::TypeAliasDeclaration,
+    Type::InterfaceDeclaration,
+    Type::ExtendsClause,
+    Type::ExtendsTypeClause,
+    Type::ImplementsClause,
+    Type::NestedTypeIdentifier,
+    Type::JsxElement,
+    Type::JsxSelfClosingElement,
+    Type::JsxOpeningElement,
+    Type::JsxClosingElement,
+    Type::JsxText,
+    Type::JsxExpression,
+    Type::JsxAttribute,
+    Type::JsxNamespaceName,
+    Type::HtmlCharacterReference,
+    Type::Spaces,
+    Type::Directory,
+    Type::ERROR,
+];
+
+static ANY_T_L: [AnyType; S_T_L.len()] = hyper_ast::types::any_types(S_T_L);
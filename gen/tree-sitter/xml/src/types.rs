@@ -86,6 +86,31 @@ mod legion_impls {
             }
         }
     }
+
+    impl hyper_ast::store::nodes::legion::persist::Persist for Type {
+        fn save(
+            &self,
+            w: &mut dyn std::io::Write,
+            _: &hyper_ast::store::nodes::legion::persist::SavedIds,
+        ) -> std::io::Result<()> {
+            hyper_ast::store::persist::write_u16(w, *self as u16)
+        }
+
+        fn load(
+            r: &mut dyn std::io::Read,
+            _: &hyper_ast::store::nodes::legion::persist::LoadedIds,
+        ) -> std::io::Result<Self> {
+            let t = hyper_ast::store::persist::read_u16(r)?;
+            if t < COUNT {
+                Ok(Type::resolve(t))
+            } else {
+                Err(hyper_ast::store::persist::invalid_data(format!(
+                    "invalid type {}",
+                    t
+                )))
+            }
+        }
+    }
 }
 pub trait XmlEnabledTypeStore<T>: TypeStore<T> {
     const LANG: u16;
@@ -170,11 +195,12 @@ impl LangRef<AnyType> for Xml {
     }
 
     fn make(&self, t: u16) -> &'static AnyType {
-        todo!()
+        &ANY_T_L[t as usize]
     }
 
     fn to_u16(&self, t: AnyType) -> u16 {
-        todo!()
+        let t = t.as_any().downcast_ref::<Type>().unwrap();
+        *t as u16
     }
 }
 impl HyperType for Type {
@@ -1108,3 +1134,5 @@ const S_T_L: &'static [Type] = &[
     Type::Directory,
     Type::ERROR,
 ];
+
+static ANY_T_L: [AnyType; S_T_L.len()] = hyper_ast::types::any_types(S_T_L);
@@ -77,6 +77,25 @@ where
     }
 }
 
+impl crate::store::nodes::legion::persist::Persist for Mcc {
+    fn save(
+        &self,
+        w: &mut dyn std::io::Write,
+        _: &crate::store::nodes::legion::persist::SavedIds,
+    ) -> std::io::Result<()> {
+        crate::store::persist::write_u32(w, self.value)
+    }
+
+    fn load(
+        r: &mut dyn std::io::Read,
+        _: &crate::store::nodes::legion::persist::LoadedIds,
+    ) -> std::io::Result<Self> {
+        Ok(Self {
+            value: crate::store::persist::read_u32(r)?,
+        })
+    }
+}
+
 pub trait MetaData<T> {
     type R;
     fn retrieve(node: &T) -> Self::R;
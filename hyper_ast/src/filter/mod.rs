@@ -49,6 +49,29 @@ impl<T, V: BitViewSized> Default for Bloom<T, V> {
         }
     }
 }
+impl<T, V: BitViewSized> Bloom<T, V> {
+    /// packs the bits, mostly to persist them
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        let mut r = vec![0u8; (self.bits.len() + 7) / 8];
+        for i in self.bits.iter_ones() {
+            r[i / 8] |= 1 << (i % 8);
+        }
+        r
+    }
+    pub(crate) fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let mut r = Self::default();
+        if bytes.len() != (r.bits.len() + 7) / 8 {
+            return None;
+        }
+        for i in 0..r.bits.len() {
+            if bytes[i / 8] & (1 << (i % 8)) != 0 {
+                r.bits.set(i, true);
+            }
+        }
+        Some(r)
+    }
+}
+
 impl<T, V: BitViewSized> Debug for Bloom<T, V> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Bloom").field("bits", &self.bits).finish()
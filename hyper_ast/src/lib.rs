@@ -11,6 +11,7 @@ pub mod hashed;
 pub mod impact;
 pub mod nodes;
 pub mod position;
+pub mod query;
 pub mod store;
 pub mod tree_gen;
 pub mod types;
@@ -0,0 +1,403 @@
+//! Tree-sitter queries matched directly on HyperAST nodes, without reparsing the text.
+//!
+//! Matches are memoized per node identifier,
+//! as subtrees are shared between versions, querying many commits mostly reuses
+//! the matches found in the previous ones.
+//!
+//! Node kinds are compared to the display of the types of the nodes, spaces are ignored,
+//! and the text used by predicates is the concatenation of the labels of the leaves.
+use std::{collections::HashMap, hash::Hash};
+
+use num::{cast, PrimInt};
+
+use crate::types::{
+    HyperAST, HyperType, IterableChildren, LabelStore, Labeled, NodeStore, TypeStore, WithChildren,
+};
+
+mod parse;
+
+pub use parse::QueryError;
+use parse::{Arg, Child, Kind, Parser, Pattern, Predicate, Quantifier, Root};
+
+/// A query made of patterns, their captures and predicates.
+///
+/// Supports the syntax of tree-sitter queries except fields, anchors and sibling sequences,
+/// the supported predicates are `#eq?`, `#match?` and their negations.
+#[derive(Debug, Clone)]
+pub struct Query {
+    patterns: Vec<Root>,
+    capture_names: Vec<String>,
+}
+
+impl Query {
+    pub fn new(source: &str) -> Result<Self, QueryError> {
+        let mut capture_names = vec![];
+        let patterns = Parser::parse(source, &mut capture_names)?;
+        Ok(Self {
+            patterns,
+            capture_names,
+        })
+    }
+
+    pub fn pattern_count(&self) -> usize {
+        self.patterns.len()
+    }
+
+    pub fn capture_names(&self) -> &[String] {
+        &self.capture_names
+    }
+
+    pub fn capture_index_for_name(&self, name: &str) -> Option<u32> {
+        self.capture_names
+            .iter()
+            .position(|x| x == name)
+            .map(|i| i as u32)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryCapture<IdN, Idx> {
+    pub index: u32,
+    pub node: IdN,
+    /// path from the queried root
+    pub path: Vec<Idx>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryMatch<IdN, Idx> {
+    pub pattern_index: usize,
+    pub captures: Vec<QueryCapture<IdN, Idx>>,
+}
+
+/// Matches found in a subtree.
+#[derive(Debug)]
+struct Summary<IdN, Idx> {
+    /// matches of patterns rooted at the node, with paths from the node
+    own: Vec<QueryMatch<IdN, Idx>>,
+    /// children containing matches
+    children: Vec<Idx>,
+}
+
+/// A [`Query`] and the matches it already found, per node identifier.
+pub struct MemoizedQuery<IdN, Idx> {
+    query: Query,
+    cache: HashMap<IdN, Summary<IdN, Idx>>,
+}
+
+impl<IdN: Clone + Eq + Hash, Idx: PrimInt> MemoizedQuery<IdN, Idx> {
+    pub fn new(query: Query) -> Self {
+        Self {
+            query,
+            cache: HashMap::new(),
+        }
+    }
+
+    pub fn query(&self) -> &Query {
+        &self.query
+    }
+
+    /// Number of nodes already queried.
+    pub fn memoized_count(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// Matches of the query in the subtree of `root`, in pre-order.
+    pub fn matches<'store, HAST>(
+        &mut self,
+        stores: &'store HAST,
+        root: &IdN,
+    ) -> Vec<QueryMatch<IdN, Idx>>
+    where
+        HAST: HyperAST<'store, IdN = IdN, Idx = Idx>,
+    {
+        self.summarize(stores, root);
+        let mut matches = vec![];
+        self.collect(stores, root, &mut vec![], &mut matches);
+        matches
+    }
+
+    /// returns true if the subtree of `id` contains matches
+    fn summarize<'store, HAST>(&mut self, stores: &'store HAST, id: &IdN) -> bool
+    where
+        HAST: HyperAST<'store, IdN = IdN, Idx = Idx>,
+    {
+        if let Some(summary) = self.cache.get(id) {
+            return !summary.own.is_empty() || !summary.children.is_empty();
+        }
+        let node = stores.node_store().resolve(id);
+        let mut children = vec![];
+        for (i, x) in children_of::<HAST>(&node).into_iter().enumerate() {
+            if self.summarize(stores, &x) {
+                children.push(cast(i).unwrap());
+            }
+        }
+        let matcher = Matcher {
+            stores,
+            query: &self.query,
+        };
+        let own = matcher.matches_at(id);
+        let found = !own.is_empty() || !children.is_empty();
+        self.cache.insert(id.clone(), Summary { own, children });
+        found
+    }
+
+    fn collect<'store, HAST>(
+        &self,
+        stores: &'store HAST,
+        id: &IdN,
+        path: &mut Vec<Idx>,
+        matches: &mut Vec<QueryMatch<IdN, Idx>>,
+    ) where
+        HAST: HyperAST<'store, IdN = IdN, Idx = Idx>,
+    {
+        let summary = &self.cache[id];
+        for m in &summary.own {
+            let captures = m
+                .captures
+                .iter()
+                .map(|c| QueryCapture {
+                    index: c.index,
+                    node: c.node.clone(),
+                    path: path.iter().chain(&c.path).copied().collect(),
+                })
+                .collect();
+            matches.push(QueryMatch {
+                pattern_index: m.pattern_index,
+                captures,
+            });
+        }
+        if summary.children.is_empty() {
+            return;
+        }
+        let node = stores.node_store().resolve(id);
+        for i in &summary.children {
+            let x = node.child(i).unwrap();
+            path.push(*i);
+            self.collect(stores, &x, path, matches);
+            path.pop();
+        }
+    }
+}
+
+struct Matcher<'a, 'store, HAST> {
+    stores: &'store HAST,
+    query: &'a Query,
+}
+
+type Captures<IdN, Idx> = Vec<QueryCapture<IdN, Idx>>;
+
+impl<'a, 'store, HAST: HyperAST<'store>> Matcher<'a, 'store, HAST>
+where
+    HAST::IdN: Clone,
+{
+    fn matches_at(&self, id: &HAST::IdN) -> Vec<QueryMatch<HAST::IdN, HAST::Idx>> {
+        let mut matches = vec![];
+        for (pattern_index, root) in self.query.patterns.iter().enumerate() {
+            for captures in self.pattern(&root.pattern, id, &mut vec![]) {
+                if root.predicates.iter().all(|p| self.predicate(p, &captures)) {
+                    matches.push(QueryMatch {
+                        pattern_index,
+                        captures,
+                    });
+                }
+            }
+        }
+        matches
+    }
+
+    /// all the ways `pattern` matches the node `id`, found at `path` from the root of the match
+    fn pattern(
+        &self,
+        pattern: &Pattern,
+        id: &HAST::IdN,
+        path: &mut Vec<HAST::Idx>,
+    ) -> Vec<Captures<HAST::IdN, HAST::Idx>> {
+        let own = |captures: &[u32]| -> Vec<_> {
+            captures
+                .iter()
+                .map(|index| QueryCapture {
+                    index: *index,
+                    node: id.clone(),
+                    path: path.clone(),
+                })
+                .collect()
+        };
+        match pattern {
+            Pattern::Alternation {
+                alternatives,
+                captures,
+            } => {
+                let own = own(captures);
+                let mut result = vec![];
+                for p in alternatives {
+                    for mut c in self.pattern(p, id, path) {
+                        c.splice(0..0, own.iter().cloned());
+                        result.push(c);
+                    }
+                }
+                result
+            }
+            Pattern::Node {
+                kind,
+                children,
+                captures,
+            } => {
+                let node = self.stores.node_store().resolve(id);
+                let t = self.stores.type_store().resolve_type(&node);
+                if t.is_spaces() {
+                    return vec![];
+                }
+                let kind_matches = match kind {
+                    Kind::Any => true,
+                    Kind::AnyNamed => !t.is_syntax(),
+                    Kind::Named(k) | Kind::Anonymous(k) => &t.to_string() == k,
+                };
+                if !kind_matches {
+                    return vec![];
+                }
+                let own = own(captures);
+                if children.is_empty() {
+                    return vec![own];
+                }
+                let cs: Vec<_> = children_of::<HAST>(&node)
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, x)| (cast(i).unwrap(), x))
+                    .filter(|(_, x)| {
+                        let n = self.stores.node_store().resolve(x);
+                        !self.stores.type_store().resolve_type(&n).is_spaces()
+                    })
+                    .collect();
+                let mut result = self.children(children, &cs, path);
+                for c in &mut result {
+                    c.splice(0..0, own.iter().cloned());
+                }
+                result
+            }
+        }
+    }
+
+    /// matches the child patterns in order, other children can be found between them,
+    /// repeated patterns match the longest runs of consecutive children
+    fn children(
+        &self,
+        patterns: &[Child],
+        cs: &[(HAST::Idx, HAST::IdN)],
+        path: &mut Vec<HAST::Idx>,
+    ) -> Vec<Captures<HAST::IdN, HAST::Idx>> {
+        let Some((first, rest)) = patterns.split_first() else {
+            return vec![vec![]];
+        };
+        let mut result = vec![];
+        if let Quantifier::ZeroOrOne | Quantifier::ZeroOrMore = first.quantifier {
+            result.extend(self.children(rest, cs, path));
+        }
+        let mut heads: Vec<_> = cs
+            .iter()
+            .map(|(i, x)| {
+                path.push(*i);
+                let r = self.pattern(&first.pattern, x, path);
+                path.pop();
+                r
+            })
+            .collect();
+        let combine =
+            |result: &mut Vec<_>, heads: Vec<Captures<_, _>>, tails: Vec<Captures<_, _>>| {
+                for h in &heads {
+                    for t in &tails {
+                        result.push(h.iter().chain(t).cloned().collect());
+                    }
+                }
+            };
+        match first.quantifier {
+            Quantifier::One | Quantifier::ZeroOrOne => {
+                for (j, h) in heads.into_iter().enumerate() {
+                    if !h.is_empty() {
+                        let tails = self.children(rest, &cs[j + 1..], path);
+                        combine(&mut result, h, tails);
+                    }
+                }
+            }
+            Quantifier::ZeroOrMore | Quantifier::OneOrMore => {
+                let mut j = 0;
+                while j < heads.len() {
+                    if heads[j].is_empty() {
+                        j += 1;
+                        continue;
+                    }
+                    let mut run = vec![vec![]];
+                    while j < heads.len() && !heads[j].is_empty() {
+                        let h = std::mem::take(&mut heads[j]);
+                        let mut r = vec![];
+                        combine(&mut r, run, h);
+                        run = r;
+                        j += 1;
+                    }
+                    let tails = self.children(rest, &cs[j..], path);
+                    combine(&mut result, run, tails);
+                }
+            }
+        }
+        result
+    }
+
+    fn predicate(&self, predicate: &Predicate, captures: &Captures<HAST::IdN, HAST::Idx>) -> bool {
+        let texts = |index: u32| -> Vec<String> {
+            captures
+                .iter()
+                .filter(|c| c.index == index)
+                .map(|c| self.text(&c.node))
+                .collect()
+        };
+        match predicate {
+            Predicate::Eq {
+                capture,
+                other,
+                negated,
+            } => {
+                let left = texts(*capture);
+                match other {
+                    Arg::String(s) => left.iter().all(|t| (t == s) != *negated),
+                    Arg::Capture(other) => left
+                        .iter()
+                        .zip(texts(*other).iter())
+                        .all(|(t, o)| (t == o) != *negated),
+                }
+            }
+            Predicate::Match {
+                capture,
+                regex,
+                negated,
+            } => texts(*capture)
+                .iter()
+                .all(|t| regex.is_match(t) != *negated),
+        }
+    }
+
+    fn text(&self, id: &HAST::IdN) -> String {
+        let mut text = String::new();
+        self.push_text(id, &mut text);
+        text
+    }
+
+    fn push_text(&self, id: &HAST::IdN, text: &mut String) {
+        let node = self.stores.node_store().resolve(id);
+        let cs = children_of::<HAST>(&node);
+        if !cs.is_empty() {
+            cs.iter().for_each(|x| self.push_text(x, text));
+        } else if let Some(l) = node.try_get_label() {
+            text.push_str(self.stores.label_store().resolve(l));
+        } else {
+            text.push_str(&self.stores.type_store().resolve_type(&node).to_string());
+        }
+    }
+}
+
+fn children_of<'store, HAST: HyperAST<'store>>(node: &HAST::T) -> Vec<HAST::IdN>
+where
+    HAST::IdN: Clone,
+{
+    node.children()
+        .map(|cs| cs.iter_children().cloned().collect())
+        .unwrap_or_default()
+}
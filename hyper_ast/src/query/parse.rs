@@ -0,0 +1,668 @@
+//! Parser of the tree-sitter query language, restricted to what can be matched on a HyperAST.
+//!
+//! Field names, negated fields, anchors and top-level sibling sequences are not supported,
+//! as they cannot be checked without the original tree-sitter tree.
+use std::{fmt::Display, iter::Peekable, str::CharIndices};
+
+use regex::Regex;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryError {
+    Syntax {
+        offset: usize,
+        message: String,
+    },
+    Unsupported {
+        offset: usize,
+        feature: &'static str,
+    },
+    Capture {
+        offset: usize,
+        name: String,
+    },
+    Predicate {
+        offset: usize,
+        name: String,
+    },
+    Regex {
+        offset: usize,
+        message: String,
+    },
+}
+
+impl Display for QueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QueryError::Syntax { offset, message } => {
+                write!(f, "invalid syntax at {}: {}", offset, message)
+            }
+            QueryError::Unsupported { offset, feature } => {
+                write!(f, "unsupported {} at {}", feature, offset)
+            }
+            QueryError::Capture { offset, name } => {
+                write!(f, "unknown capture @{} at {}", name, offset)
+            }
+            QueryError::Predicate { offset, name } => {
+                write!(f, "invalid predicate #{} at {}", name, offset)
+            }
+            QueryError::Regex { offset, message } => {
+                write!(f, "invalid regex at {}: {}", offset, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+#[derive(Debug, Clone)]
+pub(super) enum Pattern {
+    Node {
+        kind: Kind,
+        children: Vec<Child>,
+        captures: Vec<u32>,
+    },
+    Alternation {
+        alternatives: Vec<Pattern>,
+        captures: Vec<u32>,
+    },
+}
+
+impl Pattern {
+    fn captures_mut(&mut self) -> &mut Vec<u32> {
+        match self {
+            Pattern::Node { captures, .. } | Pattern::Alternation { captures, .. } => captures,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum Kind {
+    /// `_`
+    Any,
+    /// `(_)`
+    AnyNamed,
+    /// `(kind)`
+    Named(String),
+    /// `"kind"`
+    Anonymous(String),
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct Child {
+    pub(super) pattern: Pattern,
+    pub(super) quantifier: Quantifier,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Quantifier {
+    One,
+    ZeroOrOne,
+    ZeroOrMore,
+    OneOrMore,
+}
+
+#[derive(Debug, Clone)]
+pub(super) enum Predicate {
+    Eq {
+        capture: u32,
+        other: Arg,
+        negated: bool,
+    },
+    Match {
+        capture: u32,
+        regex: Regex,
+        negated: bool,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub(super) enum Arg {
+    Capture(u32),
+    String(String),
+}
+
+/// A top-level pattern with the predicates found in it.
+#[derive(Debug, Clone)]
+pub(super) struct Root {
+    pub(super) pattern: Pattern,
+    pub(super) predicates: Vec<Predicate>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Open,
+    Close,
+    OpenBracket,
+    CloseBracket,
+    Capture(String),
+    String(String),
+    Predicate(String),
+    Ident(String),
+    Field(String),
+    NegatedField(String),
+    Anchor,
+    Quantifier(Quantifier),
+}
+
+fn tokenize(source: &str) -> Result<Vec<(usize, Token)>, QueryError> {
+    let mut tokens = vec![];
+    let mut chars = source.char_indices().peekable();
+    while let Some((offset, c)) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            ';' => {
+                while chars.next_if(|(_, c)| *c != '\n').is_some() {}
+                continue;
+            }
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '[' => Token::OpenBracket,
+            ']' => Token::CloseBracket,
+            '.' => Token::Anchor,
+            '*' => Token::Quantifier(Quantifier::ZeroOrMore),
+            '+' => Token::Quantifier(Quantifier::OneOrMore),
+            '?' => Token::Quantifier(Quantifier::ZeroOrOne),
+            '@' => Token::Capture(word(&mut chars, String::new(), ".")),
+            '#' => Token::Predicate(word(&mut chars, String::new(), "?!")),
+            '!' => Token::NegatedField(word(&mut chars, String::new(), "")),
+            '"' => {
+                let mut s = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '"')) => break,
+                        Some((_, '\\')) => match chars.next() {
+                            Some((_, 'n')) => s.push('\n'),
+                            Some((_, 't')) => s.push('\t'),
+                            Some((_, 'r')) => s.push('\r'),
+                            Some((_, '0')) => s.push('\0'),
+                            Some((_, c)) => s.push(c),
+                            None => break,
+                        },
+                        Some((_, c)) => s.push(c),
+                        None => {
+                            return Err(QueryError::Syntax {
+                                offset,
+                                message: "unterminated string".to_string(),
+                            })
+                        }
+                    }
+                }
+                Token::String(s)
+            }
+            c if is_ident(c, "") => {
+                let s = word(&mut chars, c.to_string(), "");
+                if chars.next_if(|(_, c)| *c == ':').is_some() {
+                    Token::Field(s)
+                } else {
+                    Token::Ident(s)
+                }
+            }
+            c => {
+                return Err(QueryError::Syntax {
+                    offset,
+                    message: format!("unexpected character {:?}", c),
+                })
+            }
+        };
+        tokens.push((offset, token));
+    }
+    Ok(tokens)
+}
+
+fn is_ident(c: char, extra: &str) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-' || extra.contains(c)
+}
+
+fn word(chars: &mut Peekable<CharIndices>, mut s: String, extra: &str) -> String {
+    while let Some((_, c)) = chars.next_if(|(_, c)| is_ident(*c, extra)) {
+        s.push(c);
+    }
+    s
+}
+
+pub(super) struct Parser<'a> {
+    tokens: Vec<(usize, Token)>,
+    next: usize,
+    end: usize,
+    capture_names: &'a mut Vec<String>,
+}
+
+impl<'a> Parser<'a> {
+    pub(super) fn parse(
+        source: &str,
+        capture_names: &'a mut Vec<String>,
+    ) -> Result<Vec<Root>, QueryError> {
+        let mut parser = Parser {
+            tokens: tokenize(source)?,
+            next: 0,
+            end: source.len(),
+            capture_names,
+        };
+        let mut roots = vec![];
+        while parser.peek().is_some() {
+            let mut predicates = vec![];
+            let mut patterns = parser.items(&mut predicates, None)?;
+            let pattern = match patterns.len() {
+                1 => patterns.pop().unwrap(),
+                0 => return Err(parser.syntax("expected a pattern")),
+                _ => {
+                    return Err(QueryError::Unsupported {
+                        offset: parser.offset(),
+                        feature: "sibling sequence",
+                    })
+                }
+            };
+            roots.push(Root {
+                pattern,
+                predicates,
+            });
+        }
+        Ok(roots)
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next).map(|(_, t)| t)
+    }
+
+    fn bump(&mut self) -> Option<Token> {
+        let t = self.tokens.get(self.next).map(|(_, t)| t.clone());
+        self.next += 1;
+        t
+    }
+
+    fn offset(&self) -> usize {
+        self.tokens.get(self.next).map_or(self.end, |(o, _)| *o)
+    }
+
+    fn syntax(&self, message: &str) -> QueryError {
+        QueryError::Syntax {
+            offset: self.offset(),
+            message: message.to_string(),
+        }
+    }
+
+    fn expect(&mut self, token: Token) -> Result<(), QueryError> {
+        if self.peek() == Some(&token) {
+            self.next += 1;
+            Ok(())
+        } else {
+            Err(self.syntax(&format!("expected {:?}", token)))
+        }
+    }
+
+    /// Parses patterns and predicates until `close`, or a single pattern at the top-level.
+    fn items(
+        &mut self,
+        predicates: &mut Vec<Predicate>,
+        close: Option<Token>,
+    ) -> Result<Vec<Pattern>, QueryError> {
+        let mut patterns = vec![];
+        loop {
+            let offset = self.offset();
+            match self.peek() {
+                t if t == close.as_ref() => return Ok(patterns),
+                None => return Err(self.syntax("unexpected end of query")),
+                Some(Token::Field(_)) | Some(Token::NegatedField(_)) => {
+                    return Err(QueryError::Unsupported {
+                        offset,
+                        feature: "field",
+                    })
+                }
+                Some(Token::Anchor) => {
+                    return Err(QueryError::Unsupported {
+                        offset,
+                        feature: "anchor",
+                    })
+                }
+                Some(Token::Open)
+                    if matches!(
+                        self.tokens.get(self.next + 1),
+                        Some((_, Token::Predicate(_)))
+                    ) =>
+                {
+                    predicates.push(self.predicate()?);
+                }
+                Some(_) => {
+                    patterns.push(self.pattern(predicates)?);
+                    if close.is_none() {
+                        return Ok(patterns);
+                    }
+                }
+            }
+        }
+    }
+
+    fn pattern(&mut self, predicates: &mut Vec<Predicate>) -> Result<Pattern, QueryError> {
+        let mut pattern = match self.bump() {
+            Some(Token::Ident(s)) if s == "_" => Pattern::Node {
+                kind: Kind::Any,
+                children: vec![],
+                captures: vec![],
+            },
+            Some(Token::String(s)) => Pattern::Node {
+                kind: Kind::Anonymous(s),
+                children: vec![],
+                captures: vec![],
+            },
+            Some(Token::OpenBracket) => {
+                let alternatives = self.items(predicates, Some(Token::CloseBracket))?;
+                self.expect(Token::CloseBracket)?;
+                Pattern::Alternation {
+                    alternatives,
+                    captures: vec![],
+                }
+            }
+            Some(Token::Open) => match self.peek() {
+                Some(Token::Ident(s)) => {
+                    let kind = if s == "_" {
+                        Kind::AnyNamed
+                    } else {
+                        Kind::Named(s.clone())
+                    };
+                    self.next += 1;
+                    let children = self.children(predicates)?;
+                    self.expect(Token::Close)?;
+                    Pattern::Node {
+                        kind,
+                        children,
+                        captures: vec![],
+                    }
+                }
+                _ => {
+                    // a parenthesized group with a single pattern
+                    let mut patterns = self.items(predicates, Some(Token::Close))?;
+                    self.expect(Token::Close)?;
+                    match patterns.len() {
+                        1 => patterns.pop().unwrap(),
+                        0 => return Err(self.syntax("expected a pattern")),
+                        _ => {
+                            return Err(QueryError::Unsupported {
+                                offset: self.offset(),
+                                feature: "sibling sequence",
+                            })
+                        }
+                    }
+                }
+            },
+            _ => {
+                self.next -= 1;
+                return Err(self.syntax("expected a pattern"));
+            }
+        };
+        while let Some(Token::Capture(name)) = self.peek() {
+            let name = name.clone();
+            self.next += 1;
+            let index = self.capture_index(&name);
+            pattern.captures_mut().push(index);
+        }
+        Ok(pattern)
+    }
+
+    fn children(&mut self, predicates: &mut Vec<Predicate>) -> Result<Vec<Child>, QueryError> {
+        let mut children = vec![];
+        loop {
+            let offset = self.offset();
+            match self.peek() {
+                Some(Token::Close) => return Ok(children),
+                Some(Token::Field(_)) | Some(Token::NegatedField(_)) => {
+                    return Err(QueryError::Unsupported {
+                        offset,
+                        feature: "field",
+                    })
+                }
+                Some(Token::Anchor) => {
+                    return Err(QueryError::Unsupported {
+                        offset,
+                        feature: "anchor",
+                    })
+                }
+                Some(Token::Open)
+                    if matches!(
+                        self.tokens.get(self.next + 1),
+                        Some((_, Token::Predicate(_)))
+                    ) =>
+                {
+                    predicates.push(self.predicate()?);
+                }
+                _ => {
+                    let mut pattern = self.pattern(predicates)?;
+                    let quantifier = match self.peek() {
+                        Some(Token::Quantifier(q)) => {
+                            let q = *q;
+                            self.next += 1;
+                            q
+                        }
+                        _ => Quantifier::One,
+                    };
+                    // captures can also follow the quantifier
+                    while let Some(Token::Capture(name)) = self.peek() {
+                        let name = name.clone();
+                        self.next += 1;
+                        let index = self.capture_index(&name);
+                        pattern.captures_mut().push(index);
+                    }
+                    children.push(Child {
+                        pattern,
+                        quantifier,
+                    });
+                }
+            }
+        }
+    }
+
+    fn predicate(&mut self) -> Result<Predicate, QueryError> {
+        let offset = self.offset();
+        self.expect(Token::Open)?;
+        let name = match self.bump() {
+            Some(Token::Predicate(name)) => name,
+            _ => unreachable!("checked by the caller"),
+        };
+        let mut args = vec![];
+        loop {
+            let offset = self.offset();
+            match self.bump() {
+                Some(Token::Close) => break,
+                Some(Token::Capture(c)) => match self.capture_names.iter().position(|x| x == &c) {
+                    Some(i) => args.push(Arg::Capture(i as u32)),
+                    None => return Err(QueryError::Capture { offset, name: c }),
+                },
+                Some(Token::String(s)) | Some(Token::Ident(s)) => args.push(Arg::String(s)),
+                _ => {
+                    self.next -= 1;
+                    return Err(self.syntax("expected a capture or a string"));
+                }
+            }
+        }
+        let invalid = || QueryError::Predicate {
+            offset,
+            name: name.clone(),
+        };
+        let (negated, name_) = match name.strip_prefix("not-") {
+            Some(n) => (true, n),
+            None => (false, name.as_str()),
+        };
+        let mut args = args.into_iter();
+        let capture = match args.next() {
+            Some(Arg::Capture(c)) => c,
+            _ => return Err(invalid()),
+        };
+        let other = args.next().ok_or_else(invalid)?;
+        if args.next().is_some() {
+            return Err(invalid());
+        }
+        match (name_, other) {
+            ("eq?", other) => Ok(Predicate::Eq {
+                capture,
+                other,
+                negated,
+            }),
+            ("match?", Arg::String(r)) => {
+                let regex = Regex::new(&r).map_err(|e| QueryError::Regex {
+                    offset,
+                    message: e.to_string(),
+                })?;
+                Ok(Predicate::Match {
+                    capture,
+                    regex,
+                    negated,
+                })
+            }
+            _ => Err(invalid()),
+        }
+    }
+
+    fn capture_index(&mut self, name: &str) -> u32 {
+        match self.capture_names.iter().position(|x| x == name) {
+            Some(i) => i as u32,
+            None => {
+                self.capture_names.push(name.to_string());
+                (self.capture_names.len() - 1) as u32
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> Result<(Vec<Root>, Vec<String>), QueryError> {
+        let mut capture_names = vec![];
+        let roots = Parser::parse(source, &mut capture_names)?;
+        Ok((roots, capture_names))
+    }
+
+    fn children(pattern: &Pattern) -> &[Child] {
+        match pattern {
+            Pattern::Node { children, .. } => children,
+            Pattern::Alternation { .. } => panic!("expected a node"),
+        }
+    }
+
+    #[test]
+    fn test_quantifiers() {
+        let (roots, names) = parse("(a (b)? (c)* (d)+ @x (e))").unwrap();
+        assert_eq!(1, roots.len());
+        let quantifiers: Vec<_> = children(&roots[0].pattern)
+            .iter()
+            .map(|c| c.quantifier)
+            .collect();
+        use Quantifier::*;
+        assert_eq!(vec![ZeroOrOne, ZeroOrMore, OneOrMore, One], quantifiers);
+        // the capture following the quantifier is on the quantified pattern
+        let d = &children(&roots[0].pattern)[2].pattern;
+        assert!(
+            matches!(d, Pattern::Node { kind: Kind::Named(k), captures, .. } if k == "d" && captures == &[0])
+        );
+        assert_eq!(vec!["x"], names);
+    }
+
+    #[test]
+    fn test_alternation() {
+        let (roots, names) = parse(r#"[(a) (_) _ "b"] @x"#).unwrap();
+        let Pattern::Alternation {
+            alternatives,
+            captures,
+        } = &roots[0].pattern
+        else {
+            panic!("expected an alternation")
+        };
+        let kinds: Vec<_> = alternatives
+            .iter()
+            .map(|x| match x {
+                Pattern::Node { kind, .. } => kind.clone(),
+                Pattern::Alternation { .. } => panic!("expected a node"),
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                Kind::Named("a".to_string()),
+                Kind::AnyNamed,
+                Kind::Any,
+                Kind::Anonymous("b".to_string())
+            ],
+            kinds
+        );
+        assert_eq!(&[0], &captures[..]);
+        assert_eq!(vec!["x"], names);
+    }
+
+    #[test]
+    fn test_predicates() {
+        let (roots, names) =
+            parse(r#"((a (b) @x) @y (#eq? @x @y) (#not-match? @x "^_"))"#).unwrap();
+        assert_eq!(vec!["x", "y"], names);
+        let predicates = &roots[0].predicates;
+        assert!(matches!(
+            predicates[0],
+            Predicate::Eq {
+                capture: 0,
+                other: Arg::Capture(1),
+                negated: false
+            }
+        ));
+        assert!(matches!(
+            &predicates[1],
+            Predicate::Match { capture: 0, regex, negated: true } if regex.as_str() == "^_"
+        ));
+    }
+
+    #[test]
+    fn test_syntax_error() {
+        assert!(matches!(
+            parse(r#"(a "b"#),
+            Err(QueryError::Syntax { offset: 3, .. })
+        ));
+        assert!(matches!(
+            parse("(a $)"),
+            Err(QueryError::Syntax { offset: 3, .. })
+        ));
+        assert!(matches!(parse("(a (b)"), Err(QueryError::Syntax { .. })));
+        assert!(matches!(parse("()"), Err(QueryError::Syntax { .. })));
+    }
+
+    #[test]
+    fn test_unsupported() {
+        let unsupported = |source| match parse(source) {
+            Err(QueryError::Unsupported { offset, feature }) => (offset, feature),
+            x => panic!("{:?}", x),
+        };
+        assert_eq!((3, "field"), unsupported("(a b: (c))"));
+        assert_eq!((3, "field"), unsupported("(a !b)"));
+        assert_eq!((3, "anchor"), unsupported("(a . (b))"));
+        assert_eq!((7, "anchor"), unsupported("(a (b) .)"));
+        assert_eq!("sibling sequence", unsupported("((a) (b))").1);
+    }
+
+    #[test]
+    fn test_unknown_capture() {
+        assert_eq!(
+            Err(QueryError::Capture {
+                offset: 14,
+                name: "y".to_string()
+            }),
+            parse(r#"((a) @x (#eq? @y "s"))"#).map(|(_, names)| names)
+        );
+    }
+
+    #[test]
+    fn test_invalid_predicate() {
+        let invalid = |source| match parse(source) {
+            Err(QueryError::Predicate { name, .. }) => name,
+            x => panic!("{:?}", x),
+        };
+        assert_eq!("eq?", invalid("((a) @x (#eq? @x))"));
+        assert_eq!("eq?", invalid(r#"((a) @x (#eq? "s" @x))"#));
+        assert_eq!("eq?", invalid(r#"((a) @x (#eq? @x "s" "t"))"#));
+        assert_eq!("match?", invalid("((a (b) @y) @x (#match? @x @y))"));
+        assert_eq!("foo?", invalid(r#"((a) @x (#foo? @x "s"))"#));
+    }
+
+    #[test]
+    fn test_invalid_regex() {
+        assert!(matches!(
+            parse(r#"((a) @x (#match? @x "("))"#),
+            Err(QueryError::Regex { offset: 8, .. })
+        ));
+    }
+}
@@ -0,0 +1,243 @@
+//! An index of the labels of a [`super::LabelStore`] answering substring and prefix queries,
+//! eg. all the identifiers containing `Factory` or starting with `test`.
+//!
+//! It is a generalized suffix array: every suffix of every label,
+//! as a label and a byte offset, sorted by the suffix itself.
+//! The labels containing a pattern are the ones of a contiguous range of suffixes,
+//! found with two binary searches.
+//! Labels inserted after the index was built are kept aside and scanned linearly,
+//! until there are enough of them to be merged in the sorted suffixes.
+use string_interner::{StringInterner, Symbol};
+
+use super::DefaultLabelIdentifier;
+#[cfg(feature = "legion")]
+use crate::{
+    filter::BloomResult,
+    impact::serialize::{Keyed, MySerialize},
+    nodes::RefContainer,
+    store::nodes::legion::{NodeIdentifier, NodeStore},
+    types::{IterableChildren, Labeled, WithChildren},
+};
+
+/// Pending labels are merged once there are more than this many,
+/// or more than an eighth of the indexed labels.
+const MIN_PENDING: usize = 1024;
+
+#[derive(Default)]
+pub struct LabelIndex {
+    /// (label, byte offset) of the suffixes of the indexed labels, sorted by suffix
+    suffixes: Vec<(u32, u32)>,
+    /// the indexed labels, sorted
+    labels: Vec<u32>,
+    /// labels inserted since the last merge
+    pending: Vec<u32>,
+}
+
+fn label(internal: &StringInterner, l: u32) -> &str {
+    internal
+        .resolve(DefaultLabelIdentifier::try_from_usize(l as usize).unwrap())
+        .unwrap()
+}
+
+fn suffix(internal: &StringInterner, (l, o): (u32, u32)) -> &str {
+    &label(internal, l)[o as usize..]
+}
+
+/// Merges `b` in `into`, both sorted by `key`.
+fn merge_by<T: Copy, K: Ord>(into: &mut Vec<T>, b: Vec<T>, key: impl Fn(&T) -> K) {
+    let mut r = Vec::with_capacity(into.len() + b.len());
+    let mut a = std::mem::take(into).into_iter().peekable();
+    let mut b = b.into_iter().peekable();
+    loop {
+        let x = match (a.peek(), b.peek()) {
+            (Some(x), Some(y)) if key(x) <= key(y) => a.next(),
+            (_, Some(_)) => b.next(),
+            (Some(_), None) => a.next(),
+            (None, None) => break,
+        };
+        r.push(x.unwrap());
+    }
+    *into = r;
+}
+
+impl LabelIndex {
+    /// Indexes all the labels of `internal`.
+    pub(super) fn new(internal: &StringInterner) -> Self {
+        let mut r = Self::default();
+        r.pending = internal
+            .into_iter()
+            .map(|(l, _)| l.to_usize() as u32)
+            .collect();
+        r.merge(internal);
+        r
+    }
+
+    /// Number of indexed labels, pending ones included.
+    pub fn len(&self) -> usize {
+        self.labels.len() + self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Adds a label that was just inserted in `internal`.
+    pub(super) fn push(&mut self, internal: &StringInterner, l: DefaultLabelIdentifier) {
+        self.pending.push(l.to_usize() as u32);
+        if self.pending.len() > MIN_PENDING.max(self.labels.len() / 8) {
+            self.merge(internal);
+        }
+    }
+
+    /// Moves the pending labels in the sorted suffixes.
+    pub(super) fn merge(&mut self, internal: &StringInterner) {
+        if self.pending.is_empty() {
+            return;
+        }
+        let mut labels = std::mem::take(&mut self.pending);
+        let mut suffixes = vec![];
+        for &l in &labels {
+            let s = label(internal, l);
+            suffixes.extend(s.char_indices().map(|(o, _)| (l, o as u32)));
+        }
+        suffixes.sort_unstable_by(|a, b| suffix(internal, *a).cmp(suffix(internal, *b)));
+        merge_by(&mut self.suffixes, suffixes, |x| suffix(internal, *x));
+        labels.sort_unstable_by(|a, b| label(internal, *a).cmp(label(internal, *b)));
+        merge_by(&mut self.labels, labels, |x| label(internal, *x));
+    }
+
+    /// The labels containing `pattern`, sorted by identifier.
+    pub(super) fn substring(
+        &self,
+        internal: &StringInterner,
+        pattern: &str,
+    ) -> Vec<DefaultLabelIdentifier> {
+        if pattern.is_empty() {
+            // empty labels have no suffixes
+            return self.prefix(internal, pattern);
+        }
+        let start = self
+            .suffixes
+            .partition_point(|x| suffix(internal, *x) < pattern);
+        let len =
+            self.suffixes[start..].partition_point(|x| suffix(internal, *x).starts_with(pattern));
+        let found = self.suffixes[start..start + len].iter().map(|(l, _)| *l);
+        let pending =
+            (self.pending.iter().copied()).filter(|l| label(internal, *l).contains(pattern));
+        collect(found.chain(pending))
+    }
+
+    /// The labels starting with `pattern`, sorted by identifier.
+    pub(super) fn prefix(
+        &self,
+        internal: &StringInterner,
+        pattern: &str,
+    ) -> Vec<DefaultLabelIdentifier> {
+        let start = self
+            .labels
+            .partition_point(|x| label(internal, *x) < pattern);
+        let len =
+            self.labels[start..].partition_point(|x| label(internal, *x).starts_with(pattern));
+        let found = self.labels[start..start + len].iter().copied();
+        let pending =
+            (self.pending.iter().copied()).filter(|l| label(internal, *l).starts_with(pattern));
+        collect(found.chain(pending))
+    }
+}
+
+fn collect(it: impl Iterator<Item = u32>) -> Vec<DefaultLabelIdentifier> {
+    let mut r: Vec<_> = it.collect();
+    r.sort_unstable();
+    r.dedup();
+    r.into_iter()
+        .map(|l| DefaultLabelIdentifier::try_from_usize(l as usize).unwrap())
+        .collect()
+}
+
+/// Finds the nodes under `root` labeled by one of `labels`,
+/// sorted by identifier, eg. given by [`super::LabelStore::search_substring`].
+///
+/// Subtrees whose reference bloom filter does not contain any of `refs` are skipped,
+/// `refs` being the references to the labels as built by the analysis of the language.
+/// Bloom filters only hold references, so declarations in skipped subtrees are not found.
+/// Without `refs`, the whole tree is explored.
+/// Shared subtrees are only explored once.
+#[cfg(feature = "legion")]
+pub fn find_in_subtrees<U: MySerialize + Keyed<usize> + Clone>(
+    node_store: &NodeStore,
+    root: NodeIdentifier,
+    labels: &[DefaultLabelIdentifier],
+    refs: &[U],
+) -> Vec<NodeIdentifier> {
+    debug_assert!(labels.windows(2).all(|x| x[0] <= x[1]));
+    let mut r = vec![];
+    let mut visited = std::collections::HashSet::new();
+    let mut stack = vec![root];
+    while let Some(id) = stack.pop() {
+        if !visited.insert(id) {
+            continue;
+        }
+        let node = node_store.resolve(id);
+        if let Some(l) = node.try_get_label() {
+            if labels.binary_search(l).is_ok() {
+                r.push(id);
+            }
+        }
+        let Some(cs) = node.children() else {
+            continue;
+        };
+        let skip = !refs.is_empty()
+            && refs
+                .iter()
+                .all(|x| matches!(node.check(x.clone()), BloomResult::DoNotContain));
+        if !skip {
+            stack.extend(cs.iter_children().copied());
+        }
+    }
+    r
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{store::labels::LabelStore, types::LabelStore as _};
+
+    #[test]
+    fn test_same_as_scan() {
+        let mut indexed = LabelStore::new();
+        indexed.enable_index();
+        let mut scanned = LabelStore::new();
+        // enough labels to merge pending ones a few times
+        let names: Vec<_> = (0..3000)
+            .map(|i| match i % 3 {
+                0 => format!("test{}", i),
+                1 => format!("My{}Factory", i),
+                _ => format!("é{}_factory", i),
+            })
+            .collect();
+        for x in &names {
+            assert_eq!(
+                indexed.get_or_insert(x.as_str()),
+                scanned.get_or_insert(x.as_str())
+            );
+        }
+        assert_eq!(indexed.index().unwrap().len(), names.len() + 1);
+        for p in [
+            "Factory", "factory", "test", "12", "é1", "", "length", "zzz",
+        ] {
+            assert_eq!(
+                indexed.search_substring(p),
+                scanned.search_substring(p),
+                "{}",
+                p
+            );
+            assert_eq!(indexed.search_prefix(p), scanned.search_prefix(p), "{}", p);
+        }
+        assert_eq!(scanned.search_substring("Factory").len(), 1000);
+        assert_eq!(scanned.search_prefix("test1").len(), 369);
+        indexed.update_index();
+        assert_eq!(
+            indexed.search_prefix("test1"),
+            scanned.search_prefix("test1")
+        );
+    }
+}
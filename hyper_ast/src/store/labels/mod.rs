@@ -5,12 +5,16 @@ use std::{
 
 use string_interner::{DefaultSymbol, StringInterner, Symbol};
 
+pub mod index;
+pub mod sharded;
+
 use crate::types::LabelStore as _;
 
 #[derive(Default)]
 pub struct LabelStore {
     count: usize,
     internal: StringInterner, //VecMapStore<OwnedLabel, LabelIdentifier>,
+    index: Option<index::LabelIndex>,
 }
 
 impl Debug for LabelStore {
@@ -43,7 +47,14 @@ impl crate::types::LabelStore<DefaultLabelValue> for LabelStore {
     type I = DefaultLabelIdentifier;
     fn get_or_insert<T: Borrow<DefaultLabelValue>>(&mut self, node: T) -> Self::I {
         self.count += 1;
-        self.internal.get_or_intern(node.borrow())
+        let len = self.internal.len();
+        let r = self.internal.get_or_intern(node.borrow());
+        if let Some(index) = &mut self.index {
+            if self.internal.len() > len {
+                index.push(&self.internal, r);
+            }
+        }
+        r
     }
     fn get<T: Borrow<DefaultLabelValue>>(&self, node: T) -> Option<Self::I> {
         self.internal.get(node.borrow())
@@ -73,8 +84,98 @@ impl LabelStore {
         let mut r = Self {
             count: 1,
             internal: Default::default(),
+            index: None,
         };
         r.get_or_insert("length"); // TODO verify/model statically
         r
     }
+
+    /// Indexes the labels, current and future ones,
+    /// to speed up [`LabelStore::search_substring`] and [`LabelStore::search_prefix`].
+    ///
+    /// The index costs a `(u32, u32)` per character of every label, ie. per byte for ASCII labels,
+    /// so about 8 times the memory of the interned labels.
+    pub fn enable_index(&mut self) {
+        if self.index.is_none() {
+            self.index = Some(index::LabelIndex::new(&self.internal));
+        }
+    }
+
+    pub fn index(&self) -> Option<&index::LabelIndex> {
+        self.index.as_ref()
+    }
+
+    /// Merges the labels inserted since the last merge in the index, if enabled.
+    /// Done automatically while inserting, but can be forced before many searches.
+    pub fn update_index(&mut self) {
+        if let Some(index) = &mut self.index {
+            index.merge(&self.internal);
+        }
+    }
+
+    /// The labels containing `pattern`, sorted by identifier.
+    ///
+    /// Scans all the labels if the index is not enabled.
+    pub fn search_substring(&self, pattern: &str) -> Vec<DefaultLabelIdentifier> {
+        match &self.index {
+            Some(index) => index.substring(&self.internal, pattern),
+            None => self.scan(|s| s.contains(pattern)),
+        }
+    }
+
+    /// The labels starting with `pattern`, sorted by identifier.
+    ///
+    /// Scans all the labels if the index is not enabled.
+    pub fn search_prefix(&self, pattern: &str) -> Vec<DefaultLabelIdentifier> {
+        match &self.index {
+            Some(index) => index.prefix(&self.internal, pattern),
+            None => self.scan(|s| s.starts_with(pattern)),
+        }
+    }
+
+    fn scan(&self, f: impl Fn(&str) -> bool) -> Vec<DefaultLabelIdentifier> {
+        (&self.internal)
+            .into_iter()
+            .filter(|(_, s)| f(s))
+            .map(|(l, _)| l)
+            .collect()
+    }
+}
+
+const MAGIC: &[u8; 4] = b"HAls";
+
+impl LabelStore {
+    /// Writes labels in the order of their identifiers,
+    /// so that loading them back gives the same identifiers.
+    /// The index is not written, see [`LabelStore::enable_index`] after loading.
+    pub fn save<W: std::io::Write>(&self, w: &mut W) -> std::io::Result<()> {
+        use super::persist::*;
+        write_header(w, MAGIC)?;
+        write_u64(w, self.count as u64)?;
+        write_u64(w, self.internal.len() as u64)?;
+        for (_, s) in &self.internal {
+            write_str(w, s)?;
+        }
+        Ok(())
+    }
+
+    pub fn load<R: std::io::Read>(r: &mut R) -> std::io::Result<Self> {
+        use super::persist::*;
+        read_header(r, MAGIC)?;
+        let count = read_u64(r)? as usize;
+        let len = read_u64(r)? as usize;
+        let mut internal = StringInterner::with_capacity(len);
+        for i in 0..len {
+            let s = read_string(r)?;
+            let sym = internal.get_or_intern(s);
+            if sym.to_usize() != i {
+                return Err(invalid_data("duplicated label"));
+            }
+        }
+        Ok(Self {
+            count,
+            internal,
+            index: None,
+        })
+    }
 }
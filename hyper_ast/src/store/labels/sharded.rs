@@ -0,0 +1,131 @@
+//! A label store accepting insertions from many threads.
+//!
+//! Labels are distributed in shards by their hash, each shard being behind its own lock.
+//! The shard of a label is encoded in its identifier, so resolving it only locks this shard.
+use std::{borrow::Borrow, sync::RwLock};
+
+use hashbrown::hash_map::DefaultHashBuilder;
+use string_interner::Symbol;
+
+use crate::utils::make_hash;
+
+use super::{DefaultLabelIdentifier, DefaultLabelValue};
+
+#[derive(Default)]
+struct Shard {
+    dedup: hashbrown::HashMap<usize, (), ()>,
+    /// boxed such that resolved labels stay valid while other labels are inserted
+    labels: Vec<Box<str>>,
+}
+
+pub struct ShardedLabelStore {
+    shards: Box<[RwLock<Shard>]>,
+    hasher: DefaultHashBuilder,
+}
+
+impl ShardedLabelStore {
+    pub fn new(shards: usize) -> Self {
+        assert!(shards > 0);
+        Self {
+            shards: (0..shards).map(|_| Default::default()).collect(),
+            hasher: Default::default(),
+        }
+    }
+
+    fn identifier(&self, shard: usize, index: usize) -> DefaultLabelIdentifier {
+        DefaultLabelIdentifier::try_from_usize(index * self.shards.len() + shard)
+            .expect("too many labels")
+    }
+
+    pub fn get(&self, label: &str) -> Option<DefaultLabelIdentifier> {
+        let hash = make_hash(&self.hasher, label);
+        let s = hash as usize % self.shards.len();
+        let shard = self.shards[s].read().unwrap();
+        let (index, _) = shard
+            .dedup
+            .raw_entry()
+            .from_hash(hash, |i| &*shard.labels[*i] == label)?;
+        Some(self.identifier(s, *index))
+    }
+
+    pub fn get_or_insert(&self, label: &str) -> DefaultLabelIdentifier {
+        if let Some(id) = self.get(label) {
+            return id;
+        }
+        let hash = make_hash(&self.hasher, label);
+        let s = hash as usize % self.shards.len();
+        let mut shard = self.shards[s].write().unwrap();
+        let Shard { dedup, labels } = &mut *shard;
+        let entry = dedup
+            .raw_entry_mut()
+            .from_hash(hash, |i| &*labels[*i] == label);
+        let index = match entry {
+            hashbrown::hash_map::RawEntryMut::Occupied(occupied) => *occupied.key(),
+            hashbrown::hash_map::RawEntryMut::Vacant(vacant) => {
+                labels.push(label.into());
+                let index = labels.len() - 1;
+                let hasher = &self.hasher;
+                vacant.insert_with_hasher(hash, index, (), |i| make_hash(hasher, &*labels[*i]));
+                index
+            }
+        };
+        self.identifier(s, index)
+    }
+
+    pub fn resolve(&self, id: &DefaultLabelIdentifier) -> &str {
+        let id = id.to_usize();
+        let shard = self.shards[id % self.shards.len()].read().unwrap();
+        let label: *const str = &*shard.labels[id / self.shards.len()];
+        // SAFETY: labels are boxed, never removed nor mutated,
+        // so they live as long as the store even if their shard is modified
+        unsafe { &*label }
+    }
+
+    pub fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|s| s.read().unwrap().labels.len())
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for ShardedLabelStore {
+    fn default() -> Self {
+        Self::new(64)
+    }
+}
+
+/// Inserting only needs a shared reference.
+impl crate::types::LabelStore<DefaultLabelValue> for &ShardedLabelStore {
+    type I = DefaultLabelIdentifier;
+    fn get_or_insert<T: Borrow<DefaultLabelValue>>(&mut self, node: T) -> Self::I {
+        ShardedLabelStore::get_or_insert(*self, node.borrow())
+    }
+
+    fn get<T: Borrow<DefaultLabelValue>>(&self, node: T) -> Option<Self::I> {
+        ShardedLabelStore::get(*self, node.borrow())
+    }
+
+    fn resolve(&self, id: &Self::I) -> &DefaultLabelValue {
+        ShardedLabelStore::resolve(*self, id)
+    }
+}
+
+impl crate::types::LabelStore<DefaultLabelValue> for ShardedLabelStore {
+    type I = DefaultLabelIdentifier;
+    fn get_or_insert<T: Borrow<DefaultLabelValue>>(&mut self, node: T) -> Self::I {
+        ShardedLabelStore::get_or_insert(self, node.borrow())
+    }
+
+    fn get<T: Borrow<DefaultLabelValue>>(&self, node: T) -> Option<Self::I> {
+        ShardedLabelStore::get(self, node.borrow())
+    }
+
+    fn resolve(&self, id: &Self::I) -> &DefaultLabelValue {
+        ShardedLabelStore::resolve(self, id)
+    }
+}
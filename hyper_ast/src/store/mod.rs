@@ -8,6 +8,8 @@ pub mod handle;
 pub mod labels;
 // pub mod mapped_world;
 pub mod nodes;
+pub mod persist;
+pub mod snapshot;
 // pub mod ecs; // TODO try a custom ecs ?
 // pub mod radix_hash_store; // TODO yet another WIP store
 // pub mod vec_map_store; // TODO yet another WIP store
@@ -115,6 +117,36 @@ where
     }
 }
 
+#[cfg(feature = "legion")]
+impl<TS: Default> SimpleStores<TS> {
+    /// Persists the labels then the nodes,
+    /// the type store is expected to be stateless.
+    pub fn save<W: std::io::Write>(
+        &self,
+        w: &mut W,
+        registry: &nodes::legion::persist::PersistRegistry,
+    ) -> std::io::Result<nodes::legion::persist::SavedIds> {
+        self.label_store.save(w)?;
+        self.node_store.save(w, registry)
+    }
+
+    pub fn load<R: std::io::Read>(
+        r: &mut R,
+        registry: &nodes::legion::persist::PersistRegistry,
+    ) -> std::io::Result<(Self, nodes::legion::persist::LoadedIds)> {
+        let label_store = labels::LabelStore::load(r)?;
+        let (node_store, ids) = nodes::legion::NodeStore::load(r, registry)?;
+        Ok((
+            Self {
+                label_store,
+                type_store: Default::default(),
+                node_store,
+            },
+            ids,
+        ))
+    }
+}
+
 pub mod defaults {
     pub type LabelIdentifier = super::labels::DefaultLabelIdentifier;
     pub type LabelValue = super::labels::DefaultLabelValue;
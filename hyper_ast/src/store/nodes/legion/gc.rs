@@ -0,0 +1,154 @@
+//! Eviction of the nodes that are not reachable anymore from the roots a user wants to keep.
+
+use legion::storage::ComponentTypeId;
+
+use super::{
+    compo::{NoSpacesCS, CS},
+    NodeIdentifier, NodeStore,
+};
+
+/// What was evicted by [`NodeStore::gc`].
+#[derive(Debug, Clone, Copy)]
+pub struct GcStats {
+    /// number of removed nodes
+    pub removed: usize,
+    /// number of remaining nodes
+    pub live: usize,
+    /// estimation of the bytes taken by the components and the boxed children of the removed nodes
+    pub freed: usize,
+}
+
+impl NodeStore {
+    /// Removes every node that is not reachable from `roots`, using a mark and sweep.
+    ///
+    /// Identifiers of removed nodes must not be used anymore,
+    /// thus caches holding identifiers (eg. the md_cache of generators) must be purged before processing more code.
+    pub fn gc(&mut self, roots: impl IntoIterator<Item = NodeIdentifier>) -> GcStats {
+        let freed;
+        let removed = {
+            let live = self.mark(roots);
+            let dead: Vec<NodeIdentifier> = self
+                .dedup
+                .keys()
+                .filter(|x| !live.contains(*x))
+                .copied()
+                .collect();
+            let mut sizes = Default::default();
+            freed = dead
+                .iter()
+                .map(|x| self.estimated_size(*x, &mut sizes))
+                .sum();
+            self.dedup.retain(|x, _| live.contains(x));
+            for x in &dead {
+                self.internal.remove(*x);
+            }
+            dead.len()
+        };
+        GcStats {
+            removed,
+            live: self.dedup.len(),
+            freed,
+        }
+    }
+
+    /// Estimates the bytes taken by a node:
+    /// the size of each component of its archetype plus the boxed children.
+    ///
+    /// It is only an estimate, the storage of archetypes and the allocator add their own overhead,
+    /// and labels are not counted as they stay in the label store.
+    /// `sizes` caches the size of the components.
+    fn estimated_size(
+        &self,
+        id: NodeIdentifier,
+        sizes: &mut hashbrown::HashMap<ComponentTypeId, usize>,
+    ) -> usize {
+        let Ok(entry) = self.internal.entry_ref(id) else {
+            return 0;
+        };
+        let layout = entry.archetype().layout();
+        let components: usize = (layout.component_types().iter())
+            .zip(layout.component_constructors())
+            .map(|(t, new)| {
+                *sizes
+                    .entry(*t)
+                    .or_insert_with(|| new().element_vtable().size())
+            })
+            .sum();
+        let boxed = |len: usize| len * std::mem::size_of::<NodeIdentifier>();
+        let cs = (entry.get_component::<CS<NodeIdentifier>>()).map_or(0, |cs| boxed(cs.0.len()));
+        let no_spaces =
+            (entry.get_component::<NoSpacesCS<NodeIdentifier>>()).map_or(0, |cs| boxed(cs.0.len()));
+        components + cs + no_spaces
+    }
+
+    fn mark(
+        &self,
+        roots: impl IntoIterator<Item = NodeIdentifier>,
+    ) -> hashbrown::HashSet<NodeIdentifier> {
+        let mut live = hashbrown::HashSet::<NodeIdentifier>::default();
+        let mut stack: Vec<NodeIdentifier> = roots.into_iter().collect();
+        while let Some(id) = stack.pop() {
+            if !live.insert(id) {
+                continue;
+            }
+            let Ok(entry) = self.internal.entry_ref(id) else {
+                log::warn!("root {:?} is not in the store", id);
+                continue;
+            };
+            if let Ok(cs) = entry.get_component::<CS<NodeIdentifier>>() {
+                stack.extend(cs.0.iter().filter(|x| !live.contains(*x)));
+            }
+            if let Ok(cs) = entry.get_component::<NoSpacesCS<NodeIdentifier>>() {
+                stack.extend(cs.0.iter().filter(|x| !live.contains(*x)));
+            }
+        }
+        live
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::mem::size_of;
+
+    use super::*;
+    use crate::store::{
+        defaults::LabelIdentifier,
+        labels::LabelStore,
+        nodes::legion::{dyn_builder, EntryRef},
+    };
+    use crate::types::LabelStore as _;
+
+    fn insert(
+        store: &mut NodeStore,
+        label: LabelIdentifier,
+        cs: &[NodeIdentifier],
+    ) -> NodeIdentifier {
+        let mut builder = dyn_builder::EntityBuilder::new();
+        builder.add(label);
+        if !cs.is_empty() {
+            builder.add(CS(cs.into()));
+        }
+        let eq = |x: EntryRef| x.get_component::<LabelIdentifier>().ok() == Some(&label);
+        let insertion = store.prepare_insertion(&label, eq);
+        NodeStore::insert_built_after_prepare(insertion.vacant(), builder.build())
+    }
+
+    #[test]
+    fn test_freed_estimate() {
+        let mut labels = LabelStore::new();
+        let mut store = NodeStore::new();
+        let mut label = |s: &str| labels.get_or_insert(s);
+        let kept = insert(&mut store, label("kept"), &[]);
+        let a = insert(&mut store, label("a"), &[]);
+        let b = insert(&mut store, label("b"), &[]);
+        insert(&mut store, label("ab"), &[a, b]);
+        let stats = store.gc([kept]);
+        assert_eq!(stats.removed, 3);
+        assert_eq!(stats.live, 1);
+        // 3 labels, the boxed slice of children and its 2 identifiers
+        let expected = 3 * size_of::<LabelIdentifier>()
+            + size_of::<CS<NodeIdentifier>>()
+            + 2 * size_of::<NodeIdentifier>();
+        assert_eq!(stats.freed, expected);
+    }
+}
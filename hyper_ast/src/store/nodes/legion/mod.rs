@@ -15,6 +15,12 @@ pub mod dyn_builder;
 
 pub mod compo;
 
+pub mod persist;
+
+pub mod gc;
+
+pub mod sharded;
+
 mod elem;
 
 pub use elem::{EntryRef, HashedNode, HashedNodeRef, NodeIdentifier};
@@ -107,6 +113,19 @@ impl NodeStore {
         PendingInsert(entry, (hash, &mut self.internal, &self.hasher))
     }
 
+    /// Same lookup as [`NodeStore::prepare_insertion`] without preparing an insertion.
+    pub fn get<Eq: Fn(EntryRef) -> bool, V: Hash>(
+        &self,
+        hashable: &V,
+        eq: Eq,
+    ) -> Option<NodeIdentifier> {
+        let hash = make_hash(&self.hasher, hashable);
+        self.dedup
+            .raw_entry()
+            .from_hash(hash, |symbol| eq(self.internal.entry_ref(*symbol).unwrap()))
+            .map(|(id, _)| *id)
+    }
+
     pub fn insert_after_prepare<T>(
         (vacant, (hash, internal, hasher)): (
             crate::compat::hash_map::RawVacantEntryMut<legion::Entity, (), ()>,
@@ -154,6 +173,16 @@ impl NodeStore {
         symbol
     }
 
+    /// Adds a component to a node already inserted, eg. a metric derived from its children.
+    ///
+    /// The component must not be involved in the deduplication of nodes.
+    pub fn add_component<C: Component>(&mut self, id: NodeIdentifier, component: C) {
+        self.internal
+            .entry(id)
+            .expect("the node is not in the store")
+            .add_component(component)
+    }
+
     pub fn resolve(&self, id: NodeIdentifier) -> HashedNodeRef<NodeIdentifier> {
         self.internal
             .entry_ref(id)
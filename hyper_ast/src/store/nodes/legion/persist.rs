@@ -0,0 +1,438 @@
+//! On-disk persistence of the legion [`NodeStore`].
+//!
+//! Nodes are written children first, so that references to other nodes
+//! can be written as their position in the file.
+//! Each persisted component must implement [`Persist`] and be registered in a [`PersistRegistry`],
+//! language specific components (like the type of nodes) are registered by the user of the store.
+//!
+//! The dedup table is not written as is, its hashes depend on a randomly seeded hasher,
+//! it is rebuilt from the loaded nodes instead.
+
+use std::{
+    collections::HashMap,
+    io::{self, Read, Write},
+};
+
+use legion::storage::{Component, ComponentTypeId};
+
+use crate::{
+    cyclomatic::Mcc,
+    filter::{Bloom, BloomSize},
+    hashed::SyntaxNodeHashs,
+    store::{
+        defaults::LabelIdentifier,
+        labels::label_id_from_usize,
+        persist::{
+            invalid_data, read_header, read_string, read_u16, read_u32, read_u64, read_u8,
+            write_header, write_str, write_u16, write_u32, write_u64, write_u8,
+        },
+    },
+    utils::make_hash,
+};
+
+use super::{
+    compo::{self, NoSpacesCS, CS},
+    dyn_builder::EntityBuilder,
+    EntryRef, HashedNodeRef, NodeIdentifier, NodeStore,
+};
+
+const MAGIC: &[u8; 4] = b"HAns";
+
+/// Ids of the nodes written so far, used to persist references between nodes.
+#[derive(Default)]
+pub struct SavedIds(HashMap<NodeIdentifier, u64>);
+
+impl SavedIds {
+    pub fn get(&self, id: &NodeIdentifier) -> Option<u64> {
+        self.0.get(id).copied()
+    }
+}
+
+/// Nodes loaded so far, indexed by their persisted id.
+#[derive(Default)]
+pub struct LoadedIds(Vec<NodeIdentifier>);
+
+impl LoadedIds {
+    pub fn get(&self, id: u64) -> Option<NodeIdentifier> {
+        self.0.get(id as usize).copied()
+    }
+}
+
+/// A component that can be written to and read from disk.
+pub trait Persist: Component + Sized {
+    fn save(&self, w: &mut dyn Write, ids: &SavedIds) -> io::Result<()>;
+    fn load(r: &mut dyn Read, ids: &LoadedIds) -> io::Result<Self>;
+}
+
+type SaveFn = fn(&EntryRef, &mut dyn Write, &SavedIds) -> io::Result<()>;
+type LoadFn = fn(&mut dyn Read, &LoadedIds, &mut EntityBuilder) -> io::Result<()>;
+
+fn save_component<T: Persist>(
+    e: &EntryRef,
+    w: &mut dyn Write,
+    ids: &SavedIds,
+) -> io::Result<()> {
+    e.get_component::<T>()
+        .map_err(|err| invalid_data(format!("{:?}", err)))?
+        .save(w, ids)
+}
+
+fn load_component<T: Persist>(
+    r: &mut dyn Read,
+    ids: &LoadedIds,
+    builder: &mut EntityBuilder,
+) -> io::Result<()> {
+    builder.add(T::load(r, ids)?);
+    Ok(())
+}
+
+/// Components that can be persisted, by name.
+///
+/// Names are written in the header of the file, thus registering components
+/// in a different order does not break previously persisted stores.
+pub struct PersistRegistry {
+    by_type: HashMap<ComponentTypeId, u16>,
+    entries: Vec<(&'static str, SaveFn, LoadFn)>,
+}
+
+impl PersistRegistry {
+    /// An empty registry, you probably want [`PersistRegistry::new`].
+    pub fn empty() -> Self {
+        Self {
+            by_type: Default::default(),
+            entries: Default::default(),
+        }
+    }
+
+    /// A registry with the language agnostic components of the store.
+    pub fn new() -> Self {
+        let mut r = Self::empty();
+        r.register::<compo::Size>("Size");
+        r.register::<compo::SizeNoSpaces>("SizeNoSpaces");
+        r.register::<compo::Height>("Height");
+        r.register::<compo::BytesLen>("BytesLen");
+        r.register::<CS<NodeIdentifier>>("CS");
+        r.register::<NoSpacesCS<NodeIdentifier>>("NoSpacesCS");
+        r.register::<CS<LabelIdentifier>>("CS<Label>");
+        r.register::<LabelIdentifier>("Label");
+        r.register::<SyntaxNodeHashs<u32>>("SyntaxNodeHashs<u32>");
+        r.register::<BloomSize>("BloomSize");
+        r.register::<Bloom<&'static [u8], u16>>("Bloom16");
+        r.register::<Bloom<&'static [u8], u32>>("Bloom32");
+        r.register::<Bloom<&'static [u8], u64>>("Bloom64");
+        r.register::<Bloom<&'static [u8], [u64; 2]>>("Bloom128");
+        r.register::<Bloom<&'static [u8], [u64; 4]>>("Bloom256");
+        r.register::<Bloom<&'static [u8], [u64; 8]>>("Bloom512");
+        r.register::<Bloom<&'static [u8], [u64; 16]>>("Bloom1024");
+        r.register::<Bloom<&'static [u8], [u64; 32]>>("Bloom2048");
+        r.register::<Bloom<&'static [u8], [u64; 64]>>("Bloom4096");
+        r.register::<Mcc>("Mcc");
+        r
+    }
+
+    /// `name` must be unique among registered components and stable between versions.
+    pub fn register<T: Persist>(&mut self, name: &'static str) -> &mut Self {
+        assert!(
+            self.entries.iter().all(|(n, _, _)| *n != name),
+            "{} is already registered",
+            name
+        );
+        let tag = self.entries.len() as u16;
+        self.entries
+            .push((name, save_component::<T>, load_component::<T>));
+        self.by_type.insert(ComponentTypeId::of::<T>(), tag);
+        self
+    }
+}
+
+impl Default for PersistRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NodeStore {
+    /// Writes all the nodes of the store,
+    /// fails if a node holds a component missing from the `registry`.
+    pub fn save<W: Write>(&self, w: &mut W, registry: &PersistRegistry) -> io::Result<SavedIds> {
+        write_header(w, MAGIC)?;
+        write_u16(w, registry.entries.len() as u16)?;
+        for (name, _, _) in &registry.entries {
+            write_str(w, name)?;
+        }
+        write_u64(w, self.count as u64)?;
+        write_u64(w, self.errors as u64)?;
+
+        let order = self.children_first()?;
+        write_u64(w, order.len() as u64)?;
+        let mut ids = SavedIds::default();
+        for id in order {
+            let entry = self.internal.entry_ref(id).unwrap();
+            Self::save_node(w, registry, &entry, &ids)?;
+            let i = ids.0.len() as u64;
+            ids.0.insert(id, i);
+        }
+        Ok(ids)
+    }
+
+    /// post-order over every node reachable from the dedup table
+    fn children_first(&self) -> io::Result<Vec<NodeIdentifier>> {
+        let mut visited = hashbrown::HashSet::<NodeIdentifier>::with_capacity(self.dedup.len());
+        let mut order = Vec::with_capacity(self.dedup.len());
+        let mut stack: Vec<(NodeIdentifier, bool)> = vec![];
+        for root in self.dedup.keys() {
+            stack.push((*root, false));
+            while let Some((id, expanded)) = stack.pop() {
+                if expanded {
+                    order.push(id);
+                    continue;
+                }
+                if !visited.insert(id) {
+                    continue;
+                }
+                let entry = self
+                    .internal
+                    .entry_ref(id)
+                    .map_err(|err| invalid_data(format!("{:?}", err)))?;
+                stack.push((id, true));
+                if let Ok(cs) = entry.get_component::<CS<NodeIdentifier>>() {
+                    stack.extend(cs.0.iter().rev().map(|x| (*x, false)));
+                }
+                if let Ok(cs) = entry.get_component::<NoSpacesCS<NodeIdentifier>>() {
+                    stack.extend(cs.0.iter().rev().map(|x| (*x, false)));
+                }
+            }
+        }
+        Ok(order)
+    }
+
+    fn save_node<W: Write>(
+        w: &mut W,
+        registry: &PersistRegistry,
+        entry: &EntryRef,
+        ids: &SavedIds,
+    ) -> io::Result<()> {
+        let types = entry.archetype().layout().component_types();
+        write_u16(w, types.len() as u16)?;
+        for t in types {
+            let tag = *registry
+                .by_type
+                .get(t)
+                .ok_or_else(|| invalid_data(format!("component {:?} is not registered", t)))?;
+            write_u16(w, tag)?;
+            (registry.entries[tag as usize].1)(entry, w, ids)?;
+        }
+        Ok(())
+    }
+
+    /// Reads nodes previously written with [`NodeStore::save`],
+    /// components are resolved by the names written in the header.
+    pub fn load<R: Read>(r: &mut R, registry: &PersistRegistry) -> io::Result<(Self, LoadedIds)> {
+        read_header(r, MAGIC)?;
+        let n = read_u16(r)?;
+        let mut tags = Vec::with_capacity(n as usize);
+        for _ in 0..n {
+            let name = read_string(r)?;
+            let load = registry
+                .entries
+                .iter()
+                .find(|(n, _, _)| *n == name)
+                .map(|(_, _, load)| *load);
+            tags.push((name, load));
+        }
+        let mut store = Self::new();
+        store.count = read_u64(r)? as usize;
+        store.errors = read_u64(r)? as usize;
+        let len = read_u64(r)? as usize;
+        let mut ids = LoadedIds(Vec::with_capacity(len));
+        for _ in 0..len {
+            let mut builder = EntityBuilder::new();
+            let ncomp = read_u16(r)?;
+            for _ in 0..ncomp {
+                let (name, load) = tags
+                    .get(read_u16(r)? as usize)
+                    .ok_or_else(|| invalid_data("unknown component tag"))?;
+                let load = load.ok_or_else(|| {
+                    invalid_data(format!("component {} is not registered", name))
+                })?;
+                load(r, &ids, &mut builder)?;
+            }
+            let id = store.internal.extend(builder.build())[0];
+            store.insert_loaded(id);
+            ids.0.push(id);
+        }
+        Ok((store, ids))
+    }
+
+    fn insert_loaded(&mut self, id: NodeIdentifier) {
+        let Self {
+            dedup,
+            internal,
+            hasher,
+            ..
+        } = self;
+        let hash = {
+            let node: HashedNodeRef<'_, NodeIdentifier> =
+                HashedNodeRef::new(internal.entry_ref(id).unwrap());
+            make_hash(hasher, &node)
+        };
+        match dedup.raw_entry_mut().from_hash(hash, |x| *x == id) {
+            hashbrown::hash_map::RawEntryMut::Occupied(_) => (),
+            hashbrown::hash_map::RawEntryMut::Vacant(vacant) => {
+                vacant.insert_with_hasher(hash, id, (), |id| {
+                    let node: HashedNodeRef<'_, NodeIdentifier> =
+                        HashedNodeRef::new(internal.entry_ref(*id).unwrap());
+                    make_hash(hasher, &node)
+                });
+            }
+        }
+    }
+}
+
+// * builtin components
+
+fn save_ids(w: &mut dyn Write, cs: &[NodeIdentifier], ids: &SavedIds) -> io::Result<()> {
+    write_u32(w, cs.len() as u32)?;
+    for x in cs {
+        let x = ids
+            .get(x)
+            .ok_or_else(|| invalid_data("child written after its parent"))?;
+        write_u64(w, x)?;
+    }
+    Ok(())
+}
+
+fn load_ids(r: &mut dyn Read, ids: &LoadedIds) -> io::Result<Box<[NodeIdentifier]>> {
+    let len = read_u32(r)?;
+    (0..len)
+        .map(|_| {
+            let x = read_u64(r)?;
+            ids.get(x)
+                .ok_or_else(|| invalid_data(format!("unknown node {}", x)))
+        })
+        .collect()
+}
+
+macro_rules! persist_u32_compo {
+    ($($t:ty),*) => {
+        $(
+            impl Persist for $t {
+                fn save(&self, w: &mut dyn Write, _: &SavedIds) -> io::Result<()> {
+                    write_u32(w, self.0)
+                }
+                fn load(r: &mut dyn Read, _: &LoadedIds) -> io::Result<Self> {
+                    Ok(Self(read_u32(r)?))
+                }
+            }
+        )*
+    };
+}
+
+persist_u32_compo!(compo::Size, compo::SizeNoSpaces, compo::Height, compo::BytesLen);
+
+impl Persist for CS<NodeIdentifier> {
+    fn save(&self, w: &mut dyn Write, ids: &SavedIds) -> io::Result<()> {
+        save_ids(w, &self.0, ids)
+    }
+    fn load(r: &mut dyn Read, ids: &LoadedIds) -> io::Result<Self> {
+        Ok(CS(load_ids(r, ids)?))
+    }
+}
+
+impl Persist for NoSpacesCS<NodeIdentifier> {
+    fn save(&self, w: &mut dyn Write, ids: &SavedIds) -> io::Result<()> {
+        save_ids(w, &self.0, ids)
+    }
+    fn load(r: &mut dyn Read, ids: &LoadedIds) -> io::Result<Self> {
+        Ok(NoSpacesCS(load_ids(r, ids)?))
+    }
+}
+
+impl Persist for LabelIdentifier {
+    fn save(&self, w: &mut dyn Write, _: &SavedIds) -> io::Result<()> {
+        use string_interner::Symbol;
+        write_u32(w, self.to_usize() as u32)
+    }
+    fn load(r: &mut dyn Read, _: &LoadedIds) -> io::Result<Self> {
+        let x = read_u32(r)?;
+        label_id_from_usize(x as usize).ok_or_else(|| invalid_data("invalid label"))
+    }
+}
+
+impl Persist for CS<LabelIdentifier> {
+    fn save(&self, w: &mut dyn Write, ids: &SavedIds) -> io::Result<()> {
+        write_u32(w, self.0.len() as u32)?;
+        for x in self.0.iter() {
+            x.save(w, ids)?;
+        }
+        Ok(())
+    }
+    fn load(r: &mut dyn Read, ids: &LoadedIds) -> io::Result<Self> {
+        let len = read_u32(r)?;
+        let cs: io::Result<Box<[_]>> = (0..len).map(|_| LabelIdentifier::load(r, ids)).collect();
+        Ok(CS(cs?))
+    }
+}
+
+impl Persist for SyntaxNodeHashs<u32> {
+    fn save(&self, w: &mut dyn Write, _: &SavedIds) -> io::Result<()> {
+        write_u32(w, self.structt)?;
+        write_u32(w, self.label)?;
+        write_u32(w, self.syntax)
+    }
+    fn load(r: &mut dyn Read, _: &LoadedIds) -> io::Result<Self> {
+        Ok(Self {
+            structt: read_u32(r)?,
+            label: read_u32(r)?,
+            syntax: read_u32(r)?,
+        })
+    }
+}
+
+impl Persist for BloomSize {
+    fn save(&self, w: &mut dyn Write, _: &SavedIds) -> io::Result<()> {
+        write_u8(
+            w,
+            match self {
+                BloomSize::None => 0,
+                BloomSize::B16 => 1,
+                BloomSize::B32 => 2,
+                BloomSize::B64 => 3,
+                BloomSize::B128 => 4,
+                BloomSize::B256 => 5,
+                BloomSize::B512 => 6,
+                BloomSize::B1024 => 7,
+                BloomSize::B2048 => 8,
+                BloomSize::B4096 => 9,
+                BloomSize::Much => 10,
+            },
+        )
+    }
+    fn load(r: &mut dyn Read, _: &LoadedIds) -> io::Result<Self> {
+        Ok(match read_u8(r)? {
+            0 => BloomSize::None,
+            1 => BloomSize::B16,
+            2 => BloomSize::B32,
+            3 => BloomSize::B64,
+            4 => BloomSize::B128,
+            5 => BloomSize::B256,
+            6 => BloomSize::B512,
+            7 => BloomSize::B1024,
+            8 => BloomSize::B2048,
+            9 => BloomSize::B4096,
+            10 => BloomSize::Much,
+            x => return Err(invalid_data(format!("invalid bloom size {}", x))),
+        })
+    }
+}
+
+impl<V: bitvec::view::BitViewSized + Send + Sync + 'static> Persist for Bloom<&'static [u8], V> {
+    fn save(&self, w: &mut dyn Write, _: &SavedIds) -> io::Result<()> {
+        w.write_all(&self.to_bytes())
+    }
+    fn load(r: &mut dyn Read, _: &LoadedIds) -> io::Result<Self> {
+        let mut b = Self::default().to_bytes();
+        r.read_exact(&mut b)?;
+        Self::from_bytes(&b).ok_or_else(|| invalid_data("invalid bloom filter"))
+    }
+}
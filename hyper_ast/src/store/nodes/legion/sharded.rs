@@ -0,0 +1,437 @@
+//! A node store accepting insertions from many threads.
+//!
+//! Nodes are distributed in [`NodeStore`] shards by the hash used to deduplicate them,
+//! each shard being behind its own lock.
+//! Equal nodes have the same hash thus go to the same shard,
+//! which keeps the deduplication exact.
+//! Legion entities are unique across worlds, so identifiers do not collide between shards.
+//! The shard of a node is given along its identifier, so resolving it only locks this shard.
+//! Nodes only known by their [`NodeIdentifier`], like children, are located with a concurrent map,
+//! which costs an entry per node but avoids looking into each shard.
+use std::{
+    hash::Hash,
+    ops::Deref,
+    sync::{RwLock, RwLockReadGuard},
+};
+
+use hashbrown::{hash_map::DefaultHashBuilder, HashMap};
+
+use crate::{
+    hashed::SyntaxNodeHashsKinds,
+    nodes::HashSize,
+    store::defaults::LabelIdentifier,
+    types::{
+        AnyType, Labeled, MySlice, Tree, Typed, WithChildren, WithHashs, WithSerialization,
+        WithStats,
+    },
+    utils::make_hash,
+};
+
+use super::{dyn_builder, EntryRef, HashedNodeRef, NodeIdentifier, NodeStore};
+
+/// Identifier of a node in a [`ShardedNodeStore`], along with the index of its shard.
+///
+/// Children are stored as plain [`NodeIdentifier`]s, see [`ShardedNodeStore::locate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ShardedNodeIdentifier {
+    shard: u32,
+    id: NodeIdentifier,
+}
+
+impl ShardedNodeIdentifier {
+    pub fn id(&self) -> NodeIdentifier {
+        self.id
+    }
+}
+
+pub struct ShardedNodeStore {
+    shards: Box<[RwLock<NodeStore>]>,
+    /// the shard of each node, itself sharded by node identifier
+    locations: Box<[RwLock<HashMap<NodeIdentifier, u32>>]>,
+    hasher: DefaultHashBuilder,
+}
+
+impl ShardedNodeStore {
+    /// `shards` should be a few times the number of writing threads
+    pub fn new(shards: usize) -> Self {
+        assert!(shards > 0);
+        Self {
+            shards: (0..shards).map(|_| RwLock::new(NodeStore::new())).collect(),
+            locations: (0..shards).map(|_| Default::default()).collect(),
+            hasher: Default::default(),
+        }
+    }
+
+    fn shard<V: Hash>(&self, hashable: &V) -> u32 {
+        let hash = make_hash(&self.hasher, hashable);
+        (hash as usize % self.shards.len()) as u32
+    }
+
+    fn locations(&self, id: &NodeIdentifier) -> &RwLock<HashMap<NodeIdentifier, u32>> {
+        let hash = make_hash(&self.hasher, id);
+        &self.locations[hash as usize % self.locations.len()]
+    }
+
+    /// Finds a node, see [`NodeStore::prepare_insertion`] for `hashable` and `eq`.
+    pub fn get<V: Hash>(
+        &self,
+        hashable: &V,
+        eq: impl Fn(EntryRef) -> bool,
+    ) -> Option<ShardedNodeIdentifier> {
+        let shard = self.shard(hashable);
+        let id = self.shards[shard as usize]
+            .read()
+            .unwrap()
+            .get(hashable, eq)?;
+        Some(ShardedNodeIdentifier { shard, id })
+    }
+
+    /// Inserts the node built in `components` unless an equal one already exists.
+    ///
+    /// Only the shard of `hashable` is locked, `eq` is given nodes of this shard.
+    /// The components are built beforehand,
+    /// resolving other nodes while holding the lock could deadlock with another writer.
+    pub fn insert_built<V: Hash>(
+        &self,
+        hashable: &V,
+        eq: impl Fn(EntryRef) -> bool,
+        components: dyn_builder::BuiltEntity,
+    ) -> ShardedNodeIdentifier {
+        let shard = self.shard(hashable);
+        let lock = &self.shards[shard as usize];
+        if let Some(id) = lock.read().unwrap().get(hashable, &eq) {
+            return ShardedNodeIdentifier { shard, id };
+        }
+        let mut store = lock.write().unwrap();
+        let insertion = store.prepare_insertion(hashable, eq);
+        let id = match insertion.occupied_id() {
+            Some(id) => id,
+            None => {
+                let id = NodeStore::insert_built_after_prepare(insertion.vacant(), components);
+                // still holding the shard, so the node cannot be found before being located
+                self.locations(&id).write().unwrap().insert(id, shard);
+                id
+            }
+        };
+        ShardedNodeIdentifier { shard, id }
+    }
+
+    /// Calls `f` on the node `id`, only locking its shard.
+    pub fn with_node<R>(&self, id: ShardedNodeIdentifier, f: impl FnOnce(HashedNodeRef) -> R) -> R {
+        let shard = self.shards[id.shard as usize].read().unwrap();
+        f(shard.resolve(id.id))
+    }
+
+    /// Finds the shard of a node only known by its [`NodeIdentifier`], like the children of a node.
+    pub fn locate(&self, id: NodeIdentifier) -> Option<ShardedNodeIdentifier> {
+        let shard = *self.locations(&id).read().unwrap().get(&id)?;
+        Some(ShardedNodeIdentifier { shard, id })
+    }
+
+    /// Locks all the shards for reading, insertions wait until the view is dropped.
+    pub fn read(&self) -> ShardedNodeStoreView<'_> {
+        ShardedNodeStoreView {
+            store: self,
+            shards: self.shards.iter().map(|s| s.read().unwrap()).collect(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(|s| s.read().unwrap().len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drops the locations of the nodes.
+    pub fn into_shards(self) -> Vec<NodeStore> {
+        self.shards
+            .into_vec()
+            .into_iter()
+            .map(|s| s.into_inner().unwrap())
+            .collect()
+    }
+}
+
+/// A node of a [`ShardedNodeStore`], its shard stays read-locked until it is dropped.
+///
+/// Avoid inserting in the store while holding it, the shard of the insertion might be this one.
+pub struct ShardedNodeRef<'a> {
+    // declared first to be dropped before the guard it borrows from
+    node: HashedNodeRef<'a, NodeIdentifier>,
+    _shard: RwLockReadGuard<'a, NodeStore>,
+}
+
+impl<'a> ShardedNodeRef<'a> {
+    fn new(shard: &'a RwLock<NodeStore>, id: NodeIdentifier) -> Self {
+        let guard = shard.read().unwrap();
+        // SAFETY: the shard is owned by the lock, which lives for 'a,
+        // and it cannot be written while the guard, kept along the node, is alive.
+        let store: &'a NodeStore = unsafe { &*(&*guard as *const NodeStore) };
+        Self {
+            node: store.resolve(id),
+            _shard: guard,
+        }
+    }
+}
+
+impl<'a> Deref for ShardedNodeRef<'a> {
+    type Target = HashedNodeRef<'a, NodeIdentifier>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.node
+    }
+}
+
+impl<'a> crate::types::Node for ShardedNodeRef<'a> {}
+
+impl<'a> crate::types::Stored for ShardedNodeRef<'a> {
+    type TreeId = NodeIdentifier;
+}
+
+impl<'a> Typed for ShardedNodeRef<'a> {
+    type Type = AnyType;
+
+    fn get_type(&self) -> AnyType {
+        self.node.get_type()
+    }
+}
+
+impl<'a> Labeled for ShardedNodeRef<'a> {
+    type Label = LabelIdentifier;
+
+    fn get_label_unchecked(&self) -> &LabelIdentifier {
+        self.node.get_label_unchecked()
+    }
+
+    fn try_get_label(&self) -> Option<&LabelIdentifier> {
+        self.node.try_get_label()
+    }
+}
+
+impl<'a> WithChildren for ShardedNodeRef<'a> {
+    type ChildIdx = u16;
+    type Children<'b>
+        = MySlice<NodeIdentifier>
+    where
+        Self: 'b;
+
+    fn child_count(&self) -> u16 {
+        self.node.child_count()
+    }
+
+    fn child(&self, idx: &u16) -> Option<NodeIdentifier> {
+        self.node.child(idx)
+    }
+
+    fn child_rev(&self, idx: &u16) -> Option<NodeIdentifier> {
+        self.node.child_rev(idx)
+    }
+
+    fn children(&self) -> Option<&Self::Children<'_>> {
+        self.node.children()
+    }
+}
+
+impl<'a> WithStats for ShardedNodeRef<'a> {
+    fn size(&self) -> usize {
+        self.node.size()
+    }
+
+    fn height(&self) -> usize {
+        self.node.height()
+    }
+}
+
+impl<'a> WithHashs for ShardedNodeRef<'a> {
+    type HK = SyntaxNodeHashsKinds;
+    type HP = HashSize;
+
+    fn hash(&self, kind: &Self::HK) -> Self::HP {
+        WithHashs::hash(&self.node, kind)
+    }
+}
+
+impl<'a> WithSerialization for ShardedNodeRef<'a> {
+    fn try_bytes_len(&self) -> Option<usize> {
+        self.node.try_bytes_len()
+    }
+}
+
+impl<'a> Tree for ShardedNodeRef<'a> {
+    fn has_children(&self) -> bool {
+        self.node.has_children()
+    }
+
+    fn has_label(&self) -> bool {
+        self.node.has_label()
+    }
+}
+
+/// Resolving only read-locks the shard of the node.
+impl crate::types::NodeStore<ShardedNodeIdentifier> for ShardedNodeStore {
+    type R<'a> = ShardedNodeRef<'a>;
+    fn resolve(&self, id: &ShardedNodeIdentifier) -> Self::R<'_> {
+        ShardedNodeRef::new(&self.shards[id.shard as usize], id.id)
+    }
+}
+
+/// Needed to resolve children, and to be the node store of a [`crate::types::HyperAST`],
+/// the shard is found with [`ShardedNodeStore::locate`].
+impl crate::types::NodeStore<NodeIdentifier> for ShardedNodeStore {
+    type R<'a> = ShardedNodeRef<'a>;
+    fn resolve(&self, id: &NodeIdentifier) -> Self::R<'_> {
+        let id = self
+            .locate(*id)
+            .unwrap_or_else(|| panic!("{:?} is not in the store", id));
+        crate::types::NodeStore::resolve(self, &id)
+    }
+}
+
+impl Default for ShardedNodeStore {
+    fn default() -> Self {
+        Self::new(64)
+    }
+}
+
+/// A read-only view of all the shards of a [`ShardedNodeStore`].
+pub struct ShardedNodeStoreView<'a> {
+    store: &'a ShardedNodeStore,
+    shards: Vec<RwLockReadGuard<'a, NodeStore>>,
+}
+
+impl<'a> ShardedNodeStoreView<'a> {
+    /// Finds the shard of the node with [`ShardedNodeStore::locate`].
+    pub fn try_resolve(&self, id: NodeIdentifier) -> Option<HashedNodeRef<NodeIdentifier>> {
+        let id = self.store.locate(id)?;
+        self.shards[id.shard as usize].try_resolve(id.id)
+    }
+}
+
+impl<'a> crate::types::NodeStore<ShardedNodeIdentifier> for ShardedNodeStoreView<'a> {
+    type R<'b>
+        = HashedNodeRef<'b, NodeIdentifier>
+    where
+        Self: 'b;
+    fn resolve(&self, id: &ShardedNodeIdentifier) -> Self::R<'_> {
+        self.shards[id.shard as usize].resolve(id.id)
+    }
+}
+
+/// Needed to resolve children, which are not given with their shard.
+impl<'a> crate::types::NodeStore<NodeIdentifier> for ShardedNodeStoreView<'a> {
+    type R<'b>
+        = HashedNodeRef<'b, NodeIdentifier>
+    where
+        Self: 'b;
+    fn resolve(&self, id: &NodeIdentifier) -> Self::R<'_> {
+        self.try_resolve(*id)
+            .unwrap_or_else(|| panic!("{:?} is not in the store", id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        store::{labels::sharded::ShardedLabelStore, nodes::legion::compo::CS},
+        types::NodeStore as _,
+    };
+
+    #[test]
+    fn test_concurrent_dedup() {
+        let labels = ShardedLabelStore::new(8);
+        let nodes = ShardedNodeStore::new(8);
+        let insert = |s: &str| {
+            let l: LabelIdentifier = labels.get_or_insert(s);
+            let mut builder = dyn_builder::EntityBuilder::new();
+            builder.add(l);
+            let eq = |x: EntryRef| x.get_component::<LabelIdentifier>().ok() == Some(&l);
+            nodes.insert_built(&l, eq, builder.build())
+        };
+        let names: Vec<_> = (0..100).map(|i| format!("x{}", i)).collect();
+        let ids: Vec<Vec<_>> = std::thread::scope(|scope| {
+            let threads: Vec<_> = (0..4)
+                .map(|t| {
+                    let names = &names;
+                    let insert = &insert;
+                    scope.spawn(move || {
+                        // each thread inserts in a different order
+                        let step = [1, 3, 7, 9][t];
+                        let mut ids: Vec<_> = (0..names.len())
+                            .map(|i| (i * step) % names.len())
+                            .map(|i| (i, insert(&names[i])))
+                            .collect();
+                        ids.sort_by_key(|(i, _)| *i);
+                        ids
+                    })
+                })
+                .collect();
+            threads.into_iter().map(|t| t.join().unwrap()).collect()
+        });
+        assert!(ids.iter().all(|x| x == &ids[0]));
+        assert_eq!(nodes.len(), names.len());
+        for (i, id) in &ids[0] {
+            let l = *nodes.resolve(id).get_label_unchecked();
+            assert_eq!(labels.resolve(&l), names[*i]);
+            assert_eq!(Some(*id), nodes.locate(id.id()));
+        }
+        let view = nodes.read();
+        for (i, id) in &ids[0] {
+            let l = *view.resolve(id).get_label_unchecked();
+            assert_eq!(labels.resolve(&l), names[*i]);
+            let l = *view.resolve(&id.id()).get_label_unchecked();
+            assert_eq!(labels.resolve(&l), names[*i]);
+        }
+    }
+
+    /// Collects the labels in pre-order, like any user of a [`crate::types::HyperAST`] would.
+    fn labels<'a, NS>(store: &'a NS, id: &NodeIdentifier, out: &mut Vec<LabelIdentifier>)
+    where
+        NS: crate::types::NodeStore<NodeIdentifier>,
+        NS::R<'a>: Labeled<Label = LabelIdentifier>
+            + WithChildren<TreeId = NodeIdentifier, ChildIdx = u16>,
+    {
+        let node = store.resolve(id);
+        out.extend(node.try_get_label());
+        for i in 0..node.child_count() {
+            labels(store, &node.child(&i).unwrap(), out);
+        }
+    }
+
+    #[test]
+    fn test_traverse_children() {
+        let labels_store = ShardedLabelStore::new(8);
+        let nodes = ShardedNodeStore::new(8);
+        let insert = |s: &str, cs: &[NodeIdentifier]| {
+            let l: LabelIdentifier = labels_store.get_or_insert(s);
+            let mut builder = dyn_builder::EntityBuilder::new();
+            builder.add(l);
+            if !cs.is_empty() {
+                builder.add(CS::<NodeIdentifier>(cs.into()));
+            }
+            let eq = |x: EntryRef| x.get_component::<LabelIdentifier>().ok() == Some(&l);
+            nodes.insert_built(&l, eq, builder.build())
+        };
+        let a = insert("a", &[]);
+        let b = insert("b", &[]);
+        let c = insert("c", &[a.id(), b.id()]);
+        let root = insert("root", &[c.id(), a.id()]);
+
+        let node = nodes.resolve(&root);
+        assert_eq!(2, node.child_count());
+        assert_eq!(Some(c.id()), node.child(&0));
+        assert_eq!(c.shard, nodes.locate(c.id()).unwrap().shard);
+        drop(node);
+
+        let mut out = vec![];
+        labels(&nodes, &root.id(), &mut out);
+        let out: Vec<_> = out.iter().map(|l| labels_store.resolve(l)).collect();
+        assert_eq!(out, ["root", "c", "a", "b", "a"]);
+        let view = nodes.read();
+        let mut out2 = vec![];
+        labels(&view, &root.id(), &mut out2);
+        assert_eq!(out.len(), out2.len());
+    }
+}
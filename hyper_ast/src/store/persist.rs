@@ -0,0 +1,109 @@
+//! Building blocks of the versioned on-disk format of the stores.
+//!
+//! Everything is written little endian.
+//! Each store writes a magic number and [`FORMAT_VERSION`] before its content,
+//! so that a file produced by another version is rejected instead of being misread.
+
+use std::io::{self, Read, Write};
+
+/// Bump it each time the layout of a persisted store changes.
+pub const FORMAT_VERSION: u32 = 5;
+
+pub fn invalid_data<E: Into<Box<dyn std::error::Error + Send + Sync>>>(err: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+pub fn write_header<W: Write + ?Sized>(w: &mut W, magic: &[u8; 4]) -> io::Result<()> {
+    w.write_all(magic)?;
+    write_u32(w, FORMAT_VERSION)
+}
+
+pub fn read_header<R: Read + ?Sized>(r: &mut R, magic: &[u8; 4]) -> io::Result<()> {
+    let mut found = [0; 4];
+    r.read_exact(&mut found)?;
+    if &found != magic {
+        return Err(invalid_data(format!(
+            "expected magic {:?} but found {:?}",
+            magic, found
+        )));
+    }
+    let version = read_u32(r)?;
+    if version != FORMAT_VERSION {
+        return Err(invalid_data(format!(
+            "unsupported format version {} (current is {})",
+            version, FORMAT_VERSION
+        )));
+    }
+    Ok(())
+}
+
+pub fn write_u8<W: Write + ?Sized>(w: &mut W, x: u8) -> io::Result<()> {
+    w.write_all(&[x])
+}
+
+pub fn read_u8<R: Read + ?Sized>(r: &mut R) -> io::Result<u8> {
+    let mut b = [0; 1];
+    r.read_exact(&mut b)?;
+    Ok(b[0])
+}
+
+pub fn write_u16<W: Write + ?Sized>(w: &mut W, x: u16) -> io::Result<()> {
+    w.write_all(&x.to_le_bytes())
+}
+
+pub fn read_u16<R: Read + ?Sized>(r: &mut R) -> io::Result<u16> {
+    let mut b = [0; 2];
+    r.read_exact(&mut b)?;
+    Ok(u16::from_le_bytes(b))
+}
+
+pub fn write_u32<W: Write + ?Sized>(w: &mut W, x: u32) -> io::Result<()> {
+    w.write_all(&x.to_le_bytes())
+}
+
+pub fn read_u32<R: Read + ?Sized>(r: &mut R) -> io::Result<u32> {
+    let mut b = [0; 4];
+    r.read_exact(&mut b)?;
+    Ok(u32::from_le_bytes(b))
+}
+
+pub fn write_u64<W: Write + ?Sized>(w: &mut W, x: u64) -> io::Result<()> {
+    w.write_all(&x.to_le_bytes())
+}
+
+pub fn read_u64<R: Read + ?Sized>(r: &mut R) -> io::Result<u64> {
+    let mut b = [0; 8];
+    r.read_exact(&mut b)?;
+    Ok(u64::from_le_bytes(b))
+}
+
+pub fn write_u128<W: Write + ?Sized>(w: &mut W, x: u128) -> io::Result<()> {
+    w.write_all(&x.to_le_bytes())
+}
+
+pub fn read_u128<R: Read + ?Sized>(r: &mut R) -> io::Result<u128> {
+    let mut b = [0; 16];
+    r.read_exact(&mut b)?;
+    Ok(u128::from_le_bytes(b))
+}
+
+/// length prefixed bytes
+pub fn write_bytes<W: Write + ?Sized>(w: &mut W, x: &[u8]) -> io::Result<()> {
+    write_u32(w, x.len().try_into().map_err(invalid_data)?)?;
+    w.write_all(x)
+}
+
+pub fn read_bytes<R: Read + ?Sized>(r: &mut R) -> io::Result<Vec<u8>> {
+    let len = read_u32(r)? as usize;
+    let mut b = vec![0; len];
+    r.read_exact(&mut b)?;
+    Ok(b)
+}
+
+pub fn write_str<W: Write + ?Sized>(w: &mut W, x: &str) -> io::Result<()> {
+    write_bytes(w, x.as_bytes())
+}
+
+pub fn read_string<R: Read + ?Sized>(r: &mut R) -> io::Result<String> {
+    String::from_utf8(read_bytes(r)?).map_err(invalid_data)
+}
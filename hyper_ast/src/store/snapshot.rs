@@ -0,0 +1,551 @@
+//! Immutable snapshots of HyperASTs, read in place from their bytes, e.g. from a memory map.
+//!
+//! [`freeze`] writes the subtrees of some roots as fixed size node records,
+//! an array of children and the labels sorted lexicographically.
+//! [`Snapshot`] implements [`HyperAST`] directly over these bytes,
+//! so processes mapping the same snapshot share its pages instead of each rebuilding the stores.
+//!
+//! As in [`super::nodes::fetched::SimplePacked`], types are written through [`TypeStore::marshal_type`],
+//! the [`LangRef`]s given when opening a snapshot resolve them back.
+use std::{borrow::Borrow, collections::HashMap, hash::Hash, io};
+
+use super::persist::{
+    invalid_data, read_header, read_string, read_u32, write_header, write_str, write_u32,
+};
+use crate::{
+    hashed::SyntaxNodeHashsKinds,
+    nodes::HashSize,
+    types::{
+        HyperAST, HyperType, IterableChildren, LabelStore, Labeled, LangRef, LangWrapper, MySlice,
+        NodeId, NodeStore, TypeIndex, TypeStore, WithChildren, WithHashs, WithSerialization,
+        WithStats,
+    },
+};
+
+const MAGIC: &[u8; 4] = b"HAsn";
+
+/// header then counts of languages, nodes, children, labels, roots and label bytes
+const PREFIX_LEN: usize = 2 + 6;
+
+// fields of the node records
+const TYPE: usize = 0;
+const LABEL: usize = 1;
+const CS_START: usize = 2;
+const CS_LEN: usize = 3;
+const SIZE: usize = 4;
+const HEIGHT: usize = 5;
+const BYTES_LEN: usize = 6;
+const HASHS: usize = 7;
+const RECORD_LEN: usize = HASHS + 3;
+
+const NONE: u32 = u32::MAX;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash, PartialOrd, Ord)]
+#[repr(transparent)]
+pub struct NodeIdentifier(u32);
+
+impl NodeId for NodeIdentifier {
+    type IdN = Self;
+    fn as_id(&self) -> &Self::IdN {
+        self
+    }
+    unsafe fn from_id(id: Self::IdN) -> Self {
+        id
+    }
+    unsafe fn from_ref_id(id: &Self::IdN) -> &Self {
+        id
+    }
+}
+
+/// Labels are sorted, so comparing identifiers compares labels.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash, PartialOrd, Ord)]
+pub struct LabelIdentifier(u32);
+
+fn to_u32(x: usize) -> io::Result<u32> {
+    x.try_into()
+        .map_err(|_| invalid_data("too many elements for a snapshot"))
+}
+
+/// Writes the subtrees of `roots`.
+///
+/// Shared subtrees are written once, nodes are numbered in post-order.
+pub fn freeze<'store, HAST, W>(
+    stores: &'store HAST,
+    roots: &[HAST::IdN],
+    w: &mut W,
+) -> io::Result<()>
+where
+    HAST: HyperAST<'store>,
+    HAST::IdN: Hash,
+    HAST::T: WithStats + WithSerialization + WithHashs<HK = SyntaxNodeHashsKinds, HP = HashSize>,
+    HAST::TS: TypeStore<HAST::T, Marshaled = TypeIndex>,
+    W: io::Write + ?Sized,
+{
+    let children_of = |id: &HAST::IdN| -> Vec<HAST::IdN> {
+        let node = stores.node_store().resolve(id);
+        node.children()
+            .map(|cs| cs.iter_children().cloned().collect())
+            .unwrap_or_default()
+    };
+    let mut index: HashMap<HAST::IdN, u32> = HashMap::new();
+    let mut order = vec![];
+    for root in roots {
+        let mut stack = vec![(root.clone(), false)];
+        while let Some((id, visited)) = stack.pop() {
+            if index.contains_key(&id) {
+                continue;
+            }
+            if visited {
+                index.insert(id.clone(), to_u32(order.len())?);
+                order.push(id);
+            } else {
+                let cs = children_of(&id);
+                stack.push((id, true));
+                stack.extend(cs.into_iter().rev().map(|x| (x, false)));
+            }
+        }
+    }
+
+    let label_of = |id: &HAST::IdN| -> Option<&'store str> {
+        let node = stores.node_store().resolve(id);
+        node.try_get_label()
+            .map(|l| stores.label_store().resolve(l))
+    };
+    let mut labels: Vec<&str> = order.iter().filter_map(label_of).collect();
+    labels.sort_unstable();
+    labels.dedup();
+
+    let mut langs: Vec<&'static str> = vec![];
+    let mut records = Vec::with_capacity(order.len() * RECORD_LEN);
+    let mut children = vec![];
+    for id in &order {
+        let node = stores.node_store().resolve(id);
+        let TypeIndex { lang, ty } = stores.type_store().marshal_type(&node);
+        let lang = match langs.iter().position(|l| *l == lang) {
+            Some(i) => i,
+            None => {
+                langs.push(lang);
+                langs.len() - 1
+            }
+        };
+        records.push((to_u32(lang)? << 16) | ty as u32);
+        records.push(match label_of(id) {
+            Some(l) => to_u32(labels.binary_search(&l).unwrap())?,
+            None => NONE,
+        });
+        match node.children() {
+            Some(cs) => {
+                records.push(to_u32(children.len())?);
+                let start = children.len();
+                children.extend(cs.iter_children().map(|x| index[x]));
+                records.push(to_u32(children.len() - start)?);
+            }
+            None => records.extend([NONE, 0]),
+        }
+        records.push(to_u32(node.size())?);
+        records.push(to_u32(node.height())?);
+        records.push(match node.try_bytes_len() {
+            Some(x) => to_u32(x)?,
+            None => NONE,
+        });
+        records.push(node.hash(&SyntaxNodeHashsKinds::Struct));
+        records.push(node.hash(&SyntaxNodeHashsKinds::Label));
+        records.push(node.hash(&SyntaxNodeHashsKinds::Syntax));
+    }
+
+    write_header(w, MAGIC)?;
+    write_u32(w, to_u32(langs.len())?)?;
+    write_u32(w, to_u32(order.len())?)?;
+    write_u32(w, to_u32(children.len())?)?;
+    write_u32(w, to_u32(labels.len())?)?;
+    write_u32(w, to_u32(roots.len())?)?;
+    write_u32(w, to_u32(labels.iter().map(|l| l.len()).sum())?)?;
+    for root in roots {
+        write_u32(w, index[root])?;
+    }
+    for x in records.into_iter().chain(children) {
+        write_u32(w, x)?;
+    }
+    let mut offset = 0;
+    write_u32(w, 0)?;
+    for l in &labels {
+        offset += l.len();
+        write_u32(w, to_u32(offset)?)?;
+    }
+    for lang in langs {
+        write_str(w, lang)?;
+    }
+    for l in labels {
+        w.write_all(l.as_bytes())?;
+    }
+    Ok(())
+}
+
+/// A snapshot written by [`freeze`], read from `bytes`.
+pub struct Snapshot<B, Ty: 'static> {
+    bytes: B,
+    langs: Vec<&'static dyn LangRef<Ty>>,
+    node_count: usize,
+    label_count: usize,
+    // offsets in words
+    roots: usize,
+    records: usize,
+    children: usize,
+    label_offsets: usize,
+    words_len: usize,
+    // offset in bytes
+    label_bytes: usize,
+}
+
+impl<B: AsRef<[u8]>, Ty: 'static> Snapshot<B, Ty> {
+    /// `bytes` must be 4 bytes aligned, as are memory maps and heap allocations,
+    /// `langs` must contain the languages of the snapshot.
+    pub fn open(bytes: B, langs: &[&'static dyn LangRef<Ty>]) -> io::Result<Self> {
+        if cfg!(target_endian = "big") {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "snapshots are little endian",
+            ));
+        }
+        let mut r = bytes.as_ref();
+        read_header(&mut r, MAGIC)?;
+        let lang_count = read_u32(&mut r)? as usize;
+        let node_count = read_u32(&mut r)? as usize;
+        let children_len = read_u32(&mut r)? as usize;
+        let label_count = read_u32(&mut r)? as usize;
+        let root_count = read_u32(&mut r)? as usize;
+        let label_bytes_len = read_u32(&mut r)? as usize;
+        let roots = PREFIX_LEN;
+        let records = roots + root_count;
+        let children = records + node_count * RECORD_LEN;
+        let label_offsets = children + children_len;
+        let words_len = label_offsets + label_count + 1;
+
+        let mut r = bytes
+            .as_ref()
+            .get(words_len * 4..)
+            .ok_or_else(|| invalid_data("truncated snapshot"))?;
+        let found = (0..lang_count)
+            .map(|_| {
+                let name = read_string(&mut r)?;
+                langs
+                    .iter()
+                    .find(|l| l.name() == name)
+                    .copied()
+                    .ok_or_else(|| invalid_data(format!("unknown language {}", name)))
+            })
+            .collect::<io::Result<_>>()?;
+        if r.len() != label_bytes_len {
+            return Err(invalid_data("truncated snapshot"));
+        }
+        // SAFETY: any bit pattern is a valid u32
+        let (prefix, _, _) = unsafe { bytes.as_ref().align_to::<u32>() };
+        if !prefix.is_empty() {
+            return Err(invalid_data("snapshot bytes must be 4 bytes aligned"));
+        }
+        let label_bytes = bytes.as_ref().len() - label_bytes_len;
+        Ok(Self {
+            bytes,
+            langs: found,
+            node_count,
+            label_count,
+            roots,
+            records,
+            children,
+            label_offsets,
+            words_len,
+            label_bytes,
+        })
+    }
+
+    fn words(&self) -> &[u32] {
+        // SAFETY: any bit pattern is a valid u32, the alignment is checked when opening
+        let (_, words, _) = unsafe { self.bytes.as_ref()[..self.words_len * 4].align_to::<u32>() };
+        words
+    }
+
+    fn ids(words: &[u32]) -> &[NodeIdentifier] {
+        // SAFETY: NodeIdentifier is a transparent u32
+        unsafe { std::mem::transmute(words) }
+    }
+
+    /// The roots given to [`freeze`], in the same order.
+    pub fn roots(&self) -> &[NodeIdentifier] {
+        Self::ids(&self.words()[self.roots..self.records])
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.node_count
+    }
+
+    pub fn label_count(&self) -> usize {
+        self.label_count
+    }
+
+    fn label(&self, i: usize) -> &str {
+        let offsets = &self.words()[self.label_offsets + i..];
+        let bytes = &self.bytes.as_ref()[self.label_bytes..];
+        std::str::from_utf8(&bytes[offsets[0] as usize..offsets[1] as usize])
+            .expect("corrupted snapshot label")
+    }
+
+    /// Binary search among the sorted labels.
+    pub fn get(&self, label: &str) -> Option<LabelIdentifier> {
+        let (mut low, mut high) = (0, self.label_count);
+        while low < high {
+            let mid = (low + high) / 2;
+            match self.label(mid).cmp(label) {
+                std::cmp::Ordering::Less => low = mid + 1,
+                std::cmp::Ordering::Greater => high = mid,
+                std::cmp::Ordering::Equal => return Some(LabelIdentifier(mid as u32)),
+            }
+        }
+        None
+    }
+
+    pub fn resolve_label(&self, id: &LabelIdentifier) -> &str {
+        self.label(id.0 as usize)
+    }
+
+    pub fn try_resolve(&self, id: NodeIdentifier) -> Option<HashedNodeRef<'_, Ty>>
+    where
+        Ty: Copy,
+    {
+        let i = id.0 as usize;
+        if i >= self.node_count {
+            return None;
+        }
+        let start = self.records + i * RECORD_LEN;
+        let record = &self.words()[start..start + RECORD_LEN];
+        let lang = self.langs[(record[TYPE] >> 16) as usize];
+        let children = match record[CS_START] {
+            NONE => None,
+            cs => {
+                let cs = &self.words()[self.children + cs as usize..][..record[CS_LEN] as usize];
+                Some(Self::ids(cs))
+            }
+        };
+        Some(HashedNodeRef {
+            ty: *lang.make(record[TYPE] as u16),
+            lang,
+            label: match record[LABEL] {
+                NONE => None,
+                l => Some(LabelIdentifier(l)),
+            },
+            children,
+            record,
+        })
+    }
+}
+
+#[cfg(feature = "mmap")]
+impl<Ty: 'static> Snapshot<memmap2::Mmap, Ty> {
+    /// Maps the snapshot at `path`, the pages are shared with the other processes mapping it.
+    pub fn map(
+        path: impl AsRef<std::path::Path>,
+        langs: &[&'static dyn LangRef<Ty>],
+    ) -> io::Result<Self> {
+        let file = std::fs::File::open(path)?;
+        // SAFETY: snapshots are never modified after being written
+        let bytes = unsafe { memmap2::Mmap::map(&file)? };
+        Self::open(bytes, langs)
+    }
+}
+
+/// A node of a [`Snapshot`].
+pub struct HashedNodeRef<'a, Ty: 'static> {
+    ty: Ty,
+    lang: &'static dyn LangRef<Ty>,
+    label: Option<LabelIdentifier>,
+    children: Option<&'a [NodeIdentifier]>,
+    record: &'a [u32],
+}
+
+impl<'a, Ty: std::fmt::Debug> std::fmt::Debug for HashedNodeRef<'a, Ty> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HashedNodeRef")
+            .field("ty", &self.ty)
+            .field("label", &self.label)
+            .field("children", &self.children)
+            .finish()
+    }
+}
+
+impl<'a, Ty> crate::types::Node for HashedNodeRef<'a, Ty> {}
+
+impl<'a, Ty> crate::types::Stored for HashedNodeRef<'a, Ty> {
+    type TreeId = NodeIdentifier;
+}
+
+impl<'a, Ty: HyperType + Eq + Copy + Send + Sync> crate::types::Typed for HashedNodeRef<'a, Ty> {
+    type Type = Ty;
+
+    fn get_type(&self) -> Ty {
+        self.ty
+    }
+}
+
+impl<'a, Ty> crate::types::Labeled for HashedNodeRef<'a, Ty> {
+    type Label = LabelIdentifier;
+
+    fn get_label_unchecked(&self) -> &LabelIdentifier {
+        self.label.as_ref().expect("check with self.has_label()")
+    }
+
+    fn try_get_label(&self) -> Option<&LabelIdentifier> {
+        self.label.as_ref()
+    }
+}
+
+impl<'a, Ty> crate::types::WithChildren for HashedNodeRef<'a, Ty> {
+    type ChildIdx = u16;
+    type Children<'b> = MySlice<Self::TreeId> where Self: 'b;
+
+    fn child_count(&self) -> u16 {
+        self.children
+            .map_or(0, |cs| cs.len())
+            .try_into()
+            .expect("too much children")
+    }
+
+    fn child(&self, idx: &u16) -> Option<NodeIdentifier> {
+        self.children?.get(*idx as usize).copied()
+    }
+
+    fn child_rev(&self, idx: &u16) -> Option<NodeIdentifier> {
+        let cs = self.children?;
+        let i = cs.len().checked_sub(*idx as usize + 1)?;
+        cs.get(i).copied()
+    }
+
+    fn children(&self) -> Option<&Self::Children<'_>> {
+        self.children.map(Into::into)
+    }
+}
+
+impl<'a, Ty: HyperType + Eq + Copy + Send + Sync> crate::types::Tree for HashedNodeRef<'a, Ty> {
+    fn has_children(&self) -> bool {
+        self.children.map_or(false, |cs| !cs.is_empty())
+    }
+
+    fn has_label(&self) -> bool {
+        self.label.is_some()
+    }
+}
+
+impl<'a, Ty> WithStats for HashedNodeRef<'a, Ty> {
+    fn size(&self) -> usize {
+        self.record[SIZE] as usize
+    }
+
+    fn height(&self) -> usize {
+        self.record[HEIGHT] as usize
+    }
+}
+
+impl<'a, Ty> WithSerialization for HashedNodeRef<'a, Ty> {
+    fn try_bytes_len(&self) -> Option<usize> {
+        match self.record[BYTES_LEN] {
+            NONE => None,
+            x => Some(x as usize),
+        }
+    }
+}
+
+impl<'a, Ty> WithHashs for HashedNodeRef<'a, Ty> {
+    type HK = SyntaxNodeHashsKinds;
+    type HP = HashSize;
+
+    fn hash(&self, kind: &SyntaxNodeHashsKinds) -> HashSize {
+        match kind {
+            SyntaxNodeHashsKinds::Struct => self.record[HASHS],
+            SyntaxNodeHashsKinds::Label => self.record[HASHS + 1],
+            SyntaxNodeHashsKinds::Syntax => self.record[HASHS + 2],
+        }
+    }
+}
+
+impl<B: AsRef<[u8]>, Ty: Copy + 'static> NodeStore<NodeIdentifier> for Snapshot<B, Ty> {
+    type R<'a> = HashedNodeRef<'a, Ty> where Self: 'a;
+
+    fn resolve(&self, id: &NodeIdentifier) -> Self::R<'_> {
+        self.try_resolve(*id)
+            .unwrap_or_else(|| panic!("{:?} is not in the snapshot", id))
+    }
+}
+
+/// Snapshots are read-only, only labels already in the snapshot can be "inserted".
+impl<B: AsRef<[u8]>, Ty: 'static> LabelStore<str> for Snapshot<B, Ty> {
+    type I = LabelIdentifier;
+
+    fn get_or_insert<T: Borrow<str>>(&mut self, node: T) -> Self::I {
+        let node = node.borrow();
+        Snapshot::get(self, node).unwrap_or_else(|| panic!("{:?} is not in the snapshot", node))
+    }
+
+    fn get<T: Borrow<str>>(&self, node: T) -> Option<Self::I> {
+        Snapshot::get(self, node.borrow())
+    }
+
+    fn resolve(&self, id: &Self::I) -> &str {
+        self.resolve_label(id)
+    }
+}
+
+impl<'a, B: AsRef<[u8]>, Ty> TypeStore<HashedNodeRef<'a, Ty>> for Snapshot<B, Ty>
+where
+    Ty: 'static + HyperType + Eq + Hash + Copy + Send + Sync,
+{
+    type Ty = Ty;
+
+    const MASK: u16 = 0;
+
+    fn resolve_type(&self, n: &HashedNodeRef<'a, Ty>) -> Ty {
+        n.ty
+    }
+
+    fn resolve_lang(&self, n: &HashedNodeRef<'a, Ty>) -> LangWrapper<Ty> {
+        n.lang.into()
+    }
+
+    type Marshaled = TypeIndex;
+
+    fn marshal_type(&self, n: &HashedNodeRef<'a, Ty>) -> TypeIndex {
+        TypeIndex {
+            lang: n.lang.name(),
+            ty: n.record[TYPE] as u16,
+        }
+    }
+}
+
+impl<'store, B, Ty> HyperAST<'store> for Snapshot<B, Ty>
+where
+    B: 'store + AsRef<[u8]>,
+    Ty: 'static + HyperType + Eq + Hash + Copy + Send + Sync,
+{
+    type IdN = NodeIdentifier;
+
+    type Idx = u16;
+
+    type Label = LabelIdentifier;
+
+    type T = HashedNodeRef<'store, Ty>;
+
+    type NS = Self;
+
+    fn node_store(&self) -> &Self::NS {
+        self
+    }
+
+    type LS = Self;
+
+    fn label_store(&self) -> &Self::LS {
+        self
+    }
+
+    type TS = Self;
+
+    fn type_store(&self) -> &Self::TS {
+        self
+    }
+}
@@ -0,0 +1,461 @@
+//! Fully compress all subtrees of a CST, without any analysis.
+//!
+//! The generator is shared by the languages that only need the syntax,
+//! eg. typescript, python or rust,
+//! each one providing its types and how to read them from its parser through [`BasicLang`].
+use std::{collections::HashMap, fmt::Debug, hash::Hash, marker::PhantomData};
+
+use legion::{storage::Component, world::EntryRef};
+
+use crate::{
+    filter::BloomSize,
+    full::FullNode,
+    hashed::{self, IndexingHashBuilder, MetaDataHashsBuilder, SyntaxNodeHashs},
+    nodes::Space,
+    store::{
+        labels::DefaultLabelIdentifier as LabelIdentifier,
+        nodes::legion::{
+            compo::{self, NoSpacesCS, CS},
+            NodeIdentifier, PendingInsert,
+        },
+        nodes::DefaultNodeStore as NodeStore,
+        SimpleStores,
+    },
+    types::LabelStore as _,
+};
+
+use super::{
+    compute_indentation, get_spacing, has_final_space, parser, parser::Node as _,
+    parser::TreeCursor as _, AccIndentation, Accumulator, BasicAccumulator, BasicGlobalData,
+    Parents, SpacedGlobalData, Spaces, SubTreeMetrics, TextedGlobalData, TreeGen, ZippedTreeGen,
+};
+
+/// What [`BasicTreeGen`] needs to know about a language.
+pub trait BasicLang: 'static {
+    type Type: Component + Copy + Eq + Hash + Debug;
+    type Node<'a>: parser::Node<'a>;
+    type TreeCursor<'a>: parser::TreeCursor<'a, Self::Node<'a>> + Debug;
+
+    /// the type of the nodes made of spaces between the nodes of the parser
+    const SPACES: Self::Type;
+
+    fn obtain_type(node: &Self::Node<'_>) -> Self::Type;
+
+    /// literals are kept as labeled leafs, eg. strings with their escape sequences
+    fn is_literal(kind: &Self::Type) -> bool;
+}
+
+pub struct BasicTreeGen<'store, 'cache, L, TS> {
+    pub line_break: Vec<u8>,
+    pub stores: &'store mut SimpleStores<TS>,
+    pub md_cache: &'cache mut MDCache,
+    phantom: PhantomData<L>,
+}
+
+pub type MDCache = HashMap<NodeIdentifier, MD>;
+
+// NOTE only keep compute intensive metadata (where space/time tradeoff is worth storing)
+// eg. decls refs, maybe hashes but not size and height
+// * metadata: computation results from concrete code of node and its children
+// they can be qualitative metadata .eg a hash or they can be quantitative .eg lines of code
+pub struct MD {
+    metrics: SubTreeMetrics<SyntaxNodeHashs<u32>>,
+    ana: Option<PartialAnalysis>,
+}
+
+impl From<Local> for MD {
+    fn from(x: Local) -> Self {
+        MD {
+            metrics: x.metrics,
+            ana: x.ana,
+        }
+    }
+}
+
+pub type Global<'a> = SpacedGlobalData<'a>;
+
+/// TODO temporary placeholder
+#[derive(Debug, Clone, Default)]
+pub struct PartialAnalysis {}
+
+#[derive(Debug, Clone)]
+pub struct Local {
+    pub compressed_node: NodeIdentifier,
+    pub metrics: SubTreeMetrics<SyntaxNodeHashs<u32>>,
+    pub ana: Option<PartialAnalysis>,
+}
+
+impl Local {
+    fn acc<Ty>(self, acc: &mut Acc<Ty>) {
+        if self.metrics.size_no_spaces > 0 {
+            acc.no_space.push(self.compressed_node)
+        }
+        acc.simple.push(self.compressed_node);
+        acc.metrics.acc(self.metrics);
+    }
+}
+
+pub struct Acc<Ty> {
+    simple: BasicAccumulator<Ty, NodeIdentifier>,
+    no_space: Vec<NodeIdentifier>,
+    labeled: bool,
+    start_byte: usize,
+    end_byte: usize,
+    metrics: SubTreeMetrics<SyntaxNodeHashs<u32>>,
+    padding_start: usize,
+    indentation: Spaces,
+}
+
+pub type FNode = FullNode<BasicGlobalData, Local>;
+impl<Ty> Accumulator for Acc<Ty> {
+    type Node = FNode;
+    fn push(&mut self, full_node: Self::Node) {
+        full_node.local.acc(self);
+    }
+}
+
+impl<Ty> AccIndentation for Acc<Ty> {
+    fn indentation<'a>(&'a self) -> &'a Spaces {
+        &self.indentation
+    }
+}
+
+impl<'store, 'cache, L: BasicLang, TS> ZippedTreeGen for BasicTreeGen<'store, 'cache, L, TS> {
+    type Stores = SimpleStores<TS>;
+    type Text = [u8];
+    type Node<'b> = L::Node<'b>;
+    type TreeCursor<'b> = L::TreeCursor<'b>;
+
+    fn stores(&mut self) -> &mut Self::Stores {
+        &mut self.stores
+    }
+
+    fn init_val(&mut self, text: &[u8], node: &Self::Node<'_>) -> Self::Acc {
+        let kind = L::obtain_type(node);
+        let parent_indentation = Space::try_format_indentation(&self.line_break)
+            .unwrap_or_else(|| vec![Space::Space; self.line_break.len()]);
+        let indent = compute_indentation(
+            &self.line_break,
+            text,
+            node.start_byte(),
+            0,
+            &parent_indentation,
+        );
+        Acc {
+            simple: BasicAccumulator::new(kind),
+            no_space: vec![],
+            labeled: node.has_label(),
+            start_byte: node.start_byte(),
+            end_byte: node.end_byte(),
+            metrics: Default::default(),
+            padding_start: 0,
+            indentation: indent,
+        }
+    }
+
+    fn pre_skippable(
+        &mut self,
+        text: &Self::Text,
+        node: &Self::Node<'_>,
+        stack: &Parents<Self::Acc>,
+        global: &mut Self::Global,
+        skip: &mut bool,
+    ) -> Option<<Self as TreeGen>::Acc> {
+        let literal = L::is_literal(&L::obtain_type(node));
+        if literal {
+            *skip = true;
+        }
+        let mut acc = self.pre(text, node, stack, global);
+        if literal {
+            acc.labeled = true;
+        }
+        Some(acc)
+    }
+
+    fn pre(
+        &mut self,
+        text: &[u8],
+        node: &Self::Node<'_>,
+        stack: &Parents<Self::Acc>,
+        global: &mut Self::Global,
+    ) -> <Self as TreeGen>::Acc {
+        let parent_indentation = &stack.parent().unwrap().indentation();
+        let kind = L::obtain_type(node);
+        let indent = compute_indentation(
+            &self.line_break,
+            text,
+            node.start_byte(),
+            global.sum_byte_length(),
+            &parent_indentation,
+        );
+        Acc {
+            labeled: node.has_label(),
+            start_byte: node.start_byte(),
+            end_byte: node.end_byte(),
+            metrics: Default::default(),
+            padding_start: global.sum_byte_length(),
+            indentation: indent,
+            simple: BasicAccumulator::new(kind),
+            no_space: vec![],
+        }
+    }
+
+    fn post(
+        &mut self,
+        parent: &mut <Self as TreeGen>::Acc,
+        global: &mut Self::Global,
+        text: &[u8],
+        acc: <Self as TreeGen>::Acc,
+    ) -> <<Self as TreeGen>::Acc as Accumulator>::Node {
+        let spacing = get_spacing(
+            acc.padding_start,
+            acc.start_byte,
+            text,
+            parent.indentation(),
+        );
+        if let Some(spacing) = spacing {
+            parent.push(FullNode {
+                global: global.into(),
+                local: self.make_spacing(spacing),
+            });
+        }
+        let label = if acc.labeled {
+            std::str::from_utf8(&text[acc.start_byte..acc.end_byte])
+                .ok()
+                .map(|x| x.to_string())
+        } else {
+            None
+        };
+        self.make(global, acc, label)
+    }
+}
+
+impl<'store, 'cache, L: BasicLang, TS> BasicTreeGen<'store, 'cache, L, TS> {
+    fn make_spacing(&mut self, spacing: Vec<u8>) -> Local {
+        let bytes_len = spacing.len();
+        let spacing = std::str::from_utf8(&spacing).unwrap().to_string();
+        let spacing_id = self.stores.label_store.get_or_insert(spacing.clone());
+        let hbuilder: hashed::Builder<SyntaxNodeHashs<u32>> =
+            hashed::Builder::new(Default::default(), &L::SPACES, &spacing, 1);
+        let hsyntax = hbuilder.most_discriminating();
+        let hashable = &hsyntax;
+
+        let eq = eq_node(&L::SPACES, Some(&spacing_id), &[]);
+
+        let insertion = self.stores.node_store.prepare_insertion(&hashable, eq);
+
+        let mut hashs = hbuilder.build();
+        hashs.structt = 0;
+        hashs.label = 0;
+
+        let compressed_node = if let Some(id) = insertion.occupied_id() {
+            id
+        } else {
+            let vacant = insertion.vacant();
+            let bytes_len = compo::BytesLen(bytes_len.try_into().unwrap());
+            NodeStore::insert_after_prepare(
+                vacant,
+                (L::SPACES, spacing_id, bytes_len, hashs, BloomSize::None),
+            )
+        };
+        Local {
+            compressed_node,
+            metrics: SubTreeMetrics {
+                size: 1,
+                height: 1,
+                hashs,
+                size_no_spaces: 0,
+            },
+            ana: Default::default(),
+        }
+    }
+
+    pub fn new(
+        stores: &'store mut SimpleStores<TS>,
+        md_cache: &'cache mut MDCache,
+    ) -> BasicTreeGen<'store, 'cache, L, TS> {
+        BasicTreeGen {
+            line_break: "\n".as_bytes().to_vec(),
+            stores,
+            md_cache,
+            phantom: PhantomData,
+        }
+    }
+
+    pub fn generate_file(
+        &mut self,
+        name: &[u8],
+        text: &'store [u8],
+        mut cursor: L::TreeCursor<'_>,
+    ) -> FullNode<BasicGlobalData, Local> {
+        let mut global = Global::from(TextedGlobalData::new(Default::default(), text));
+        let mut init = self.init_val(text, &cursor.node());
+
+        let spacing = get_spacing(
+            init.padding_start,
+            init.start_byte,
+            text,
+            init.indentation(),
+        );
+        if let Some(spacing) = spacing {
+            global.down();
+            init.start_byte = 0;
+            init.push(FullNode {
+                global: global.into(),
+                local: self.make_spacing(spacing),
+            });
+            global.right();
+        }
+        let mut stack = init.into();
+
+        self.gen(text, &mut stack, &mut cursor, &mut global);
+
+        let mut acc = stack.finalize();
+
+        if has_final_space(&0, global.sum_byte_length(), text) {
+            let spacing = get_spacing(
+                global.sum_byte_length(),
+                text.len(),
+                text,
+                acc.indentation(),
+            );
+            if let Some(spacing) = spacing {
+                global.right();
+                acc.push(FullNode {
+                    global: global.into(),
+                    local: self.make_spacing(spacing),
+                });
+            }
+        }
+        let label = Some(std::str::from_utf8(name).unwrap().to_owned());
+        self.make(&mut global, acc, label)
+    }
+}
+
+pub fn eq_node<'a, K>(
+    kind: &'a K,
+    label_id: Option<&'a LabelIdentifier>,
+    children: &'a [NodeIdentifier],
+) -> impl Fn(EntryRef) -> bool + 'a
+where
+    K: 'static + Eq + std::hash::Hash + Copy + std::marker::Send + std::marker::Sync,
+{
+    move |x: EntryRef| {
+        let t = x.get_component::<K>();
+        if t != Ok(kind) {
+            return false;
+        }
+        let l = x.get_component::<LabelIdentifier>().ok();
+        if l != label_id {
+            return false;
+        } else {
+            let cs = x.get_component::<CS<legion::Entity>>();
+            let r = match cs {
+                Ok(CS(cs)) => cs.as_ref() == children,
+                Err(_) => children.is_empty(),
+            };
+            if !r {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+impl<'store, 'cache, L: BasicLang, TS> TreeGen for BasicTreeGen<'store, 'cache, L, TS> {
+    type Acc = Acc<L::Type>;
+    type Global = SpacedGlobalData<'store>;
+    fn make(
+        &mut self,
+        global: &mut <Self as TreeGen>::Global,
+        acc: <Self as TreeGen>::Acc,
+        label: Option<String>,
+    ) -> <<Self as TreeGen>::Acc as Accumulator>::Node {
+        let node_store = &mut self.stores.node_store;
+        let label_store = &mut self.stores.label_store;
+        let kind = acc.simple.kind;
+        let hashs = acc.metrics.hashs;
+        let size = acc.metrics.size + 1;
+        let height = acc.metrics.height + 1;
+        let size_no_spaces = acc.metrics.size_no_spaces + 1;
+        let hbuilder = hashed::Builder::new(hashs, &kind, &label, size_no_spaces);
+        let hsyntax = hbuilder.most_discriminating();
+        let hashable = &hsyntax;
+
+        let label_id = label
+            .as_ref()
+            .map(|label| label_store.get_or_insert(label.as_str()));
+        let eq = eq_node(&kind, label_id.as_ref(), &acc.simple.children);
+
+        let insertion = node_store.prepare_insertion(&hashable, eq);
+
+        let hashs = hbuilder.build();
+        let compressed_node = if let Some(compressed_node) = insertion.occupied_id() {
+            compressed_node
+        } else {
+            let bytes_len = compo::BytesLen((acc.end_byte - acc.start_byte).try_into().unwrap());
+            compress(
+                label_id,
+                acc.simple,
+                acc.no_space,
+                size,
+                height,
+                size_no_spaces,
+                insertion,
+                (kind, hashs, bytes_len),
+            )
+        };
+        let metrics = SubTreeMetrics {
+            size,
+            height,
+            hashs,
+            size_no_spaces,
+        };
+        FullNode {
+            global: global.into(),
+            local: Local {
+                compressed_node,
+                metrics,
+                ana: None,
+            },
+        }
+    }
+}
+
+fn compress<T: Component>(
+    label_id: Option<LabelIdentifier>,
+    simple: BasicAccumulator<T, NodeIdentifier>,
+    no_space: Vec<NodeIdentifier>,
+    size: u32,
+    height: u32,
+    size_no_spaces: u32,
+    insertion: PendingInsert,
+    (kind, hashs, bytes_len): (T, SyntaxNodeHashs<u32>, compo::BytesLen),
+) -> legion::Entity {
+    let vacant = insertion.vacant();
+    macro_rules! insert {
+        ( $($c:expr),* $(,)? ) => {
+            match label_id {
+                None => NodeStore::insert_after_prepare(vacant, (kind, hashs, bytes_len, $($c,)*)),
+                Some(label) => NodeStore::insert_after_prepare(vacant, (kind, hashs, bytes_len, label, $($c,)*)),
+            }
+        };
+    }
+    if simple.children.is_empty() {
+        assert_eq!(1, size);
+        assert_eq!(1, height);
+        return insert!(BloomSize::None);
+    }
+    let metrics = (
+        compo::Size(size),
+        compo::SizeNoSpaces(size_no_spaces),
+        compo::Height(height),
+    );
+    let cs = CS(simple.children.into_boxed_slice());
+    if cs.0.len() == no_space.len() {
+        insert!(metrics.0, metrics.1, metrics.2, cs)
+    } else {
+        let no_space = NoSpacesCS(no_space.into_boxed_slice());
+        insert!(metrics.0, metrics.1, metrics.2, cs, no_space)
+    }
+}
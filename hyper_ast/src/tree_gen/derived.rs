@@ -0,0 +1,124 @@
+//! Metrics derived bottom-up while generating trees,
+//! eg. Halstead volume, nesting depth, fan-out or comment ratio.
+//!
+//! A [`DerivedMetric`] is registered in a [`DerivedMetrics`] lent to the tree generators,
+//! its value is stored as a component of each node,
+//! thus retrieved with [`HashedNodeRef::get_component`].
+//! As subtrees are shared, the value of a node must only depend on the node and on the values of its children.
+//! To persist the stores, the values must also be registered in the
+//! [`crate::store::nodes::legion::persist::PersistRegistry`].
+use legion::storage::Component;
+
+use crate::{
+    store::{
+        labels::LabelStore,
+        nodes::legion::{HashedNodeRef, NodeIdentifier, NodeStore},
+    },
+    types::{IterableChildren, LabelStore as _, Labeled, WithChildren},
+};
+
+/// What a [`DerivedMetric`] knows about the node being computed.
+pub struct MetricInput<'a, Ty> {
+    pub kind: Ty,
+    pub label: Option<&'a str>,
+    /// gives access to the other components, eg. [`crate::store::nodes::legion::compo::BytesLen`]
+    pub node: &'a HashedNodeRef<'a, NodeIdentifier>,
+}
+
+/// A metric of the nodes of the language typed by `Ty`.
+pub trait DerivedMetric<Ty>: Send + Sync {
+    type Value: Component + Clone;
+
+    /// `children` are the values of all the children of the node, spaces included
+    fn compute(&self, node: MetricInput<'_, Ty>, children: &[Self::Value]) -> Self::Value;
+}
+
+trait AnyMetric<Ty>: Send + Sync {
+    fn ensure(&self, node_store: &mut NodeStore, label_store: &LabelStore, id: NodeIdentifier);
+}
+
+impl<Ty: Component + Copy, M: DerivedMetric<Ty>> AnyMetric<Ty> for M {
+    fn ensure(&self, node_store: &mut NodeStore, label_store: &LabelStore, id: NodeIdentifier) {
+        ensure(self, node_store, label_store, id);
+    }
+}
+
+fn ensure<Ty: Component + Copy, M: DerivedMetric<Ty>>(
+    metric: &M,
+    node_store: &mut NodeStore,
+    label_store: &LabelStore,
+    id: NodeIdentifier,
+) -> M::Value {
+    let children: Vec<_> = {
+        let node = node_store.resolve(id);
+        if let Ok(value) = node.get_component::<M::Value>() {
+            return value.clone();
+        }
+        node.children()
+            .map(|cs| cs.iter_children().copied().collect())
+            .unwrap_or_default()
+    };
+    let values: Vec<_> = children
+        .into_iter()
+        .map(|x| ensure(metric, node_store, label_store, x))
+        .collect();
+    let value = {
+        let node = node_store.resolve(id);
+        let kind = *node
+            .get_component::<Ty>()
+            .expect("the node is not of the language of the metric");
+        let label = node.try_get_label().map(|l| label_store.resolve(l));
+        metric.compute(
+            MetricInput {
+                kind,
+                label,
+                node: &node,
+            },
+            &values,
+        )
+    };
+    node_store.add_component(id, value.clone());
+    value
+}
+
+/// The metrics computed by the tree generators it is lent to.
+pub struct DerivedMetrics<Ty> {
+    metrics: Vec<Box<dyn AnyMetric<Ty>>>,
+}
+
+impl<Ty> DerivedMetrics<Ty> {
+    /// no metrics, usable in a `static` shared by the tree generators
+    pub const fn new() -> Self {
+        Self { metrics: vec![] }
+    }
+}
+
+impl<Ty> Default for DerivedMetrics<Ty> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Ty: Component + Copy> DerivedMetrics<Ty> {
+    pub fn register<M: DerivedMetric<Ty> + 'static>(&mut self, metric: M) -> &mut Self {
+        self.metrics.push(Box::new(metric));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.metrics.is_empty()
+    }
+
+    /// Stores the values of the metrics on the node `id`,
+    /// and on the descendants missing them, eg. generated before registering a metric.
+    pub fn compute(
+        &self,
+        node_store: &mut NodeStore,
+        label_store: &LabelStore,
+        id: NodeIdentifier,
+    ) {
+        for metric in &self.metrics {
+            metric.ensure(node_store, label_store, id);
+        }
+    }
+}
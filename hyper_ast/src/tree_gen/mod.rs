@@ -1,5 +1,11 @@
 pub mod parser;
 
+#[cfg(feature = "legion")]
+pub mod basic;
+
+#[cfg(feature = "legion")]
+pub mod derived;
+
 // use std::hash::Hash;
 
 use std::fmt::Debug;
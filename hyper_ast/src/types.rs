@@ -3441,6 +3441,21 @@ impl From<&'static dyn HyperType> for AnyType {
     }
 }
 
+/// Wraps each type of a language, in the same order,
+/// such that [`LangRef<AnyType>::make`] can give static references.
+///
+/// eg. `static ANY_TYPES: [AnyType; S_T_L.len()] = any_types(S_T_L);`
+pub const fn any_types<T: HyperType, const N: usize>(types: &'static [T]) -> [AnyType; N] {
+    assert!(types.len() == N && N > 0);
+    let mut r = [AnyType(&types[0]); N];
+    let mut i = 1;
+    while i < N {
+        r[i] = AnyType(&types[i]);
+        i += 1;
+    }
+    r
+}
+
 impl HyperType for AnyType {
     fn is_file(&self) -> bool {
         self.0.is_file()
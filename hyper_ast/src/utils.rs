@@ -183,6 +183,12 @@ impl Bytes {
     }
 }
 
+impl From<isize> for Bytes {
+    fn from(x: isize) -> Self {
+        Self(x)
+    }
+}
+
 impl fmt::Display for Bytes {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let bytes = self.0;
@@ -0,0 +1,100 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use hyper_ast::store::{labels::LabelStore, nodes::legion::NodeStore, SimpleStores};
+use hyper_ast::types::DecompressedSubtree;
+use hyper_ast_gen_ts_java::legion_with_refs::JavaTreeGen;
+use hyper_ast_gen_ts_java::types::TStore;
+use hyper_diff::decompressed_tree_store::{ShallowDecompressedTreeStore, SimpleZsTree};
+use hyper_diff::matchers::{
+    mapping_store::DefaultMappingStore,
+    optimal::{
+        apted::{AptedMatcher, TypedCostModel},
+        zs::ZsMatcher,
+    },
+};
+
+/// Pairs of java files of increasing sizes, each one differing by a few edits.
+fn java_pairs() -> Vec<(String, String)> {
+    let method = |i: usize, op: &str| {
+        format!("int m{i}(int x){{if(x>{i}){{return x{op}{i};}}else{{return m{i}(x+1);}}}}")
+    };
+    [1, 2, 4, 8]
+        .into_iter()
+        .map(|n| {
+            let src: String = (0..n).map(|i| method(i, "+")).collect();
+            let dst: String = (0..n)
+                .map(|i| method(i, if i % 2 == 0 { "-" } else { "+" }))
+                .collect();
+            (
+                format!("class A{{{src}}}"),
+                format!("class A{{{dst}int f;}}"),
+            )
+        })
+        .collect()
+}
+
+fn compare_optimal_matchers(c: &mut Criterion) {
+    let mut group = c.benchmark_group("OptimalMatchers");
+    group.sample_size(10);
+
+    let mut stores = SimpleStores {
+        label_store: LabelStore::new(),
+        type_store: TStore::default(),
+        node_store: NodeStore::new(),
+    };
+    let mut md_cache = Default::default();
+    let mut java_tree_gen = JavaTreeGen::new(&mut stores, &mut md_cache);
+    let pairs: Vec<_> = java_pairs()
+        .iter()
+        .map(|(src, dst)| {
+            let mut parse = |text: &str| {
+                let tree = match JavaTreeGen::<TStore>::tree_sitter_parse(text.as_bytes()) {
+                    Ok(t) => t,
+                    Err(t) => t,
+                };
+                java_tree_gen
+                    .generate_file(b"", text.as_bytes(), tree.walk())
+                    .local
+                    .compressed_node
+            };
+            (parse(src), parse(dst))
+        })
+        .collect();
+
+    for (i, (src, dst)) in pairs.into_iter().enumerate() {
+        let size = SimpleZsTree::<_, u32>::decompress(&stores.node_store, &src).len()
+            + SimpleZsTree::<_, u32>::decompress(&stores.node_store, &dst).len();
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::new("zs", i), &(src, dst), |b, (src, dst)| {
+            b.iter(|| {
+                black_box(
+                    ZsMatcher::<DefaultMappingStore<u32>, SimpleZsTree<_, u32>>::matchh(
+                        &stores.node_store,
+                        &stores.label_store,
+                        *src,
+                        *dst,
+                    ),
+                )
+            })
+        });
+        group.bench_with_input(
+            BenchmarkId::new("apted", i),
+            &(src, dst),
+            |b, (src, dst)| {
+                b.iter(|| {
+                    black_box(
+                        AptedMatcher::<DefaultMappingStore<u32>, SimpleZsTree<_, u32>>::matchh(
+                            &stores.node_store,
+                            &TypedCostModel,
+                            *src,
+                            *dst,
+                        ),
+                    )
+                })
+            },
+        );
+    }
+    group.finish()
+}
+
+criterion_group!(optimal_matchers, compare_optimal_matchers);
+criterion_main!(optimal_matchers);
@@ -465,21 +465,23 @@ pub fn apply_action<T, S, P>(
             let mut children = Vec::with_capacity(cs.len());
             children.extend_from_slice(&cs[..i.to_usize().unwrap()]);
             let sub = {
-                // let node = s.resolve(&sub.unwrap());
-                // let node = BuildableTree::build(node.get_type(), node.get_label().clone(), vec![]);
-                // s.get_or_insert(node)
-                sub.unwrap()
+                // the moved node is the one updated
+                let sub = sub.unwrap();
+                let (t, cs) = {
+                    let node = s.resolve(&sub);
+                    let t = node.get_type().to_owned();
+                    let cs = node.children();
+                    let cs = cs.map(|cs| cs.iter_children().cloned().collect());
+                    (t, cs)
+                };
+                s.build_then_insert(sub, t, Some(new.clone()), cs.unwrap_or_default())
             };
             children.push(sub);
             if i.to_usize().unwrap() < cs.len() {
                 children.extend_from_slice(&cs[i.to_usize().unwrap()..]);
             }
-            let t = {
-                let node = s.resolve(&x);
-                let t = node.get_type().to_owned();
-                t
-            };
-            s.build_then_insert(x, t, Some(new.clone()), children)
+            let (t, l) = fun_name(s, &x);
+            s.build_then_insert(x, t, l, children)
         }
     };
     let mut node = node;
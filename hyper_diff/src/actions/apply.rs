@@ -0,0 +1,223 @@
+//! Application of edit scripts directly on the HyperAST.
+//!
+//! Nodes produced by the actions are deduplicated in the legion node store,
+//! such that applying the script of a diff on its source gives back the identifier of its destination.
+use std::{fmt::Debug, marker::PhantomData};
+
+use hyper_ast::{
+    hashed::{self, IndexingHashBuilder, MetaDataHashsBuilder, NodeHashs, SyntaxNodeHashs},
+    store::{
+        defaults::{LabelIdentifier, NodeIdentifier},
+        labels::LabelStore,
+        nodes::legion::{
+            compo::{self, NoSpacesCS, CS},
+            dyn_builder, EntryRef, HashedNodeRef, NodeStore,
+        },
+    },
+    types::{
+        self, HyperType, IterableChildren, LabelStore as _, Labeled, NodeStoreExt, Typed,
+        TypedNodeId, WithChildren, WithHashs, WithSerialization, WithStats,
+    },
+};
+
+use crate::tree::tree_path::TreePath;
+
+use super::{
+    action_vec::{apply_action, ActionsVec},
+    script_generator2::SimpleAction,
+};
+
+/// Applies `actions` on `src`, returns the resulting root.
+///
+/// All the nodes must be of the language of `TIdN`.
+pub fn apply_script<TIdN, P>(
+    node_store: &mut NodeStore,
+    label_store: &LabelStore,
+    actions: &ActionsVec<SimpleAction<LabelIdentifier, P, NodeIdentifier>>,
+    src: NodeIdentifier,
+) -> NodeIdentifier
+where
+    TIdN: 'static + TypedNodeId<IdN = NodeIdentifier>,
+    P: TreePath<Item = u16> + Debug,
+{
+    let mut builder = NodeBuilder::<TIdN>::new(node_store, label_store);
+    let mut roots = vec![src];
+    for a in actions.iter() {
+        apply_action::<HashedNodeRef<'static, TIdN>, _, P>(a, &mut roots, &mut builder);
+    }
+    *roots.last().unwrap()
+}
+
+/// Builds the nodes needed by [`apply_action`] in a legion [`NodeStore`].
+pub struct NodeBuilder<'store, TIdN> {
+    node_store: &'store mut NodeStore,
+    label_store: &'store LabelStore,
+    phantom: PhantomData<TIdN>,
+}
+
+impl<'store, TIdN> NodeBuilder<'store, TIdN>
+where
+    TIdN: 'static + TypedNodeId<IdN = NodeIdentifier>,
+{
+    pub fn new(node_store: &'store mut NodeStore, label_store: &'store LabelStore) -> Self {
+        Self {
+            node_store,
+            label_store,
+            phantom: PhantomData,
+        }
+    }
+
+    /// true if `id` is already the node that would be built,
+    /// reusing it keeps untouched nodes as they are, eg. spaces.
+    fn is_built(
+        &self,
+        id: &NodeIdentifier,
+        t: &TIdN::Ty,
+        l: Option<&LabelIdentifier>,
+        cs: &[NodeIdentifier],
+    ) -> bool {
+        let node = types::NodeStore::resolve(self, id);
+        if &node.get_type() != t || node.try_get_label() != l {
+            return false;
+        }
+        match node.children() {
+            Some(children) => children.iter_children().eq(cs.iter()),
+            None => cs.is_empty(),
+        }
+    }
+}
+
+impl<'store, TIdN> types::NodeStore<NodeIdentifier> for NodeBuilder<'store, TIdN>
+where
+    TIdN: 'static + TypedNodeId<IdN = NodeIdentifier>,
+{
+    type R<'a> = HashedNodeRef<'a, TIdN> where Self: 'a;
+
+    fn resolve(&self, id: &NodeIdentifier) -> Self::R<'_> {
+        // SAFETY: all nodes are considered to be of the language of TIdN
+        self.node_store
+            .resolve_typed(unsafe { TIdN::from_ref_id(id) })
+    }
+}
+
+impl<'store, TIdN> NodeStoreExt<HashedNodeRef<'static, TIdN>> for NodeBuilder<'store, TIdN>
+where
+    TIdN: 'static + TypedNodeId<IdN = NodeIdentifier>,
+{
+    fn build_then_insert(
+        &mut self,
+        i: NodeIdentifier,
+        t: TIdN::Ty,
+        l: Option<LabelIdentifier>,
+        cs: Vec<NodeIdentifier>,
+    ) -> NodeIdentifier {
+        if self.is_built(&i, &t, l.as_ref(), &cs) {
+            return i;
+        }
+        let mut hashs = SyntaxNodeHashs::<u32>::default();
+        let mut size = 0;
+        let mut height = 0;
+        let mut size_no_spaces = 0;
+        let mut bytes_len = 0;
+        let mut no_space = vec![];
+        for x in &cs {
+            let node = types::NodeStore::resolve(self, x);
+            hashs.acc(&SyntaxNodeHashs {
+                structt: node.hash(&hashed::SyntaxNodeHashsKinds::Struct),
+                label: node.hash(&hashed::SyntaxNodeHashsKinds::Label),
+                syntax: node.hash(&hashed::SyntaxNodeHashsKinds::Syntax),
+            });
+            size += node.size() as u32;
+            bytes_len += node.try_bytes_len().unwrap_or(0) as u32;
+            // spaces do not count in the height nor in the size without spaces
+            if !node.get_type().is_spaces() {
+                height = height.max(node.height() as u32);
+                size_no_spaces += node.size_no_spaces() as u32;
+                no_space.push(*x);
+            }
+        }
+        let size = size + 1;
+        let height = height + 1;
+        let size_no_spaces = size_no_spaces + 1;
+
+        let label_store = self.label_store;
+        let label = l.map(|l| label_store.resolve(&l));
+        if cs.is_empty() {
+            bytes_len = label.map_or(0, |l| l.len() as u32);
+        }
+        let hbuilder = hashed::Builder::new(hashs, &t, &label, size_no_spaces);
+        let hsyntax = hbuilder.most_discriminating();
+        let eq = |x: EntryRef| {
+            if x.get_component::<TIdN::Ty>() != Ok(&t) {
+                return false;
+            }
+            if x.get_component::<LabelIdentifier>().ok() != l.as_ref() {
+                return false;
+            }
+            match x.get_component::<CS<NodeIdentifier>>() {
+                Ok(CS(children)) => children.as_ref() == cs.as_slice(),
+                Err(_) => cs.is_empty(),
+            }
+        };
+        let insertion = self.node_store.prepare_insertion(&hsyntax, eq);
+        if let Some(id) = insertion.occupied_id() {
+            return id;
+        }
+
+        let mut dyn_builder = dyn_builder::EntityBuilder::new();
+        dyn_builder.add(t);
+        dyn_builder.add(hbuilder.build());
+        dyn_builder.add(compo::BytesLen(bytes_len));
+        if let Some(l) = l {
+            dyn_builder.add(l);
+        }
+        if !cs.is_empty() {
+            dyn_builder.add(compo::Size(size));
+            dyn_builder.add(compo::SizeNoSpaces(size_no_spaces));
+            dyn_builder.add(compo::Height(height));
+            if no_space.len() != cs.len() {
+                dyn_builder.add(NoSpacesCS(no_space.into_boxed_slice()));
+            }
+            dyn_builder.add(CS(cs.into_boxed_slice()));
+        }
+        // references are not analyzed on the produced nodes,
+        // without a BloomSize they are considered as maybe containing any reference
+        NodeStore::insert_built_after_prepare(insertion.vacant(), dyn_builder.build())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper_ast_gen_ts_java::types::TIdN;
+
+    use super::*;
+    use crate::{algorithms::gumtree, tests::java::java_files};
+
+    fn check_round_trip(src: &str, dst: &str) {
+        let (mut stores, [src, dst]) = java_files([src, dst]);
+        let actions = gumtree::diff(&stores, &src, &dst).actions.unwrap();
+        let applied = apply_script::<TIdN<NodeIdentifier>, _>(
+            &mut stores.node_store,
+            &stores.label_store,
+            &actions,
+            src,
+        );
+        assert_eq!(dst, applied, "{:?}", actions.0);
+    }
+
+    #[test]
+    fn test_apply_update() {
+        check_round_trip(
+            "class A { int f(int x) { int y = x; return y; } }",
+            "class A { int f(int x) { int z = x; return z; } }",
+        );
+    }
+
+    #[test]
+    fn test_apply_insert_delete_move() {
+        check_round_trip(
+            "class A {\n  int a;\n  void f() { g(1); }\n  void h() {}\n}",
+            "class A {\n  void h() { g(1); }\n  int b = 2;\n  void f() {}\n}",
+        );
+    }
+}
@@ -0,0 +1,293 @@
+//! Inversion and composition of edit scripts, without diffing again.
+//!
+//! Paths are resolved through the intermediate trees of the scripts,
+//! the first element of a `mid` path being the index of the root it applies to.
+//! As in the [`super::script_generator2::ScriptGenerator`],
+//! the `ori` paths of deletes, updates and of the origin of moves are in the source,
+//! the other ones are in the destination.
+use std::fmt::Debug;
+
+use num_traits::{cast, one, PrimInt};
+
+use hyper_ast::types::{Labeled, NodeId, NodeStore, NodeStoreExt, Tree, WithChildren};
+
+use crate::tree::tree_path::TreePath;
+
+use super::{
+    action_vec::{apply_action, ActionsVec},
+    script_generator2::{Act, ApplicablePath, SimpleAction},
+};
+
+/// Computes the script going from the destination of `actions` back to `src`.
+///
+/// The intermediate trees are built in `s` to find the deleted nodes and the previous labels.
+pub fn invert_actions<T, S, P>(
+    actions: &ActionsVec<SimpleAction<T::Label, P, T::TreeId>>,
+    src: T::TreeId,
+    s: &mut S,
+) -> ActionsVec<SimpleAction<T::Label, P, T::TreeId>>
+where
+    P: TreePath<Item = T::ChildIdx> + From<Vec<T::ChildIdx>> + Debug,
+    T: Tree,
+    T::Type: Debug + Copy + Send + Sync,
+    T::Label: Debug + Copy,
+    T::TreeId: Debug + Copy + NodeId<IdN = T::TreeId>,
+    T::ChildIdx: Debug + Copy,
+    S: NodeStoreExt<T> + NodeStore<T::TreeId>,
+    for<'d> S::R<'d>:
+        Tree<TreeId = T::TreeId, Type = T::Type, Label = T::Label, ChildIdx = T::ChildIdx>,
+{
+    let roots_count = roots_count(actions);
+    let rev_root = |p: Vec<T::ChildIdx>| -> P {
+        let mut p = p;
+        p[0] = cast::<_, T::ChildIdx>(roots_count - 1).unwrap() - p[0];
+        p.into()
+    };
+    let mut roots = vec![src];
+    let mut inverted = Vec::with_capacity(actions.0.len());
+    for (i, a) in actions.iter().enumerate() {
+        let mid: Vec<_> = a.path.mid.iter().collect();
+        let ori = || a.path.ori.iter().collect::<Vec<_>>().into();
+        let action = match &a.action {
+            Act::Delete {} => SimpleAction {
+                path: ApplicablePath {
+                    ori: ori(),
+                    mid: rev_root(mid.clone()),
+                },
+                action: Act::Insert {
+                    sub: resolve_path(s, &roots, &mid),
+                },
+            },
+            Act::Insert { .. } => SimpleAction {
+                path: ApplicablePath {
+                    ori: ori(),
+                    mid: rev_root(mid.clone()),
+                },
+                action: Act::Delete {},
+            },
+            Act::Update { .. } => {
+                let x = resolve_path(s, &roots, &mid);
+                let old = s.resolve(&x).try_get_label().cloned();
+                // the updated node is found in the destination by following the rest of the script
+                let ori = actions.0[i + 1..]
+                    .iter()
+                    .try_fold(mid.clone(), |p, a| forward(p, a))
+                    .map_or_else(ori, |p| p[1..].to_vec().into());
+                SimpleAction {
+                    path: ApplicablePath {
+                        ori,
+                        mid: rev_root(mid.clone()),
+                    },
+                    action: Act::Update {
+                        new: old.expect("an updated node should have a label"),
+                    },
+                }
+            }
+            Act::Move { from } | Act::MovUpd { from, .. } => {
+                let from_mid: Vec<_> = from.mid.iter().collect();
+                let path = ApplicablePath {
+                    ori: from.ori.iter().collect::<Vec<_>>().into(),
+                    mid: rev_root(from_mid.clone()),
+                };
+                let from = ApplicablePath {
+                    ori: ori(),
+                    mid: rev_root(mid.clone()),
+                };
+                let action = if let Act::MovUpd { .. } = &a.action {
+                    let x = resolve_path(s, &roots, &from_mid);
+                    let old = s.resolve(&x).try_get_label().cloned();
+                    Act::MovUpd {
+                        from,
+                        new: old.expect("an updated node should have a label"),
+                    }
+                } else {
+                    Act::Move { from }
+                };
+                SimpleAction { path, action }
+            }
+        };
+        inverted.push(action);
+        apply_action::<T, S, P>(a, &mut roots, s);
+    }
+    inverted.reverse();
+    ActionsVec(inverted)
+}
+
+/// Concatenates the scripts going from A to B and from B to C into a script going from A to C.
+///
+/// The `ori` paths of `second` that are in B are resolved in A,
+/// they are left as is for nodes that do not exist in A.
+pub fn compose_actions<L, P, I>(
+    first: ActionsVec<SimpleAction<L, P, I>>,
+    second: ActionsVec<SimpleAction<L, P, I>>,
+) -> ActionsVec<SimpleAction<L, P, I>>
+where
+    P: TreePath + From<Vec<P::Item>>,
+    P::Item: PrimInt,
+{
+    let offset = cast::<_, P::Item>(roots_count(&first) - 1).unwrap();
+    let shift_root = |p: &P| -> P {
+        let mut p: Vec<_> = p.iter().collect();
+        p[0] = p[0] + offset;
+        p.into()
+    };
+    // paths in B are paths in the last root of `first`
+    let in_src = |ori: &P| -> P {
+        let p: Vec<_> = [offset].into_iter().chain(ori.iter()).collect();
+        match first.0.iter().rev().try_fold(p, |p, a| backward(p, a)) {
+            Some(p) if p[0].is_zero() => p[1..].to_vec().into(),
+            _ => ori.iter().collect::<Vec<_>>().into(),
+        }
+    };
+    let second: Vec<_> = second
+        .0
+        .into_iter()
+        .map(|SimpleAction { path, action }| {
+            let ApplicablePath { ori, mid } = path;
+            let (ori, action) = match action {
+                Act::Delete {} => (in_src(&ori), Act::Delete {}),
+                Act::Update { new } => (in_src(&ori), Act::Update { new }),
+                Act::Insert { sub } => (ori, Act::Insert { sub }),
+                Act::Move { from } => (
+                    ori,
+                    Act::Move {
+                        from: ApplicablePath {
+                            ori: in_src(&from.ori),
+                            mid: shift_root(&from.mid),
+                        },
+                    },
+                ),
+                Act::MovUpd { from, new } => (
+                    ori,
+                    Act::MovUpd {
+                        from: ApplicablePath {
+                            ori: in_src(&from.ori),
+                            mid: shift_root(&from.mid),
+                        },
+                        new,
+                    },
+                ),
+            };
+            let mid = shift_root(&mid);
+            SimpleAction {
+                path: ApplicablePath { ori, mid },
+                action,
+            }
+        })
+        .collect();
+    let mut first = first;
+    first.0.extend(second);
+    first
+}
+
+/// number of roots used while applying `actions`, the last one being the result
+fn roots_count<L, P: TreePath, I>(actions: &ActionsVec<SimpleAction<L, P, I>>) -> usize
+where
+    P::Item: PrimInt,
+{
+    let first = |p: &P| p.iter().next().and_then(|x| x.to_usize()).unwrap_or(0);
+    actions
+        .iter()
+        .map(|a| match &a.action {
+            Act::Move { from } | Act::MovUpd { from, .. } => {
+                first(&a.path.mid).max(first(&from.mid))
+            }
+            _ => first(&a.path.mid),
+        })
+        .max()
+        .unwrap_or(0)
+        + 1
+}
+
+fn resolve_path<IdN, Idx, S>(s: &S, roots: &[IdN], path: &[Idx]) -> IdN
+where
+    IdN: Copy + NodeId<IdN = IdN>,
+    Idx: PrimInt,
+    S: NodeStore<IdN>,
+    for<'d> S::R<'d>: WithChildren<TreeId = IdN, ChildIdx = Idx>,
+{
+    let mut x = roots[path[0].to_usize().unwrap()];
+    for i in &path[1..] {
+        x = s
+            .resolve(&x)
+            .child(i)
+            .expect("the path should exist in the tree");
+    }
+    x
+}
+
+/// path of the same node after the removal of the node at `removed`,
+/// none if it was removed with it
+fn after_remove<Idx: PrimInt>(mut p: Vec<Idx>, removed: &[Idx]) -> Option<Vec<Idx>> {
+    let d = removed.len() - 1;
+    if d == 0 {
+        // roots are not shifted
+        return (p[0] != removed[0]).then_some(p);
+    }
+    if p.len() > d && p[..d] == removed[..d] {
+        if p[d] == removed[d] {
+            return None;
+        } else if p[d] > removed[d] {
+            p[d] = p[d] - one();
+        }
+    }
+    Some(p)
+}
+
+/// path of the same node after an insertion at `inserted`
+fn after_insert<Idx: PrimInt>(mut p: Vec<Idx>, inserted: &[Idx]) -> Option<Vec<Idx>> {
+    let d = inserted.len() - 1;
+    if d == 0 {
+        // a new root replaces the previous one
+        return (p[0] != inserted[0]).then_some(p);
+    }
+    if p.len() > d && p[..d] == inserted[..d] && p[d] >= inserted[d] {
+        p[d] = p[d] + one();
+    }
+    Some(p)
+}
+
+/// path of the same node after applying `a`
+fn forward<L, P: TreePath, I>(p: Vec<P::Item>, a: &SimpleAction<L, P, I>) -> Option<Vec<P::Item>>
+where
+    P::Item: PrimInt,
+{
+    let mid: Vec<_> = a.path.mid.iter().collect();
+    match &a.action {
+        Act::Delete {} => after_remove(p, &mid),
+        Act::Update { .. } => Some(p),
+        Act::Insert { .. } => after_insert(p, &mid),
+        Act::Move { from } | Act::MovUpd { from, .. } => {
+            let from: Vec<_> = from.mid.iter().collect();
+            moved(p, &from, &mid)
+        }
+    }
+}
+
+/// path of the same node before applying `a`
+fn backward<L, P: TreePath, I>(p: Vec<P::Item>, a: &SimpleAction<L, P, I>) -> Option<Vec<P::Item>>
+where
+    P::Item: PrimInt,
+{
+    let mid: Vec<_> = a.path.mid.iter().collect();
+    match &a.action {
+        // the deleted nodes are leaves, thus restoring them is an insertion
+        Act::Delete {} => after_insert(p, &mid),
+        Act::Update { .. } => Some(p),
+        // the inserted nodes are leaves, thus removing them is a deletion
+        Act::Insert { .. } => after_remove(p, &mid),
+        Act::Move { from } | Act::MovUpd { from, .. } => {
+            let from: Vec<_> = from.mid.iter().collect();
+            moved(p, &mid, &from)
+        }
+    }
+}
+
+fn moved<Idx: PrimInt>(p: Vec<Idx>, from: &[Idx], to: &[Idx]) -> Option<Vec<Idx>> {
+    if p.starts_with(from) {
+        // in the moved subtree
+        Some(to.iter().chain(&p[from.len()..]).copied().collect())
+    } else {
+        after_insert(after_remove(p, from)?, to)
+    }
+}
@@ -0,0 +1,510 @@
+//! Output of mappings and edit scripts in the JSON and XML formats of GumTree's textdiff,
+//! such that the tooling made for GumTree can consume the diffs of the HyperAST.
+//!
+//! As in GumTree, a tree is written as `type: label [start,end]`,
+//! the label being omitted when empty,
+//! the positions being computed by [`compute_position`].
+use std::{
+    io::{self, Write},
+    ops::Range,
+};
+
+use num_traits::ToPrimitive;
+
+use hyper_ast::{
+    position::compute_position,
+    types::{HyperAST, LabelStore, Labeled, NodeStore, TypeStore, WithSerialization},
+};
+
+use crate::{
+    decompressed_tree_store::{DecompressedTreeStore, DecompressedWithParent},
+    matchers::mapping_store::MonoMappingStore,
+    tree::tree_path::TreePath,
+};
+
+use super::{
+    action_vec::ActionsVec,
+    script_generator2::{Act, SimpleAction},
+};
+
+/// Mappings and actions of a diff, as written by GumTree.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TextDiff {
+    pub matches: Vec<Match>,
+    pub actions: Vec<Action>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Match {
+    pub src: String,
+    pub dest: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Action {
+    pub action: Kind,
+    pub tree: String,
+    pub parent: Option<String>,
+    pub at: Option<usize>,
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Upd,
+    Move,
+    Ins,
+    Del,
+}
+
+impl Kind {
+    /// name of the action in GumTree
+    pub fn name(&self) -> &'static str {
+        match self {
+            Kind::Upd => "update-node",
+            Kind::Move => "move-tree",
+            Kind::Ins => "insert-node",
+            Kind::Del => "delete-node",
+        }
+    }
+}
+
+impl TextDiff {
+    /// Adds the mappings of `mappings` as matches.
+    pub fn with_matches<'store, 'a, HAST, Dsrc, Ddst, M>(
+        mut self,
+        stores: &'store HAST,
+        src_arena: &Dsrc,
+        dst_arena: &Ddst,
+        mappings: &M,
+    ) -> Self
+    where
+        HAST: HyperAST<'store>,
+        HAST::IdN: Clone,
+        HAST::T: WithSerialization,
+        Dsrc: DecompressedTreeStore<'a, HAST::T, M::Src>
+            + DecompressedWithParent<'a, HAST::T, M::Src>,
+        Ddst: DecompressedTreeStore<'a, HAST::T, M::Dst>
+            + DecompressedWithParent<'a, HAST::T, M::Dst>,
+        M: MonoMappingStore,
+    {
+        let src_root = src_arena.root();
+        let dst_root = dst_arena.root();
+        let src = src_arena.original(&src_root);
+        let dst = dst_arena.original(&dst_root);
+        for (s, d) in mappings.iter() {
+            let s = src_arena.path(&src_root, &s);
+            let d = dst_arena.path(&dst_root, &d);
+            self.matches.push(Match {
+                src: tree(stores, src.clone(), s),
+                dest: tree(stores, dst.clone(), d),
+            });
+        }
+        self
+    }
+
+    /// Adds the actions of a script going from `src` to `dst`.
+    ///
+    /// A [`Act::MovUpd`] is written as a move followed by an update of the moved node.
+    pub fn with_actions<'store, HAST, P>(
+        mut self,
+        stores: &'store HAST,
+        src: HAST::IdN,
+        dst: HAST::IdN,
+        actions: &ActionsVec<SimpleAction<HAST::Label, P, HAST::IdN>>,
+    ) -> Self
+    where
+        HAST: HyperAST<'store>,
+        HAST::IdN: Clone,
+        HAST::T: WithSerialization,
+        P: TreePath<Item = HAST::Idx>,
+    {
+        let label = |l: &HAST::Label| Some(stores.label_store().resolve(l).to_string());
+        // the parent in dst and the position in it
+        let parent = |p: &P| {
+            let p: Vec<_> = p.iter().collect();
+            match p.split_last() {
+                Some((at, p)) => (Some(tree(stores, dst.clone(), p.to_vec())), at.to_usize()),
+                None => (None, None),
+            }
+        };
+        for a in actions.iter() {
+            let ori = &a.path.ori;
+            let action = match &a.action {
+                Act::Delete {} => Action {
+                    action: Kind::Del,
+                    tree: tree(stores, src.clone(), ori.iter()),
+                    parent: None,
+                    at: None,
+                    label: None,
+                },
+                Act::Update { new } => Action {
+                    action: Kind::Upd,
+                    tree: tree(stores, src.clone(), ori.iter()),
+                    parent: None,
+                    at: None,
+                    label: label(new),
+                },
+                Act::Insert { .. } => {
+                    let (parent, at) = parent(ori);
+                    Action {
+                        action: Kind::Ins,
+                        tree: tree(stores, dst.clone(), ori.iter()),
+                        parent,
+                        at,
+                        label: None,
+                    }
+                }
+                Act::Move { from } | Act::MovUpd { from, .. } => {
+                    let (parent, at) = parent(ori);
+                    let moved = tree(stores, src.clone(), from.ori.iter());
+                    if let Act::MovUpd { new, .. } = &a.action {
+                        self.actions.push(Action {
+                            action: Kind::Move,
+                            tree: moved.clone(),
+                            parent,
+                            at,
+                            label: None,
+                        });
+                        Action {
+                            action: Kind::Upd,
+                            tree: moved,
+                            parent: None,
+                            at: None,
+                            label: label(new),
+                        }
+                    } else {
+                        Action {
+                            action: Kind::Move,
+                            tree: moved,
+                            parent,
+                            at,
+                            label: None,
+                        }
+                    }
+                }
+            };
+            self.actions.push(action);
+        }
+        self
+    }
+
+    /// Writes the diff in the JSON format of GumTree's textdiff.
+    pub fn write_json<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        writeln!(w, "{{")?;
+        write!(w, "  \"matches\": [")?;
+        if !self.matches.is_empty() {
+            writeln!(w)?;
+        }
+        for (i, m) in self.matches.iter().enumerate() {
+            writeln!(w, "    {{")?;
+            writeln!(w, "      \"src\": \"{}\",", JsonEscaped(&m.src))?;
+            writeln!(w, "      \"dest\": \"{}\"", JsonEscaped(&m.dest))?;
+            writeln!(w, "    }}{}", separator(i, self.matches.len()))?;
+        }
+        if !self.matches.is_empty() {
+            write!(w, "  ")?;
+        }
+        writeln!(w, "],")?;
+        write!(w, "  \"actions\": [")?;
+        if !self.actions.is_empty() {
+            writeln!(w)?;
+        }
+        for (i, a) in self.actions.iter().enumerate() {
+            writeln!(w, "    {{")?;
+            write!(w, "      \"action\": \"{}\",", a.action.name())?;
+            write!(w, "\n      \"tree\": \"{}\"", JsonEscaped(&a.tree))?;
+            if let Some(parent) = &a.parent {
+                write!(w, ",\n      \"parent\": \"{}\"", JsonEscaped(parent))?;
+            }
+            if let Some(at) = a.at {
+                write!(w, ",\n      \"at\": {}", at)?;
+            }
+            if let Some(label) = &a.label {
+                write!(w, ",\n      \"label\": \"{}\"", JsonEscaped(label))?;
+            }
+            writeln!(w)?;
+            writeln!(w, "    }}{}", separator(i, self.actions.len()))?;
+        }
+        if !self.actions.is_empty() {
+            write!(w, "  ")?;
+        }
+        writeln!(w, "]")?;
+        writeln!(w, "}}")
+    }
+
+    /// Writes the diff in the XML format of GumTree's textdiff.
+    pub fn write_xml<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        writeln!(w, "<?xml version=\"1.0\" ?>")?;
+        writeln!(w, "<diff>")?;
+        writeln!(w, "  <matches>")?;
+        for m in &self.matches {
+            writeln!(
+                w,
+                "    <match src=\"{}\" dest=\"{}\"/>",
+                XmlEscaped(&m.src),
+                XmlEscaped(&m.dest)
+            )?;
+        }
+        writeln!(w, "  </matches>")?;
+        writeln!(w, "  <actions>")?;
+        for a in &self.actions {
+            write!(
+                w,
+                "    <{} tree=\"{}\"",
+                a.action.name(),
+                XmlEscaped(&a.tree)
+            )?;
+            if let Some(parent) = &a.parent {
+                write!(w, " parent=\"{}\"", XmlEscaped(parent))?;
+            }
+            if let Some(at) = a.at {
+                write!(w, " at=\"{}\"", at)?;
+            }
+            if let Some(label) = &a.label {
+                write!(w, " label=\"{}\"", XmlEscaped(label))?;
+            }
+            writeln!(w, "/>")?;
+        }
+        writeln!(w, "  </actions>")?;
+        writeln!(w, "</diff>")
+    }
+}
+
+/// Formats the node at `path` from `root` as GumTree does.
+fn tree<'store, HAST, It>(stores: &'store HAST, root: HAST::IdN, path: It) -> String
+where
+    HAST: HyperAST<'store>,
+    HAST::IdN: Clone,
+    HAST::T: WithSerialization,
+    It: IntoIterator<Item = HAST::Idx>,
+{
+    let (pos, x) = compute_position(root, &mut path.into_iter(), stores);
+    let Range { start, end } = pos.range();
+    let r = stores.node_store().resolve(&x);
+    let t = stores.type_store().resolve_type(&r);
+    let label = r
+        .try_get_label()
+        .map(|l| stores.label_store().resolve(l))
+        .filter(|l| !l.is_empty());
+    match label {
+        Some(label) => format!("{}: {} [{},{}]", t, label, start, end),
+        None => format!("{} [{},{}]", t, start, end),
+    }
+}
+
+fn separator(i: usize, len: usize) -> &'static str {
+    if i + 1 < len {
+        ","
+    } else {
+        ""
+    }
+}
+
+struct JsonEscaped<'a>(&'a str);
+
+impl<'a> std::fmt::Display for JsonEscaped<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use std::fmt::Write;
+        for c in self.0.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                '\r' => f.write_str("\\r")?,
+                '\t' => f.write_str("\\t")?,
+                c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+                c => f.write_char(c)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+struct XmlEscaped<'a>(&'a str);
+
+impl<'a> std::fmt::Display for XmlEscaped<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use std::fmt::Write;
+        for c in self.0.chars() {
+            match c {
+                '"' => f.write_str("&quot;")?,
+                '&' => f.write_str("&amp;")?,
+                '<' => f.write_str("&lt;")?,
+                '>' => f.write_str("&gt;")?,
+                '\n' => f.write_str("&#10;")?,
+                c => f.write_char(c)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        algorithms::gumtree, matchers::mapping_store::MappingStore, tests::java::java_files,
+    };
+
+    fn example() -> TextDiff {
+        TextDiff {
+            matches: vec![Match {
+                src: "SimpleName: f [4,5]".into(),
+                dest: "SimpleName: f [4,5]".into(),
+            }],
+            actions: vec![
+                Action {
+                    action: Kind::Upd,
+                    tree: "StringLiteral: \"a\" [8,11]".into(),
+                    parent: None,
+                    at: None,
+                    label: Some("\"b<c\"".into()),
+                },
+                Action {
+                    action: Kind::Ins,
+                    tree: "Block [6,12]".into(),
+                    parent: Some("MethodDeclaration [0,12]".into()),
+                    at: Some(2),
+                    label: None,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_write_json() {
+        let mut out = vec![];
+        example().write_json(&mut out).unwrap();
+        let expected = r#"{
+  "matches": [
+    {
+      "src": "SimpleName: f [4,5]",
+      "dest": "SimpleName: f [4,5]"
+    }
+  ],
+  "actions": [
+    {
+      "action": "update-node",
+      "tree": "StringLiteral: \"a\" [8,11]",
+      "label": "\"b<c\""
+    },
+    {
+      "action": "insert-node",
+      "tree": "Block [6,12]",
+      "parent": "MethodDeclaration [0,12]",
+      "at": 2
+    }
+  ]
+}
+"#;
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_write_xml() {
+        let mut out = vec![];
+        example().write_xml(&mut out).unwrap();
+        let expected = r#"<?xml version="1.0" ?>
+<diff>
+  <matches>
+    <match src="SimpleName: f [4,5]" dest="SimpleName: f [4,5]"/>
+  </matches>
+  <actions>
+    <update-node tree="StringLiteral: &quot;a&quot; [8,11]" label="&quot;b&lt;c&quot;"/>
+    <insert-node tree="Block [6,12]" parent="MethodDeclaration [0,12]" at="2"/>
+  </actions>
+</diff>
+"#;
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_java_rename() {
+        let (stores, [src, dst]) = java_files([
+            "class A { int f(int x) { int y = x; return y; } }",
+            "class A { int f(int x) { int z = x; return z; } }",
+        ]);
+        assert_eq!("program [0,49]", tree(&stores, src, []));
+        let diff = gumtree::diff(&stores, &src, &dst);
+        let mapping = &diff.mapper.mapping;
+        let out = TextDiff::default()
+            .with_matches(
+                &stores,
+                &mapping.src_arena,
+                &mapping.dst_arena,
+                &mapping.mappings,
+            )
+            .with_actions(&stores, src, dst, diff.actions.as_ref().unwrap());
+
+        assert_eq!(diff.mapper.mappings().len(), out.matches.len());
+        let matched = |src: &str, dest: &str| {
+            out.matches.contains(&Match {
+                src: src.into(),
+                dest: dest.into(),
+            })
+        };
+        assert!(matched("identifier: x [20,21]", "identifier: x [20,21]"));
+        assert!(matched("identifier: y [29,30]", "identifier: z [29,30]"));
+        assert!(matched("identifier: y [43,44]", "identifier: z [43,44]"));
+
+        let mut actions = out.actions;
+        actions.sort_by(|a, b| a.tree.cmp(&b.tree));
+        let update = |tree: &str| Action {
+            action: Kind::Upd,
+            tree: tree.into(),
+            parent: None,
+            at: None,
+            label: Some("z".into()),
+        };
+        assert_eq!(
+            vec![
+                update("identifier: y [29,30]"),
+                update("identifier: y [43,44]")
+            ],
+            actions
+        );
+    }
+
+    #[test]
+    fn test_java_move_update_split() {
+        let (mut stores, [src, dst]) = java_files([
+            "class A {\n  int x;\n  void f() {}\n}",
+            "class A {\n  void f() {}\n  int x;\n}",
+        ]);
+        let actions = gumtree::diff(&stores, &src, &dst).actions.unwrap();
+        let moves = TextDiff::default().with_actions(&stores, src, dst, &actions);
+        let new = stores.label_store.get_or_insert("g");
+        // same script but also renaming the moved nodes
+        let actions = ActionsVec(
+            actions
+                .0
+                .into_iter()
+                .map(|SimpleAction { path, action }| match action {
+                    Act::Move { from } => SimpleAction {
+                        path,
+                        action: Act::MovUpd { from, new },
+                    },
+                    action => SimpleAction { path, action },
+                })
+                .collect(),
+        );
+        let out = TextDiff::default().with_actions(&stores, src, dst, &actions);
+
+        let mut out = out.actions.into_iter();
+        let mut moved = 0;
+        for m in moves.actions {
+            let kind = m.action;
+            assert_eq!(Some(m.clone()), out.next());
+            if kind == Kind::Move {
+                moved += 1;
+                let upd = out.next().unwrap();
+                assert_eq!(Kind::Upd, upd.action);
+                assert_eq!(m.tree, upd.tree);
+                assert_eq!(Some("g".to_string()), upd.label);
+            }
+        }
+        assert_eq!(None, out.next());
+        assert!(moved > 0);
+    }
+}
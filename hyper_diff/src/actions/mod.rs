@@ -1,7 +1,12 @@
 pub mod action_tree;
 pub mod action_vec;
+pub mod apply;
+pub mod compose;
+pub mod gumtree_output;
 pub mod script_generator;
 pub mod script_generator2;
+#[cfg(any(feature = "java", test))]
+pub mod semantic;
 
 pub trait Actions {
     fn len(&self) -> usize;
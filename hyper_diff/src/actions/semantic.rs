@@ -0,0 +1,566 @@
+//! Grouping of the actions of an edit script into refactoring-level changes of Java code.
+//!
+//! Actions are located through their `ori` paths, in the source for deletes, updates and the origin of moves,
+//! in the destination for inserts and the target of moves.
+//! Actions that do not take part in a recognized change are kept as [`SemanticChange::Other`].
+use hyper_ast::types::{
+    AnyType, HyperAST, HyperType, IterableChildren, Labeled, NodeStore, TypeStore, WithChildren,
+};
+use hyper_ast_gen_ts_java::types::Type;
+
+use crate::tree::tree_path::TreePath;
+
+use super::{
+    action_vec::ActionsVec,
+    script_generator2::{Act, SimpleAction},
+};
+
+/// A change at the level of a refactoring, made of the actions at the given indexes of the script.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SemanticChange<L> {
+    /// a method declaration and the matching invocations are renamed
+    RenameMethod { old: L, new: L, actions: Vec<usize> },
+    /// a method is inserted with code moved from other methods
+    ExtractMethod {
+        name: Option<L>,
+        from: Vec<L>,
+        actions: Vec<usize>,
+    },
+    /// a field declaration is moved to another class
+    MoveField {
+        from: Option<L>,
+        to: Option<L>,
+        actions: Vec<usize>,
+    },
+    /// the type of a parameter is changed
+    ChangeParameterType {
+        method: Option<L>,
+        parameter: Option<L>,
+        actions: Vec<usize>,
+    },
+    /// an action that is not part of a recognized change
+    Other { action: usize },
+}
+
+impl<L> SemanticChange<L> {
+    pub fn actions(&self) -> &[usize] {
+        match self {
+            SemanticChange::RenameMethod { actions, .. }
+            | SemanticChange::ExtractMethod { actions, .. }
+            | SemanticChange::MoveField { actions, .. }
+            | SemanticChange::ChangeParameterType { actions, .. } => actions,
+            SemanticChange::Other { action } => std::slice::from_ref(action),
+        }
+    }
+}
+
+/// Groups the `actions` going from `src` to `dst`,
+/// the changes are ordered by the first of their actions.
+pub fn group_actions<'store, P, HAST>(
+    stores: &'store HAST,
+    src: HAST::IdN,
+    dst: HAST::IdN,
+    actions: &ActionsVec<SimpleAction<HAST::Label, P, HAST::IdN>>,
+) -> Vec<SemanticChange<HAST::Label>>
+where
+    P: TreePath<Item = HAST::Idx>,
+    HAST: HyperAST<'store>,
+    HAST::IdN: Copy,
+    HAST::Label: Copy + Eq,
+    HAST::TS: TypeStore<HAST::T, Ty = AnyType>,
+{
+    let grouper = Grouper { stores };
+    let located: Vec<_> = actions
+        .iter()
+        .map(|a| grouper.locate_action(src, dst, a))
+        .collect();
+    let mut grouped = vec![false; located.len()];
+    let mut changes = vec![];
+    grouper.rename_methods(actions, &located, &mut grouped, &mut changes);
+    grouper.move_fields(actions, &located, &mut grouped, &mut changes);
+    grouper.extract_methods(actions, &located, &mut grouped, &mut changes);
+    grouper.change_parameter_types(&located, &mut grouped, &mut changes);
+    changes.extend(
+        (0..located.len())
+            .filter(|i| !grouped[*i])
+            .map(|action| SemanticChange::Other { action }),
+    );
+    changes.sort_by_key(|c| c.actions()[0]);
+    changes
+}
+
+/// Nodes from the root to the one at the end of `path`.
+struct Located<IdN, Idx> {
+    path: Vec<Idx>,
+    nodes: Vec<IdN>,
+}
+
+impl<IdN: Copy, Idx> Located<IdN, Idx> {
+    fn node(&self) -> IdN {
+        *self.nodes.last().unwrap()
+    }
+}
+
+/// Where an action applies, in the source and in the destination.
+struct LocatedAction<IdN, Idx> {
+    src: Option<Located<IdN, Idx>>,
+    dst: Option<Located<IdN, Idx>>,
+}
+
+struct Grouper<'store, HAST> {
+    stores: &'store HAST,
+}
+
+impl<'store, HAST> Grouper<'store, HAST>
+where
+    HAST: HyperAST<'store>,
+    HAST::IdN: Copy,
+    HAST::Label: Copy + Eq,
+    HAST::TS: TypeStore<HAST::T, Ty = AnyType>,
+{
+    fn locate_action<P: TreePath<Item = HAST::Idx>>(
+        &self,
+        src: HAST::IdN,
+        dst: HAST::IdN,
+        a: &SimpleAction<HAST::Label, P, HAST::IdN>,
+    ) -> LocatedAction<HAST::IdN, HAST::Idx> {
+        match &a.action {
+            Act::Delete {} | Act::Update { .. } => LocatedAction {
+                src: self.locate(src, &a.path.ori),
+                dst: None,
+            },
+            Act::Insert { .. } => LocatedAction {
+                src: None,
+                dst: self.locate(dst, &a.path.ori),
+            },
+            Act::Move { from } | Act::MovUpd { from, .. } => LocatedAction {
+                src: self.locate(src, &from.ori),
+                dst: self.locate(dst, &a.path.ori),
+            },
+        }
+    }
+
+    fn locate<P: TreePath<Item = HAST::Idx>>(
+        &self,
+        root: HAST::IdN,
+        path: &P,
+    ) -> Option<Located<HAST::IdN, HAST::Idx>> {
+        let mut x = root;
+        let mut nodes = vec![x];
+        for i in path.iter() {
+            x = self.stores.node_store().resolve(&x).child(&i)?;
+            nodes.push(x);
+        }
+        Some(Located {
+            path: path.iter().collect(),
+            nodes,
+        })
+    }
+
+    fn ty(&self, id: &HAST::IdN) -> Option<Type> {
+        let n = self.stores.node_store().resolve(id);
+        let t = self.stores.type_store().resolve_type(&n);
+        t.as_any().downcast_ref::<Type>().copied()
+    }
+
+    fn label(&self, id: &HAST::IdN) -> Option<HAST::Label> {
+        let n = self.stores.node_store().resolve(id);
+        n.try_get_label().copied()
+    }
+
+    /// label of the identifier directly under `id`, ie. the name of declarations
+    fn name(&self, id: &HAST::IdN) -> Option<HAST::Label> {
+        let n = self.stores.node_store().resolve(id);
+        let cs = n.children()?;
+        cs.iter_children()
+            .find(|x| self.ty(x) == Some(Type::Identifier))
+            .and_then(|x| self.label(x))
+    }
+
+    /// index in `nodes` of the closest ancestor of the last node with a type matching `pred`
+    fn enclosing(&self, nodes: &[HAST::IdN], pred: impl Fn(Type) -> bool) -> Option<usize> {
+        (0..nodes.len().saturating_sub(1))
+            .rev()
+            .find(|i| self.ty(&nodes[*i]).map_or(false, &pred))
+    }
+
+    /// true if the located node is the name of a node with a type matching `pred`
+    fn is_name_of(
+        &self,
+        located: &Located<HAST::IdN, HAST::Idx>,
+        pred: impl Fn(Type) -> bool,
+    ) -> bool {
+        let n = located.nodes.len();
+        n >= 2
+            && self.ty(&located.nodes[n - 1]) == Some(Type::Identifier)
+            && self.ty(&located.nodes[n - 2]).map_or(false, pred)
+    }
+
+    fn rename_methods<P>(
+        &self,
+        actions: &ActionsVec<SimpleAction<HAST::Label, P, HAST::IdN>>,
+        located: &[LocatedAction<HAST::IdN, HAST::Idx>],
+        grouped: &mut [bool],
+        changes: &mut Vec<SemanticChange<HAST::Label>>,
+    ) {
+        // old label and new label of an updated identifier
+        let renamed = |i: usize, pred: fn(Type) -> bool| {
+            let Act::Update { new } = &actions.0[i].action else {
+                return None;
+            };
+            let src = located[i].src.as_ref()?;
+            if !self.is_name_of(src, pred) {
+                return None;
+            }
+            Some((self.label(&src.node())?, *new))
+        };
+        for i in 0..located.len() {
+            if grouped[i] {
+                continue;
+            }
+            let Some((old, new)) = renamed(i, is_method) else {
+                continue;
+            };
+            let mut group = vec![];
+            for j in 0..located.len() {
+                if j == i || (!grouped[j] && renamed(j, is_invocation) == Some((old, new))) {
+                    grouped[j] = true;
+                    group.push(j);
+                }
+            }
+            changes.push(SemanticChange::RenameMethod {
+                old,
+                new,
+                actions: group,
+            });
+        }
+    }
+
+    fn move_fields<P>(
+        &self,
+        actions: &ActionsVec<SimpleAction<HAST::Label, P, HAST::IdN>>,
+        located: &[LocatedAction<HAST::IdN, HAST::Idx>],
+        grouped: &mut [bool],
+        changes: &mut Vec<SemanticChange<HAST::Label>>,
+    ) {
+        for (i, a) in actions.iter().enumerate() {
+            if grouped[i] || !matches!(a.action, Act::Move { .. } | Act::MovUpd { .. }) {
+                continue;
+            }
+            let (Some(src), Some(dst)) = (&located[i].src, &located[i].dst) else {
+                continue;
+            };
+            if self.ty(&src.node()) != Some(Type::FieldDeclaration) {
+                continue;
+            }
+            let class = |l: &Located<_, _>| {
+                let c = self.enclosing(&l.nodes, is_type_declaration)?;
+                self.name(&l.nodes[c])
+            };
+            let from = class(src);
+            let to = class(dst);
+            if from == to {
+                // only reordered in the same class
+                continue;
+            }
+            grouped[i] = true;
+            changes.push(SemanticChange::MoveField {
+                from,
+                to,
+                actions: vec![i],
+            });
+        }
+    }
+
+    fn extract_methods<P>(
+        &self,
+        actions: &ActionsVec<SimpleAction<HAST::Label, P, HAST::IdN>>,
+        located: &[LocatedAction<HAST::IdN, HAST::Idx>],
+        grouped: &mut [bool],
+        changes: &mut Vec<SemanticChange<HAST::Label>>,
+    ) {
+        for (i, a) in actions.iter().enumerate() {
+            if grouped[i] || !matches!(a.action, Act::Insert { .. }) {
+                continue;
+            }
+            let Some(method) = &located[i].dst else {
+                continue;
+            };
+            if self.ty(&method.node()) != Some(Type::MethodDeclaration) {
+                continue;
+            }
+            let mut group = vec![];
+            let mut from = vec![];
+            for (j, b) in actions.iter().enumerate() {
+                let Some(dst) = &located[j].dst else {
+                    continue;
+                };
+                if grouped[j] || !dst.path.starts_with(&method.path) {
+                    continue;
+                }
+                if let Act::Move { .. } | Act::MovUpd { .. } = &b.action {
+                    // code coming from another method
+                    let src = located[j].src.as_ref();
+                    let m = src.and_then(|src| {
+                        let m = self.enclosing(&src.nodes, is_callable)?;
+                        self.name(&src.nodes[m])
+                    });
+                    if let Some(m) = m {
+                        if !from.contains(&m) {
+                            from.push(m);
+                        }
+                    }
+                }
+                group.push(j);
+            }
+            if from.is_empty() {
+                // only an added method
+                continue;
+            }
+            for j in &group {
+                grouped[*j] = true;
+            }
+            changes.push(SemanticChange::ExtractMethod {
+                name: self.name(&method.node()),
+                from,
+                actions: group,
+            });
+        }
+    }
+
+    fn change_parameter_types(
+        &self,
+        located: &[LocatedAction<HAST::IdN, HAST::Idx>],
+        grouped: &mut [bool],
+        changes: &mut Vec<SemanticChange<HAST::Label>>,
+    ) {
+        for (i, l) in located.iter().enumerate() {
+            if grouped[i] {
+                continue;
+            }
+            let Some(l) = l.src.as_ref().or(l.dst.as_ref()) else {
+                continue;
+            };
+            let Some(p) = self.enclosing(&l.nodes, is_parameter) else {
+                continue;
+            };
+            // the name and modifiers of the parameter are not part of its type
+            let part = self.ty(&l.nodes[p + 1]);
+            if matches!(
+                part,
+                Some(Type::Identifier | Type::VariableDeclarator | Type::Modifiers)
+            ) {
+                continue;
+            }
+            let method = self
+                .enclosing(&l.nodes[..=p], is_callable)
+                .and_then(|m| self.name(&l.nodes[m]));
+            let parameter = self.name(&l.nodes[p]);
+            grouped[i] = true;
+            let same = changes.iter_mut().find_map(|c| match c {
+                SemanticChange::ChangeParameterType {
+                    method: m,
+                    parameter: n,
+                    actions,
+                } if m == &method && n == &parameter => Some(actions),
+                _ => None,
+            });
+            if let Some(actions) = same {
+                actions.push(i);
+            } else {
+                changes.push(SemanticChange::ChangeParameterType {
+                    method,
+                    parameter,
+                    actions: vec![i],
+                });
+            }
+        }
+    }
+}
+
+fn is_method(t: Type) -> bool {
+    t == Type::MethodDeclaration
+}
+
+fn is_invocation(t: Type) -> bool {
+    t == Type::MethodInvocation
+}
+
+fn is_callable(t: Type) -> bool {
+    t == Type::MethodDeclaration || t == Type::ConstructorDeclaration
+}
+
+fn is_parameter(t: Type) -> bool {
+    t == Type::FormalParameter || t == Type::SpreadParameter
+}
+
+fn is_type_declaration(t: Type) -> bool {
+    matches!(
+        t,
+        Type::ClassDeclaration
+            | Type::InterfaceDeclaration
+            | Type::EnumDeclaration
+            | Type::RecordDeclaration
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper_ast::{
+        store::defaults::{LabelIdentifier, NodeIdentifier},
+        types::LabelStore,
+    };
+
+    use super::*;
+    use crate::{
+        actions::Actions as _,
+        algorithms::gumtree,
+        tests::java::{java_files, JavaStores},
+        tree::tree_path::CompressedTreePath,
+    };
+
+    type Script =
+        ActionsVec<SimpleAction<LabelIdentifier, CompressedTreePath<u16>, NodeIdentifier>>;
+
+    fn group(src: &str, dst: &str) -> (JavaStores, Script, Vec<SemanticChange<LabelIdentifier>>) {
+        let (stores, [src, dst]) = java_files([src, dst]);
+        let actions = gumtree::diff(&stores, &src, &dst).actions.unwrap();
+        let changes = group_actions(&stores, src, dst, &actions);
+        // each action is in exactly one change
+        let mut all: Vec<_> = changes.iter().flat_map(|c| c.actions()).copied().collect();
+        all.sort();
+        assert_eq!(all, (0..actions.len()).collect::<Vec<_>>());
+        (stores, actions, changes)
+    }
+
+    #[test]
+    fn test_rename_method() {
+        let (stores, actions, changes) = group(
+            "class A {\n  void foo() {}\n  void f() { foo(); foo(); }\n}",
+            "class A {\n  void bar() {}\n  void f() { bar(); bar(); }\n}",
+        );
+        let label = |l: &LabelIdentifier| stores.label_store.resolve(l);
+        let renames: Vec<_> = changes
+            .iter()
+            .filter(|c| matches!(c, SemanticChange::RenameMethod { .. }))
+            .collect();
+        assert_eq!(1, renames.len(), "{:?}", changes);
+        let SemanticChange::RenameMethod {
+            old,
+            new,
+            actions: group,
+        } = renames[0]
+        else {
+            unreachable!()
+        };
+        assert_eq!("foo", label(old));
+        assert_eq!("bar", label(new));
+        // the declaration and the two invocations
+        let updates: Vec<_> = (0..actions.len())
+            .filter(|i| matches!(&actions.0[*i].action, Act::Update { new } if label(new) == "bar"))
+            .collect();
+        assert_eq!(3, updates.len(), "{:?}", actions.0);
+        assert_eq!(&updates, group);
+    }
+
+    #[test]
+    fn test_move_field() {
+        let (stores, actions, changes) = group(
+            "class A {\n  int x;\n  void f() {}\n}\nclass B {\n  void g() {}\n}",
+            "class A {\n  void f() {}\n}\nclass B {\n  int x;\n  void g() {}\n}",
+        );
+        let label = |l: &LabelIdentifier| stores.label_store.resolve(l);
+        let moves: Vec<_> = changes
+            .iter()
+            .filter(|c| matches!(c, SemanticChange::MoveField { .. }))
+            .collect();
+        assert_eq!(1, moves.len(), "{:?}", changes);
+        let SemanticChange::MoveField {
+            from,
+            to,
+            actions: group,
+        } = moves[0]
+        else {
+            unreachable!()
+        };
+        assert_eq!(Some("A"), from.as_ref().map(label));
+        assert_eq!(Some("B"), to.as_ref().map(label));
+        assert_eq!(1, group.len());
+        assert!(
+            matches!(actions.0[group[0]].action, Act::Move { .. }),
+            "{:?}",
+            actions.0[group[0]]
+        );
+    }
+
+    #[test]
+    fn test_field_reordered_in_same_class() {
+        let (_, actions, changes) = group(
+            "class A {\n  int x;\n  void f() {}\n}",
+            "class A {\n  void f() {}\n  int x;\n}",
+        );
+        assert!(actions.iter().any(|a| matches!(a.action, Act::Move { .. })));
+        assert!(
+            changes
+                .iter()
+                .all(|c| matches!(c, SemanticChange::Other { .. })),
+            "{:?}",
+            changes
+        );
+    }
+
+    #[test]
+    fn test_extract_method() {
+        let (stores, actions, changes) = group(
+            "class A {\n  void f() {\n    a();\n    d();\n    e();\n    x();\n    b();\n    c();\n  }\n}",
+            "class A {\n  void f() {\n    a();\n    d();\n    e();\n    x();\n    g();\n  }\n  void g() {\n    b();\n    c();\n  }\n}",
+        );
+        let label = |l: &LabelIdentifier| stores.label_store.resolve(l);
+        let extracts: Vec<_> = changes
+            .iter()
+            .filter(|c| matches!(c, SemanticChange::ExtractMethod { .. }))
+            .collect();
+        assert_eq!(1, extracts.len(), "{:?}", changes);
+        let SemanticChange::ExtractMethod {
+            name,
+            from,
+            actions: group,
+        } = extracts[0]
+        else {
+            unreachable!()
+        };
+        assert_eq!(Some("g"), name.as_ref().map(label));
+        assert_eq!(vec!["f"], from.iter().map(label).collect::<Vec<_>>());
+        // the insertion of the method comes first, then the statements moved in its body
+        assert!(matches!(actions.0[group[0]].action, Act::Insert { .. }));
+        let moved = group
+            .iter()
+            .filter(|i| matches!(actions.0[**i].action, Act::Move { .. }))
+            .count();
+        assert_eq!(2, moved, "{:?}", actions.0);
+    }
+
+    #[test]
+    fn test_change_parameter_type() {
+        let (stores, actions, changes) = group(
+            "class A {\n  void f(String x) {}\n}",
+            "class A {\n  void f(Object x) {}\n}",
+        );
+        let label = |l: &LabelIdentifier| stores.label_store.resolve(l);
+        let [SemanticChange::ChangeParameterType {
+            method,
+            parameter,
+            actions: group,
+        }] = &changes[..]
+        else {
+            panic!("{:?}", changes)
+        };
+        assert_eq!(Some("f"), method.as_ref().map(label));
+        assert_eq!(Some("x"), parameter.as_ref().map(label));
+        assert_eq!(&vec![0], group);
+        let Act::Update { new } = &actions.0[0].action else {
+            panic!("{:?}", actions.0)
+        };
+        assert_eq!("Object", label(new));
+    }
+}
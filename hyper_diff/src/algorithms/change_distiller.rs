@@ -0,0 +1,127 @@
+use std::{fmt::Debug, time::Instant};
+
+use crate::{
+    actions::script_generator2::{ScriptGenerator, SimpleAction},
+    decompressed_tree_store::{bfs_wrapper::SimpleBfsMapper, CompletePostOrder},
+    matchers::{
+        heuristic::cd::{
+            bottom_up_matcher::ChangeDistillerBottomUpMatcher,
+            leaves_matcher::ChangeDistillerLeavesMatcher,
+        },
+        mapping_store::{MappingStore, VecStore},
+        Mapper,
+    },
+    tree::tree_path::CompressedTreePath,
+};
+use hyper_ast::types::{self, HyperAST};
+
+type CDS<T> = CompletePostOrder<T, u32>;
+
+use super::MappingDurations;
+
+use super::{DiffResult, PreparedMappingDurations};
+
+/// Alternative to [`super::gumtree::diff`] without the hash-based top-down phase,
+/// leaves are matched by the similarity of their labels then inner nodes by their common leaves,
+/// see [`crate::matchers::heuristic::cd`].
+pub fn diff<'store, HAST: HyperAST<'store>>(
+    hyperast: &'store HAST,
+    src: &HAST::IdN,
+    dst: &HAST::IdN,
+) -> DiffResult<
+    SimpleAction<
+        HAST::Label,
+        CompressedTreePath<<HAST::T as types::WithChildren>::ChildIdx>,
+        HAST::IdN,
+    >,
+    Mapper<'store, HAST, CDS<HAST::T>, CDS<HAST::T>, VecStore<u32>>,
+    PreparedMappingDurations<2>,
+>
+where
+    HAST::IdN: Clone + Debug + Eq,
+    HAST::Label: Debug + Clone + Copy,
+    <HAST::T as types::Typed>::Type: Debug,
+    <HAST::T as types::WithChildren>::ChildIdx: Debug,
+    HAST::T: 'store + types::WithHashs + types::WithStats,
+{
+    let now = Instant::now();
+    let mapper: Mapper<_, CDS<HAST::T>, CDS<HAST::T>, VecStore<_>> =
+        hyperast.decompress_pair(src, dst).into();
+    let leaves_prepare_t = now.elapsed().as_secs_f64();
+    let now = Instant::now();
+    let mapper = ChangeDistillerLeavesMatcher::<_, _, _, _, _, _>::match_it(mapper);
+    let leaves_matcher_t = now.elapsed().as_secs_f64();
+    let leaves_mappings_s = mapper.mappings().len();
+    log::debug!(
+        "leaves matcher: {}s {} mappings",
+        leaves_matcher_t,
+        leaves_mappings_s
+    );
+    let now = Instant::now();
+    let mapper = ChangeDistillerBottomUpMatcher::<_, _, _, _, _>::match_it(mapper);
+    let bottomup_matcher_t = now.elapsed().as_secs_f64();
+    let bottomup_mappings_s = mapper.mappings().len();
+    log::debug!(
+        "bottom-up matcher: {}s {} mappings",
+        bottomup_matcher_t,
+        bottomup_mappings_s
+    );
+    let now = Instant::now();
+
+    let node_store = hyperast.node_store();
+
+    let mapper = mapper.map(
+        |x| x,
+        |dst_arena| SimpleBfsMapper::from(node_store, dst_arena),
+    );
+    let prepare_gen_t = now.elapsed().as_secs_f64();
+    let now = Instant::now();
+    let actions = ScriptGenerator::compute_actions(mapper.hyperast, &mapper.mapping).ok();
+    let gen_t = now.elapsed().as_secs_f64();
+    log::debug!("script generation: {}s", gen_t);
+    let mapper = mapper.map(|x| x, |dst_arena| dst_arena.back);
+    DiffResult {
+        mapping_durations: PreparedMappingDurations {
+            mappings: MappingDurations([leaves_matcher_t, bottomup_matcher_t]),
+            preparation: [leaves_prepare_t, 0.0],
+        },
+        mapper,
+        actions,
+        prepare_gen_t,
+        gen_t,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper_ast::types::LabelStore;
+
+    use super::*;
+    use crate::{actions::script_generator2::Act, tests::java::java_files};
+
+    #[test]
+    fn test_identical_files() {
+        let text = "class A { int f(int x) { return x; } }";
+        let (stores, [src, dst]) = java_files([text, text]);
+        let diff = diff(&stores, &src, &dst);
+        assert!(diff.actions.unwrap().0.is_empty());
+    }
+
+    #[test]
+    fn test_similar_identifiers() {
+        let (stores, [src, dst]) = java_files([
+            "class A { int f(int x) { int count = x; return count; } }",
+            "class A { int f(int x) { int counts = x; return counts; } }",
+        ]);
+        let diff = diff(&stores, &src, &dst);
+        let actions = diff.actions.unwrap().0;
+        // the leaves are matched by the similarity of their labels, then updated
+        assert_eq!(2, actions.len(), "{:?}", actions);
+        for a in &actions {
+            let Act::Update { new } = &a.action else {
+                panic!("{:?}", a.action)
+            };
+            assert_eq!("counts", stores.label_store.resolve(new));
+        }
+    }
+}
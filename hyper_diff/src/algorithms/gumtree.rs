@@ -9,7 +9,7 @@ use crate::{
             greedy_subtree_matcher::GreedySubtreeMatcher,
         },
         mapping_store::{DefaultMultiMappingStore, MappingStore, VecStore},
-        Mapper,
+        Mapper, MatcherConfig,
     },
     tree::tree_path::CompressedTreePath,
 };
@@ -34,6 +34,32 @@ pub fn diff<'store, HAST: HyperAST<'store>>(
     Mapper<'store, HAST, CDS<HAST::T>, CDS<HAST::T>, VecStore<u32>>,
     PreparedMappingDurations<2>,
 >
+where
+    HAST::IdN: Clone + Debug + Eq,
+    HAST::Label: Debug + Clone + Copy,
+    <HAST::T as types::Typed>::Type: Debug,
+    <HAST::T as types::WithChildren>::ChildIdx: Debug,
+    HAST::T: 'store + types::WithHashs + types::WithStats,
+{
+    diff_with_config(hyperast, src, dst, &MatcherConfig::default())
+}
+
+/// Same as [`diff`] but with the thresholds of the matchers given at runtime,
+/// the const generic matchers are still used with the default thresholds.
+pub fn diff_with_config<'store, HAST: HyperAST<'store>>(
+    hyperast: &'store HAST,
+    src: &HAST::IdN,
+    dst: &HAST::IdN,
+    config: &MatcherConfig,
+) -> DiffResult<
+    SimpleAction<
+        HAST::Label,
+        CompressedTreePath<<HAST::T as types::WithChildren>::ChildIdx>,
+        HAST::IdN,
+    >,
+    Mapper<'store, HAST, CDS<HAST::T>, CDS<HAST::T>, VecStore<u32>>,
+    PreparedMappingDurations<2>,
+>
 where
     HAST::IdN: Clone + Debug + Eq,
     HAST::Label: Debug + Clone + Copy,
@@ -46,13 +72,27 @@ where
         hyperast.decompress_pair(src, dst).into();
     let subtree_prepare_t = now.elapsed().as_secs_f64();
     let now = Instant::now();
-    let mapper =
-        GreedySubtreeMatcher::<_, _, _, _, _>::match_it::<_, DefaultMultiMappingStore<_>>(mapper);
+    let mapper = if config.is_default() {
+        GreedySubtreeMatcher::<_, _, _, _, _>::match_it::<_, DefaultMultiMappingStore<_>>(mapper)
+    } else {
+        GreedySubtreeMatcher::<_, _, _, _, _>::match_it_with::<_, DefaultMultiMappingStore<_>>(
+            mapper,
+            config.min_height,
+        )
+    };
     let subtree_matcher_t = now.elapsed().as_secs_f64();
     let subtree_mappings_s = mapper.mappings().len();
     dbg!(&subtree_matcher_t, &subtree_mappings_s);
     let now = Instant::now();
-    let mapper = GreedyBottomUpMatcher::<_, _, _, _, _, _>::match_it(mapper);
+    let mapper = if config.is_default() {
+        GreedyBottomUpMatcher::<_, _, _, _, _, _>::match_it(mapper)
+    } else {
+        GreedyBottomUpMatcher::<_, _, _, _, _, _>::match_it_with(
+            mapper,
+            config.size_threshold,
+            config.sim_threshold,
+        )
+    };
     dbg!(&now.elapsed().as_secs_f64());
     let bottomup_matcher_t = now.elapsed().as_secs_f64();
     let bottomup_mappings_s = mapper.mappings().len();
@@ -82,3 +122,76 @@ where
         gen_t,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::java::java_files;
+
+    #[test]
+    fn test_min_height_drops_small_subtrees() {
+        let (stores, [src, dst]) = java_files([
+            "class A { int f() { return 1; } }",
+            "class B { void g() { h(1); } }",
+        ]);
+        let subtree_mappings = |min_height| {
+            let mapper: Mapper<_, CDS<_>, CDS<_>, VecStore<_>> =
+                stores.decompress_pair(&src, &dst).into();
+            let mapper = GreedySubtreeMatcher::<_, _, _, _, _>::match_it_with::<
+                _,
+                DefaultMultiMappingStore<_>,
+            >(mapper, min_height);
+            mapper.mappings().len()
+        };
+        let default = subtree_mappings(MatcherConfig::default().min_height);
+        // most of the shared subtrees are leaves, like the literal `1`, of height 1
+        assert!(subtree_mappings(2) < default);
+        assert_eq!(0, subtree_mappings(1000));
+    }
+
+    #[test]
+    fn test_sim_threshold_drops_containers() {
+        let (stores, [src, dst]) = java_files([
+            "class A { int f(int x) { int y = x; return y; } }",
+            "class A { int f(int x) { int z = x; return z; } }",
+        ]);
+        let default = diff(&stores, &src, &dst).mapper.mappings().len();
+        let config = MatcherConfig {
+            size_threshold: 0,
+            sim_threshold: 1.1,
+            ..Default::default()
+        };
+        let strict = diff_with_config(&stores, &src, &dst, &config);
+        // no container can be similar enough, only the roots are matched by the bottom-up matcher
+        assert!(strict.mapper.mappings().len() < default);
+        let actions = strict.actions.unwrap().0;
+        assert!(actions.len() > 2, "{:?}", actions);
+    }
+
+    #[test]
+    fn test_default_thresholds_at_runtime() {
+        let (stores, [src, dst]) = java_files([
+            "class A { int f(int x) { int y = x; return y; } }",
+            "class A { int f(int x) { int z = x; return z + 1; } }",
+        ]);
+        let bottom_up = |config: Option<&MatcherConfig>| {
+            let mapper: Mapper<_, CDS<_>, CDS<_>, VecStore<_>> =
+                stores.decompress_pair(&src, &dst).into();
+            let mapper = GreedySubtreeMatcher::<_, _, _, _, _>::match_it::<
+                _,
+                DefaultMultiMappingStore<_>,
+            >(mapper);
+            let mapper = match config {
+                None => GreedyBottomUpMatcher::<_, _, _, _, _, _>::match_it(mapper),
+                Some(config) => GreedyBottomUpMatcher::<_, _, _, _, _, _>::match_it_with(
+                    mapper,
+                    config.size_threshold,
+                    config.sim_threshold,
+                ),
+            };
+            mapper.mappings().iter().collect::<Vec<_>>()
+        };
+        // the const generic matcher and its runtime counterpart must agree
+        assert_eq!(bottom_up(None), bottom_up(Some(&MatcherConfig::default())));
+    }
+}
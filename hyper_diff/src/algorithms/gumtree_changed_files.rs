@@ -0,0 +1,422 @@
+//! Diff of two commit roots restricted to the changed files.
+//!
+//! Directories and files are paired by name from the roots.
+//! Pairs with equal node identifiers are identical, thus trivially mapped without being decompressed.
+//! Only the remaining pairs of files are diffed with [`gumtree_lazy`],
+//! then identical subtrees left unmapped in different files are paired as moves across files,
+//! such that the work is proportional to the size of the change.
+use std::{collections::HashMap, fmt::Debug, hash::Hash};
+
+use num_traits::{cast, ToPrimitive};
+
+use hyper_ast::types::{
+    self, HyperAST, HyperType, IterableChildren, Labeled, NodeStore, TypeStore, WithChildren,
+    WithStats,
+};
+
+use crate::{
+    actions::script_generator2::SimpleAction,
+    decompressed_tree_store::{
+        CompletePostOrder, DecompressedWithParent, PostOrder, ShallowDecompressedTreeStore,
+    },
+    matchers::{
+        mapping_store::{MappingStore, VecStore},
+        Mapper, MatcherConfig,
+    },
+    tree::tree_path::CompressedTreePath,
+};
+
+use super::{gumtree_lazy, DiffResult, PreparedMappingDurations};
+
+type CDS<T> = CompletePostOrder<T, u32>;
+
+/// A subtree and its path from the root it was found in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Located<IdN, Idx> {
+    pub id: IdN,
+    pub path: Vec<Idx>,
+}
+
+/// Result of [`diff`], paths are from the commit roots.
+#[derive(Debug)]
+pub struct ChangedFilesDiff<IdN, Idx, D> {
+    /// identical directories or files found at the same path
+    pub unchanged: Vec<(Located<IdN, Idx>, Located<IdN, Idx>)>,
+    /// files found at the same path, with their diff
+    pub changed: Vec<(Located<IdN, Idx>, Located<IdN, Idx>, D)>,
+    /// directories or files only found in the source
+    pub removed: Vec<Located<IdN, Idx>>,
+    /// directories or files only found in the destination
+    pub added: Vec<Located<IdN, Idx>>,
+    /// identical subtrees, unmapped in their diffs, moved from a file to another one
+    pub moves: Vec<(Located<IdN, Idx>, Located<IdN, Idx>)>,
+}
+
+impl<IdN, Idx, D> Default for ChangedFilesDiff<IdN, Idx, D> {
+    fn default() -> Self {
+        Self {
+            unchanged: vec![],
+            changed: vec![],
+            removed: vec![],
+            added: vec![],
+            moves: vec![],
+        }
+    }
+}
+
+pub type FileDiff<'store, HAST> = DiffResult<
+    SimpleAction<
+        <HAST as HyperAST<'store>>::Label,
+        CompressedTreePath<<HAST as HyperAST<'store>>::Idx>,
+        <HAST as HyperAST<'store>>::IdN,
+    >,
+    Mapper<
+        'store,
+        HAST,
+        CDS<<HAST as HyperAST<'store>>::T>,
+        CDS<<HAST as HyperAST<'store>>::T>,
+        VecStore<u32>,
+    >,
+    PreparedMappingDurations<2>,
+>;
+
+pub fn diff<'store, HAST: HyperAST<'store>>(
+    hyperast: &'store HAST,
+    src: &HAST::IdN,
+    dst: &HAST::IdN,
+) -> ChangedFilesDiff<HAST::IdN, HAST::Idx, FileDiff<'store, HAST>>
+where
+    HAST::IdN: Clone + Debug + Eq + Hash,
+    HAST::Label: Clone + Copy + Eq + Debug + Hash,
+    <HAST::T as types::Typed>::Type: Eq + Debug,
+    HAST::Idx: Debug,
+    HAST::T: 'store + types::WithHashs + types::WithStats,
+{
+    diff_with_config(hyperast, src, dst, &MatcherConfig::default())
+}
+
+/// Same as [`diff`] but with the thresholds of the matchers given at runtime,
+/// `min_height` also being the minimal height of the subtrees moved across files.
+pub fn diff_with_config<'store, HAST: HyperAST<'store>>(
+    hyperast: &'store HAST,
+    src: &HAST::IdN,
+    dst: &HAST::IdN,
+    config: &MatcherConfig,
+) -> ChangedFilesDiff<HAST::IdN, HAST::Idx, FileDiff<'store, HAST>>
+where
+    HAST::IdN: Clone + Debug + Eq + Hash,
+    HAST::Label: Clone + Copy + Eq + Debug + Hash,
+    <HAST::T as types::Typed>::Type: Eq + Debug,
+    HAST::Idx: Debug,
+    HAST::T: 'store + types::WithHashs + types::WithStats,
+{
+    let mut result = ChangedFilesDiff::default();
+    let src = Located {
+        id: src.clone(),
+        path: vec![],
+    };
+    let dst = Located {
+        id: dst.clone(),
+        path: vec![],
+    };
+    pair(hyperast, src, dst, config, &mut result);
+    result.moves = moves_across_files(hyperast, &result, config.min_height);
+    result
+}
+
+fn pair<'store, HAST: HyperAST<'store>>(
+    hyperast: &'store HAST,
+    src: Located<HAST::IdN, HAST::Idx>,
+    dst: Located<HAST::IdN, HAST::Idx>,
+    config: &MatcherConfig,
+    result: &mut ChangedFilesDiff<HAST::IdN, HAST::Idx, FileDiff<'store, HAST>>,
+) where
+    HAST::IdN: Clone + Debug + Eq + Hash,
+    HAST::Label: Clone + Copy + Eq + Debug + Hash,
+    <HAST::T as types::Typed>::Type: Eq + Debug,
+    HAST::Idx: Debug,
+    HAST::T: 'store + types::WithHashs + types::WithStats,
+{
+    if src.id == dst.id {
+        result.unchanged.push((src, dst));
+        return;
+    }
+    let src_node = hyperast.node_store().resolve(&src.id);
+    let dst_node = hyperast.node_store().resolve(&dst.id);
+    let src_type = hyperast.type_store().resolve_type(&src_node);
+    let dst_type = hyperast.type_store().resolve_type(&dst_node);
+    if src_type.is_directory() != dst_type.is_directory() {
+        // a directory replaced by a file of the same name, or the reverse
+        result.removed.push(src);
+        result.added.push(dst);
+        return;
+    }
+    if !src_type.is_directory() {
+        let diff = gumtree_lazy::diff_with_config(hyperast, &src.id, &dst.id, config);
+        result.changed.push((src, dst, diff));
+        return;
+    }
+    // children of a directory are identified by their name and their type
+    let key = |x: &HAST::IdN| {
+        let n = hyperast.node_store().resolve(x);
+        let l = n.try_get_label().copied();
+        l.map(|l| (l, hyperast.type_store().resolve_type(&n)))
+    };
+    let mut dst_children: HashMap<_, _> = children::<HAST>(&dst_node)
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, x)| Some((key(&x)?, (i, x))))
+        .collect();
+    for (i, x) in children::<HAST>(&src_node).into_iter().enumerate() {
+        let c = located::<HAST>(&src.path, i, x);
+        match key(&c.id).and_then(|k| dst_children.remove(&k)) {
+            Some((j, y)) => pair(
+                hyperast,
+                c,
+                located::<HAST>(&dst.path, j, y),
+                config,
+                result,
+            ),
+            None => result.removed.push(c),
+        }
+    }
+    let mut added: Vec<_> = dst_children.into_values().collect();
+    added.sort_by_key(|(j, _)| *j);
+    let added = added
+        .into_iter()
+        .map(|(j, y)| located::<HAST>(&dst.path, j, y));
+    result.added.extend(added);
+}
+
+/// Pairs identical subtrees unmapped in different files, largest ones first.
+fn moves_across_files<'store, HAST: HyperAST<'store>>(
+    hyperast: &'store HAST,
+    result: &ChangedFilesDiff<HAST::IdN, HAST::Idx, FileDiff<'store, HAST>>,
+    min_height: usize,
+) -> Vec<(Located<HAST::IdN, HAST::Idx>, Located<HAST::IdN, HAST::Idx>)>
+where
+    HAST::IdN: Clone + Debug + Eq + Hash,
+    HAST::T: 'store + types::WithStats,
+{
+    let node_store = hyperast.node_store();
+    let large_enough = |x: &HAST::IdN| node_store.resolve(x).height() >= min_height;
+    // files are numbered, first the changed ones, then the removed or added ones
+    let changed_count = result.changed.len();
+    let mut inserted: HashMap<HAST::IdN, Vec<(usize, Vec<HAST::Idx>)>> = HashMap::new();
+    for (file, (_, dst, diff)) in result.changed.iter().enumerate() {
+        let mapping = &diff.mapper.mapping;
+        let arena = &mapping.dst_arena;
+        for x in (0..arena.len()).rev().map(|x| cast::<_, u32>(x).unwrap()) {
+            let id = arena.original(&x);
+            if !mapping.mappings.is_dst(&x) && large_enough(&id) {
+                let mut path = dst.path.clone();
+                path.extend(arena.path(&arena.root(), &x));
+                inserted.entry(id).or_default().push((file, path));
+            }
+        }
+    }
+    for (file, dst) in result.added.iter().enumerate() {
+        let file = changed_count + result.removed.len() + file;
+        walk::<HAST>(hyperast, dst.clone(), min_height, &mut |x| {
+            inserted.entry(x.id).or_default().push((file, x.path));
+            true
+        });
+    }
+
+    let mut moves = vec![];
+    let mut used: Vec<Vec<HAST::Idx>> = vec![];
+    let mut take = |file: usize, src: &Located<HAST::IdN, HAST::Idx>| {
+        let candidates = inserted.get_mut(&src.id)?;
+        let i = candidates.iter().position(|(f, p)| {
+            *f != file && !used.iter().any(|u| u.starts_with(p) || p.starts_with(u))
+        })?;
+        let (_, path) = candidates.swap_remove(i);
+        used.push(path.clone());
+        Some(Located {
+            id: src.id.clone(),
+            path,
+        })
+    };
+    for (file, (src, _, diff)) in result.changed.iter().enumerate() {
+        let mapping = &diff.mapper.mapping;
+        let arena = &mapping.src_arena;
+        // in reverse post-order parents come before their descendants
+        let mut x = arena.len();
+        while x > 0 {
+            let curr = cast::<_, u32>(x - 1).unwrap();
+            x -= 1;
+            let id = arena.original(&curr);
+            if mapping.mappings.is_src(&curr) || !large_enough(&id) {
+                continue;
+            }
+            let mut path = src.path.clone();
+            path.extend(arena.path(&arena.root(), &curr));
+            let deleted = Located { id, path };
+            if let Some(inserted) = take(file, &deleted) {
+                moves.push((deleted, inserted));
+                // skips the descendants, moved with it
+                x = arena.lld(&curr).to_usize().unwrap();
+            }
+        }
+    }
+    for (file, src) in result.removed.iter().enumerate() {
+        let file = changed_count + file;
+        walk::<HAST>(
+            hyperast,
+            src.clone(),
+            min_height,
+            &mut |deleted| match take(file, &deleted) {
+                Some(inserted) => {
+                    moves.push((deleted, inserted));
+                    false
+                }
+                None => true,
+            },
+        );
+    }
+    moves
+}
+
+/// Visits the subtrees of `x` at least `min_height` high, in pre-order,
+/// the descendants of a subtree are skipped if `f` returns false.
+fn walk<'store, HAST: HyperAST<'store>>(
+    hyperast: &'store HAST,
+    x: Located<HAST::IdN, HAST::Idx>,
+    min_height: usize,
+    f: &mut impl FnMut(Located<HAST::IdN, HAST::Idx>) -> bool,
+) where
+    HAST::IdN: Clone,
+    HAST::T: 'store + types::WithStats,
+{
+    let node = hyperast.node_store().resolve(&x.id);
+    if node.height() < min_height {
+        return;
+    }
+    let path = x.path.clone();
+    if !f(x) {
+        return;
+    }
+    for (i, c) in children::<HAST>(&node).into_iter().enumerate() {
+        walk(hyperast, located::<HAST>(&path, i, c), min_height, f);
+    }
+}
+
+fn children<'store, HAST: HyperAST<'store>>(node: &HAST::T) -> Vec<HAST::IdN>
+where
+    HAST::IdN: Clone,
+{
+    node.children()
+        .map(|cs| cs.iter_children().cloned().collect())
+        .unwrap_or_default()
+}
+
+fn located<'store, HAST: HyperAST<'store>>(
+    parent: &[HAST::Idx],
+    i: usize,
+    id: HAST::IdN,
+) -> Located<HAST::IdN, HAST::Idx> {
+    let mut path = parent.to_vec();
+    path.push(cast(i).unwrap());
+    Located { id, path }
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper_ast::store::defaults::NodeIdentifier;
+    use hyper_ast_gen_ts_java::types::Type;
+
+    use super::*;
+    use crate::tests::java::{java_dirs, JavaFs, JavaStores};
+
+    fn ty(stores: &JavaStores, x: &NodeIdentifier) -> Option<Type> {
+        let n = stores.node_store().resolve(x);
+        let t = stores.type_store().resolve_type(&n);
+        t.as_any().downcast_ref::<Type>().copied()
+    }
+
+    #[test]
+    fn test_changed_files() {
+        let moved = "int f() { int x = 1; return x + 2; }";
+        let (stores, [src, dst]) = java_dirs([
+            JavaFs::Dir(
+                "r",
+                vec![
+                    JavaFs::File("A.java", &format!("class A {{ {} void g() {{}} }}", moved)),
+                    JavaFs::File("B.java", "class B { void h() {} }"),
+                    JavaFs::Dir("s", vec![JavaFs::File("S.java", "class S {}")]),
+                    JavaFs::File("T.java", "class T {}"),
+                    JavaFs::File("Old.java", "class Old {}"),
+                ],
+            ),
+            JavaFs::Dir(
+                "r",
+                vec![
+                    JavaFs::File("A.java", "class A { void g() {} }"),
+                    JavaFs::File("B.java", &format!("class B {{ void h() {{}} {} }}", moved)),
+                    JavaFs::Dir("s", vec![JavaFs::File("S.java", "class S {}")]),
+                    JavaFs::File("T.java", "class T {}"),
+                    JavaFs::File("New.java", "class New {}"),
+                ],
+            ),
+        ]);
+        let result = diff(&stores, &src, &dst);
+
+        // identical files and directories are paired without being diffed
+        let unchanged: Vec<_> = result
+            .unchanged
+            .iter()
+            .map(|(s, d)| (&s.path[..], &d.path[..]))
+            .collect();
+        assert_eq!(vec![(&[2][..], &[2][..]), (&[3][..], &[3][..])], unchanged);
+        let changed: Vec<_> = result
+            .changed
+            .iter()
+            .map(|(s, d, _)| (&s.path[..], &d.path[..]))
+            .collect();
+        assert_eq!(vec![(&[0][..], &[0][..]), (&[1][..], &[1][..])], changed);
+        assert_eq!(
+            vec![vec![4]],
+            result
+                .removed
+                .iter()
+                .map(|x| x.path.clone())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![vec![4]],
+            result
+                .added
+                .iter()
+                .map(|x| x.path.clone())
+                .collect::<Vec<_>>()
+        );
+
+        // the method is deleted from A.java and inserted in B.java
+        let (deleted, inserted) = result
+            .moves
+            .iter()
+            .find(|(x, _)| ty(&stores, &x.id) == Some(Type::MethodDeclaration))
+            .expect("the method should be moved across files");
+        assert_eq!(deleted.id, inserted.id);
+        assert_eq!(0, deleted.path[0]);
+        assert_eq!(1, inserted.path[0]);
+    }
+
+    #[test]
+    fn test_directory_replaced_by_file() {
+        let (stores, [src, dst]) = java_dirs([
+            JavaFs::Dir("A", vec![JavaFs::File("A.java", "class A {}")]),
+            JavaFs::File("A", "class A {}"),
+        ]);
+        let result = diff(&stores, &src, &dst);
+        assert!(result.changed.is_empty());
+        assert_eq!(
+            vec![src],
+            result.removed.iter().map(|x| x.id).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![dst],
+            result.added.iter().map(|x| x.id).collect::<Vec<_>>()
+        );
+    }
+}
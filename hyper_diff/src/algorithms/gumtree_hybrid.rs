@@ -0,0 +1,133 @@
+use std::{fmt::Debug, time::Instant};
+
+use crate::{
+    actions::script_generator2::{ScriptGenerator, SimpleAction},
+    decompressed_tree_store::{bfs_wrapper::SimpleBfsMapper, CompletePostOrder},
+    matchers::{
+        heuristic::gt::{
+            greedy_subtree_matcher::GreedySubtreeMatcher,
+            hybrid_bottom_up_matcher::HybridBottomUpMatcher,
+        },
+        mapping_store::{DefaultMultiMappingStore, MappingStore, VecStore},
+        Mapper,
+    },
+    tree::tree_path::CompressedTreePath,
+};
+use hyper_ast::types::{self, HyperAST};
+
+type CDS<T> = CompletePostOrder<T, u32>;
+
+use super::MappingDurations;
+
+use super::{DiffResult, PreparedMappingDurations};
+
+/// Same as [`super::gumtree::diff`] but with the bottom-up phase of GumTree's default configuration,
+/// ie. small unmatched containers are recovered by an optimal matching,
+/// see [`HybridBottomUpMatcher`].
+pub fn diff<'store, HAST: HyperAST<'store>>(
+    hyperast: &'store HAST,
+    src: &HAST::IdN,
+    dst: &HAST::IdN,
+) -> DiffResult<
+    SimpleAction<
+        HAST::Label,
+        CompressedTreePath<<HAST::T as types::WithChildren>::ChildIdx>,
+        HAST::IdN,
+    >,
+    Mapper<'store, HAST, CDS<HAST::T>, CDS<HAST::T>, VecStore<u32>>,
+    PreparedMappingDurations<2>,
+>
+where
+    HAST::IdN: Clone + Debug + Eq,
+    HAST::Label: Debug + Clone + Copy,
+    <HAST::T as types::Typed>::Type: Debug + std::hash::Hash,
+    <HAST::T as types::WithChildren>::ChildIdx: Debug,
+    HAST::T: 'store + types::WithHashs + types::WithStats,
+{
+    let now = Instant::now();
+    let mapper: Mapper<_, CDS<HAST::T>, CDS<HAST::T>, VecStore<_>> =
+        hyperast.decompress_pair(src, dst).into();
+    let subtree_prepare_t = now.elapsed().as_secs_f64();
+    let now = Instant::now();
+    let mapper =
+        GreedySubtreeMatcher::<_, _, _, _, _>::match_it::<_, DefaultMultiMappingStore<_>>(mapper);
+    let subtree_matcher_t = now.elapsed().as_secs_f64();
+    let subtree_mappings_s = mapper.mappings().len();
+    log::debug!(
+        "subtree matcher: {}s {} mappings",
+        subtree_matcher_t,
+        subtree_mappings_s
+    );
+    let now = Instant::now();
+    let mapper = HybridBottomUpMatcher::<_, _, _, _, _, _>::match_it(mapper);
+    let bottomup_matcher_t = now.elapsed().as_secs_f64();
+    let bottomup_mappings_s = mapper.mappings().len();
+    log::debug!(
+        "bottom-up matcher: {}s {} mappings",
+        bottomup_matcher_t,
+        bottomup_mappings_s
+    );
+    let now = Instant::now();
+
+    let node_store = hyperast.node_store();
+
+    let mapper = mapper.map(
+        |x| x,
+        |dst_arena| SimpleBfsMapper::from(node_store, dst_arena),
+    );
+    let prepare_gen_t = now.elapsed().as_secs_f64();
+    let now = Instant::now();
+    let actions = ScriptGenerator::compute_actions(mapper.hyperast, &mapper.mapping).ok();
+    let gen_t = now.elapsed().as_secs_f64();
+    log::debug!("script generation: {}s", gen_t);
+    let mapper = mapper.map(|x| x, |dst_arena| dst_arena.back);
+    DiffResult {
+        mapping_durations: PreparedMappingDurations {
+            mappings: MappingDurations([subtree_matcher_t, bottomup_matcher_t]),
+            preparation: [subtree_prepare_t, 0.0],
+        },
+        mapper,
+        actions,
+        prepare_gen_t,
+        gen_t,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper_ast::types::LabelStore;
+
+    use super::*;
+    use crate::{
+        actions::script_generator2::Act, decompressed_tree_store::ShallowDecompressedTreeStore,
+        tests::java::java_files,
+    };
+
+    #[test]
+    fn test_identical_files() {
+        let text = "class A { int f(int x) { return x; } }";
+        let (stores, [src, dst]) = java_files([text, text]);
+        let diff = diff(&stores, &src, &dst);
+        assert!(diff.actions.unwrap().0.is_empty());
+        let src_len = diff.mapper.mapping.src_arena.len();
+        assert_eq!(diff.mapper.mappings().len(), src_len);
+    }
+
+    #[test]
+    fn test_rename_in_small_method() {
+        let (stores, [src, dst]) = java_files([
+            "class A { int f(int x) { int y = x; return y; } }",
+            "class A { int f(int x) { int z = x; return z; } }",
+        ]);
+        let diff = diff(&stores, &src, &dst);
+        let actions = diff.actions.unwrap().0;
+        // the small containers are recovered by zs, so both occurrences are updated in place
+        assert_eq!(2, actions.len(), "{:?}", actions);
+        for a in &actions {
+            let Act::Update { new } = &a.action else {
+                panic!("{:?}", a.action)
+            };
+            assert_eq!("z", stores.label_store.resolve(new));
+        }
+    }
+}
@@ -12,7 +12,7 @@ use crate::{
             lazy2_greedy_subtree_matcher::LazyGreedySubtreeMatcher,
         },
         mapping_store::{DefaultMultiMappingStore, MappingStore, VecStore},
-        Mapper,
+        Mapper, MatcherConfig,
     },
     tree::tree_path::CompressedTreePath,
 };
@@ -41,6 +41,32 @@ pub fn diff<'store, HAST: HyperAST<'store>>(
     Mapper<'store, HAST, CDS<HAST::T>, CDS<HAST::T>, VecStore<u32>>,
     PreparedMappingDurations<2>,
 >
+where
+    HAST::IdN: Clone + Debug + Eq,
+    HAST::Label: Clone + Copy + Eq + Debug,
+    <HAST::T as types::Typed>::Type: Eq + Debug,
+    <HAST::T as types::WithChildren>::ChildIdx: Debug,
+    HAST::T: 'store + types::WithHashs + types::WithStats,
+{
+    diff_with_config(hyperast, src, dst, &MatcherConfig::default())
+}
+
+/// Same as [`diff`] but with the thresholds of the matchers given at runtime,
+/// the const generic matchers are still used with the default thresholds.
+pub fn diff_with_config<'store, HAST: HyperAST<'store>>(
+    hyperast: &'store HAST,
+    src: &HAST::IdN,
+    dst: &HAST::IdN,
+    config: &MatcherConfig,
+) -> DiffResult<
+    SimpleAction<
+        HAST::Label,
+        CompressedTreePath<<HAST::T as types::WithChildren>::ChildIdx>,
+        HAST::IdN,
+    >,
+    Mapper<'store, HAST, CDS<HAST::T>, CDS<HAST::T>, VecStore<u32>>,
+    PreparedMappingDurations<2>,
+>
 where
     HAST::IdN: Clone + Debug + Eq,
     HAST::Label: Clone + Copy + Eq + Debug,
@@ -53,14 +79,28 @@ where
         hyperast.decompress_pair(src, dst).into();
     let subtree_prepare_t = now.elapsed().as_secs_f64();
     let now = Instant::now();
-    let mapper =
-        LazyGreedySubtreeMatcher::<_, _, _, _>::match_it::<DefaultMultiMappingStore<_>>(mapper);
+    let mapper = if config.is_default() {
+        LazyGreedySubtreeMatcher::<_, _, _, _>::match_it::<DefaultMultiMappingStore<_>>(mapper)
+    } else {
+        LazyGreedySubtreeMatcher::<_, _, _, _>::match_it_with::<DefaultMultiMappingStore<_>>(
+            mapper,
+            config.min_height,
+        )
+    };
     let subtree_matcher_t = now.elapsed().as_secs_f64();
     let subtree_mappings_s = mapper.mappings().len();
     dbg!(&subtree_matcher_t, &subtree_mappings_s);
     let bottomup_prepare_t = 0.;
     let now = Instant::now();
-    let mapper = GreedyBottomUpMatcher::<_, _, _, _, VecStore<_>>::match_it(mapper);
+    let mapper = if config.is_default() {
+        GreedyBottomUpMatcher::<_, _, _, _, VecStore<_>>::match_it(mapper)
+    } else {
+        GreedyBottomUpMatcher::<_, _, _, _, VecStore<_>>::match_it_with(
+            mapper,
+            config.size_threshold,
+            config.sim_threshold,
+        )
+    };
     dbg!(&now.elapsed().as_secs_f64());
     let bottomup_matcher_t = now.elapsed().as_secs_f64();
     let bottomup_mappings_s = mapper.mappings().len();
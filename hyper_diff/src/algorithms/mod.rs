@@ -3,7 +3,10 @@ use crate::{
     matchers::{mapping_store::VecStore, Mapper},
 };
 
+pub mod change_distiller;
 pub mod gumtree;
+pub mod gumtree_changed_files;
+pub mod gumtree_hybrid;
 pub mod gumtree_lazy;
 pub mod gumtree_partial_lazy;
 
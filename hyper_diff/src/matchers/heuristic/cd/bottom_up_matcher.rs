@@ -0,0 +1,187 @@
+use std::{fmt::Debug, marker::PhantomData};
+
+use bitvec::vec::BitVec;
+use num_traits::{cast, PrimInt, ToPrimitive};
+
+use crate::decompressed_tree_store::{
+    ContiguousDescendants, DecompressedTreeStore, PostOrderIterable,
+};
+use crate::matchers::mapping_store::MonoMappingStore;
+use crate::matchers::similarity_metrics::SimilarityMeasure;
+use hyper_ast::types::{HyperAST, NodeStore, Tree};
+
+/// Matches inner nodes of the same type by the proportion of their leaves that are matched together.
+///
+/// Inner nodes with more than `MAX_LEAVES` leaves need a similarity of `SIM_THRESHOLD1`,
+/// smaller ones need a similarity of `SIM_THRESHOLD2`.
+pub struct ChangeDistillerBottomUpMatcher<
+    'a,
+    Dsrc,
+    Ddst,
+    T,
+    S,
+    M,
+    const MAX_LEAVES: usize = 4,
+    const SIM_THRESHOLD1_NUM: u64 = 3,
+    const SIM_THRESHOLD1_DEN: u64 = 5,
+    const SIM_THRESHOLD2_NUM: u64 = 2,
+    const SIM_THRESHOLD2_DEN: u64 = 5,
+> {
+    node_store: &'a S,
+    pub src_arena: Dsrc,
+    pub dst_arena: Ddst,
+    pub mappings: M,
+    _phantom: PhantomData<*const T>,
+}
+
+impl<
+        'a,
+        Dsrc: 'a
+            + DecompressedTreeStore<'a, T, IdD>
+            + PostOrderIterable<'a, T, IdD>
+            + ContiguousDescendants<'a, T, IdD>,
+        Ddst: 'a
+            + DecompressedTreeStore<'a, T, IdD>
+            + PostOrderIterable<'a, T, IdD>
+            + ContiguousDescendants<'a, T, IdD>,
+        T: 'a + Tree,
+        S: 'a + NodeStore<T::TreeId, R<'a> = T>,
+        M: MonoMappingStore<Src = IdD, Dst = IdD>,
+        IdD: PrimInt + Debug,
+        const MAX_LEAVES: usize,
+        const SIM_THRESHOLD1_NUM: u64,
+        const SIM_THRESHOLD1_DEN: u64,
+        const SIM_THRESHOLD2_NUM: u64,
+        const SIM_THRESHOLD2_DEN: u64,
+    >
+    ChangeDistillerBottomUpMatcher<
+        'a,
+        Dsrc,
+        Ddst,
+        T,
+        S,
+        M,
+        MAX_LEAVES,
+        SIM_THRESHOLD1_NUM,
+        SIM_THRESHOLD1_DEN,
+        SIM_THRESHOLD2_NUM,
+        SIM_THRESHOLD2_DEN,
+    >
+{
+    pub fn new(node_store: &'a S, src_arena: Dsrc, dst_arena: Ddst, mappings: M) -> Self {
+        Self {
+            node_store,
+            src_arena,
+            dst_arena,
+            mappings,
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn match_it<HAST>(
+        mapping: crate::matchers::Mapper<'a, HAST, Dsrc, Ddst, M>,
+    ) -> crate::matchers::Mapper<'a, HAST, Dsrc, Ddst, M>
+    where
+        HAST: HyperAST<'a, NS = S>,
+    {
+        let mut matcher = Self {
+            node_store: mapping.hyperast.node_store(),
+            src_arena: mapping.mapping.src_arena,
+            dst_arena: mapping.mapping.dst_arena,
+            mappings: mapping.mapping.mappings,
+            _phantom: PhantomData,
+        };
+        matcher
+            .mappings
+            .topit(matcher.src_arena.len(), matcher.dst_arena.len());
+        Self::execute(&mut matcher);
+        crate::matchers::Mapper {
+            hyperast: mapping.hyperast,
+            mapping: crate::matchers::Mapping {
+                src_arena: matcher.src_arena,
+                dst_arena: matcher.dst_arena,
+                mappings: matcher.mappings,
+            },
+        }
+    }
+
+    pub fn execute(&mut self) {
+        let threshold1 = SIM_THRESHOLD1_NUM as f64 / SIM_THRESHOLD1_DEN as f64;
+        let threshold2 = SIM_THRESHOLD2_NUM as f64 / SIM_THRESHOLD2_DEN as f64;
+        let src_leaves = self.leaves(&self.src_arena);
+        let dst_leaves = self.leaves(&self.dst_arena);
+        // the leaves under each inner dst node, computed once for all the src nodes
+        let dst_ls: Vec<_> = (0..self.dst_arena.len())
+            .map(|j| {
+                if dst_leaves[j] {
+                    return vec![];
+                }
+                let dst = cast(j).unwrap();
+                Self::leaves_in(&dst_leaves, self.dst_arena.descendants_range(&dst))
+            })
+            .collect();
+        for src in self.src_arena.iter_df_post::<true>() {
+            let i = src.to_usize().unwrap();
+            if src_leaves[i] || self.mappings.is_src(&src) {
+                continue;
+            }
+            let src_l = Self::leaves_in(&src_leaves, self.src_arena.descendants_range(&src));
+            let threshold = if src_l.len() > MAX_LEAVES {
+                threshold1
+            } else {
+                threshold2
+            };
+            let src_t = self
+                .node_store
+                .resolve(&self.src_arena.original(&src))
+                .get_type();
+            for dst in self.dst_arena.iter_df_post::<true>() {
+                let j = dst.to_usize().unwrap();
+                if dst_leaves[j] || self.mappings.is_dst(&dst) {
+                    continue;
+                }
+                let dst_t = self
+                    .node_store
+                    .resolve(&self.dst_arena.original(&dst))
+                    .get_type();
+                if src_t != dst_t {
+                    continue;
+                }
+                let dst_l = &dst_ls[j];
+                if src_l.is_empty() || dst_l.is_empty() {
+                    continue;
+                }
+                let sim = SimilarityMeasure::new(&src_l, dst_l, &self.mappings).chawathe();
+                if sim >= threshold {
+                    self.mappings.link(src, dst);
+                    break;
+                }
+            }
+        }
+        // the roots are always matched
+        let src = self.src_arena.root();
+        let dst = self.dst_arena.root();
+        if !self.mappings.is_src(&src) && !self.mappings.is_dst(&dst) {
+            self.mappings.link(src, dst);
+        }
+    }
+
+    fn leaves<D: DecompressedTreeStore<'a, T, IdD>>(&self, arena: &D) -> BitVec {
+        (0..arena.len())
+            .map(|i| {
+                !self
+                    .node_store
+                    .resolve(&arena.original(&cast(i).unwrap()))
+                    .has_children()
+            })
+            .collect()
+    }
+
+    fn leaves_in(leaves: &BitVec, range: std::ops::Range<IdD>) -> Vec<IdD> {
+        let range = range.start.to_usize().unwrap()..range.end.to_usize().unwrap();
+        range
+            .filter(|i| leaves[*i])
+            .map(|i| cast(i).unwrap())
+            .collect()
+    }
+}
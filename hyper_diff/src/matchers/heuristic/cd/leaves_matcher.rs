@@ -0,0 +1,155 @@
+use std::{fmt::Debug, marker::PhantomData};
+
+use num_traits::PrimInt;
+use str_distance::DistanceMetric;
+
+use crate::decompressed_tree_store::{DecompressedTreeStore, PostOrderIterable};
+use crate::matchers::mapping_store::MonoMappingStore;
+use crate::matchers::optimal::zs::str_distance_patched::QGram;
+use hyper_ast::types::{HyperAST, LabelStore, NodeStore, SlicedLabel, Tree};
+
+/// Matches leaves of the same type whose labels have a bigram similarity above the threshold,
+/// the most similar pairs being matched first.
+pub struct ChangeDistillerLeavesMatcher<
+    'a,
+    Dsrc,
+    Ddst,
+    T,
+    S,
+    LS,
+    M,
+    const SIM_THRESHOLD_NUM: u64 = 1,
+    const SIM_THRESHOLD_DEN: u64 = 2,
+> {
+    node_store: &'a S,
+    label_store: &'a LS,
+    pub src_arena: Dsrc,
+    pub dst_arena: Ddst,
+    pub mappings: M,
+    _phantom: PhantomData<*const T>,
+}
+
+impl<
+        'a,
+        Dsrc: 'a + DecompressedTreeStore<'a, T, M::Src> + PostOrderIterable<'a, T, M::Src>,
+        Ddst: 'a + DecompressedTreeStore<'a, T, M::Dst> + PostOrderIterable<'a, T, M::Dst>,
+        T: 'a + Tree,
+        S: 'a + NodeStore<T::TreeId, R<'a> = T>,
+        LS: 'a + LabelStore<SlicedLabel, I = T::Label>,
+        M: MonoMappingStore,
+        const SIM_THRESHOLD_NUM: u64,
+        const SIM_THRESHOLD_DEN: u64,
+    >
+    ChangeDistillerLeavesMatcher<'a, Dsrc, Ddst, T, S, LS, M, SIM_THRESHOLD_NUM, SIM_THRESHOLD_DEN>
+where
+    M::Src: PrimInt + Debug,
+    M::Dst: PrimInt + Debug,
+{
+    pub fn new(
+        node_store: &'a S,
+        label_store: &'a LS,
+        src_arena: Dsrc,
+        dst_arena: Ddst,
+        mappings: M,
+    ) -> Self {
+        Self {
+            node_store,
+            label_store,
+            src_arena,
+            dst_arena,
+            mappings,
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn match_it<HAST>(
+        mapping: crate::matchers::Mapper<'a, HAST, Dsrc, Ddst, M>,
+    ) -> crate::matchers::Mapper<'a, HAST, Dsrc, Ddst, M>
+    where
+        HAST: HyperAST<'a, NS = S, LS = LS>,
+    {
+        let mut matcher = Self {
+            node_store: mapping.hyperast.node_store(),
+            label_store: mapping.hyperast.label_store(),
+            src_arena: mapping.mapping.src_arena,
+            dst_arena: mapping.mapping.dst_arena,
+            mappings: mapping.mapping.mappings,
+            _phantom: PhantomData,
+        };
+        matcher
+            .mappings
+            .topit(matcher.src_arena.len(), matcher.dst_arena.len());
+        Self::execute(&mut matcher);
+        crate::matchers::Mapper {
+            hyperast: mapping.hyperast,
+            mapping: crate::matchers::Mapping {
+                src_arena: matcher.src_arena,
+                dst_arena: matcher.dst_arena,
+                mappings: matcher.mappings,
+            },
+        }
+    }
+
+    pub fn execute(&mut self) {
+        let threshold = SIM_THRESHOLD_NUM as f64 / SIM_THRESHOLD_DEN as f64;
+        let dst_leaves: Vec<_> = self
+            .dst_arena
+            .iter_df_post::<true>()
+            .filter(|x| !self.mappings.is_dst(x))
+            .filter(|x| {
+                !self
+                    .node_store
+                    .resolve(&self.dst_arena.original(x))
+                    .has_children()
+            })
+            .collect();
+        let mut candidates = vec![];
+        for src in self.src_arena.iter_df_post::<true>() {
+            if self.mappings.is_src(&src) {
+                continue;
+            }
+            let src_node = self.node_store.resolve(&self.src_arena.original(&src));
+            if src_node.has_children() {
+                continue;
+            }
+            for dst in &dst_leaves {
+                let dst_node = self.node_store.resolve(&self.dst_arena.original(dst));
+                if src_node.get_type() != dst_node.get_type() {
+                    continue;
+                }
+                let sim = self.label_similarity(src_node.try_get_label(), dst_node.try_get_label());
+                if sim > threshold {
+                    candidates.push((src, *dst, sim));
+                }
+            }
+        }
+        // the sort is stable, so ties are resolved in post-order
+        candidates.sort_by(|a, b| b.2.total_cmp(&a.2));
+        for (src, dst, _) in candidates {
+            if !self.mappings.is_src(&src) && !self.mappings.is_dst(&dst) {
+                self.mappings.link(src, dst);
+            }
+        }
+    }
+
+    fn label_similarity(&self, src: Option<&T::Label>, dst: Option<&T::Label>) -> f64 {
+        match (src, dst) {
+            (None, None) => 1.,
+            (Some(src), Some(dst)) if src == dst => 1.,
+            (Some(src), Some(dst)) => {
+                let src = self.label_store.resolve(src).as_bytes();
+                let dst = self.label_store.resolve(dst).as_bytes();
+                1. - bigram_distance(src, dst)
+            }
+            _ => 0.,
+        }
+    }
+}
+
+/// Normalized bigram distance, with padded extremities so that single characters still make a bigram.
+fn bigram_distance(s: &[u8], t: &[u8]) -> f64 {
+    const S: &[u8] = b"#";
+    let s = [S, s, S].concat();
+    let t = [S, t, S].concat();
+    QGram::new(2).normalized(s, t)
+}
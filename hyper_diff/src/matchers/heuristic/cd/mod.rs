@@ -0,0 +1,9 @@
+//! ChangeDistiller, Fluri et al., 2007
+//!
+//! Leaves are matched first, by the similarity of their labels,
+//! then inner nodes are matched bottom-up, by the proportion of their leaves that are matched together.
+//! Contrary to GumTree, it does not rely on identical subtrees,
+//! so statements that were both moved and edited can still be matched.
+
+pub mod bottom_up_matcher;
+pub mod leaves_matcher;
@@ -1,6 +1,6 @@
 use std::{collections::HashMap, fmt::Debug, hash::Hash, marker::PhantomData};
 
-use num_traits::{PrimInt, ToPrimitive, Zero};
+use num_traits::{PrimInt, ToPrimitive};
 
 use crate::{
     decompressed_tree_store::{DecompressedTreeStore, DecompressedWithParent, Shallow},
@@ -80,9 +80,11 @@ where
     pub(super) fn last_chance_match_histogram(&mut self, src: &M::Src, dst: &M::Dst) {
         self.lcs_equal_matching(src, dst);
         self.lcs_structure_matching(src, dst);
-        if !src.is_zero() && !dst.is_zero() {
+        let src_is_root = self.src_arena.parent(src).is_none();
+        let dst_is_root = self.dst_arena.parent(dst).is_none();
+        if src_is_root && dst_is_root {
             self.histogram_matching(src, dst); //self.histogramMaking(src, dst),
-        } else if !(src.is_zero() || dst.is_zero()) {
+        } else if !(src_is_root || dst_is_root) {
             if self
                 .node_store
                 .resolve(
@@ -215,7 +217,6 @@ where
                 }
             }
         }
-        todo!()
     }
 }
 
@@ -291,9 +292,11 @@ where
     pub(super) fn last_chance_match_histogram(&mut self, src: &M::Src, dst: &M::Dst) {
         self.lcs_equal_matching(src, dst);
         self.lcs_structure_matching(src, dst);
-        if !src.is_zero() && !dst.is_zero() {
+        let src_is_root = self.src_arena.parent(src).is_none();
+        let dst_is_root = self.dst_arena.parent(dst).is_none();
+        if src_is_root && dst_is_root {
             self.histogram_matching(src, dst); //self.histogramMaking(src, dst),
-        } else if !(src.is_zero() || dst.is_zero()) {
+        } else if !(src_is_root || dst_is_root) {
             if self
                 .hyperast
                 .node_store()
@@ -430,6 +433,5 @@ where
                 }
             }
         }
-        todo!()
     }
 }
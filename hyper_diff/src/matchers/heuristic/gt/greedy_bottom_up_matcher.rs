@@ -6,6 +6,9 @@ use crate::decompressed_tree_store::{
     ContiguousDescendants, DecompressedTreeStore, DecompressedWithParent, POBorrowSlice, PostOrder,
     PostOrderIterable, PostOrderKeyRoots,
 };
+use crate::matchers::heuristic::gt::{
+    DEFAULT_SIM_THRESHOLD_DEN, DEFAULT_SIM_THRESHOLD_NUM, DEFAULT_SIZE_THRESHOLD,
+};
 use crate::matchers::mapping_store::MonoMappingStore;
 use crate::matchers::{optimal::zs::ZsMatcher, similarity_metrics};
 use hyper_ast::types::{
@@ -26,9 +29,9 @@ pub struct GreedyBottomUpMatcher<
     S,
     LS: LabelStore<SlicedLabel, I = T::Label>,
     M: MonoMappingStore,
-    const SIZE_THRESHOLD: usize = 1000,
-    const SIM_THRESHOLD_NUM: u64 = 1,
-    const SIM_THRESHOLD_DEN: u64 = 2,
+    const SIZE_THRESHOLD: usize = DEFAULT_SIZE_THRESHOLD,
+    const SIM_THRESHOLD_NUM: u64 = DEFAULT_SIM_THRESHOLD_NUM,
+    const SIM_THRESHOLD_DEN: u64 = DEFAULT_SIM_THRESHOLD_DEN,
 > {
     label_store: &'a LS,
     internal: BottomUpMatcher<'a, Dsrc, Ddst, T, S, M>,
@@ -169,6 +172,40 @@ where
         }
     }
 
+    /// Same as [`Self::match_it`] but with the thresholds given at runtime
+    pub fn match_it_with<HAST>(
+        mapping: crate::matchers::Mapper<'a, HAST, Dsrc, Ddst, M>,
+        size_threshold: usize,
+        sim_threshold: f64,
+    ) -> crate::matchers::Mapper<'a, HAST, Dsrc, Ddst, M>
+    where
+        HAST: HyperAST<'a, NS = S, LS = LS>,
+    {
+        let mut matcher = Self {
+            internal: BottomUpMatcher {
+                node_store: mapping.hyperast.node_store(),
+                src_arena: mapping.mapping.src_arena,
+                dst_arena: mapping.mapping.dst_arena,
+                mappings: mapping.mapping.mappings,
+                _phantom: PhantomData,
+            },
+            label_store: mapping.hyperast.label_store(),
+        };
+        matcher.internal.mappings.topit(
+            matcher.internal.src_arena.len(),
+            matcher.internal.dst_arena.len(),
+        );
+        Self::execute_with(&mut matcher, size_threshold, sim_threshold);
+        crate::matchers::Mapper {
+            hyperast: mapping.hyperast,
+            mapping: crate::matchers::Mapping {
+                src_arena: matcher.internal.src_arena,
+                dst_arena: matcher.internal.dst_arena,
+                mappings: matcher.internal.mappings,
+            },
+        }
+    }
+
     pub fn matchh(
         compressed_node_store: &'a S,
         label_store: &'a LS,
@@ -242,6 +279,59 @@ where
         // println!("nodes:{}", c2);
     }
 
+    /// Same as [`Self::execute`] but with the thresholds given at runtime
+    pub fn execute_with(&mut self, size_threshold: usize, sim_threshold: f64) {
+        assert_eq!(
+            // TODO move it inside the arena ...
+            self.internal.src_arena.root(),
+            cast::<_, M::Src>(self.internal.src_arena.len()).unwrap() - one()
+        );
+        assert!(self.internal.src_arena.len() > 0);
+        // println!("mappings={}", self.internal.mappings.len());
+        // // WARN it is in postorder and it depends on decomp store
+        // // -1 as root is handled after forloop
+        for a in self.internal.src_arena.iter_df_post::<true>() {
+            if self.internal.src_arena.parent(&a).is_none() {
+                // TODO remove and flip const param of iter_df_post
+                break;
+            }
+            if !(self.internal.mappings.is_src(&a) || !self.src_has_children(a)) {
+                let candidates = self.internal.get_dst_candidates(&a);
+                let mut best = None;
+                let mut max: f64 = -1.;
+                for cand in candidates {
+                    let sim = similarity_metrics::SimilarityMeasure::range(
+                        &self.internal.src_arena.descendants_range(&a),
+                        &self.internal.dst_arena.descendants_range(&cand),
+                        &self.internal.mappings,
+                    )
+                    .dice();
+                    if sim > max && sim >= sim_threshold {
+                        max = sim;
+                        best = Some(cand);
+                    }
+                }
+
+                if let Some(best) = best {
+                    self.last_chance_match_zs_with(a, best, size_threshold);
+                    self.internal.mappings.link(a, best);
+                }
+            }
+        }
+        // for root
+        self.internal.mappings.link(
+            self.internal.src_arena.root(),
+            self.internal.dst_arena.root(),
+        );
+        self.last_chance_match_zs_with(
+            self.internal.src_arena.root(),
+            self.internal.dst_arena.root(),
+            size_threshold,
+        );
+        // println!("nodes:{}", c);
+        // println!("nodes:{}", c2);
+    }
+
     fn src_has_children(&mut self, src: M::Src) -> bool {
         use num_traits::ToPrimitive;
         let r = self
@@ -260,6 +350,15 @@ where
     }
 
     pub(crate) fn last_chance_match_zs(&mut self, src: M::Src, dst: M::Dst) {
+        self.last_chance_match_zs_with(src, dst, SIZE_THRESHOLD)
+    }
+
+    pub(crate) fn last_chance_match_zs_with(
+        &mut self,
+        src: M::Src,
+        dst: M::Dst,
+        size_threshold: usize,
+    ) {
         // WIP https://blog.rust-lang.org/2022/10/28/gats-stabilization.html#implied-static-requirement-from-higher-ranked-trait-bounds
         let src_s = self
             .internal
@@ -269,7 +368,7 @@ where
             .internal
             .dst_arena
             .descendants_count(self.internal.node_store, &dst);
-        if !(src_s < cast(SIZE_THRESHOLD).unwrap() || dst_s < cast(SIZE_THRESHOLD).unwrap()) {
+        if !(src_s < cast(size_threshold).unwrap() || dst_s < cast(size_threshold).unwrap()) {
             return;
         }
         let node_store = self.internal.node_store;
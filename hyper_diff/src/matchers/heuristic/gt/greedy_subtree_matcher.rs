@@ -4,7 +4,7 @@ use std::{fmt::Debug, marker::PhantomData};
 use crate::decompressed_tree_store::{
     ContiguousDescendants, DecompressedTreeStore, DecompressedWithParent,
 };
-use crate::matchers::heuristic::gt::height;
+use crate::matchers::heuristic::gt::{height, DEFAULT_MIN_HEIGHT};
 use crate::matchers::mapping_store::MonoMappingStore;
 use crate::matchers::{mapping_store::MultiMappingStore, similarity_metrics};
 use crate::utils::sequence_algorithms::longest_common_subsequence;
@@ -14,7 +14,15 @@ use hyper_ast::types::{
 };
 use num_traits::{one, zero, PrimInt, ToPrimitive};
 
-pub struct GreedySubtreeMatcher<'a, Dsrc, Ddst, T, S, M, const MIN_HEIGHT: usize = 1> {
+pub struct GreedySubtreeMatcher<
+    'a,
+    Dsrc,
+    Ddst,
+    T,
+    S,
+    M,
+    const MIN_HEIGHT: usize = DEFAULT_MIN_HEIGHT,
+> {
     internal: SubtreeMatcher<'a, Dsrc, Ddst, T, S, M, MIN_HEIGHT>,
 }
 
@@ -46,6 +54,19 @@ where
     pub fn match_it<HAST, MM>(
         mapping: crate::matchers::Mapper<'a, HAST, Dsrc, Ddst, M>,
     ) -> crate::matchers::Mapper<'a, HAST, Dsrc, Ddst, M>
+    where
+        Self: 'a,
+        HAST: HyperAST<'a, NS = S>,
+        MM: MultiMappingStore<Src = M::Src, Dst = M::Dst> + Default,
+    {
+        Self::match_it_with::<HAST, MM>(mapping, MIN_HEIGHT)
+    }
+
+    /// Same as [`Self::match_it`] but with the minimal height given at runtime
+    pub fn match_it_with<HAST, MM>(
+        mapping: crate::matchers::Mapper<'a, HAST, Dsrc, Ddst, M>,
+        min_height: usize,
+    ) -> crate::matchers::Mapper<'a, HAST, Dsrc, Ddst, M>
     where
         Self: 'a,
         HAST: HyperAST<'a, NS = S>,
@@ -64,7 +85,7 @@ where
             matcher.internal.src_arena.len(),
             matcher.internal.dst_arena.len(),
         );
-        Self::execute::<MM>(&mut matcher);
+        Self::execute_with::<MM>(&mut matcher, min_height);
         crate::matchers::Mapper {
             hyperast: mapping.hyperast,
             mapping: crate::matchers::Mapping {
@@ -102,9 +123,16 @@ where
     }
 
     pub(crate) fn execute<MM: MultiMappingStore<Src = M::Src, Dst = M::Dst> + Default>(&mut self) {
+        self.execute_with::<MM>(MIN_HEIGHT)
+    }
+
+    pub(crate) fn execute_with<MM: MultiMappingStore<Src = M::Src, Dst = M::Dst> + Default>(
+        &mut self,
+        min_height: usize,
+    ) {
         let mut mm: MM = Default::default();
         mm.topit(self.internal.src_arena.len(), self.internal.dst_arena.len());
-        self.internal.matchh_to_be_filtered(&mut mm);
+        self.internal.matchh_to_be_filtered(&mut mm, min_height);
         self.filter_mappings(&mm);
     }
 
@@ -481,8 +509,8 @@ where
 
     fn pop_larger<'b>(
         &self,
-        src_trees: &mut PriorityTreeList<'a, 'b, Dsrc, M::Src, T, S, MIN_HEIGHT>,
-        dst_trees: &mut PriorityTreeList<'a, 'b, Ddst, M::Dst, T, S, MIN_HEIGHT>,
+        src_trees: &mut PriorityTreeList<'a, 'b, Dsrc, M::Src, T, S>,
+        dst_trees: &mut PriorityTreeList<'a, 'b, Ddst, M::Dst, T, S>,
     ) {
         if src_trees.peek_height() > dst_trees.peek_height() {
             src_trees.open();
@@ -494,11 +522,20 @@ where
     fn matchh_to_be_filtered<MM: MultiMappingStore<Src = M::Src, Dst = M::Dst>>(
         &self,
         multi_mappings: &mut MM,
+        min_height: usize,
     ) {
-        let mut src_trees =
-            PriorityTreeList::new(self.node_store, &self.src_arena, self.src_arena.root());
-        let mut dst_trees =
-            PriorityTreeList::new(self.node_store, &self.dst_arena, self.dst_arena.root());
+        let mut src_trees = PriorityTreeList::new(
+            self.node_store,
+            &self.src_arena,
+            self.src_arena.root(),
+            min_height,
+        );
+        let mut dst_trees = PriorityTreeList::new(
+            self.node_store,
+            &self.dst_arena,
+            self.dst_arena.root(),
+            min_height,
+        );
         // let mut aaa = 0;
         while src_trees.peek_height() != -1 && dst_trees.peek_height() != -1 {
             // aaa += 1;
@@ -682,13 +719,14 @@ where
     }
 }
 
-struct PriorityTreeList<'a, 'b, D, IdD, T: Tree, S, const MIN_HEIGHT: usize> {
+struct PriorityTreeList<'a, 'b, D, IdD, T: Tree, S> {
     trees: Vec<Option<Vec<IdD>>>,
 
     store: &'a S,
     arena: &'b D,
 
     max_height: usize,
+    min_height: usize,
 
     current_idx: isize,
 
@@ -702,15 +740,14 @@ impl<
         IdD: PrimInt,
         T: Tree,
         S: 'a + NodeStore<T::TreeId, R<'a> = T>,
-        const MIN_HEIGHT: usize,
-    > PriorityTreeList<'a, 'b, D, IdD, T, S, MIN_HEIGHT>
+    > PriorityTreeList<'a, 'b, D, IdD, T, S>
 where
     T::TreeId: Clone + NodeId<IdN = T::TreeId>,
 {
-    pub(super) fn new(store: &'a S, arena: &'b D, tree: IdD) -> Self {
+    pub(super) fn new(store: &'a S, arena: &'b D, tree: IdD, min_height: usize) -> Self {
         let h = height(store, &arena.original(&tree)); // TODO subtree opti, use metadata
-        let list_size = if h >= MIN_HEIGHT {
-            h + 1 - MIN_HEIGHT
+        let list_size = if h >= min_height {
+            h + 1 - min_height
         } else {
             0
         };
@@ -719,6 +756,7 @@ where
             store,
             arena,
             max_height: h,
+            min_height,
             current_idx: if list_size == 0 { -1 } else { 0 },
             phantom: PhantomData,
         };
@@ -740,7 +778,7 @@ where
     }
 
     fn add_tree_aux(&mut self, tree: IdD, h: usize) {
-        if h >= MIN_HEIGHT {
+        if h >= self.min_height {
             let idx = self.idx(h);
             if self.trees[idx].is_none() {
                 self.trees[idx] = Some(vec![]);
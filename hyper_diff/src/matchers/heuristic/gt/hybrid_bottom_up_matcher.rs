@@ -0,0 +1,283 @@
+use std::{fmt::Debug, hash::Hash, marker::PhantomData};
+
+use num_traits::{cast, one, PrimInt, ToPrimitive};
+
+use crate::decompressed_tree_store::{
+    ContiguousDescendants, DecompressedTreeStore, DecompressedWithParent, POBorrowSlice, PostOrder,
+    PostOrderIterable, ShallowDecompressedTreeStore,
+};
+use crate::matchers::mapping_store::MonoMappingStore;
+use crate::matchers::{optimal::zs::ZsMatcher, similarity_metrics};
+use hyper_ast::types::{
+    DecompressedSubtree, HyperAST, LabelStore, NodeId, NodeStore, SlicedLabel, Tree, WithHashs,
+};
+
+use super::bottom_up_matcher::BottomUpMatcher;
+
+/// Bottom-up matcher of GumTree's default configuration (ie. `gumtree-hybrid`).
+///
+/// Like the simple bottom-up matcher, containers are matched with an adaptive similarity threshold,
+/// but when one of the matched containers has less than `SIZE_THRESHOLD` descendants,
+/// their unmatched descendants are recovered with an optimal ZS matching instead of the lcs/histogram heuristics.
+/// Already matched containers are also given a last chance if they still have unmatched children.
+pub struct HybridBottomUpMatcher<
+    'a,
+    Dsrc,
+    Ddst,
+    T: 'a + Tree + WithHashs,
+    S,
+    LS: LabelStore<SlicedLabel, I = T::Label>,
+    M: MonoMappingStore,
+    const SIZE_THRESHOLD: usize = 20,
+> {
+    label_store: &'a LS,
+    internal: BottomUpMatcher<'a, Dsrc, Ddst, T, S, M>,
+}
+
+impl<
+        'a,
+        Dsrc: 'a
+            + DecompressedTreeStore<'a, T, M::Src>
+            + DecompressedWithParent<'a, T, M::Src>
+            + PostOrder<'a, T, M::Src>,
+        Ddst: 'a
+            + DecompressedTreeStore<'a, T, M::Dst>
+            + DecompressedWithParent<'a, T, M::Dst>
+            + PostOrder<'a, T, M::Dst>,
+        T: Tree + WithHashs,
+        S,
+        LS: LabelStore<SlicedLabel, I = T::Label>,
+        M: MonoMappingStore,
+        const SIZE_THRESHOLD: usize,
+    > Into<BottomUpMatcher<'a, Dsrc, Ddst, T, S, M>>
+    for HybridBottomUpMatcher<'a, Dsrc, Ddst, T, S, LS, M, SIZE_THRESHOLD>
+{
+    fn into(self) -> BottomUpMatcher<'a, Dsrc, Ddst, T, S, M> {
+        self.internal
+    }
+}
+
+impl<
+        'a,
+        Dsrc: 'a
+            + DecompressedTreeStore<'a, T, M::Src>
+            + DecompressedWithParent<'a, T, M::Src>
+            + PostOrder<'a, T, M::Src>
+            + PostOrderIterable<'a, T, M::Src>
+            + DecompressedSubtree<'a, T, Out = Dsrc>
+            + ContiguousDescendants<'a, T, M::Src>
+            + POBorrowSlice<'a, T, M::Src>,
+        Ddst: 'a
+            + DecompressedTreeStore<'a, T, M::Dst>
+            + DecompressedWithParent<'a, T, M::Dst>
+            + PostOrder<'a, T, M::Dst>
+            + PostOrderIterable<'a, T, M::Dst>
+            + DecompressedSubtree<'a, T, Out = Ddst>
+            + ContiguousDescendants<'a, T, M::Dst>
+            + POBorrowSlice<'a, T, M::Dst>,
+        T: 'a + Tree + WithHashs,
+        S: 'a + NodeStore<T::TreeId, R<'a> = T>,
+        LS: 'a + LabelStore<SlicedLabel, I = T::Label>,
+        M: MonoMappingStore + Default,
+        const SIZE_THRESHOLD: usize,
+    > HybridBottomUpMatcher<'a, Dsrc, Ddst, T, S, LS, M, SIZE_THRESHOLD>
+where
+    T::TreeId: 'a + Clone + Debug + NodeId<IdN = T::TreeId>,
+    T::Type: Debug + Eq + Copy + Hash + Send + Sync,
+    M::Src: 'a + PrimInt + std::ops::SubAssign + Debug,
+    M::Dst: 'a + PrimInt + std::ops::SubAssign + Debug,
+{
+    pub fn new(
+        node_store: &'a S,
+        label_store: &'a LS,
+        src_arena: Dsrc,
+        dst_arena: Ddst,
+        mappings: M,
+    ) -> Self {
+        Self {
+            label_store,
+            internal: BottomUpMatcher {
+                node_store,
+                src_arena,
+                dst_arena,
+                mappings,
+                _phantom: PhantomData,
+            },
+        }
+    }
+
+    pub fn match_it<HAST>(
+        mapping: crate::matchers::Mapper<'a, HAST, Dsrc, Ddst, M>,
+    ) -> crate::matchers::Mapper<'a, HAST, Dsrc, Ddst, M>
+    where
+        HAST: HyperAST<'a, NS = S, LS = LS>,
+    {
+        let mut matcher = Self {
+            internal: BottomUpMatcher {
+                node_store: mapping.hyperast.node_store(),
+                src_arena: mapping.mapping.src_arena,
+                dst_arena: mapping.mapping.dst_arena,
+                mappings: mapping.mapping.mappings,
+                _phantom: PhantomData,
+            },
+            label_store: mapping.hyperast.label_store(),
+        };
+        matcher.internal.mappings.topit(
+            matcher.internal.src_arena.len(),
+            matcher.internal.dst_arena.len(),
+        );
+        Self::execute(&mut matcher);
+        crate::matchers::Mapper {
+            hyperast: mapping.hyperast,
+            mapping: crate::matchers::Mapping {
+                src_arena: matcher.internal.src_arena,
+                dst_arena: matcher.internal.dst_arena,
+                mappings: matcher.internal.mappings,
+            },
+        }
+    }
+
+    pub fn matchh(
+        compressed_node_store: &'a S,
+        label_store: &'a LS,
+        src: &'a T::TreeId,
+        dst: &'a T::TreeId,
+        mappings: M,
+    ) -> Self {
+        let mut matcher = Self::new(
+            compressed_node_store,
+            label_store,
+            Dsrc::decompress(compressed_node_store, src),
+            Ddst::decompress(compressed_node_store, dst),
+            mappings,
+        );
+        matcher.internal.mappings.topit(
+            matcher.internal.src_arena.len(),
+            matcher.internal.dst_arena.len(),
+        );
+        Self::execute(&mut matcher);
+        matcher
+    }
+
+    pub fn execute(&mut self) {
+        assert_eq!(
+            // TODO move it inside the arena ...
+            self.internal.src_arena.root(),
+            cast::<_, M::Src>(self.internal.src_arena.len()).unwrap() - one()
+        );
+        assert!(self.internal.src_arena.len() > 0);
+        for a in self.internal.src_arena.iter_df_post::<true>() {
+            if self.internal.src_arena.parent(&a).is_none() {
+                // the root is handled after the loop
+                break;
+            }
+            if !(self.internal.mappings.is_src(&a) || !self.src_has_children(a)) {
+                let candidates = self.internal.get_dst_candidates(&a);
+                let src_range = self.internal.src_arena.descendants_range(&a);
+                let src_size = (src_range.end - src_range.start).to_f64().unwrap();
+                let mut best = None;
+                let mut max: f64 = -1.;
+                for cand in candidates {
+                    let dst_range = self.internal.dst_arena.descendants_range(&cand);
+                    let dst_size = (dst_range.end - dst_range.start).to_f64().unwrap();
+                    let threshold = 1.0 / (1.0 + (dst_size + src_size).ln());
+                    let sim = similarity_metrics::SimilarityMeasure::range(
+                        &src_range,
+                        &dst_range,
+                        &self.internal.mappings,
+                    )
+                    .chawathe();
+                    if sim > max && sim >= threshold {
+                        max = sim;
+                        best = Some(cand);
+                    }
+                }
+
+                if let Some(best) = best {
+                    self.last_chance_match_hybrid(a, best);
+                    self.internal.mappings.link(a, best);
+                }
+            } else if self.internal.mappings.is_src(&a) {
+                let b = self.internal.mappings.get_dst_unchecked(&a);
+                if self.has_unmapped_src_children(a) && self.has_unmapped_dst_children(b) {
+                    self.last_chance_match_hybrid(a, b);
+                }
+            }
+        }
+        // for root
+        self.internal.mappings.link(
+            self.internal.src_arena.root(),
+            self.internal.dst_arena.root(),
+        );
+        self.last_chance_match_hybrid(
+            self.internal.src_arena.root(),
+            self.internal.dst_arena.root(),
+        );
+    }
+
+    fn src_has_children(&self, src: M::Src) -> bool {
+        self.internal
+            .node_store
+            .resolve(&self.internal.src_arena.original(&src))
+            .has_children()
+    }
+
+    fn has_unmapped_src_children(&self, src: M::Src) -> bool {
+        self.internal
+            .src_arena
+            .children(self.internal.node_store, &src)
+            .iter()
+            .any(|x| !self.internal.mappings.is_src(x))
+    }
+
+    fn has_unmapped_dst_children(&self, dst: M::Dst) -> bool {
+        self.internal
+            .dst_arena
+            .children(self.internal.node_store, &dst)
+            .iter()
+            .any(|x| !self.internal.mappings.is_dst(x))
+    }
+
+    /// Optimal recovery on small containers, otherwise the heuristics of the simple bottom-up matcher.
+    fn last_chance_match_hybrid(&mut self, src: M::Src, dst: M::Dst) {
+        let src_s = self
+            .internal
+            .src_arena
+            .descendants_count(self.internal.node_store, &src);
+        let dst_s = self
+            .internal
+            .dst_arena
+            .descendants_count(self.internal.node_store, &dst);
+        if src_s < SIZE_THRESHOLD || dst_s < SIZE_THRESHOLD {
+            self.last_chance_match_zs(src, dst);
+        } else {
+            self.internal.last_chance_match_histogram(&src, &dst);
+        }
+    }
+
+    pub(crate) fn last_chance_match_zs(&mut self, src: M::Src, dst: M::Dst) {
+        let node_store = self.internal.node_store;
+        let label_store = self.label_store;
+        let src_arena = self.internal.src_arena.slice_po(&src);
+        let src_offset = src - src_arena.root();
+        let dst_arena = self.internal.dst_arena.slice_po(&dst);
+        let mappings: M = ZsMatcher::match_with(node_store, label_store, src_arena, dst_arena);
+        let dst_offset = self.internal.dst_arena.first_descendant(&dst);
+        for (i, t) in mappings.iter() {
+            //remapping
+            let src: M::Src = src_offset + cast(i).unwrap();
+            let dst: M::Dst = dst_offset + cast(t).unwrap();
+            if !self.internal.mappings.is_src(&src) && !self.internal.mappings.is_dst(&dst) {
+                let tsrc = node_store
+                    .resolve(&self.internal.src_arena.original(&src))
+                    .get_type();
+                let tdst = node_store
+                    .resolve(&self.internal.dst_arena.original(&dst))
+                    .get_type();
+                if tsrc == tdst {
+                    self.internal.mappings.link(src, dst);
+                }
+            }
+        }
+    }
+}
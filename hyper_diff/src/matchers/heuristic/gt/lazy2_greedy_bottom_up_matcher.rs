@@ -10,6 +10,9 @@ use crate::decompressed_tree_store::{
     LazyDecompressedTreeStore, LazyPOBorrowSlice, PostOrder, PostOrderIterable, PostOrderKeyRoots,
     Shallow, ShallowDecompressedTreeStore,
 };
+use crate::matchers::heuristic::gt::{
+    DEFAULT_SIM_THRESHOLD_DEN, DEFAULT_SIM_THRESHOLD_NUM, DEFAULT_SIZE_THRESHOLD,
+};
 use crate::matchers::mapping_store::MonoMappingStore;
 use crate::matchers::Mapper;
 use crate::matchers::{optimal::zs::ZsMatcher, similarity_metrics};
@@ -30,9 +33,9 @@ pub struct GreedyBottomUpMatcher<
     HAST: HyperAST<'a>,
     M: MonoMappingStore,
     MZs: MonoMappingStore = M,
-    const SIZE_THRESHOLD: usize = 1000,
-    const SIM_THRESHOLD_NUM: u64 = 1,
-    const SIM_THRESHOLD_DEN: u64 = 2,
+    const SIZE_THRESHOLD: usize = DEFAULT_SIZE_THRESHOLD,
+    const SIM_THRESHOLD_NUM: u64 = DEFAULT_SIM_THRESHOLD_NUM,
+    const SIM_THRESHOLD_DEN: u64 = DEFAULT_SIM_THRESHOLD_DEN,
 > {
     label_store: &'a HAST::LS,
     internal: Mapper<'a, HAST, Dsrc, Ddst, M>,
@@ -184,6 +187,30 @@ where
         matcher.internal
     }
 
+    /// Same as [`Self::match_it`] but with the thresholds given at runtime
+    pub fn match_it_with(
+        mapping: crate::matchers::Mapper<'a, HAST, Dsrc, Ddst, M>,
+        size_threshold: usize,
+        sim_threshold: f64,
+    ) -> crate::matchers::Mapper<'a, HAST, Dsrc, Ddst, M> {
+        let mut matcher = Self {
+            label_store: mapping.hyperast.label_store(),
+            internal: mapping,
+            _phantom: PhantomData,
+        };
+        matcher.internal.mapping.mappings.topit(
+            matcher.internal.mapping.src_arena.len(),
+            matcher.internal.mapping.dst_arena.len(),
+        );
+        Self::execute_with(
+            &mut matcher.internal,
+            &matcher.label_store,
+            size_threshold,
+            sim_threshold,
+        );
+        matcher.internal
+    }
+
     pub fn execute<'b>(internal: &mut Mapper<'a, HAST, Dsrc, Ddst, M>, label_store: &'a HAST::LS) {
         assert_eq!(
             // TODO move it inside the arena ...
@@ -240,6 +267,74 @@ where
         // println!("nodes:{}", c2);
     }
 
+    /// Same as [`Self::execute`] but with the thresholds given at runtime
+    pub fn execute_with(
+        internal: &mut Mapper<'a, HAST, Dsrc, Ddst, M>,
+        label_store: &'a HAST::LS,
+        size_threshold: usize,
+        sim_threshold: f64,
+    ) {
+        assert_eq!(
+            // TODO move it inside the arena ...
+            internal.src_arena.root(),
+            cast::<_, M::Src>(internal.src_arena.len()).unwrap() - one()
+        );
+        assert!(internal.src_arena.len() > 0);
+        // println!("mappings={}", internal.mappings.len());
+        // // WARN it is in postorder and it depends on decomp store
+        // // -1 as root is handled after forloop
+        for a in internal.src_arena.iter_df_post::<false>() {
+            // if internal.src_arena.parent(&a).is_none() {
+            //     break;
+            // }
+            if internal.mappings.is_src(&a) {
+                continue;
+            }
+            let a = internal
+                .mapping
+                .src_arena
+                .decompress_to(internal.hyperast.node_store(), &a);
+            if Self::src_has_children(internal, a) {
+                let candidates = internal.get_dst_candidates_lazily(&a);
+                let mut best = None;
+                let mut max: f64 = -1.;
+                for cand in candidates {
+                    let sim = similarity_metrics::SimilarityMeasure::range(
+                        &internal.src_arena.descendants_range(&a),
+                        &internal.dst_arena.descendants_range(&cand),
+                        &internal.mappings,
+                    )
+                    .dice();
+                    if sim > max && sim >= sim_threshold {
+                        max = sim;
+                        best = Some(cand);
+                    }
+                }
+
+                if let Some(best) = best {
+                    Self::last_chance_match_zs_with(
+                        internal,
+                        &label_store,
+                        a,
+                        best,
+                        size_threshold,
+                    );
+                    internal.mappings.link(*a.shallow(), *best.shallow());
+                }
+            }
+        }
+        // for root
+        internal.mapping.mappings.link(
+            internal.mapping.src_arena.root(),
+            internal.mapping.dst_arena.root(),
+        );
+        let src = internal.src_arena.starter();
+        let dst = internal.dst_arena.starter();
+        Self::last_chance_match_zs_with(internal, label_store, src, dst, size_threshold);
+        // println!("nodes:{}", c);
+        // println!("nodes:{}", c2);
+    }
+
     fn src_has_children(internal: &Mapper<'a, HAST, Dsrc, Ddst, M>, src: Dsrc::IdD) -> bool {
         let o = internal.src_arena.original(&src);
         let r = internal.hyperast.node_store().resolve(&o).has_children();
@@ -260,6 +355,16 @@ where
         label_store: &'a HAST::LS,
         src: Dsrc::IdD,
         dst: Ddst::IdD,
+    ) {
+        Self::last_chance_match_zs_with(internal, label_store, src, dst, SIZE_THRESHOLD)
+    }
+
+    pub(crate) fn last_chance_match_zs_with(
+        internal: &mut Mapper<'a, HAST, Dsrc, Ddst, M>,
+        label_store: &'a HAST::LS,
+        src: Dsrc::IdD,
+        dst: Ddst::IdD,
+        size_threshold: usize,
     ) {
         // allow using another internal mapping store
         // WIP https://blog.rust-lang.org/2022/10/28/gats-stabilization.html#implied-static-requirement-from-higher-ranked-trait-bounds
@@ -269,7 +374,7 @@ where
         let dst_arena = &mut mapping.dst_arena;
         let src_s = src_arena.descendants_count(node_store, &src);
         let dst_s = dst_arena.descendants_count(node_store, &dst);
-        if !(src_s < cast(SIZE_THRESHOLD).unwrap() || dst_s < cast(SIZE_THRESHOLD).unwrap()) {
+        if !(src_s < cast(size_threshold).unwrap() || dst_s < cast(size_threshold).unwrap()) {
             // dbg!(src_s, dst_s);
             return;
         }
@@ -10,6 +10,9 @@ use crate::decompressed_tree_store::{
     LazyDecompressedTreeStore, LazyPOBorrowSlice, PostOrder, PostOrderIterable, PostOrderKeyRoots,
     Shallow, ShallowDecompressedTreeStore,
 };
+use crate::matchers::heuristic::gt::{
+    DEFAULT_SIM_THRESHOLD_DEN, DEFAULT_SIM_THRESHOLD_NUM, DEFAULT_SIZE_THRESHOLD,
+};
 use crate::matchers::mapping_store::MonoMappingStore;
 use crate::matchers::{optimal::zs::ZsMatcher, similarity_metrics};
 use hyper_ast::types::{
@@ -33,9 +36,9 @@ pub struct GreedyBottomUpMatcher<
     LS: LabelStore<SlicedLabel, I = T::Label>,
     M: MonoMappingStore,
     MZs: MonoMappingStore = M,
-    const SIZE_THRESHOLD: usize = 1000,
-    const SIM_THRESHOLD_NUM: u64 = 1,
-    const SIM_THRESHOLD_DEN: u64 = 2,
+    const SIZE_THRESHOLD: usize = DEFAULT_SIZE_THRESHOLD,
+    const SIM_THRESHOLD_NUM: u64 = DEFAULT_SIM_THRESHOLD_NUM,
+    const SIM_THRESHOLD_DEN: u64 = DEFAULT_SIM_THRESHOLD_DEN,
 > {
     label_store: &'a LS,
     internal: BottomUpMatcher<'a, Dsrc, Ddst, T, S, M>,
@@ -232,6 +235,42 @@ where
         }
     }
 
+    /// Same as [`Self::match_it`] but with the thresholds given at runtime
+    pub fn match_it_with<HAST>(
+        mapping: crate::matchers::Mapper<'a, HAST, Dsrc, Ddst, M>,
+        size_threshold: usize,
+        sim_threshold: f64,
+    ) -> crate::matchers::Mapper<'a, HAST, Dsrc, Ddst, M>
+    where
+        HAST: HyperAST<'a, NS = S, LS = LS>,
+        M: Default,
+    {
+        let mut matcher = Self {
+            internal: BottomUpMatcher {
+                node_store: mapping.hyperast.node_store(),
+                src_arena: mapping.mapping.src_arena,
+                dst_arena: mapping.mapping.dst_arena,
+                mappings: mapping.mapping.mappings,
+                _phantom: PhantomData,
+            },
+            label_store: mapping.hyperast.label_store(),
+            _phantom: PhantomData,
+        };
+        matcher.internal.mappings.topit(
+            matcher.internal.src_arena.len(),
+            matcher.internal.dst_arena.len(),
+        );
+        Self::execute_with(&mut matcher, size_threshold, sim_threshold);
+        crate::matchers::Mapper {
+            hyperast: mapping.hyperast,
+            mapping: crate::matchers::Mapping {
+                src_arena: matcher.internal.src_arena,
+                dst_arena: matcher.internal.dst_arena,
+                mappings: matcher.internal.mappings,
+            },
+        }
+    }
+
     pub fn execute<'b>(&mut self)
     where
         M: Default,
@@ -292,6 +331,68 @@ where
         // println!("nodes:{}", c2);
     }
 
+    /// Same as [`Self::execute`] but with the thresholds given at runtime
+    pub fn execute_with(&mut self, size_threshold: usize, sim_threshold: f64)
+    where
+        M: Default,
+    {
+        assert_eq!(
+            // TODO move it inside the arena ...
+            self.internal.src_arena.root(),
+            cast::<_, M::Src>(self.internal.src_arena.len()).unwrap() - one()
+        );
+        assert!(self.internal.src_arena.len() > 0);
+        // println!("mappings={}", self.internal.mappings.len());
+        // // WARN it is in postorder and it depends on decomp store
+        // // -1 as root is handled after forloop
+        for a in self.internal.src_arena.iter_df_post::<false>() {
+            // if self.internal.src_arena.parent(&a).is_none() {
+            //     break;
+            // }
+            if self.internal.mappings.is_src(&a) {
+                continue;
+            }
+            let a = self
+                .internal
+                .src_arena
+                .decompress_to(self.internal.node_store, &a);
+            if self.src_has_children(a) {
+                let candidates = self.internal.get_dst_candidates(&a);
+                let mut best = None;
+                let mut max: f64 = -1.;
+                for cand in candidates {
+                    let sim = similarity_metrics::SimilarityMeasure::range(
+                        &self.internal.src_arena.descendants_range(&a),
+                        &self.internal.dst_arena.descendants_range(&cand),
+                        &self.internal.mappings,
+                    )
+                    .dice();
+                    if sim > max && sim >= sim_threshold {
+                        max = sim;
+                        best = Some(cand);
+                    }
+                }
+
+                if let Some(best) = best {
+                    self.last_chance_match_zs_with(a, best, size_threshold);
+                    self.internal.mappings.link(*a.shallow(), *best.shallow());
+                }
+            }
+        }
+        // for root
+        self.internal.mappings.link(
+            self.internal.src_arena.root(),
+            self.internal.dst_arena.root(),
+        );
+        self.last_chance_match_zs_with(
+            self.internal.src_arena.starter(),
+            self.internal.dst_arena.starter(),
+            size_threshold,
+        );
+        // println!("nodes:{}", c);
+        // println!("nodes:{}", c2);
+    }
+
     fn src_has_children(&mut self, src: Dsrc::IdD) -> bool {
         let o = self.internal.src_arena.original(&src);
         let r = self.internal.node_store.resolve(&o).has_children();
@@ -309,6 +410,17 @@ where
     pub(crate) fn last_chance_match_zs(&mut self, src: Dsrc::IdD, dst: Ddst::IdD)
     where
         M: Default,
+    {
+        self.last_chance_match_zs_with(src, dst, SIZE_THRESHOLD)
+    }
+
+    pub(crate) fn last_chance_match_zs_with(
+        &mut self,
+        src: Dsrc::IdD,
+        dst: Ddst::IdD,
+        size_threshold: usize,
+    ) where
+        M: Default,
     {
         // WIP https://blog.rust-lang.org/2022/10/28/gats-stabilization.html#implied-static-requirement-from-higher-ranked-trait-bounds
         let src_s = self
@@ -319,7 +431,7 @@ where
             .internal
             .dst_arena
             .descendants_count(self.internal.node_store, &dst);
-        if !(src_s < cast(SIZE_THRESHOLD).unwrap() || dst_s < cast(SIZE_THRESHOLD).unwrap()) {
+        if !(src_s < cast(size_threshold).unwrap() || dst_s < cast(size_threshold).unwrap()) {
             return;
         }
         let node_store = self.internal.node_store;
@@ -4,6 +4,7 @@ use std::{fmt::Debug, marker::PhantomData};
 use crate::decompressed_tree_store::{
     ContiguousDescendants, DecompressedWithParent, LazyDecompressedTreeStore, Shallow,
 };
+use crate::matchers::heuristic::gt::DEFAULT_MIN_HEIGHT;
 use crate::matchers::mapping_store::MonoMappingStore;
 use crate::matchers::{mapping_store::MultiMappingStore, similarity_metrics};
 use crate::utils::sequence_algorithms::longest_common_subsequence;
@@ -15,7 +16,15 @@ use hyper_ast::types::{
 use logging_timer::time;
 use num_traits::{PrimInt, ToPrimitive};
 
-pub struct LazyGreedySubtreeMatcher<'a, Dsrc, Ddst, T, S, M, const MIN_HEIGHT: usize = 1> {
+pub struct LazyGreedySubtreeMatcher<
+    'a,
+    Dsrc,
+    Ddst,
+    T,
+    S,
+    M,
+    const MIN_HEIGHT: usize = DEFAULT_MIN_HEIGHT,
+> {
     internal: SubtreeMatcher<'a, Dsrc, Ddst, T, S, M, MIN_HEIGHT>,
 }
 
@@ -3,6 +3,8 @@ use hyper_ast::types::{IterableChildren, NodeId, NodeStore, WithChildren};
 pub mod bottom_up_matcher;
 pub mod greedy_bottom_up_matcher;
 pub mod greedy_subtree_matcher;
+pub mod hybrid_bottom_up_matcher;
+pub mod par_greedy_bottom_up_matcher;
 pub mod simple_bottom_up_matcher;
 
 // lazy versions, that do not decompress directly subtrees
@@ -13,6 +15,15 @@ pub mod lazy_greedy_bottom_up_matcher;
 pub mod lazy_greedy_subtree_matcher;
 // pub mod simple_bottom_up_matcher2;
 
+/// Default minimal height of the subtrees matched by the subtree matchers
+pub const DEFAULT_MIN_HEIGHT: usize = 1;
+/// Default size under which the bottom-up matchers recover mappings with an optimal algorithm
+pub const DEFAULT_SIZE_THRESHOLD: usize = 1000;
+/// Numerator of the default minimal similarity of containers matched by the bottom-up matchers
+pub const DEFAULT_SIM_THRESHOLD_NUM: u64 = 1;
+/// Denominator of the default minimal similarity of containers matched by the bottom-up matchers
+pub const DEFAULT_SIM_THRESHOLD_DEN: u64 = 2;
+
 pub fn size<'a, IdC: Clone + NodeId<IdN = IdC>, S>(store: &'a S, x: &IdC) -> usize
 where
     S: 'a + NodeStore<IdC>,
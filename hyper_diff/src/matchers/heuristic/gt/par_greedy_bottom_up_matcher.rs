@@ -0,0 +1,607 @@
+//! Parallel version of the [`GreedyBottomUpMatcher`](super::greedy_bottom_up_matcher::GreedyBottomUpMatcher).
+//!
+//! The source is split in parts, the largest subtrees under a given size,
+//! ie. files or modules when diffing whole repositories.
+//! Given the top-down mappings, parts are matched in parallel on top of the shared mappings,
+//! then they are merged in post-order with the containers that are in none of them.
+//! A part that looked at a destination node mapped meanwhile is matched again,
+//! such that the mappings are the same as the ones of the sequential matcher.
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    hash::Hash,
+    marker::PhantomData,
+};
+
+use num_traits::{cast, one, zero, PrimInt, ToPrimitive};
+use specs::prelude::ParallelIterator;
+use specs::rayon::prelude::IntoParallelIterator;
+
+use crate::decompressed_tree_store::{
+    ContiguousDescendants, DecompressedTreeStore, DecompressedWithParent, POBorrowSlice, PostOrder,
+    ShallowDecompressedTreeStore,
+};
+use crate::matchers::heuristic::gt::{
+    DEFAULT_SIM_THRESHOLD_DEN, DEFAULT_SIM_THRESHOLD_NUM, DEFAULT_SIZE_THRESHOLD,
+};
+use crate::matchers::mapping_store::MonoMappingStore;
+use crate::matchers::optimal::zs::ZsMatcher;
+use hyper_ast::types::{HyperAST, LabelStore, NodeId, NodeStore, SlicedLabel, Tree, WithHashs};
+
+use super::bottom_up_matcher::BottomUpMatcher;
+
+/// parts made by thread, smaller parts balance better the work between threads
+const PARTS_PER_THREAD: usize = 4;
+
+pub struct ParGreedyBottomUpMatcher<
+    'a,
+    Dsrc,
+    Ddst,
+    T: 'a + Tree + WithHashs,
+    S,
+    LS: LabelStore<SlicedLabel, I = T::Label>,
+    M: MonoMappingStore,
+    const SIZE_THRESHOLD: usize = DEFAULT_SIZE_THRESHOLD,
+    const SIM_THRESHOLD_NUM: u64 = DEFAULT_SIM_THRESHOLD_NUM,
+    const SIM_THRESHOLD_DEN: u64 = DEFAULT_SIM_THRESHOLD_DEN,
+> {
+    label_store: &'a LS,
+    internal: BottomUpMatcher<'a, Dsrc, Ddst, T, S, M>,
+}
+
+impl<
+        'a,
+        Dsrc,
+        Ddst,
+        T: Tree + WithHashs,
+        S,
+        LS: LabelStore<SlicedLabel, I = T::Label>,
+        M: MonoMappingStore,
+        const SIZE_THRESHOLD: usize,
+        const SIM_THRESHOLD_NUM: u64,
+        const SIM_THRESHOLD_DEN: u64,
+    > Into<BottomUpMatcher<'a, Dsrc, Ddst, T, S, M>>
+    for ParGreedyBottomUpMatcher<
+        'a,
+        Dsrc,
+        Ddst,
+        T,
+        S,
+        LS,
+        M,
+        SIZE_THRESHOLD,
+        SIM_THRESHOLD_NUM,
+        SIM_THRESHOLD_DEN,
+    >
+{
+    fn into(self) -> BottomUpMatcher<'a, Dsrc, Ddst, T, S, M> {
+        self.internal
+    }
+}
+
+impl<
+        'a,
+        Dsrc: 'a
+            + DecompressedTreeStore<'a, T, M::Src>
+            + DecompressedWithParent<'a, T, M::Src>
+            + PostOrder<'a, T, M::Src>
+            + ContiguousDescendants<'a, T, M::Src>
+            + POBorrowSlice<'a, T, M::Src>
+            + Sync,
+        Ddst: 'a
+            + DecompressedTreeStore<'a, T, M::Dst>
+            + DecompressedWithParent<'a, T, M::Dst>
+            + PostOrder<'a, T, M::Dst>
+            + ContiguousDescendants<'a, T, M::Dst>
+            + POBorrowSlice<'a, T, M::Dst>
+            + Sync,
+        T: 'a + Tree + WithHashs,
+        S: 'a + NodeStore<T::TreeId, R<'a> = T> + Sync,
+        LS: 'a + LabelStore<SlicedLabel, I = T::Label> + Sync,
+        M: MonoMappingStore + Default + Sync,
+        const SIZE_THRESHOLD: usize,
+        const SIM_THRESHOLD_NUM: u64,
+        const SIM_THRESHOLD_DEN: u64,
+    >
+    ParGreedyBottomUpMatcher<
+        'a,
+        Dsrc,
+        Ddst,
+        T,
+        S,
+        LS,
+        M,
+        SIZE_THRESHOLD,
+        SIM_THRESHOLD_NUM,
+        SIM_THRESHOLD_DEN,
+    >
+where
+    T::TreeId: 'a + Clone + Debug + NodeId<IdN = T::TreeId>,
+    T::Type: Debug + Eq + Copy + Send + Sync,
+    M::Src: 'a + PrimInt + std::ops::SubAssign + Debug + Hash + Send + Sync,
+    M::Dst: 'a + PrimInt + std::ops::SubAssign + Debug + Hash + Send + Sync,
+{
+    pub fn new(
+        node_store: &'a S,
+        label_store: &'a LS,
+        src_arena: Dsrc,
+        dst_arena: Ddst,
+        mappings: M,
+    ) -> Self {
+        Self {
+            label_store,
+            internal: BottomUpMatcher {
+                node_store,
+                src_arena,
+                dst_arena,
+                mappings,
+                _phantom: PhantomData,
+            },
+        }
+    }
+
+    pub fn match_it<HAST>(
+        mapping: crate::matchers::Mapper<'a, HAST, Dsrc, Ddst, M>,
+    ) -> crate::matchers::Mapper<'a, HAST, Dsrc, Ddst, M>
+    where
+        HAST: HyperAST<'a, NS = S, LS = LS>,
+    {
+        Self::match_it_with(
+            mapping,
+            SIZE_THRESHOLD,
+            SIM_THRESHOLD_NUM as f64 / SIM_THRESHOLD_DEN as f64,
+        )
+    }
+
+    /// Same as [`Self::match_it`] but with the thresholds given at runtime
+    pub fn match_it_with<HAST>(
+        mapping: crate::matchers::Mapper<'a, HAST, Dsrc, Ddst, M>,
+        size_threshold: usize,
+        sim_threshold: f64,
+    ) -> crate::matchers::Mapper<'a, HAST, Dsrc, Ddst, M>
+    where
+        HAST: HyperAST<'a, NS = S, LS = LS>,
+    {
+        let mut matcher = Self::new(
+            mapping.hyperast.node_store(),
+            mapping.hyperast.label_store(),
+            mapping.mapping.src_arena,
+            mapping.mapping.dst_arena,
+            mapping.mapping.mappings,
+        );
+        matcher.internal.mappings.topit(
+            matcher.internal.src_arena.len(),
+            matcher.internal.dst_arena.len(),
+        );
+        Self::execute_with(&mut matcher, size_threshold, sim_threshold);
+        crate::matchers::Mapper {
+            hyperast: mapping.hyperast,
+            mapping: crate::matchers::Mapping {
+                src_arena: matcher.internal.src_arena,
+                dst_arena: matcher.internal.dst_arena,
+                mappings: matcher.internal.mappings,
+            },
+        }
+    }
+
+    pub fn execute(&mut self) {
+        self.execute_with(
+            SIZE_THRESHOLD,
+            SIM_THRESHOLD_NUM as f64 / SIM_THRESHOLD_DEN as f64,
+        )
+    }
+
+    pub fn execute_with(&mut self, size_threshold: usize, sim_threshold: f64) {
+        let threads = specs::rayon::current_num_threads();
+        let part_size = self.internal.src_arena.len() / (PARTS_PER_THREAD * threads);
+        self.execute_with_parts(size_threshold, sim_threshold, part_size)
+    }
+
+    /// Same as [`Self::execute_with`] but parts are the largest subtrees of at most `part_size` nodes
+    pub fn execute_with_parts(
+        &mut self,
+        size_threshold: usize,
+        sim_threshold: f64,
+        part_size: usize,
+    ) {
+        let ctx = Ctx::<T, _, _, _, _, M> {
+            node_store: self.internal.node_store,
+            label_store: self.label_store,
+            src_arena: &self.internal.src_arena,
+            dst_arena: &self.internal.dst_arena,
+            size_threshold,
+            sim_threshold,
+            _phantom: PhantomData,
+        };
+        let root = ctx.src_arena.root();
+        assert_eq!(
+            root,
+            cast::<_, M::Src>(ctx.src_arena.len()).unwrap() - one()
+        );
+
+        let base = &self.internal.mappings;
+        let speculated: Vec<_> = ctx
+            .parts(part_size)
+            .into_par_iter()
+            .map(|p| {
+                let mut overlay = Overlay::new(base);
+                ctx.match_part(&mut overlay, p);
+                (p, overlay.links, overlay.read)
+            })
+            .collect();
+
+        let mappings = &mut self.internal.mappings;
+        // destination nodes mapped since the speculation of parts
+        let mut written = bitvec::bitbox![0; ctx.dst_arena.len()];
+        let mut speculated = speculated.into_iter().peekable();
+        let mut a: M::Src = zero();
+        while a < root {
+            let next_part = speculated.peek().map(|(p, _, _)| *p);
+            let links = match next_part {
+                Some(p) if ctx.src_arena.first_descendant(&p) == a => {
+                    let (p, links, read) = speculated.next().unwrap();
+                    a = p + one();
+                    if read.iter().any(|d| written[d.to_usize().unwrap()]) {
+                        let mut overlay = Overlay::new(&*mappings);
+                        ctx.match_part(&mut overlay, p);
+                        overlay.links
+                    } else {
+                        links
+                    }
+                }
+                _ => {
+                    let mut overlay = Overlay::new(&*mappings);
+                    ctx.match_container(&mut overlay, a);
+                    a = a + one();
+                    overlay.links
+                }
+            };
+            for (src, dst) in links {
+                mappings.link(src, dst);
+                written.set(dst.to_usize().unwrap(), true);
+            }
+        }
+        // for root
+        let dst_root = ctx.dst_arena.root();
+        let mut overlay = Overlay::new(&*mappings);
+        overlay.link(root, dst_root);
+        ctx.last_chance_match_zs(&mut overlay, root, dst_root);
+        for (src, dst) in overlay.links {
+            mappings.link(src, dst);
+        }
+    }
+}
+
+/// Mappings made on top of `base`, keeping track of the destination nodes looked at.
+struct Overlay<'m, M: MonoMappingStore> {
+    base: &'m M,
+    src_to_dst: HashMap<M::Src, M::Dst>,
+    dsts: HashSet<M::Dst>,
+    /// in the order they were made
+    links: Vec<(M::Src, M::Dst)>,
+    read: HashSet<M::Dst>,
+}
+
+impl<'m, M: MonoMappingStore> Overlay<'m, M>
+where
+    M::Src: Copy + Eq + Hash,
+    M::Dst: Copy + Eq + Hash,
+{
+    fn new(base: &'m M) -> Self {
+        Self {
+            base,
+            src_to_dst: HashMap::new(),
+            dsts: HashSet::new(),
+            links: vec![],
+            read: HashSet::new(),
+        }
+    }
+
+    fn is_src(&self, src: &M::Src) -> bool {
+        self.src_to_dst.contains_key(src) || self.base.is_src(src)
+    }
+
+    fn get_dst(&self, src: &M::Src) -> Option<M::Dst> {
+        match self.src_to_dst.get(src) {
+            Some(dst) => Some(*dst),
+            None => self.base.get_dst(src),
+        }
+    }
+
+    fn is_dst(&mut self, dst: &M::Dst) -> bool {
+        self.read.insert(*dst);
+        self.dsts.contains(dst) || self.base.is_dst(dst)
+    }
+
+    fn link(&mut self, src: M::Src, dst: M::Dst) {
+        self.src_to_dst.insert(src, dst);
+        self.dsts.insert(dst);
+        self.links.push((src, dst));
+    }
+}
+
+/// What is shared by the parts matched in parallel
+struct Ctx<'a, 'b, T, Dsrc, Ddst, S, LS, M> {
+    node_store: &'a S,
+    label_store: &'a LS,
+    src_arena: &'b Dsrc,
+    dst_arena: &'b Ddst,
+    size_threshold: usize,
+    sim_threshold: f64,
+    _phantom: PhantomData<fn() -> (T, M)>,
+}
+
+impl<
+        'a,
+        'b,
+        Dsrc: 'a
+            + DecompressedTreeStore<'a, T, M::Src>
+            + DecompressedWithParent<'a, T, M::Src>
+            + PostOrder<'a, T, M::Src>
+            + ContiguousDescendants<'a, T, M::Src>
+            + POBorrowSlice<'a, T, M::Src>,
+        Ddst: 'a
+            + DecompressedTreeStore<'a, T, M::Dst>
+            + DecompressedWithParent<'a, T, M::Dst>
+            + PostOrder<'a, T, M::Dst>
+            + ContiguousDescendants<'a, T, M::Dst>
+            + POBorrowSlice<'a, T, M::Dst>,
+        T: 'a + Tree + WithHashs,
+        S: 'a + NodeStore<T::TreeId, R<'a> = T>,
+        LS: 'a + LabelStore<SlicedLabel, I = T::Label>,
+        M: MonoMappingStore + Default,
+    > Ctx<'a, 'b, T, Dsrc, Ddst, S, LS, M>
+where
+    T::TreeId: 'a + Clone + Debug + NodeId<IdN = T::TreeId>,
+    T::Type: Debug + Eq + Copy + Send + Sync,
+    M::Src: 'a + PrimInt + std::ops::SubAssign + Debug + Hash,
+    M::Dst: 'a + PrimInt + std::ops::SubAssign + Debug + Hash,
+{
+    /// roots of the largest subtrees of at most `part_size` nodes, in post-order
+    fn parts(&self, part_size: usize) -> Vec<M::Src> {
+        let mut parts = vec![];
+        let mut stack = self
+            .src_arena
+            .children(self.node_store, &self.src_arena.root());
+        while let Some(x) = stack.pop() {
+            let r = self.src_arena.descendants_range(&x);
+            if (r.end - r.start).to_usize().unwrap() < part_size {
+                parts.push(x);
+            } else {
+                stack.extend(self.src_arena.children(self.node_store, &x));
+            }
+        }
+        parts.sort();
+        parts
+    }
+
+    fn match_part(&self, overlay: &mut Overlay<M>, p: M::Src) {
+        let mut a = self.src_arena.first_descendant(&p);
+        while a <= p {
+            self.match_container(overlay, a);
+            a = a + one();
+        }
+    }
+
+    fn match_container(&self, overlay: &mut Overlay<M>, a: M::Src) {
+        if overlay.is_src(&a) || self.src_arena.lld(&a) == a {
+            // mapped or leaf
+            return;
+        }
+        let candidates = self.get_dst_candidates(overlay, &a);
+        let mut best = None;
+        let mut max: f64 = -1.;
+        for cand in candidates {
+            let sim = self.dice(overlay, &a, &cand);
+            if sim > max && sim >= self.sim_threshold {
+                max = sim;
+                best = Some(cand);
+            }
+        }
+        if let Some(best) = best {
+            self.last_chance_match_zs(overlay, a, best);
+            overlay.link(a, best);
+        }
+    }
+
+    fn get_dst_candidates(&self, overlay: &mut Overlay<M>, src: &M::Src) -> Vec<M::Dst> {
+        let mut seeds = vec![];
+        let s = &self.src_arena.original(src);
+        for c in self.src_arena.descendants(self.node_store, src) {
+            if let Some(m) = overlay.get_dst(&c) {
+                seeds.push(m);
+            }
+        }
+        let mut candidates = vec![];
+        let mut visited = bitvec::bitbox![0;self.dst_arena.len()];
+        let t = self.node_store.resolve(s).get_type();
+        for mut seed in seeds {
+            loop {
+                let Some(parent) = self.dst_arena.parent(&seed) else {
+                    break;
+                };
+                if visited[parent.to_usize().unwrap()] {
+                    break;
+                }
+                visited.set(parent.to_usize().unwrap(), true);
+                let p = &self.dst_arena.original(&parent);
+                if self.node_store.resolve(p).get_type() == t
+                    && !(overlay.is_dst(&parent) || parent == self.dst_arena.root())
+                {
+                    candidates.push(parent);
+                }
+                seed = parent;
+            }
+        }
+        candidates
+    }
+
+    /// same as [`similarity_metrics::SimilarityMeasure::range`](crate::matchers::similarity_metrics::SimilarityMeasure::range) then dice
+    fn dice(&self, overlay: &Overlay<M>, src: &M::Src, dst: &M::Dst) -> f64 {
+        let src = self.src_arena.descendants_range(src);
+        let dst = self.dst_arena.descendants_range(dst);
+        let ncd = (src.start.to_usize().unwrap()..src.end.to_usize().unwrap())
+            .filter_map(|t| overlay.get_dst(&cast(t).unwrap()))
+            .filter(|t| dst.contains(t))
+            .count();
+        let src_l = (src.end - src.start).to_usize().unwrap();
+        let dst_l = (dst.end - dst.start).to_usize().unwrap();
+        (2.0_f64 * (ncd as f64)) / (src_l as f64 + dst_l as f64)
+    }
+
+    fn last_chance_match_zs(&self, overlay: &mut Overlay<M>, src: M::Src, dst: M::Dst) {
+        let src_s = self.src_arena.descendants_count(self.node_store, &src);
+        let dst_s = self.dst_arena.descendants_count(self.node_store, &dst);
+        if !(src_s < self.size_threshold || dst_s < self.size_threshold) {
+            return;
+        }
+        let src_arena = self.src_arena.slice_po(&src);
+        let src_offset = src - src_arena.root();
+        let dst_arena = self.dst_arena.slice_po(&dst);
+        let mappings: M =
+            ZsMatcher::match_with(self.node_store, self.label_store, src_arena, dst_arena);
+        let dst_offset = self.dst_arena.first_descendant(&dst);
+        for (i, t) in mappings.iter() {
+            //remapping
+            let src: M::Src = src_offset + cast(i).unwrap();
+            let dst: M::Dst = dst_offset + cast(t).unwrap();
+            if !overlay.is_src(&src) && !overlay.is_dst(&dst) {
+                let tsrc = self
+                    .node_store
+                    .resolve(&self.src_arena.original(&src))
+                    .get_type();
+                let tdst = self
+                    .node_store
+                    .resolve(&self.dst_arena.original(&dst))
+                    .get_type();
+                if tsrc == tdst {
+                    overlay.link(src, dst);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper_ast::types::DecompressedSubtree;
+
+    use super::*;
+    use crate::{
+        decompressed_tree_store::CompletePostOrder,
+        matchers::{
+            heuristic::gt::greedy_bottom_up_matcher::GreedyBottomUpMatcher,
+            mapping_store::{DefaultMappingStore, MappingStore},
+        },
+        tests::examples::example_bottom_up,
+        tree::simple_tree::{tree, vpair_to_stores, SimpleTree, Tree, TreeRef, NS},
+    };
+
+    /// Matches with the given mappings as top-down mappings,
+    /// for every part size the parallel matcher must give the same mappings as the sequential one.
+    fn check_same_as_sequential(
+        example: (SimpleTree<u8>, SimpleTree<u8>),
+        links: &[(&[u8], &[u8])],
+    ) -> DefaultMappingStore<u16> {
+        let (label_store, node_store, src, dst) = vpair_to_stores(example);
+        let init = || {
+            let src_arena = CompletePostOrder::<TreeRef<Tree>, u16>::decompress(&node_store, &src);
+            let dst_arena = CompletePostOrder::<TreeRef<Tree>, u16>::decompress(&node_store, &dst);
+            let mut ms = DefaultMappingStore::default();
+            ms.topit(src_arena.len(), dst_arena.len());
+            let src = &src_arena.root();
+            let dst = &dst_arena.root();
+            for (s, d) in links {
+                ms.link(
+                    src_arena.child(&node_store, src, s),
+                    dst_arena.child(&node_store, dst, d),
+                );
+            }
+            (src_arena, dst_arena, ms)
+        };
+
+        let (src_arena, dst_arena, ms) = init();
+        let mut mapper = GreedyBottomUpMatcher::<_, _, _, NS<Tree>, _, _, 10, 1, 2>::new(
+            &node_store,
+            &label_store,
+            src_arena,
+            dst_arena,
+            ms,
+        );
+        mapper.execute();
+        let BottomUpMatcher {
+            mappings: expected, ..
+        } = mapper.into();
+
+        // from parts reduced to leaves to a single part
+        for part_size in 0..expected.src_to_dst.len() {
+            let (src_arena, dst_arena, ms) = init();
+            let mut mapper = ParGreedyBottomUpMatcher::<_, _, _, NS<Tree>, _, _, 10, 1, 2>::new(
+                &node_store,
+                &label_store,
+                src_arena,
+                dst_arena,
+                ms,
+            );
+            mapper.execute_with_parts(10, 0.5, part_size);
+            let BottomUpMatcher {
+                mappings: actual, ..
+            } = mapper.into();
+            assert_eq!(expected.src_to_dst, actual.src_to_dst, "{}", part_size);
+            assert_eq!(expected.dst_to_src, actual.dst_to_src, "{}", part_size);
+        }
+        expected
+    }
+
+    #[test]
+    fn test_same_as_sequential() {
+        let links: Vec<(&[u8], &[u8])> = (0..4).map(|i| (&[0, 2, i][..], &[0, 2, i][..])).collect();
+        let mappings = check_same_as_sequential(example_bottom_up(), &links);
+        assert_eq!(9, mappings.len());
+    }
+
+    #[test]
+    fn test_several_files_same_as_sequential() {
+        // each file shares leaves with both files of the destination,
+        // thus the parts of the second and third files look at the destination files
+        // mapped by the parts before them and are matched again
+        let src = tree!(
+            0, "r"; [
+                tree!(1, "A"; [
+                    tree!(2, "C"; [tree!(3, "x1"), tree!(3, "x2"), tree!(3, "y1")]),
+                ]),
+                tree!(1, "B"; [
+                    tree!(2, "D"; [tree!(3, "y2"), tree!(3, "y3"), tree!(3, "x3")]),
+                ]),
+                tree!(1, "E"; [
+                    tree!(2, "F"; [tree!(3, "z1"), tree!(3, "z2"), tree!(3, "x4")]),
+                ]),
+        ]);
+        let dst = tree!(
+            0, "r"; [
+                tree!(1, "A"; [
+                    tree!(2, "C"; [tree!(3, "x1"), tree!(3, "x2"), tree!(3, "x3"), tree!(3, "x4")]),
+                ]),
+                tree!(1, "B"; [
+                    tree!(2, "D"; [tree!(3, "y1"), tree!(3, "y2"), tree!(3, "y3")]),
+                ]),
+                tree!(1, "E"; [
+                    tree!(2, "F"; [tree!(3, "z1"), tree!(3, "z2")]),
+                ]),
+        ]);
+        let links: &[(&[u8], &[u8])] = &[
+            (&[0, 0, 0], &[0, 0, 0]),
+            (&[0, 0, 1], &[0, 0, 1]),
+            (&[0, 0, 2], &[1, 0, 0]),
+            (&[1, 0, 0], &[1, 0, 1]),
+            (&[1, 0, 1], &[1, 0, 2]),
+            (&[1, 0, 2], &[0, 0, 2]),
+            (&[2, 0, 0], &[2, 0, 0]),
+            (&[2, 0, 1], &[2, 0, 1]),
+            (&[2, 0, 2], &[0, 0, 3]),
+        ];
+        let mappings = check_same_as_sequential((src, dst), links);
+        // the leaves, the files and their containers, and the root
+        assert_eq!(16, mappings.len());
+    }
+}
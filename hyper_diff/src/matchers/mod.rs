@@ -42,6 +42,40 @@ pub struct Mapping<Dsrc, Ddst, M> {
     pub mappings: M,
 }
 
+/// Thresholds of the GumTree matchers, given at runtime.
+///
+/// The defaults are the ones of the const generic parameters of the matchers,
+/// see [`heuristic::gt::DEFAULT_MIN_HEIGHT`] and the following constants,
+/// which stay the fast path when the thresholds are not changed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MatcherConfig {
+    /// minimal height of subtrees considered by the subtree matchers
+    pub min_height: usize,
+    /// size under which the bottom-up matchers recover mappings with an optimal algorithm
+    pub size_threshold: usize,
+    /// minimal similarity of containers matched by the bottom-up matchers
+    pub sim_threshold: f64,
+}
+
+impl Default for MatcherConfig {
+    fn default() -> Self {
+        use heuristic::gt::*;
+        Self {
+            min_height: DEFAULT_MIN_HEIGHT,
+            size_threshold: DEFAULT_SIZE_THRESHOLD,
+            sim_threshold: DEFAULT_SIM_THRESHOLD_NUM as f64 / DEFAULT_SIM_THRESHOLD_DEN as f64,
+        }
+    }
+}
+
+impl MatcherConfig {
+    /// Used to keep the const generic matchers, and to only cache the mappings,
+    /// when the thresholds are the default ones
+    pub fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
 impl<'store, HAST, Dsrc, Ddst, M: MappingStore + Default> From<(&'store HAST, (Dsrc, Ddst))>
     for Mapper<'store, HAST, Dsrc, Ddst, M>
 {
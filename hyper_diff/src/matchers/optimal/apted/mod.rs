@@ -0,0 +1,539 @@
+// All Path Tree Edit Distance, Pawlik and Augsten, 2016
+//
+// Like RTED, the subproblems are decomposed along the path (leftmost or rightmost,
+// in the source or in the destination tree) that minimizes the number of relevant subforests,
+// thus it is never worse than Zhang-Shasha which only uses leftmost paths of both trees.
+// Heavy paths are not considered, they would need the spfA single path function.
+
+use std::fmt::Debug;
+
+use num_traits::{cast, PrimInt, ToPrimitive};
+
+use crate::decompressed_tree_store::PostOrder;
+use crate::matchers::mapping_store::MonoMappingStore;
+use hyper_ast::types::{DecompressedSubtree, NodeStore, Tree};
+
+/// Costs of the edit operations on nodes, described by their type and label.
+pub trait CostModel<Ty, L> {
+    fn delete(&self, ty: &Ty, label: Option<&L>) -> f64;
+    fn insert(&self, ty: &Ty, label: Option<&L>) -> f64;
+    fn rename(&self, src: (&Ty, Option<&L>), dst: (&Ty, Option<&L>)) -> f64;
+}
+
+/// Unit costs, renaming is free if both the type and the label are equal.
+pub struct UnitCostModel;
+
+impl<Ty: Eq, L: Eq> CostModel<Ty, L> for UnitCostModel {
+    fn delete(&self, _ty: &Ty, _label: Option<&L>) -> f64 {
+        1.0
+    }
+
+    fn insert(&self, _ty: &Ty, _label: Option<&L>) -> f64 {
+        1.0
+    }
+
+    fn rename(&self, src: (&Ty, Option<&L>), dst: (&Ty, Option<&L>)) -> f64 {
+        if src == dst {
+            0.0
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Unit costs, but nodes of different types are never renamed into each other,
+/// as in the ZS matcher of GumTree.
+pub struct TypedCostModel;
+
+impl<Ty: Eq, L: Eq> CostModel<Ty, L> for TypedCostModel {
+    fn delete(&self, _ty: &Ty, _label: Option<&L>) -> f64 {
+        1.0
+    }
+
+    fn insert(&self, _ty: &Ty, _label: Option<&L>) -> f64 {
+        1.0
+    }
+
+    fn rename(&self, src: (&Ty, Option<&L>), dst: (&Ty, Option<&L>)) -> f64 {
+        if src.0 != dst.0 {
+            // same as deleting then inserting
+            2.0
+        } else if src.1 == dst.1 {
+            0.0
+        } else {
+            1.0
+        }
+    }
+}
+
+// TODO use the Mapping struct
+pub struct AptedMatcher<M, SD, DD = SD> {
+    pub mappings: M,
+    pub src_arena: SD,
+    pub dst_arena: DD,
+    pub distance: f64,
+}
+
+impl<SD, DD, M: MonoMappingStore + Default> AptedMatcher<M, SD, DD> {
+    pub fn matchh<'store, T, S, C>(
+        node_store: &'store S,
+        cost_model: &C,
+        src: T::TreeId,
+        dst: T::TreeId,
+    ) -> Self
+    where
+        T: 'store + Tree,
+        T::Label: Clone,
+        M::Src: PrimInt + Debug,
+        M::Dst: PrimInt + Debug,
+        SD: PostOrder<'store, T, M::Src> + DecompressedSubtree<'store, T, Out = SD>,
+        DD: PostOrder<'store, T, M::Dst> + DecompressedSubtree<'store, T, Out = DD>,
+        S: 'store + NodeStore<T::TreeId, R<'store> = T>,
+        C: CostModel<T::Type, T::Label>,
+    {
+        let src_arena = SD::decompress(node_store, &src);
+        let dst_arena = DD::decompress(node_store, &dst);
+        let mut apted = Apted::new(node_store, cost_model, &src_arena, &dst_arena);
+        let distance = apted.compute_distance();
+        let mut mappings = M::default();
+        mappings.topit(src_arena.len() + 1, dst_arena.len() + 1);
+        apted.compute_mappings(&mut mappings);
+        Self {
+            mappings,
+            src_arena,
+            dst_arena,
+            distance,
+        }
+    }
+
+    pub fn match_with<'store, 'b, T, S, C>(
+        node_store: &'store S,
+        cost_model: &C,
+        src_arena: SD,
+        dst_arena: DD,
+    ) -> M
+    where
+        T: 'store + Tree,
+        T::Label: Clone,
+        M::Src: PrimInt + Debug,
+        M::Dst: PrimInt + Debug,
+        SD: 'b + PostOrder<'b, T, M::Src>,
+        DD: 'b + PostOrder<'b, T, M::Dst>,
+        S: NodeStore<T::TreeId, R<'store> = T>,
+        C: CostModel<T::Type, T::Label>,
+    {
+        let mut apted = Apted::new(node_store, cost_model, &src_arena, &dst_arena);
+        apted.compute_distance();
+        let mut mappings = M::default();
+        mappings.topit(src_arena.len() + 1, dst_arena.len() + 1);
+        apted.compute_mappings(&mut mappings);
+        mappings
+    }
+}
+
+const LEFT: usize = 0;
+const RIGHT: usize = 1;
+
+/// A post-order numbering of a tree, either left-to-right or right-to-left.
+struct View {
+    lld: Vec<usize>,
+    to_lr: Vec<usize>,
+    from_lr: Vec<usize>,
+    /// not the first child of its parent in this numbering,
+    /// ie. a keyroot of any subtree containing its parent
+    kr: Vec<bool>,
+}
+
+struct Indexed<Ty, L> {
+    nodes: Vec<(Ty, Option<L>)>,
+    /// cost of deleting (resp. inserting) the node from the source (resp. in the destination)
+    cost: Vec<f64>,
+    size: Vec<usize>,
+    children: Vec<Vec<usize>>,
+    views: [View; 2],
+    /// number of relevant subforests of the full decomposition of each subtree, for both directions
+    full: [Vec<f64>; 2],
+}
+
+impl<Ty, L: Clone> Indexed<Ty, L> {
+    fn new<'store, 'b, T, IdD, D, S>(
+        node_store: &'store S,
+        arena: &D,
+        cost: impl Fn(&Ty, Option<&L>) -> f64,
+    ) -> Self
+    where
+        T: 'store + Tree<Type = Ty, Label = L>,
+        IdD: PrimInt,
+        D: PostOrder<'b, T, IdD>,
+        S: NodeStore<T::TreeId, R<'store> = T>,
+    {
+        let n = arena.len();
+        let mut nodes = Vec::with_capacity(n);
+        let mut lld = Vec::with_capacity(n);
+        for i in 0..n {
+            let id: IdD = cast(i).unwrap();
+            let r = node_store.resolve(&arena.tree(&id));
+            nodes.push((r.get_type(), r.try_get_label().cloned()));
+            lld.push(arena.lld(&id).to_usize().unwrap());
+        }
+        let cost = nodes.iter().map(|(t, l)| cost(t, l.as_ref())).collect();
+        let size: Vec<usize> = (0..n).map(|i| i + 1 - lld[i]).collect();
+        let mut children = vec![vec![]; n];
+        for (i, cs) in children.iter_mut().enumerate() {
+            let mut c = i;
+            while c > lld[i] {
+                c -= 1;
+                cs.push(c);
+                c = lld[c];
+            }
+            cs.reverse();
+        }
+        let mut left_kr = vec![true; n];
+        let mut right_kr = vec![true; n];
+        for cs in &children {
+            if let (Some(&first), Some(&last)) = (cs.first(), cs.last()) {
+                left_kr[first] = false;
+                right_kr[last] = false;
+            }
+        }
+        let left = View {
+            lld: lld.clone(),
+            to_lr: (0..n).collect(),
+            from_lr: (0..n).collect(),
+            kr: left_kr,
+        };
+        // right-to-left post-order, ie. the post-order of the mirrored tree
+        let mut to_lr = Vec::with_capacity(n);
+        if n > 0 {
+            let mut stack = vec![(n - 1, 0)];
+            while let Some(&(x, k)) = stack.last() {
+                let cs = &children[x];
+                if k < cs.len() {
+                    stack.last_mut().unwrap().1 += 1;
+                    stack.push((cs[cs.len() - 1 - k], 0));
+                } else {
+                    to_lr.push(x);
+                    stack.pop();
+                }
+            }
+        }
+        let mut from_lr = vec![0; n];
+        for (r, &x) in to_lr.iter().enumerate() {
+            from_lr[x] = r;
+        }
+        let right = View {
+            lld: (0..n).map(|r| r + 1 - size[to_lr[r]]).collect(),
+            kr: to_lr.iter().map(|&x| right_kr[x]).collect(),
+            to_lr,
+            from_lr,
+        };
+        let mut full = [vec![0.0; n], vec![0.0; n]];
+        for i in 0..n {
+            for (dir, full) in full.iter_mut().enumerate() {
+                let cs = &children[i];
+                let path_child = if dir == LEFT { cs.first() } else { cs.last() };
+                let mut x = size[i] as f64;
+                for &c in cs {
+                    x += full[c];
+                }
+                if let Some(&c) = path_child {
+                    x -= size[c] as f64;
+                }
+                full[i] = x;
+            }
+        }
+        Self {
+            nodes,
+            cost,
+            size,
+            children,
+            views: [left, right],
+            full,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    fn path_child(&self, x: usize, dir: usize) -> Option<usize> {
+        let cs = &self.children[x];
+        if dir == LEFT {
+            cs.first().copied()
+        } else {
+            cs.last().copied()
+        }
+    }
+
+    /// roots of the subtrees hanging off the leftmost or rightmost path of `x`
+    fn hanging(&self, x: usize, dir: usize) -> Vec<usize> {
+        let mut res = vec![];
+        let mut p = x;
+        while let Some(c) = self.path_child(p, dir) {
+            res.extend(self.children[p].iter().copied().filter(|&x| x != c));
+            p = c;
+        }
+        res
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+struct Path {
+    in_dst: bool,
+    dir: usize,
+}
+
+struct Apted<'c, Ty, L, C> {
+    cost_model: &'c C,
+    src: Indexed<Ty, L>,
+    dst: Indexed<Ty, L>,
+    /// tree distances, indexed by the left-to-right post-order ids of the source then of the destination
+    tree: Vec<f64>,
+    strategy: Vec<Path>,
+}
+
+impl<'c, Ty, L: Clone, C: CostModel<Ty, L>> Apted<'c, Ty, L, C> {
+    fn new<'store, 'b, T, S, SD, DD, IdS, IdD>(
+        node_store: &'store S,
+        cost_model: &'c C,
+        src_arena: &SD,
+        dst_arena: &DD,
+    ) -> Self
+    where
+        T: 'store + Tree<Type = Ty, Label = L>,
+        S: NodeStore<T::TreeId, R<'store> = T>,
+        IdS: PrimInt,
+        IdD: PrimInt,
+        SD: PostOrder<'b, T, IdS>,
+        DD: PostOrder<'b, T, IdD>,
+    {
+        let src = Indexed::new(node_store, src_arena, |t, l| cost_model.delete(t, l));
+        let dst = Indexed::new(node_store, dst_arena, |t, l| cost_model.insert(t, l));
+        Self {
+            cost_model,
+            tree: vec![0.0; src.len() * dst.len()],
+            strategy: vec![Path::default(); src.len() * dst.len()],
+            src,
+            dst,
+        }
+    }
+
+    fn compute_distance(&mut self) -> f64 {
+        let (n1, n2) = (self.src.len(), self.dst.len());
+        if n1 == 0 || n2 == 0 {
+            return self.src.cost.iter().chain(&self.dst.cost).sum();
+        }
+        self.compute_strategy();
+        self.gted(n1 - 1, n2 - 1);
+        self.tree[(n1 - 1) * n2 + n2 - 1]
+    }
+
+    /// For each pair of subtrees, chooses the path minimizing the number of relevant subproblems.
+    fn compute_strategy(&mut self) {
+        let (n1, n2) = (self.src.len(), self.dst.len());
+        let mut opt = vec![0.0; n1 * n2];
+        // costs of the subtrees hanging off the paths
+        let mut src_sums = [vec![0.0; n1 * n2], vec![0.0; n1 * n2]];
+        let mut dst_sums = [vec![0.0; n1 * n2], vec![0.0; n1 * n2]];
+        for v in 0..n1 {
+            for w in 0..n2 {
+                let k = v * n2 + w;
+                let mut best = f64::INFINITY;
+                for dir in [LEFT, RIGHT] {
+                    if let Some(p) = self.src.path_child(v, dir) {
+                        let mut s = src_sums[dir][p * n2 + w];
+                        for &c in &self.src.children[v] {
+                            if c != p {
+                                s += opt[c * n2 + w];
+                            }
+                        }
+                        src_sums[dir][k] = s;
+                    }
+                    if let Some(p) = self.dst.path_child(w, dir) {
+                        let mut s = dst_sums[dir][v * n2 + p];
+                        for &c in &self.dst.children[w] {
+                            if c != p {
+                                s += opt[v * n2 + c];
+                            }
+                        }
+                        dst_sums[dir][k] = s;
+                    }
+                    let c = self.src.size[v] as f64 * self.dst.full[dir][w] + src_sums[dir][k];
+                    if c < best {
+                        best = c;
+                        self.strategy[k] = Path { in_dst: false, dir };
+                    }
+                    let c = self.dst.size[w] as f64 * self.src.full[dir][v] + dst_sums[dir][k];
+                    if c < best {
+                        best = c;
+                        self.strategy[k] = Path { in_dst: true, dir };
+                    }
+                }
+                opt[k] = best;
+            }
+        }
+    }
+
+    /// Computes the distances between all subtrees of `v` and all subtrees of `w`.
+    fn gted(&mut self, v: usize, w: usize) {
+        let path = self.strategy[v * self.dst.len() + w];
+        if !path.in_dst {
+            for h in self.src.hanging(v, path.dir) {
+                self.gted(h, w);
+            }
+            self.spf(v, w, false, path.dir);
+        } else {
+            for h in self.dst.hanging(w, path.dir) {
+                self.gted(v, h);
+            }
+            self.spf(w, v, true, path.dir);
+        }
+    }
+
+    /// Single path function, the path of `a` is followed against the full decomposition of `b`.
+    ///
+    /// `a` is in the destination if `swapped`.
+    fn spf(&mut self, a: usize, b: usize, swapped: bool, dir: usize) {
+        let (ta, tb) = if swapped {
+            (&self.dst, &self.src)
+        } else {
+            (&self.src, &self.dst)
+        };
+        let (va, vb) = (&ta.views[dir], &tb.views[dir]);
+        let (a, b) = (va.from_lr[a], vb.from_lr[b]);
+        let (la, lb) = (va.lld[a], vb.lld[b]);
+        let keyroots: Vec<_> = (lb..=b).filter(|&j| j == b || vb.kr[j]).collect();
+        let mut fd = vec![0.0; (a - la + 2) * (b - lb + 2)];
+        for j in keyroots {
+            self.forest_dist(a, j, b, swapped, dir, &mut fd);
+        }
+    }
+
+    /// Forest distances between the prefixes of the subtrees `a` and `j`, `j` being in the subtree `b`.
+    ///
+    /// Ids are in the numbering of `dir`,
+    /// `fd` has a row per prefix of `a` and a column per prefix of `b`.
+    fn forest_dist(
+        &mut self,
+        a: usize,
+        j: usize,
+        b: usize,
+        swapped: bool,
+        dir: usize,
+        fd: &mut [f64],
+    ) {
+        let Self {
+            cost_model,
+            src,
+            dst,
+            tree,
+            ..
+        } = self;
+        let n2 = dst.len();
+        let (ta, tb) = if swapped {
+            (&*dst, &*src)
+        } else {
+            (&*src, &*dst)
+        };
+        let (va, vb) = (&ta.views[dir], &tb.views[dir]);
+        let (la, lb, lj) = (va.lld[a], vb.lld[b], vb.lld[j]);
+        let width = b - lb + 2;
+        let at = |x: usize, y: usize| x * width + y;
+        let tree_idx = |x: usize, y: usize| {
+            if swapped {
+                y * n2 + x
+            } else {
+                x * n2 + y
+            }
+        };
+        let y0 = lj - lb;
+        fd[at(0, y0)] = 0.0;
+        for x in la..=a {
+            let xi = x - la + 1;
+            fd[at(xi, y0)] = fd[at(xi - 1, y0)] + ta.cost[va.to_lr[x]];
+        }
+        for y in lj..=j {
+            let yi = y - lb + 1;
+            fd[at(0, yi)] = fd[at(0, yi - 1)] + tb.cost[vb.to_lr[y]];
+        }
+        for x in la..=a {
+            let xi = x - la + 1;
+            let x_lr = va.to_lr[x];
+            let cost_a = ta.cost[x_lr];
+            for y in lj..=j {
+                let yi = y - lb + 1;
+                let y_lr = vb.to_lr[y];
+                let cost_b = tb.cost[y_lr];
+                let d = f64::min(fd[at(xi - 1, yi)] + cost_a, fd[at(xi, yi - 1)] + cost_b);
+                if va.lld[x] == la && vb.lld[y] == lj {
+                    let (na, nb) = (&ta.nodes[x_lr], &tb.nodes[y_lr]);
+                    let (na, nb) = ((&na.0, na.1.as_ref()), (&nb.0, nb.1.as_ref()));
+                    let cost_ren = if swapped {
+                        cost_model.rename(nb, na)
+                    } else {
+                        cost_model.rename(na, nb)
+                    };
+                    let v = f64::min(d, fd[at(xi - 1, yi - 1)] + cost_ren);
+                    fd[at(xi, yi)] = v;
+                    tree[tree_idx(x_lr, y_lr)] = v;
+                } else {
+                    let v = fd[at(va.lld[x] - la, vb.lld[y] - lb)] + tree[tree_idx(x_lr, y_lr)];
+                    fd[at(xi, yi)] = f64::min(d, v);
+                }
+            }
+        }
+    }
+
+    fn rename_cost(&self, x: usize, y: usize) -> f64 {
+        let (n1, n2) = (&self.src.nodes[x], &self.dst.nodes[y]);
+        self.cost_model
+            .rename((&n1.0, n1.1.as_ref()), (&n2.0, n2.1.as_ref()))
+    }
+
+    /// Backtracks the forest distances of each pair of mapped subtrees, starting from the roots.
+    fn compute_mappings<M: MonoMappingStore>(&mut self, mappings: &mut M)
+    where
+        M::Src: PrimInt,
+        M::Dst: PrimInt,
+    {
+        let (n1, n2) = (self.src.len(), self.dst.len());
+        if n1 == 0 || n2 == 0 {
+            return;
+        }
+        let mut tree_pairs = vec![(n1 - 1, n2 - 1)];
+        let mut fd = vec![];
+        while let Some((r, c)) = tree_pairs.pop() {
+            let lr = self.src.views[LEFT].lld[r];
+            let lc = self.dst.views[LEFT].lld[c];
+            let width = c - lc + 2;
+            fd.clear();
+            fd.resize((r - lr + 2) * width, 0.0);
+            self.forest_dist(r, c, c, false, LEFT, &mut fd);
+            let at = |row: usize, col: usize| fd[(row - lr) * width + col - lc];
+            let (mut row, mut col) = (r + 1, c + 1);
+            while row > lr || col > lc {
+                if row > lr && at(row - 1, col) + self.src.cost[row - 1] == at(row, col) {
+                    // deleted from src
+                    row -= 1;
+                } else if col > lc && at(row, col - 1) + self.dst.cost[col - 1] == at(row, col) {
+                    // inserted in dst
+                    col -= 1;
+                } else {
+                    let (x, y) = (row - 1, col - 1);
+                    if self.src.views[LEFT].lld[x] == lr && self.dst.views[LEFT].lld[y] == lc {
+                        // both subforests are trees, renamed
+                        if self.rename_cost(x, y) < self.src.cost[x] + self.dst.cost[y] {
+                            mappings.link(cast(x).unwrap(), cast(y).unwrap());
+                        }
+                        row -= 1;
+                        col -= 1;
+                    } else {
+                        tree_pairs.push((x, y));
+                        row = self.src.views[LEFT].lld[x];
+                        col = self.dst.views[LEFT].lld[y];
+                    }
+                }
+            }
+        }
+    }
+}
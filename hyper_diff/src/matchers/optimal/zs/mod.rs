@@ -18,6 +18,7 @@ pub struct ZsMatcher<M, SD, DD = SD> {
     pub mappings: M,
     pub src_arena: SD,
     pub dst_arena: DD,
+    pub distance: f64,
 }
 
 impl<SD, DD, M: MonoMappingStore + Default> ZsMatcher<M, SD, DD> {
@@ -42,7 +43,7 @@ impl<SD, DD, M: MonoMappingStore + Default> ZsMatcher<M, SD, DD> {
         let src_arena = SD::decompress(node_store, &src);
         let dst_arena = DD::decompress(node_store, &dst);
         // let mappings = ZsMatcher::<M, SD, DD>::match_with(node_store, label_store, &src_arena, &dst_arena);
-        let mappings = {
+        let (mappings, distance) = {
             let mut mappings = M::default();
             mappings.topit(
                 (&src_arena).len().to_usize().unwrap(),
@@ -56,13 +57,15 @@ impl<SD, DD, M: MonoMappingStore + Default> ZsMatcher<M, SD, DD> {
                 phantom: PhantomData,
             };
             let mut dist = base.compute_dist();
+            let distance = dist.tree[src_arena.len()][dst_arena.len()];
             base.compute_mappings(&mut mappings, &mut dist);
-            mappings
+            (mappings, distance)
         };
         Self {
             src_arena,
             dst_arena,
             mappings,
+            distance,
         }
     }
 
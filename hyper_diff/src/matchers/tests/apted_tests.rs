@@ -0,0 +1,139 @@
+use crate::{
+    decompressed_tree_store::{ShallowDecompressedTreeStore, SimpleZsTree},
+    matchers::{
+        mapping_store::{DefaultMappingStore, MappingStore},
+        optimal::{
+            apted::{AptedMatcher, TypedCostModel, UnitCostModel},
+            zs::ZsMatcher,
+        },
+    },
+    tests::examples::{
+        example_gt_java_code, example_gt_slides, example_move, example_move1, example_move2,
+        example_move3, example_simple, example_simple1,
+    },
+    tree::simple_tree::vpair_to_stores,
+};
+
+#[test]
+fn test_with_simple_example() {
+    let (_label_store, node_store, src, dst) = vpair_to_stores(example_simple());
+    let mapper = AptedMatcher::<DefaultMappingStore<u16>, SimpleZsTree<_, u16>>::matchh(
+        &node_store,
+        &UnitCostModel,
+        src,
+        dst,
+    );
+    let AptedMatcher {
+        src_arena,
+        dst_arena,
+        mappings,
+        distance,
+    } = mapper;
+    assert_eq!(1.0, distance);
+    let src = &src_arena.root();
+    let dst = &dst_arena.root();
+    assert_eq!(3, mappings.src_to_dst.iter().filter(|x| **x != 0).count());
+    assert!(mappings.has(src, dst));
+    assert!(mappings.has(
+        &src_arena.child(&node_store, src, &[0]),
+        &dst_arena.child(&node_store, dst, &[0])
+    ));
+    assert!(mappings.has(
+        &src_arena.child(&node_store, src, &[1]),
+        &dst_arena.child(&node_store, dst, &[1])
+    ));
+}
+
+#[test]
+fn test_with_identical_trees() {
+    let (_label_store, node_store, src, _) = vpair_to_stores(example_gt_java_code());
+    let mapper = AptedMatcher::<DefaultMappingStore<u16>, SimpleZsTree<_, u16>>::matchh(
+        &node_store,
+        &TypedCostModel,
+        src,
+        src,
+    );
+    assert_eq!(0.0, mapper.distance);
+    let len = mapper.src_arena.len();
+    assert_eq!(
+        len,
+        mapper
+            .mappings
+            .src_to_dst
+            .iter()
+            .filter(|x| **x != 0)
+            .count()
+    );
+    for i in 0..len as u16 {
+        assert!(mapper.mappings.has(&i, &i));
+    }
+}
+
+#[test]
+fn test_with_slide_example() {
+    let (_label_store, node_store, src, dst) = vpair_to_stores(example_gt_slides());
+    let mapper = AptedMatcher::<DefaultMappingStore<u16>, SimpleZsTree<_, u16>>::matchh(
+        &node_store,
+        &UnitCostModel,
+        src,
+        dst,
+    );
+    let AptedMatcher {
+        src_arena,
+        dst_arena,
+        mappings,
+        distance,
+    } = mapper;
+    // 5 is deleted, 4 is inserted and the leaf 4 is renamed to 5
+    assert_eq!(3.0, distance);
+    let src = &src_arena.root();
+    let dst = &dst_arena.root();
+    assert_eq!(5, mappings.src_to_dst.iter().filter(|x| **x != 0).count());
+    assert!(mappings.has(src, dst));
+    assert!(mappings.has(
+        &src_arena.child(&node_store, src, &[0, 0]),
+        &dst_arena.child(&node_store, dst, &[0])
+    ));
+    assert!(mappings.has(
+        &src_arena.child(&node_store, src, &[0, 0, 0]),
+        &dst_arena.child(&node_store, dst, &[0, 0])
+    ));
+    assert!(mappings.has(
+        &src_arena.child(&node_store, src, &[0, 1]),
+        &dst_arena.child(&node_store, dst, &[1, 0])
+    ));
+    assert!(mappings.has(
+        &src_arena.child(&node_store, src, &[0, 2]),
+        &dst_arena.child(&node_store, dst, &[2])
+    ));
+}
+
+#[test]
+fn test_same_distance_as_zs() {
+    // all the nodes have the same type and single letter labels,
+    // so the label based update costs of zs are unit costs
+    for example in [
+        example_simple(),
+        example_simple1(),
+        example_move(),
+        example_move1(),
+        example_move2(),
+        example_move3(),
+        example_gt_slides(),
+    ] {
+        let (label_store, node_store, src, dst) = vpair_to_stores(example);
+        let apted = AptedMatcher::<DefaultMappingStore<u16>, SimpleZsTree<_, u16>>::matchh(
+            &node_store,
+            &UnitCostModel,
+            src,
+            dst,
+        );
+        let zs = ZsMatcher::<DefaultMappingStore<u16>, SimpleZsTree<_, u16>>::matchh(
+            &node_store,
+            &label_store,
+            src,
+            dst,
+        );
+        assert_eq!(zs.distance, apted.distance);
+    }
+}
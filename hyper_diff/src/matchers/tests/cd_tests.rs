@@ -0,0 +1,148 @@
+use hyper_ast::types::DecompressedSubtree;
+
+use crate::{
+    decompressed_tree_store::{CompletePostOrder, ShallowDecompressedTreeStore},
+    matchers::{
+        heuristic::cd::{
+            bottom_up_matcher::ChangeDistillerBottomUpMatcher,
+            leaves_matcher::ChangeDistillerLeavesMatcher,
+        },
+        mapping_store::{DefaultMappingStore, MappingStore},
+    },
+    tree::simple_tree::{tree, vpair_to_stores, SimpleTree, Tree, TreeRef},
+};
+
+fn example_leaves() -> (SimpleTree<u8>, SimpleTree<u8>) {
+    let src = tree!(
+        0, "r"; [
+            tree!(1, "print"),
+            tree!(1, "values"),
+            tree!(2, "ab"),
+    ]);
+    let dst = tree!(
+        0, "r"; [
+            tree!(1, "prints"),
+            tree!(1, "count"),
+            tree!(3, "ab"),
+    ]);
+    (src, dst)
+}
+
+fn example_inner() -> (SimpleTree<u8>, SimpleTree<u8>) {
+    let src = tree!(
+        0, "r"; [
+            tree!(1, "a"; [
+                tree!(2, "x"),
+                tree!(2, "y"),
+                tree!(2, "z"),
+            ]),
+            tree!(1, "b"; [
+                tree!(2, "u"),
+                tree!(2, "v"),
+            ]),
+            tree!(1, "c"; [
+                tree!(2, "m"),
+                tree!(2, "n"),
+                tree!(2, "o"),
+            ]),
+    ]);
+    let dst = tree!(
+        0, "r"; [
+            tree!(1, "b2"; [
+                tree!(2, "u"),
+                tree!(2, "w"),
+            ]),
+            tree!(1, "a2"; [
+                tree!(2, "x"),
+                tree!(2, "y"),
+                tree!(2, "q"),
+            ]),
+            tree!(1, "c2"; [
+                tree!(2, "m"),
+                tree!(2, "p"),
+                tree!(2, "s"),
+            ]),
+    ]);
+    (src, dst)
+}
+
+#[test]
+fn test_leaves_label_similarity() {
+    let (label_store, node_store, src, dst) = vpair_to_stores(example_leaves());
+    let src_arena = CompletePostOrder::<TreeRef<Tree>, u16>::decompress(&node_store, &src);
+    let dst_arena = CompletePostOrder::<TreeRef<Tree>, u16>::decompress(&node_store, &dst);
+    let mut ms = DefaultMappingStore::default();
+    ms.topit(src_arena.len(), dst_arena.len());
+    let mut matcher = ChangeDistillerLeavesMatcher::<_, _, _, _, _, _>::new(
+        &node_store,
+        &label_store,
+        src_arena,
+        dst_arena,
+        ms,
+    );
+    matcher.execute();
+    let src = &matcher.src_arena.root();
+    let dst = &matcher.dst_arena.root();
+    let from_src = |path: &[u8]| matcher.src_arena.child(&node_store, src, path);
+    let from_dst = |path: &[u8]| matcher.dst_arena.child(&node_store, dst, path);
+    // "print" and "prints" share 5 of their bigrams, "values" and "count" none,
+    // and the "ab" leaves do not have the same type
+    assert!(matcher.mappings.has(&from_src(&[0]), &from_dst(&[0])));
+    assert_eq!(1, matcher.mappings.len());
+}
+
+#[test]
+fn test_leaves_above_threshold() {
+    let (label_store, node_store, src, dst) = vpair_to_stores(example_leaves());
+    let src_arena = CompletePostOrder::<TreeRef<Tree>, u16>::decompress(&node_store, &src);
+    let dst_arena = CompletePostOrder::<TreeRef<Tree>, u16>::decompress(&node_store, &dst);
+    let mut ms = DefaultMappingStore::default();
+    ms.topit(src_arena.len(), dst_arena.len());
+    // "print" and "prints" have a similarity of 10/13
+    let mut matcher = ChangeDistillerLeavesMatcher::<_, _, _, _, _, _, 4, 5>::new(
+        &node_store,
+        &label_store,
+        src_arena,
+        dst_arena,
+        ms,
+    );
+    matcher.execute();
+    assert_eq!(0, matcher.mappings.len());
+}
+
+#[test]
+fn test_inner_nodes_by_common_leaves() {
+    let (_label_store, node_store, src, dst) = vpair_to_stores(example_inner());
+    let src_arena = CompletePostOrder::<TreeRef<Tree>, u16>::decompress(&node_store, &src);
+    let dst_arena = CompletePostOrder::<TreeRef<Tree>, u16>::decompress(&node_store, &dst);
+    let mut ms = DefaultMappingStore::default();
+    ms.topit(src_arena.len(), dst_arena.len());
+    {
+        let src = &src_arena.root();
+        let dst = &dst_arena.root();
+        let from_src = |path: &[u8]| src_arena.child(&node_store, src, path);
+        let from_dst = |path: &[u8]| dst_arena.child(&node_store, dst, path);
+        ms.link(from_src(&[0, 0]), from_dst(&[1, 0]));
+        ms.link(from_src(&[0, 1]), from_dst(&[1, 1]));
+        ms.link(from_src(&[1, 0]), from_dst(&[0, 0]));
+        ms.link(from_src(&[2, 0]), from_dst(&[2, 0]));
+    }
+    let mut matcher =
+        ChangeDistillerBottomUpMatcher::<_, _, _, _, _>::new(&node_store, src_arena, dst_arena, ms);
+    matcher.execute();
+    let src = &matcher.src_arena.root();
+    let dst = &matcher.dst_arena.root();
+    let from_src = |path: &[u8]| matcher.src_arena.child(&node_store, src, path);
+    let from_dst = |path: &[u8]| matcher.dst_arena.child(&node_store, dst, path);
+    let mappings = &matcher.mappings;
+    // 2 of 3 leaves in common, despite the move
+    assert!(mappings.has(&from_src(&[0]), &from_dst(&[1])));
+    // 1 of 2 leaves in common
+    assert!(mappings.has(&from_src(&[1]), &from_dst(&[0])));
+    // 1 of 3 leaves in common is below the threshold of 2/5
+    assert!(!mappings.is_src(&from_src(&[2])));
+    assert!(!mappings.is_dst(&from_dst(&[2])));
+    // the roots are always matched
+    assert!(mappings.has(src, dst));
+    assert_eq!(7, mappings.len());
+}
@@ -0,0 +1,77 @@
+use hyper_ast::types::DecompressedSubtree;
+
+use crate::{
+    decompressed_tree_store::{CompletePostOrder, ShallowDecompressedTreeStore},
+    matchers::{
+        heuristic::gt::{
+            bottom_up_matcher::BottomUpMatcher, hybrid_bottom_up_matcher::HybridBottomUpMatcher,
+        },
+        mapping_store::{DefaultMappingStore, MappingStore},
+    },
+    tests::examples::example_bottom_up,
+    tree::simple_tree::{vpair_to_stores, Tree, TreeRef},
+};
+
+/// Bottom-up phase on [`example_bottom_up`] with only the statements matched beforehand,
+/// the modifier and the name of the method must be recovered by the last chance matching.
+fn check_example_bottom_up<const SIZE_THRESHOLD: usize>() {
+    let (label_store, node_store, src, dst) = vpair_to_stores(example_bottom_up());
+    let src_arena = CompletePostOrder::<TreeRef<Tree>, u16>::decompress(&node_store, &src);
+    let dst_arena = CompletePostOrder::<TreeRef<Tree>, u16>::decompress(&node_store, &dst);
+    let mut ms = DefaultMappingStore::default();
+    ms.topit(src_arena.len(), dst_arena.len());
+    {
+        let src = &src_arena.root();
+        let dst = &dst_arena.root();
+        for i in 0..4 {
+            ms.link(
+                src_arena.child(&node_store, src, &[0, 2, i]),
+                dst_arena.child(&node_store, dst, &[0, 2, i]),
+            );
+        }
+    }
+    let statements = ms.clone();
+
+    let mut mapper = HybridBottomUpMatcher::<_, _, _, _, _, _, SIZE_THRESHOLD>::new(
+        &node_store,
+        &label_store,
+        src_arena,
+        dst_arena,
+        ms,
+    );
+    mapper.execute();
+    let BottomUpMatcher {
+        src_arena,
+        dst_arena,
+        mappings,
+        ..
+    } = mapper.into();
+    let src = &src_arena.root();
+    let dst = &dst_arena.root();
+    let from_src = |path: &[u8]| src_arena.child(&node_store, src, path);
+    let from_dst = |path: &[u8]| dst_arena.child(&node_store, dst, path);
+
+    for (f, s) in statements.iter() {
+        assert!(mappings.has(&f, &s), "{} -x-> {}", f, s);
+    }
+    assert!(mappings.has(src, dst));
+    assert!(mappings.has(&from_src(&[0]), &from_dst(&[0])));
+    assert!(mappings.has(&from_src(&[0, 2]), &from_dst(&[0, 2])));
+    // "public" -> "private" and "foo" -> "bar"
+    assert!(mappings.has(&from_src(&[0, 0]), &from_dst(&[0, 0])));
+    assert!(mappings.has(&from_src(&[0, 1]), &from_dst(&[0, 1])));
+    assert_eq!(9, mappings.len());
+}
+
+#[test]
+fn test_zs_recovery_of_small_containers() {
+    // all the containers are smaller than the threshold
+    check_example_bottom_up::<20>();
+}
+
+#[test]
+fn test_histogram_recovery_of_large_containers() {
+    // no container is small enough, the lcs and histogram heuristics are used,
+    // down to the roots and the leaves
+    check_example_bottom_up::<0>();
+}
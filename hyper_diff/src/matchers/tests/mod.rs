@@ -1,5 +1,11 @@
+#[cfg(test)]
+pub mod apted_tests;
+#[cfg(test)]
+pub mod cd_tests;
 // TODO put it back
 // #[cfg(test)]
 // pub mod gumtree_tests;
 #[cfg(test)]
+pub mod hybrid_tests;
+#[cfg(test)]
 pub mod zs_tests;
@@ -6,7 +6,8 @@ use crate::tree::tree_path::CompressedTreePath;
 use crate::{
     actions::{
         action_vec::{apply_actions, TestActions},
-        script_generator2::ScriptGenerator,
+        compose::{compose_actions, invert_actions},
+        script_generator2::{Act, ScriptGenerator, SimpleAction},
         Actions,
     },
     decompressed_tree_store::{CompletePostOrder, ShallowDecompressedTreeStore},
@@ -15,7 +16,7 @@ use crate::{
         action_generator2_tests::{make_delete, make_insert, make_move, make_update, Fmt},
         simple_examples::{example_delete_action, example_move_action, example_rename_action},
     },
-    tree::simple_tree::{vpair_to_stores, DisplayTree, TreeRef, NS},
+    tree::simple_tree::{tree, vpair_to_stores, vtrees_to_stores, DisplayTree, TreeRef, NS},
 };
 use hyper_ast::types::{DecompressedSubtree, LabelStore, Labeled, NodeStore};
 
@@ -452,3 +453,304 @@ fn test_move_actions_1() {
 
     assert_eq!(then, s_dst);
 }
+
+#[test]
+fn test_invert_actions_1() {
+    let (label_store, node_store, s_src, s_dst) = vpair_to_stores(example_delete_action());
+    let mut ms = DefaultMappingStore::default();
+    let src_arena = CompletePostOrder::<_, u16>::decompress(&node_store, &s_src);
+    let dst_arena = CompletePostOrder::<_, u16>::decompress(&node_store, &s_dst);
+    let src = &(src_arena.root());
+    let dst = &(dst_arena.root());
+    ms.topit(src_arena.len(), dst_arena.len());
+    let from_src = |path: &[u8]| src_arena.child(&node_store, src, path);
+    let from_dst = |path: &[u8]| dst_arena.child(&node_store, dst, path);
+    ms.link(from_src(&[]), from_dst(&[]));
+    ms.link(from_src(&[0]), from_dst(&[0]));
+    ms.link(from_src(&[1]), from_dst(&[1]));
+    ms.link(from_src(&[1, 0]), from_dst(&[1, 0]));
+    ms.link(from_src(&[1, 1]), from_dst(&[1, 1]));
+
+    let dst_arena = SimpleBfsMapper::from(&node_store, &dst_arena);
+    let actions = ScriptGenerator::<
+        _,
+        TreeRef<Tree>,
+        _,
+        SimpleBfsMapper<_, _, CompletePostOrder<_, IdD>, _>,
+        NS<Tree>,
+        _,
+        CompressedTreePath<_>,
+    >::_compute_actions(&node_store, &src_arena, &dst_arena, &ms)
+    .unwrap();
+
+    let mut node_store = node_store;
+    let inverted = invert_actions::<_, NS<Tree>, _>(&actions, s_src, &mut node_store);
+    println!("{:?}", inverted);
+
+    // ins f
+    assert_eq!(1, inverted.len());
+
+    let mut root = vec![s_dst];
+    apply_actions::<_, NS<Tree>, _>(inverted, &mut root, &mut node_store);
+    let then = *root.last().unwrap();
+
+    println!(
+        "then tree:\n{:?}",
+        DisplayTree::new(&label_store, &node_store, then)
+    );
+
+    assert_eq!(then, s_src);
+}
+
+#[test]
+fn test_compose_actions_1() {
+    let (label_store, node_store, s_src, s_dst) = vpair_to_stores(example_move_action());
+    let mut ms = DefaultMappingStore::default();
+    let src_arena = CompletePostOrder::<_, u16>::decompress(&node_store, &s_src);
+    let dst_arena = CompletePostOrder::<_, u16>::decompress(&node_store, &s_dst);
+    let src = &(src_arena.root());
+    let dst = &(dst_arena.root());
+    ms.topit(src_arena.len(), dst_arena.len());
+    let from_src = |path: &[u8]| src_arena.child(&node_store, src, path);
+    let from_dst = |path: &[u8]| dst_arena.child(&node_store, dst, path);
+    ms.link(from_src(&[]), from_dst(&[]));
+    ms.link(from_src(&[0]), from_dst(&[0]));
+    ms.link(from_src(&[0, 0]), from_dst(&[1, 1]));
+    ms.link(from_src(&[1]), from_dst(&[1]));
+    ms.link(from_src(&[1, 0]), from_dst(&[1, 0]));
+    ms.link(from_src(&[1, 1]), from_dst(&[1, 2]));
+
+    let dst_arena = SimpleBfsMapper::from(&node_store, &dst_arena);
+    let actions = ScriptGenerator::<
+        _,
+        TreeRef<Tree>,
+        _,
+        SimpleBfsMapper<_, _, CompletePostOrder<_, IdD>, _>,
+        NS<Tree>,
+        _,
+        CompressedTreePath<_>,
+    >::_compute_actions(&node_store, &src_arena, &dst_arena, &ms)
+    .unwrap();
+
+    let mut node_store = node_store;
+    let inverted = invert_actions::<_, NS<Tree>, _>(&actions, s_src, &mut node_store);
+    // going there and back
+    let composed = compose_actions(actions, inverted);
+    println!("{:?}", composed);
+
+    assert_eq!(2, composed.len());
+
+    let mut root = vec![s_src];
+    apply_actions::<_, NS<Tree>, _>(composed, &mut root, &mut node_store);
+    let then = *root.last().unwrap();
+
+    println!(
+        "then tree:\n{:?}",
+        DisplayTree::new(&label_store, &node_store, then)
+    );
+
+    assert_eq!(then, s_src);
+}
+
+/// script going from `s_src` to `s_dst`, the mappings being given as pairs of paths
+fn compute_actions(
+    node_store: &NS<Tree>,
+    s_src: u16,
+    s_dst: u16,
+    links: &[(&[u8], &[u8])],
+) -> ActionsVec<SimpleAction<u16, CompressedTreePath<u8>, u16>> {
+    let mut ms = DefaultMappingStore::default();
+    let src_arena = CompletePostOrder::<_, u16>::decompress(node_store, &s_src);
+    let dst_arena = CompletePostOrder::<_, u16>::decompress(node_store, &s_dst);
+    let src = &(src_arena.root());
+    let dst = &(dst_arena.root());
+    ms.topit(src_arena.len(), dst_arena.len());
+    for (s, d) in links {
+        ms.link(
+            src_arena.child(node_store, src, s),
+            dst_arena.child(node_store, dst, d),
+        );
+    }
+    let dst_arena = SimpleBfsMapper::from(node_store, &dst_arena);
+    ScriptGenerator::<
+        _,
+        TreeRef<Tree>,
+        _,
+        SimpleBfsMapper<_, _, CompletePostOrder<_, IdD>, _>,
+        NS<Tree>,
+        _,
+        CompressedTreePath<_>,
+    >::_compute_actions(node_store, &src_arena, &dst_arena, &ms)
+    .unwrap()
+}
+
+#[test]
+fn test_invert_update() {
+    let (label_store, node_store, s_src, s_dst) = vpair_to_stores(example_rename_action());
+    let actions = compute_actions(
+        &node_store,
+        s_src,
+        s_dst,
+        &[
+            (&[], &[]),
+            (&[0], &[0]),
+            (&[0, 0], &[0, 0]),
+            (&[1], &[1]),
+            (&[1, 0], &[1, 0]),
+            (&[1, 1], &[1, 1]),
+        ],
+    );
+
+    let mut node_store = node_store;
+    let inverted = invert_actions::<_, NS<Tree>, _>(&actions, s_src, &mut node_store);
+    println!("{:?}", inverted);
+
+    // upd g to f
+    assert_eq!(1, inverted.len());
+    let Act::Update { new } = &inverted.0[0].action else {
+        panic!("{:?}", inverted.0[0])
+    };
+    assert_eq!("f", label_store.resolve(new));
+
+    let mut root = vec![s_dst];
+    apply_actions::<_, NS<Tree>, _>(inverted, &mut root, &mut node_store);
+    let then = *root.last().unwrap();
+
+    println!(
+        "then tree:\n{:?}",
+        DisplayTree::new(&label_store, &node_store, then)
+    );
+
+    assert_eq!(then, s_src);
+}
+
+#[test]
+fn test_invert_move_update() {
+    let (src, dst) = example_move_action();
+    let renamed = tree!(
+        0,"a"; [
+            tree!(0, "e"),
+            tree!(0, "b"; [
+                tree!(0, "c"),
+                tree!(0, "g"),
+                tree!(0, "d")]),
+    ]);
+    let (mut label_store, node_store, [s_src, s_dst, s_renamed]) =
+        vtrees_to_stores([src, dst, renamed]);
+    let actions = compute_actions(
+        &node_store,
+        s_src,
+        s_dst,
+        &[
+            (&[], &[]),
+            (&[0], &[0]),
+            (&[0, 0], &[1, 1]),
+            (&[1], &[1]),
+            (&[1, 0], &[1, 0]),
+            (&[1, 1], &[1, 2]),
+        ],
+    );
+    // the generator splits moves and updates, thus make a move and update of f to g
+    let g = label_store.get_or_insert("g");
+    let actions = ActionsVec(
+        actions
+            .0
+            .into_iter()
+            .map(|SimpleAction { path, action }| match action {
+                Act::Move { from } => SimpleAction {
+                    path,
+                    action: Act::MovUpd { from, new: g },
+                },
+                action => SimpleAction { path, action },
+            })
+            .collect(),
+    );
+    assert_eq!(1, actions.len());
+
+    let mut node_store = node_store;
+    let inverted = invert_actions::<_, NS<Tree>, _>(&actions, s_src, &mut node_store);
+    println!("{:?}", inverted);
+
+    let mut root = vec![s_src];
+    apply_actions::<_, NS<Tree>, _>(actions, &mut root, &mut node_store);
+    assert_eq!(*root.last().unwrap(), s_renamed);
+
+    // mov and upd g to f
+    assert_eq!(1, inverted.len());
+    let Act::MovUpd { new, .. } = &inverted.0[0].action else {
+        panic!("{:?}", inverted.0[0])
+    };
+    assert_eq!("f", label_store.resolve(new));
+
+    let mut root = vec![s_renamed];
+    apply_actions::<_, NS<Tree>, _>(inverted, &mut root, &mut node_store);
+    let then = *root.last().unwrap();
+
+    println!(
+        "then tree:\n{:?}",
+        DisplayTree::new(&label_store, &node_store, then)
+    );
+
+    assert_eq!(then, s_src);
+}
+
+#[test]
+fn test_compose_independent_actions() {
+    // a to b renames f to g, b to c moves g in b
+    let (a, b) = example_rename_action();
+    let c = tree!(
+        0,"a"; [
+            tree!(0, "e"),
+            tree!(0, "b"; [
+                tree!(0, "c"),
+                tree!(0, "g"),
+                tree!(0, "d")]),
+    ]);
+    let (label_store, node_store, [s_a, s_b, s_c]) = vtrees_to_stores([a, b, c]);
+    let a_to_b = compute_actions(
+        &node_store,
+        s_a,
+        s_b,
+        &[
+            (&[], &[]),
+            (&[0], &[0]),
+            (&[0, 0], &[0, 0]),
+            (&[1], &[1]),
+            (&[1, 0], &[1, 0]),
+            (&[1, 1], &[1, 1]),
+        ],
+    );
+    let b_to_c = compute_actions(
+        &node_store,
+        s_b,
+        s_c,
+        &[
+            (&[], &[]),
+            (&[0], &[0]),
+            (&[0, 0], &[1, 1]),
+            (&[1], &[1]),
+            (&[1, 0], &[1, 0]),
+            (&[1, 1], &[1, 2]),
+        ],
+    );
+    assert_eq!(1, a_to_b.len());
+    assert_eq!(1, b_to_c.len());
+
+    let composed = compose_actions(a_to_b, b_to_c);
+    println!("{:?}", composed);
+
+    // upd f to g then mov g to b.1
+    assert_eq!(2, composed.len());
+
+    let mut node_store = node_store;
+    let mut root = vec![s_a];
+    apply_actions::<_, NS<Tree>, _>(composed, &mut root, &mut node_store);
+    let then = *root.last().unwrap();
+
+    println!(
+        "then tree:\n{:?}",
+        DisplayTree::new(&label_store, &node_store, then)
+    );
+
+    assert_eq!(then, s_c);
+}
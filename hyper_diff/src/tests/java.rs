@@ -0,0 +1,117 @@
+use hyper_ast::{
+    filter::BloomSize,
+    hashed::{self, IndexingHashBuilder, MetaDataHashsBuilder, SyntaxNodeHashs},
+    store::{
+        defaults::NodeIdentifier,
+        labels::LabelStore,
+        nodes::legion::{compo, compo::CS, NodeStore},
+        SimpleStores,
+    },
+    tree_gen::SubTreeMetrics,
+    types::LabelStore as _,
+};
+use hyper_ast_gen_ts_java::{
+    legion_with_refs::{self, eq_node, JavaTreeGen},
+    types::{TStore, Type},
+};
+
+pub(crate) type JavaStores = SimpleStores<TStore>;
+
+/// Generates each text as a java file, all of them in the same stores.
+pub(crate) fn java_files<const N: usize>(texts: [&str; N]) -> (JavaStores, [NodeIdentifier; N]) {
+    let mut stores = SimpleStores {
+        label_store: LabelStore::new(),
+        type_store: TStore::default(),
+        node_store: NodeStore::new(),
+    };
+    let mut md_cache = Default::default();
+    let mut java_tree_gen = JavaTreeGen::new(&mut stores, &mut md_cache);
+    let roots = texts.map(|text| {
+        let tree = legion_with_refs::tree_sitter_parse(text.as_bytes()).unwrap_or_else(|t| t);
+        java_tree_gen
+            .generate_file(b"A.java", text.as_bytes(), tree.walk())
+            .local
+            .compressed_node
+    });
+    (stores, roots)
+}
+
+/// A java file or a directory, to generate with [`java_dirs`].
+pub(crate) enum JavaFs<'a> {
+    File(&'a str, &'a str),
+    Dir(&'a str, Vec<JavaFs<'a>>),
+}
+
+/// Generates each directory in the same stores,
+/// directories being made like the ones of a processed repository.
+pub(crate) fn java_dirs<const N: usize>(dirs: [JavaFs; N]) -> (JavaStores, [NodeIdentifier; N]) {
+    let mut stores = SimpleStores {
+        label_store: LabelStore::new(),
+        type_store: TStore::default(),
+        node_store: NodeStore::new(),
+    };
+    let mut md_cache = Default::default();
+    let mut java_tree_gen = JavaTreeGen::new(&mut stores, &mut md_cache);
+    let roots = dirs.map(|x| java_fs(&mut java_tree_gen, &x).0);
+    (stores, roots)
+}
+
+fn java_fs<'a: 'stores, 'stores>(
+    java_tree_gen: &mut JavaTreeGen<'stores, '_, TStore>,
+    x: &JavaFs<'a>,
+) -> (NodeIdentifier, SubTreeMetrics<SyntaxNodeHashs<u32>>) {
+    let (name, children) = match x {
+        JavaFs::File(name, text) => {
+            let tree = legion_with_refs::tree_sitter_parse(text.as_bytes()).unwrap_or_else(|t| t);
+            let local = java_tree_gen
+                .generate_file(name.as_bytes(), text.as_bytes(), tree.walk())
+                .local;
+            return (local.compressed_node, local.metrics);
+        }
+        JavaFs::Dir(name, children) => (name, children),
+    };
+    let mut metrics = SubTreeMetrics::<SyntaxNodeHashs<u32>>::default();
+    let mut children_ids = vec![];
+    let mut children_names = vec![];
+    for c in children {
+        let (JavaFs::File(n, _) | JavaFs::Dir(n, _)) = c;
+        let (id, md) = java_fs(java_tree_gen, c);
+        metrics.acc(md);
+        children_ids.push(id);
+        children_names.push(java_tree_gen.stores.label_store.get_or_insert(*n));
+    }
+    let stores = &mut java_tree_gen.stores;
+    let size = metrics.size + 1;
+    let height = metrics.height + 1;
+    let size_no_spaces = metrics.size_no_spaces + 1;
+    let hbuilder = hashed::Builder::new(metrics.hashs, &Type::Directory, *name, size_no_spaces);
+    let hashable = &hbuilder.most_discriminating();
+    let label_id = stores.label_store.get_or_insert(*name);
+    let eq = eq_node(&Type::Directory, Some(&label_id), &children_ids);
+    let insertion = stores.node_store.prepare_insertion(hashable, eq);
+    let hashs = hbuilder.build();
+    let id = match insertion.occupied_id() {
+        Some(id) => id,
+        None => NodeStore::insert_after_prepare(
+            insertion.vacant(),
+            (
+                Type::Directory,
+                label_id,
+                compo::Size(size),
+                compo::Height(height),
+                compo::SizeNoSpaces(size_no_spaces),
+                hashs,
+                CS(children_names.into_boxed_slice()),
+                CS(children_ids.into_boxed_slice()),
+                BloomSize::Much,
+            ),
+        ),
+    };
+    let metrics = SubTreeMetrics {
+        hashs,
+        size,
+        height,
+        size_no_spaces,
+    };
+    (id, metrics)
+}
@@ -5,6 +5,8 @@ pub mod action_generator_tests;
 pub mod examples;
 pub mod hungarian_tests;
 #[cfg(test)]
+pub(crate) mod java;
+#[cfg(test)]
 pub mod lazy_decompression_tests;
 pub mod pair_tests;
 pub mod simple_examples;
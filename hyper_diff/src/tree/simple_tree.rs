@@ -71,6 +71,15 @@ pub(crate) fn vpair_to_stores<'a>(
     (label_store, compressed_node_store, src, dst)
 }
 
+#[cfg(test)]
+pub(crate) fn vtrees_to_stores<const N: usize>(
+    trees: [SimpleTree<u8>; N],
+) -> (LS<u16>, NS<Tree>, [u16; N]) {
+    let (mut label_store, mut compressed_node_store) = make_stores();
+    let roots = trees.map(|t| store(&mut label_store, &mut compressed_node_store, &t));
+    (label_store, compressed_node_store, roots)
+}
+
 impl AsRef<Tree> for &Tree {
     fn as_ref(&self) -> &Tree {
         self
@@ -615,7 +624,7 @@ where
 pub(crate) struct LS<I: PrimInt> {
     // v: RefCell<Vec<hyper_ast::types::OwnedLabel>>,
     v: Vec<hyper_ast::types::OwnedLabel>,
-    phantom: PhantomData<*const I>,
+    phantom: PhantomData<I>,
 }
 
 impl<'a, I: PrimInt> LabelStore<hyper_ast::types::SlicedLabel> for LS<I> {